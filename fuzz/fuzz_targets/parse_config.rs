@@ -0,0 +1,17 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use mako_tui::config::Config;
+
+// Run with `cargo +nightly fuzz run parse_config` from the repo root.
+fuzz_target!(|data: &[u8]| {
+    let Ok(s) = std::str::from_utf8(data) else {
+        return;
+    };
+    let Ok(cfg) = s.parse::<Config>();
+
+    // Whatever we write must read back as the same config.
+    let saved = cfg.to_string();
+    let Ok(reparsed) = saved.parse::<Config>();
+    assert_eq!(reparsed.to_string(), saved);
+});
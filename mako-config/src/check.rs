@@ -0,0 +1,178 @@
+//! Checking a config file line by line, the way mako will read it: for
+//! `mako-tui validate` in scripts and CI, and the editor's problems view.
+
+use std::fmt;
+
+use crate::config::Criteria;
+use crate::i18n::{tr, trf};
+use crate::keys::{known_keys, validate, CriterionKind, CRITERIA};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Severity {
+    /// Valid for mako, but likely a mistake.
+    Warning,
+    /// mako will refuse the line, or the whole file.
+    Error,
+}
+
+impl Severity {
+    pub fn label(self) -> &'static str {
+        match self {
+            Severity::Warning => tr("check.warning"),
+            Severity::Error => tr("check.error"),
+        }
+    }
+}
+
+/// Something wrong with one line of a config file.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Problem {
+    /// 1-based, as editors and compilers count.
+    pub line: usize,
+    pub severity: Severity,
+    pub message: String,
+}
+
+/// `12: error: message`.
+impl fmt::Display for Problem {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}: {}", self.line, self.severity.label(), self.message)
+    }
+}
+
+/// Every problem in `text`, in line order: lines that are neither
+/// `key=value` nor a `[criteria]` header, malformed headers and unknown
+/// criteria, values mako won't parse, unknown keys, and keys set twice in
+/// one section.
+pub fn check(text: &str) -> Vec<Problem> {
+    let known: Vec<&str> = known_keys().into_iter().map(|(k, _)| k).collect();
+    let mut out = Vec::new();
+    // Keys of the current section, with the line each was first set on.
+    let mut seen: Vec<(String, usize)> = Vec::new();
+    for (i, line) in text.lines().enumerate() {
+        let n = i + 1;
+        let mut report = |severity, message| out.push(Problem { line: n, severity, message });
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        if line.starts_with('[') {
+            seen.clear();
+            match line.strip_prefix('[').and_then(|l| l.strip_suffix(']')) {
+                None => report(Severity::Error, tr("check.unclosed_header").to_string()),
+                Some(header) if header.trim().is_empty() => report(Severity::Error, tr("check.empty_header").to_string()),
+                Some(header) if !quotes_balance(header) => report(Severity::Error, tr("check.unbalanced_quote").to_string()),
+                Some(header) => {
+                    for message in criteria_problems(header) {
+                        report(Severity::Error, message);
+                    }
+                }
+            }
+            continue;
+        }
+        let Some((key, value)) = line.split_once('=') else {
+            report(Severity::Error, tr("check.missing_equals").to_string());
+            continue;
+        };
+        let (key, value) = (key.trim(), unquote(value.trim()));
+        if key.is_empty() {
+            report(Severity::Error, tr("check.missing_key").to_string());
+            continue;
+        }
+        if !known.contains(&key) {
+            report(Severity::Warning, trf("check.unknown_key", &[&key]));
+        } else if let Err(e) = validate(key, value) {
+            report(Severity::Error, e);
+        }
+        match seen.iter().find(|(k, _)| k == key) {
+            Some((_, first)) => report(Severity::Warning, trf("check.duplicate", &[&key, first])),
+            None => seen.push((key.to_string(), n)),
+        }
+    }
+    out
+}
+
+/// Whether the quotes in a header pair up, not counting escaped ones.
+fn quotes_balance(header: &str) -> bool {
+    let mut quoted = false;
+    let mut chars = header.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '"' => quoted = !quoted,
+            '\\' if quoted => {
+                chars.next();
+            }
+            _ => {}
+        }
+    }
+    !quoted
+}
+
+/// What is wrong with the conditions in a header: unknown criteria, values
+/// a fixed-choice criterion doesn't take, and text criteria with no value.
+fn criteria_problems(header: &str) -> Vec<String> {
+    let Ok(criteria) = header.parse::<Criteria>();
+    let mut out = Vec::new();
+    for c in &criteria.conditions {
+        let Some((_, kind)) = CRITERIA.iter().find(|(k, _)| *k == c.key) else {
+            out.push(trf("check.unknown_criterion", &[&c.key]));
+            continue;
+        };
+        match (kind, &c.value) {
+            (CriterionKind::Choice(values), Some(v)) if !c.regex && !values.contains(&v.as_str()) => {
+                out.push(trf("check.criterion_value", &[v, &c.key, &values.join(", ")]));
+            }
+            // A bare `[grouped]` means `grouped=true`.
+            (CriterionKind::Choice(values), None) if *values != ["true", "false"] => {
+                out.push(trf("check.criterion_needs_value", &[&c.key, &c.key]));
+            }
+            (CriterionKind::Text, None) => out.push(trf("check.criterion_needs_value", &[&c.key, &c.key])),
+            _ => {}
+        }
+    }
+    out
+}
+
+/// A value as the parser stores it: without its surrounding quotes.
+fn unquote(value: &str) -> &str {
+    let quoted = value.len() >= 2
+        && ((value.starts_with('"') && value.ends_with('"')) || (value.starts_with('\'') && value.ends_with('\'')));
+    if quoted { &value[1..value.len() - 1] } else { value }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn lines(problems: &[Problem]) -> Vec<(usize, Severity)> {
+        problems.iter().map(|p| (p.line, p.severity)).collect()
+    }
+
+    #[test]
+    fn reports_each_kind_of_problem_with_its_line() {
+        let text = "# comment\nfont=mono 10\nwidth=wide\nborder-size\nmy-key=1\nfont=sans 9\n\n[urgency=urgent]\nfont=serif\n[app-name=\"x]\n[colour=red]\n[summary]\n[grouped]\n=5\n";
+        let problems = check(text);
+        assert_eq!(
+            lines(&problems),
+            [
+                (3, Severity::Error),
+                (4, Severity::Error),
+                (5, Severity::Warning),
+                (6, Severity::Warning),
+                (8, Severity::Error),
+                (10, Severity::Error),
+                (11, Severity::Error),
+                (12, Severity::Error),
+                (14, Severity::Error),
+            ]
+        );
+        assert!(problems[3].message.contains("line 2"), "{}", problems[3].message);
+        assert_eq!(problems[0].to_string(), format!("3: error: {}", validate("width", "wide").unwrap_err()));
+    }
+
+    #[test]
+    fn a_clean_config_has_no_problems() {
+        let text = "font=monospace 10\nformat=\"<b>%s</b>\"\n\n[urgency=critical]\nborder-color=#ff0000\n\n[grouped]\nformat=(%g) %s\n\n[summary~=\"^Update\"]\ninvisible=1\n";
+        assert_eq!(check(text), []);
+    }
+}
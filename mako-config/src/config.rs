@@ -0,0 +1,545 @@
+use std::{
+    convert::Infallible,
+    env,
+    fmt,
+    fs::{self, File},
+    io::{self, Write},
+    path::{Path, PathBuf},
+    str::FromStr,
+    time::SystemTime,
+};
+use home::home_dir;
+
+use crate::i18n::tr;
+use crate::keys::{default_config_text, deprecated, is_default};
+
+/// Representation of one config line (key = value).
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Param {
+    pub key: String,
+    pub value: String,
+}
+
+impl Param {
+    pub fn new<K: Into<String>, V: Into<String>>(key: K, value: V) -> Self {
+        Param {
+            key: key.into(),
+            value: value.into(),
+        }
+    }
+
+    /// The value as written to the file. Values that would otherwise be
+    /// read back with their surrounding quotes stripped get quoted.
+    pub fn formatted_value(&self) -> String {
+        let v = self.value.trim();
+        if is_quoted(v) {
+            format!("\"{}\"", v.replace('"', "\\\""))
+        } else {
+            v.to_string()
+        }
+    }
+}
+
+/// One condition of a criteria header, such as `urgency=critical`,
+/// `summary~="^Update"` or a bare `grouped`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Condition {
+    pub key: String,
+    /// Matched as a regular expression (`~=`) rather than for equality.
+    pub regex: bool,
+    /// `None` for boolean criteria written without a value.
+    pub value: Option<String>,
+}
+
+/// The conditions in a section header, all of which a notification must
+/// meet for the section's params to apply.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Criteria {
+    pub conditions: Vec<Condition>,
+}
+
+impl Criteria {
+    /// The condition on `key`, if there is one.
+    pub fn get(&self, key: &str) -> Option<&Condition> {
+        self.conditions.iter().find(|c| c.key == key)
+    }
+
+    /// The criteria of a `[mode=NAME]` section.
+    pub fn mode(name: &str) -> Criteria {
+        Criteria { conditions: vec![Condition { key: "mode".to_string(), regex: false, value: Some(name.to_string()) }] }
+    }
+
+    /// Replace the condition on `key` with `condition`, adding it at the
+    /// end if there was none, or drop it for `None`.
+    pub fn set(&mut self, key: &str, condition: Option<Condition>) {
+        match (self.conditions.iter().position(|c| c.key == key), condition) {
+            (Some(i), Some(c)) => self.conditions[i] = c,
+            (Some(i), None) => {
+                self.conditions.remove(i);
+            }
+            (None, Some(c)) => self.conditions.push(c),
+            (None, None) => {}
+        }
+    }
+}
+
+/// A `[criteria]` section and the params that follow its header.
+#[derive(Clone, Debug, Default)]
+pub struct Section {
+    pub criteria: Criteria,
+    pub params: Vec<Param>,
+}
+
+/// A `<path>.bak.N` file kept by `save_with_backups`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Backup {
+    /// 1 for the newest.
+    pub n: usize,
+    pub path: PathBuf,
+    pub modified: Option<SystemTime>,
+    /// How many params it holds, counting every section.
+    pub keys: usize,
+}
+
+/// Representation of the whole config file.
+#[derive(Clone, Debug, Default)]
+pub struct Config {
+    /// Params before the first section header, which apply to every
+    /// notification.
+    pub params: Vec<Param>,
+    /// Criteria sections in file order.
+    pub sections: Vec<Section>,
+    /// File this config was loaded from and is saved to.
+    pub path: PathBuf,
+    /// Named values params can use as `@name`, such as a palette's
+    /// `@accent`. The file gets them expanded, with the symbolic value in
+    /// a `TEMPLATE_MARK` comment above each param that used one, which
+    /// parsing reads back; so it is the symbolic value that gets edited,
+    /// and changing the palette changes every param using it on the next
+    /// save.
+    pub palette: Vec<(String, String)>,
+}
+
+/// The comment `Display` writes above a param whose value uses the
+/// palette: `# template: border-color=@accent`.
+pub const TEMPLATE_MARK: &str = "# template: ";
+
+impl Config {
+    /// Where mako reads its config: `$XDG_CONFIG_HOME/mako/config`, or
+    /// `~/.config/mako/config`. Fails like `config_dir`.
+    pub fn config_path() -> io::Result<PathBuf> {
+        Ok(config_dir()?.join("mako/config"))
+    }
+
+    /// An empty config that will be saved to `path`.
+    pub fn new(path: PathBuf) -> Self {
+        Config { path, ..Config::default() }
+    }
+
+    /// What line `line` (1-based) of `to_string()` holds: the section
+    /// number (as for `params_of`) and the index of the param on it, or
+    /// `None` for a section's header or the blank line before it.
+    pub fn locate_line(&self, line: usize) -> Option<(usize, Option<usize>)> {
+        let mut start = 1;
+        for n in 0..self.section_count() {
+            // Before each header: a blank line unless it opens the file.
+            let header = match n {
+                0 => 0,
+                1 if self.params.is_empty() => 1,
+                _ => 2,
+            };
+            let len = header + self.params_of(n).len();
+            if line >= start && line < start + len {
+                return Some((n, (line - start).checked_sub(header)));
+            }
+            start += len;
+        }
+        None
+    }
+
+    /// The params of section `n`, where 0 is the global params and `n` is
+    /// `sections[n - 1]`. Out-of-range sections read as the global params.
+    pub fn params_of(&self, n: usize) -> &Vec<Param> {
+        match n.checked_sub(1).and_then(|i| self.sections.get(i)) {
+            Some(section) => &section.params,
+            None => &self.params,
+        }
+    }
+
+    pub fn params_of_mut(&mut self, n: usize) -> &mut Vec<Param> {
+        match n.checked_sub(1).and_then(|i| self.sections.get_mut(i)) {
+            Some(section) => &mut section.params,
+            None => &mut self.params,
+        }
+    }
+
+    /// How many sections `params_of` accepts, the global one included.
+    pub fn section_count(&self) -> usize {
+        1 + self.sections.len()
+    }
+
+    pub fn load() -> io::Result<Self> {
+        Self::load_from(Self::config_path()?)
+    }
+
+    pub fn load_from(path: PathBuf) -> io::Result<Self> {
+        if !path.exists() {
+            return Ok(Config::new(path));
+        }
+        let s = fs::read_to_string(&path)?;
+        let Ok(cfg) = s.parse::<Config>();
+        Ok(Config { path, ..cfg })
+    }
+
+    pub fn save(&self) -> io::Result<PathBuf> {
+        if let Some(dir) = self.path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        write_atomic(&self.path, &self.to_string())?;
+        Ok(self.path.clone())
+    }
+
+    /// Save, first keeping the current file as `<path>.bak.1` and shifting
+    /// older backups up to `<path>.bak.<keep>`.
+    pub fn save_with_backups(&self, keep: usize) -> io::Result<PathBuf> {
+        self.back_up(keep)?;
+        self.save()
+    }
+
+    /// Replace the file with mako's defaults, all commented out, backing
+    /// up the current one like `save_with_backups`. At least one backup is
+    /// kept whatever `keep` says, since this throws the whole config away.
+    pub fn reset_to_defaults(&self, keep: usize) -> io::Result<PathBuf> {
+        self.back_up(keep.max(1))?;
+        if let Some(dir) = self.path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        write_atomic(&self.path, &default_config_text())?;
+        Ok(self.path.clone())
+    }
+
+    /// Put `previous`, the file as it was before the last save, back in
+    /// place, e.g. because mako rejected the new one. No backup is taken:
+    /// the rejected version is still in memory.
+    pub fn roll_back(&self, previous: &str) -> io::Result<()> {
+        write_atomic(&self.path, previous)
+    }
+
+    fn back_up(&self, keep: usize) -> io::Result<()> {
+        if keep > 0 && self.path.exists() {
+            let _ = fs::remove_file(self.backup_path(keep));
+            for n in (1..keep).rev() {
+                let from = self.backup_path(n);
+                if from.exists() {
+                    fs::rename(&from, self.backup_path(n + 1))?;
+                }
+            }
+            fs::copy(&self.path, self.backup_path(1))?;
+        }
+        Ok(())
+    }
+
+    pub fn backup_path(&self, n: usize) -> PathBuf {
+        let mut name = self.path.clone().into_os_string();
+        name.push(format!(".bak.{}", n));
+        PathBuf::from(name)
+    }
+
+    /// The backups that exist, newest (`.bak.1`) first.
+    pub fn backups(&self) -> Vec<Backup> {
+        (1..)
+            .map(|n| (n, self.backup_path(n)))
+            .map_while(|(n, path)| {
+                let meta = fs::metadata(&path).ok()?;
+                let keys = fs::read_to_string(&path).ok().map_or(0, |s| {
+                    let Ok(cfg) = s.parse::<Config>();
+                    cfg.params.len() + cfg.sections.iter().map(|s| s.params.len()).sum::<usize>()
+                });
+                Some(Backup { n, path, modified: meta.modified().ok(), keys })
+            })
+            .collect()
+    }
+
+    /// Replace the params and sections with those in `backup`, keeping the
+    /// path. Nothing is written; saving makes the current file the newest
+    /// backup, so a restore can itself be restored away.
+    pub fn restore(&mut self, backup: &Backup) -> io::Result<()> {
+        let Ok(cfg) = fs::read_to_string(&backup.path)?.parse::<Config>();
+        self.params = cfg.params;
+        self.sections = cfg.sections;
+        Ok(())
+    }
+
+    /// Set `key` in section `n`. mako uses the last occurrence of a key,
+    /// so that's the one changed; an unset key is appended.
+    pub fn set(&mut self, n: usize, key: &str, value: String) {
+        match self.params_of_mut(n).iter_mut().rev().find(|p| p.key == key) {
+            Some(p) => p.value = value,
+            None => self.add_param(n, key.to_string(), value),
+        }
+    }
+
+    /// The number `params_of` uses for the section with `criteria`,
+    /// adding an empty section at the end if there is none.
+    pub fn section_for(&mut self, criteria: &Criteria) -> usize {
+        match self.sections.iter().position(|s| s.criteria == *criteria) {
+            Some(i) => i + 1,
+            None => {
+                self.sections.push(Section { criteria: criteria.clone(), params: Vec::new() });
+                self.sections.len()
+            }
+        }
+    }
+
+    /// The modes that `[mode=NAME]` sections style, in file order and
+    /// each once.
+    pub fn modes(&self) -> Vec<&str> {
+        let mut modes = Vec::new();
+        for s in &self.sections {
+            if let Some(Condition { regex: false, value: Some(name), .. }) = s.criteria.get("mode")
+                && !modes.contains(&name.as_str())
+            {
+                modes.push(name.as_str());
+            }
+        }
+        modes
+    }
+
+    /// Append `key = value` to section `n` (0 for the global params).
+    pub fn add_param(&mut self, n: usize, key: String, value: String) {
+        self.params_of_mut(n).push(Param::new(key, value));
+    }
+
+    pub fn remove_param(&mut self, idx: usize) {
+        if idx < self.params.len() {
+            self.params.remove(idx);
+        }
+    }
+
+    /// The same config without the global params mako would default to
+    /// anyway: just what makes this setup special. Sections are kept as
+    /// they are, since their params override the global ones.
+    pub fn without_defaults(&self) -> Config {
+        let params = self.params.iter().filter(|p| !is_default(&p.key, &p.value)).cloned().collect();
+        Config { params, sections: self.sections.clone(), path: self.path.clone(), palette: self.palette.clone() }
+    }
+
+    /// `value` with each `@name` of the palette replaced by its value.
+    /// Other `@` words are left as they are.
+    pub fn expand(&self, value: &str) -> String {
+        if self.palette.is_empty() || !value.contains('@') {
+            return value.to_string();
+        }
+        let mut out = String::new();
+        let mut rest = value;
+        while let Some(at) = rest.find('@') {
+            out.push_str(&rest[..at]);
+            let word = &rest[at + 1..];
+            let len = word.find(|c: char| !(c.is_ascii_alphanumeric() || c == '-' || c == '_')).unwrap_or(word.len());
+            match self.palette.iter().find(|(name, _)| name == &word[..len]) {
+                Some((_, v)) => out.push_str(v),
+                None => out.push_str(&rest[at..at + 1 + len]),
+            }
+            rest = &word[len..];
+        }
+        out.push_str(rest);
+        out
+    }
+
+    /// Rename deprecated keys (see `DEPRECATED_KEYS`) to what mako reads
+    /// now, in every section, and take out the ones it no longer reads.
+    /// Returns how many were renamed and the params taken out.
+    pub fn migrate(&mut self) -> (usize, Vec<Param>) {
+        let (mut renamed, mut removed) = (0, Vec::new());
+        for n in 0..=self.sections.len() {
+            let params = self.params_of_mut(n);
+            for p in params.iter_mut() {
+                if let Some(Some(new)) = deprecated(&p.key) {
+                    p.key = new.to_string();
+                    renamed += 1;
+                }
+            }
+            params.retain(|p| {
+                let keep = deprecated(&p.key).is_none();
+                if !keep {
+                    removed.push(p.clone());
+                }
+                keep
+            });
+        }
+        (renamed, removed)
+    }
+}
+
+/// Parse config file contents. Parsing never fails: lines that aren't
+/// `key = value` or a `[criteria]` header are skipped.
+impl FromStr for Config {
+    type Err = Infallible;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut cfg = Config::default();
+        // The symbolic value of the next param, from a `TEMPLATE_MARK`.
+        let mut template: Option<Param> = None;
+        for line in s.lines() {
+            let line = line.trim();
+            if let Some((key, value)) = line.strip_prefix(TEMPLATE_MARK).and_then(|t| t.split_once('=')) {
+                template = Some(Param::new(key.trim(), value.trim()));
+                continue;
+            }
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let template = template.take();
+            if let Some(header) = line.strip_prefix('[').and_then(|l| l.strip_suffix(']')) {
+                let Ok(criteria) = header.parse();
+                cfg.sections.push(Section { criteria, params: Vec::new() });
+            } else if let Some(idx) = line.find('=') {
+                let key = line[..idx].trim().to_string();
+                let mut value = line[idx + 1..].trim().to_string();
+                if is_quoted(&value) {
+                    value = value[1..value.len() - 1].to_string();
+                    value = value.replace("\\\"", "\"");
+                }
+                if let Some(t) = template.filter(|t| t.key == key) {
+                    value = t.value;
+                }
+                let n = cfg.section_count() - 1;
+                cfg.params_of_mut(n).push(Param::new(key, value));
+            } else {
+                // line with no '=' — we'll ignore for now
+            }
+        }
+        Ok(cfg)
+    }
+}
+
+/// Serialize to the file format `save()` writes.
+impl fmt::Display for Config {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let write = |f: &mut fmt::Formatter<'_>, p: &Param| {
+            let expanded = self.expand(&p.value);
+            if expanded == p.value {
+                return writeln!(f, "{}={}", p.key, p.formatted_value());
+            }
+            writeln!(f, "{}{}={}", TEMPLATE_MARK, p.key, p.value)?;
+            writeln!(f, "{}={}", p.key, Param::new(&p.key, expanded).formatted_value())
+        };
+        for p in &self.params {
+            write(f, p)?;
+        }
+        for (i, section) in self.sections.iter().enumerate() {
+            if i > 0 || !self.params.is_empty() {
+                writeln!(f)?;
+            }
+            writeln!(f, "[{}]", section.criteria)?;
+            for p in &section.params {
+                write(f, p)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Parse the inside of a section header. Conditions are separated by
+/// spaces; values may be double-quoted to contain spaces.
+impl FromStr for Criteria {
+    type Err = Infallible;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut conditions = Vec::new();
+        let mut chars = s.trim().chars().peekable();
+        while chars.peek().is_some() {
+            let mut word = String::new();
+            let mut quoted = false;
+            while let Some(c) = chars.next() {
+                match c {
+                    '"' => quoted = !quoted,
+                    '\\' if quoted => word.extend(chars.next()),
+                    c if c.is_whitespace() && !quoted => break,
+                    c => word.push(c),
+                }
+            }
+            if word.is_empty() {
+                continue;
+            }
+            conditions.push(match word.split_once('=') {
+                Some((key, value)) => {
+                    let (key, regex) = match key.strip_suffix('~') {
+                        Some(key) => (key, true),
+                        None => (key, false),
+                    };
+                    Condition { key: key.to_string(), regex, value: Some(value.to_string()) }
+                }
+                None => Condition { key: word, regex: false, value: None },
+            });
+        }
+        Ok(Criteria { conditions })
+    }
+}
+
+/// The header as written between the brackets.
+impl fmt::Display for Criteria {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (i, c) in self.conditions.iter().enumerate() {
+            if i > 0 {
+                write!(f, " ")?;
+            }
+            write!(f, "{}", c.key)?;
+            if let Some(value) = &c.value {
+                let op = if c.regex { "~=" } else { "=" };
+                if value.is_empty() || value.contains(char::is_whitespace) || value.contains('"') {
+                    write!(f, "{}\"{}\"", op, value.replace('\\', "\\\\").replace('"', "\\\""))?;
+                } else {
+                    write!(f, "{}{}", op, value)?;
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Replace the file at `path` with `contents` so that a crash leaves either
+/// the old or the new file, never a truncated one: write a temporary file
+/// next to it, flush it to disk and rename it into place. A symlinked
+/// `path` stays a symlink; its target is what gets replaced.
+pub fn write_atomic(path: &Path, contents: &str) -> io::Result<()> {
+    let target = fs::canonicalize(path).unwrap_or_else(|_| path.to_path_buf());
+    let mut tmp = target.clone().into_os_string();
+    tmp.push(".tmp");
+    let tmp = PathBuf::from(tmp);
+    let result = (|| {
+        let mut file = File::create(&tmp)?;
+        file.write_all(contents.as_bytes())?;
+        file.sync_all()?;
+        if let Ok(meta) = fs::metadata(&target) {
+            fs::set_permissions(&tmp, meta.permissions())?;
+        }
+        fs::rename(&tmp, &target)
+    })();
+    if result.is_err() {
+        let _ = fs::remove_file(&tmp);
+    }
+    result
+}
+
+/// Whether `s` is wrapped in a matching pair of quotes. A lone quote
+/// character doesn't count.
+fn is_quoted(s: &str) -> bool {
+    s.len() >= 2 && ((s.starts_with('"') && s.ends_with('"')) || (s.starts_with('\'') && s.ends_with('\'')))
+}
+
+/// The base directory for configuration files: `$XDG_CONFIG_HOME`, or
+/// `~/.config`. Fails when `$XDG_CONFIG_HOME` is unset and there is no
+/// home directory.
+pub fn config_dir() -> io::Result<PathBuf> {
+    try_config_dir().ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, tr("config.no_home")))
+}
+
+/// `config_dir`, or `None` when `$XDG_CONFIG_HOME` is unset and there is no
+/// home directory.
+pub fn try_config_dir() -> Option<PathBuf> {
+    match env::var_os("XDG_CONFIG_HOME") {
+        Some(dir) if !dir.is_empty() => Some(PathBuf::from(dir)),
+        _ => home_dir().map(|home| home.join(".config")),
+    }
+}
@@ -0,0 +1,62 @@
+//! Messages the library reports, such as why a value doesn't validate.
+//!
+//! They are looked up by id like the editor's own strings, so a frontend
+//! can show its own text for an id and fall back to these.
+
+use std::fmt::Display;
+
+/// The English message for `id`, or the id itself if it is unknown.
+pub fn tr(id: &'static str) -> &'static str {
+    en(id).unwrap_or(id)
+}
+
+/// `tr()` with each `{}` in the message replaced by the next argument.
+pub fn trf(id: &'static str, args: &[&dyn Display]) -> String {
+    let mut out = String::new();
+    let mut args = args.iter();
+    let mut parts = tr(id).split("{}").peekable();
+    while let Some(part) = parts.next() {
+        out.push_str(part);
+        if parts.peek().is_some()
+            && let Some(arg) = args.next()
+        {
+            out.push_str(&arg.to_string());
+        }
+    }
+    out
+}
+
+/// Every id the library uses, with its message.
+pub fn en(id: &str) -> Option<&'static str> {
+    Some(match id {
+        "config.no_home" => "Could not find the home directory",
+        "validate.invalid" => "'{}' is not valid for {}: expected {}",
+        "validate.one_of" => "'{}' is not one of the allowed values for {}",
+        "validate.px" => "a size in pixels, e.g. 300 or 300px",
+        "validate.margin" => "1 to 4 comma-separated pixel sizes, e.g. 10 or 5,10",
+        "validate.corners" => "1 to 4 comma-separated radii in pixels, e.g. 8 or 8,8,0,0",
+        "validate.bool" => "1 or 0 (true or false)",
+        "validate.invalid_color" => "'{}' is not a color for {}: {}",
+        "color.no_hash" => "colors start with #, e.g. #285577",
+        "color.bad_digit" => "'{}' is not a hex digit",
+        "color.bad_length" => "{} hex digits: use #rrggbb or #rrggbbaa",
+        "color.short" => "mako doesn't read the short form; use {}",
+        "validate.duration" => "milliseconds or a duration like 5s",
+        "validate.number" => "a whole number",
+        "validate.binding" => "none, invoke-default-action, dismiss, dismiss-all, dismiss-group or exec <command>",
+        "validate.group_by" => "comma-separated fields: app-name, app-icon, summary, body, urgency, category, desktop-entry, actionable, expiring or none",
+        "check.error" => "error",
+        "check.warning" => "warning",
+        "check.missing_equals" => "expected key=value or a [criteria] header",
+        "check.missing_key" => "no key before '='",
+        "check.unknown_key" => "unknown key {}",
+        "check.duplicate" => "{} is already set on line {}; mako uses the last one",
+        "check.unclosed_header" => "section header has no closing ']'",
+        "check.empty_header" => "section header has no criteria",
+        "check.unbalanced_quote" => "section header has an unclosed quote",
+        "check.unknown_criterion" => "unknown criterion {}",
+        "check.criterion_value" => "'{}' is not a value {} takes: {}",
+        "check.criterion_needs_value" => "{} needs a value, e.g. {}=...",
+        _ => return None,
+    })
+}
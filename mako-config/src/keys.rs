@@ -0,0 +1,1354 @@
+use std::fmt;
+use std::str::FromStr;
+
+use crate::config::Param;
+use crate::i18n::{tr, trf};
+
+/// Typed representation of common `mako` configuration options.
+///
+/// This module provides a conservative, extensible Rust model of the
+/// configuration keys found in mako's man page. Fields are optional
+/// so the struct can be used to hold a partial configuration and
+/// round-trip values to/from key/value strings.
+#[derive(Debug, Clone, Default)]
+pub struct MakoConfig {
+    pub sort: Option<String>, // e.g. "-time" or "+priority" (kept as string)
+    pub layer: Option<Layer>,
+    pub background_color: Option<Color>,
+    pub width: Option<u32>,   // pixels
+    pub height: Option<u32>,  // pixels
+    pub border_size: Option<u32>,
+    pub border_color: Option<Color>,
+    pub border_radius: Option<Vec<u32>>, // 1 to 4 corners, from the top left clockwise
+    pub icons: Option<bool>,
+    pub max_icon_size: Option<u32>,
+    pub default_timeout: Option<u32>, // milliseconds
+    pub ignore_timeout: Option<bool>,
+    pub font: Option<String>,
+    pub outer_margin: Option<Vec<u32>>, // 1 to 4 sides, CSS order
+    pub padding: Option<Vec<u32>>,
+    pub markup: Option<bool>,
+    pub progress_color: Option<String>, // a color, optionally after `over ` or `source `
+    pub progress_background_color: Option<Color>,
+    pub icon_path: Option<String>,
+    pub icon_location: Option<IconLocation>,
+    pub icon_border_radius: Option<u32>,
+    pub group_by: Option<String>,
+    pub layout: Option<LayoutKind>,
+    pub text_align: Option<TextAlign>,
+    pub text_alignment: Option<TextAlign>,
+    pub text_color: Option<Color>,
+    pub margin: Option<Vec<u32>>,
+    pub anchor: Option<String>,
+    pub format: Option<String>,
+    pub output: Option<String>,
+    pub max_visible: Option<i32>, // -1 for no limit
+    pub max_history: Option<u32>,
+    pub actions: Option<bool>,
+    pub history: Option<bool>,
+    pub invisible: Option<bool>,
+    pub on_button_left: Option<String>, // a binding action, e.g. "dismiss" or "exec notify-send hi"
+    pub on_button_middle: Option<String>,
+    pub on_button_right: Option<String>,
+    pub on_touch: Option<String>,
+    pub on_notify: Option<String>,
+    pub include: Option<String>,
+}
+
+/// Where to draw the notifications (common mako values).
+#[derive(Clone)]
+pub enum Layer {
+    Overlay,
+    Bottom,
+    Top,
+    Background,
+    Normal,
+}
+
+impl FromStr for Layer {
+    type Err = ();
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_lowercase().as_str() {
+            "overlay" => Ok(Layer::Overlay),
+            "bottom" => Ok(Layer::Bottom),
+            "top" => Ok(Layer::Top),
+            "background" => Ok(Layer::Background),
+            "normal" => Ok(Layer::Normal),
+            _ => Err(()),
+        }
+    }
+}
+
+/// Simple layout hint (mako supports several named layouts).
+#[derive(Clone)]
+pub enum LayoutKind {
+    Normal,
+    Overlay,
+    Center,
+    Other(String),
+}
+
+impl FromStr for LayoutKind {
+    type Err = ();
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_lowercase().as_str() {
+            "normal" => Ok(LayoutKind::Normal),
+            "overlay" => Ok(LayoutKind::Overlay),
+            "center" => Ok(LayoutKind::Center),
+            other => Ok(LayoutKind::Other(other.to_string())),
+        }
+    }
+}
+
+/// Where the icon is placed relative to the notification box.
+#[derive(Clone)]
+pub enum IconLocation {
+    Left,
+    Right,
+    Top,
+    Bottom,
+    TopLeft,
+    TopRight,
+    BottomLeft,
+    BottomRight,
+    Center,
+    Other(String),
+}
+
+impl FromStr for IconLocation {
+    type Err = ();
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_lowercase().as_str() {
+            "left" => Ok(IconLocation::Left),
+            "right" => Ok(IconLocation::Right),
+            "top" => Ok(IconLocation::Top),
+            "bottom" => Ok(IconLocation::Bottom),
+            "top-left" | "topleft" => Ok(IconLocation::TopLeft),
+            "top-right" | "topright" => Ok(IconLocation::TopRight),
+            "bottom-right" | "bottomright" => Ok(IconLocation::BottomRight),
+            "center" => Ok(IconLocation::Center),
+            other => Ok(IconLocation::Other(other.to_string())),
+        }
+    }
+}
+#[derive(Clone)]
+pub enum TextAlign {
+    Left,
+    Center,
+    Right,
+}
+
+impl FromStr for TextAlign {
+    type Err = ();
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_lowercase().as_str() {
+            "left" => Ok(TextAlign::Left),
+            "center" => Ok(TextAlign::Center),
+            "right" => Ok(TextAlign::Right),
+            _ => Err(()),
+        }
+    }
+}
+
+/// A color as mako reads it, `#rrggbb` or `#rrggbbaa`. Parsing also takes
+/// the CSS-style `#rgb`; the color always displays as mako's lowercase
+/// form, leaving out the alpha when it is opaque.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Color {
+    pub r: u8,
+    pub g: u8,
+    pub b: u8,
+    pub a: u8,
+}
+
+impl FromStr for Color {
+    type Err = String;
+
+    /// The error says what is wrong: the missing `#`, a character that
+    /// isn't a hex digit, or the wrong number of digits.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let Some(hex) = s.trim().strip_prefix('#') else { return Err(tr("color.no_hash").to_string()) };
+        if let Some(c) = hex.chars().find(|c| !c.is_ascii_hexdigit()) {
+            return Err(trf("color.bad_digit", &[&c]));
+        }
+        let byte = |i: usize| u8::from_str_radix(&hex[i..i + 2], 16).unwrap_or(0);
+        match hex.len() {
+            3 => {
+                let digit = |i: usize| u8::from_str_radix(&hex[i..i + 1], 16).unwrap_or(0) * 17;
+                Ok(Color { r: digit(0), g: digit(1), b: digit(2), a: 255 })
+            }
+            6 => Ok(Color { r: byte(0), g: byte(2), b: byte(4), a: 255 }),
+            8 => Ok(Color { r: byte(0), g: byte(2), b: byte(4), a: byte(6) }),
+            n => Err(trf("color.bad_length", &[&n])),
+        }
+    }
+}
+
+impl fmt::Display for Color {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "#{:02x}{:02x}{:02x}", self.r, self.g, self.b)?;
+        if self.a != 255 {
+            write!(f, "{:02x}", self.a)?;
+        }
+        Ok(())
+    }
+}
+
+impl MakoConfig {
+    /// Create an empty config.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The typed form of `params`, such as one section of a loaded
+    /// `Config`. As in mako, a key given twice takes its last value.
+    pub fn from_params(params: &[Param]) -> Self {
+        let mut cfg = Self::new();
+        for p in params {
+            cfg.set_from_kv(&p.key, &p.value);
+        }
+        cfg
+    }
+
+    /// The populated fields as params, in the order `to_kv_pairs` gives.
+    /// Values come back in canonical form: `300px` reads back as `300`.
+    pub fn to_params(&self) -> Vec<Param> {
+        self.to_kv_pairs().into_iter().map(|(key, value)| Param::new(key, value)).collect()
+    }
+
+    /// Set a known key from a string key/value pair. Unknown keys are ignored.
+    ///
+    /// This is a forgiving parser: it accepts values like "100", "100px",
+    /// boolean strings like "1", "0", "true", "false", and preserves
+    /// color/font strings as-is.
+    pub fn set_from_kv(&mut self, key: &str, value: &str) {
+        match key.trim() {
+            "sort" => self.sort = Some(value.trim().to_string()),
+            "layer" => self.layer = Layer::from_str(value).ok(),
+            "background-color" => self.background_color = value.parse().ok(),
+            "width" => self.width = parse_px(value),
+            "height" => self.height = parse_px(value),
+            "border-size" => self.border_size = parse_px(value),
+            "border-color" => self.border_color = value.parse().ok(),
+            "border-radius" => self.border_radius = parse_sides(value),
+            "icons" => self.icons = parse_bool(value),
+            "max-icon-size" => self.max_icon_size = parse_px(value),
+            "default-timeout" => self.default_timeout = parse_u32(value),
+            "ignore-timeout" => self.ignore_timeout = parse_bool(value),
+            "font" => self.font = Some(value.trim().to_string()),
+            "outer-margin" => self.outer_margin = parse_sides(value),
+            "padding" => self.padding = parse_sides(value),
+            "markup" => self.markup = parse_bool(value),
+            "progress-color" => self.progress_color = Some(value.trim().to_string()),
+            "progress-background-color" => self.progress_background_color = value.parse().ok(),
+            "icon-path" => self.icon_path = Some(value.trim().to_string()),
+            "icon-location" => self.icon_location = IconLocation::from_str(value).ok(),
+            "icon-border-radius" => self.icon_border_radius = parse_px(value),
+            "group-by" => self.group_by = Some(value.trim().to_string()),
+            "layout" => self.layout = LayoutKind::from_str(value).ok(),
+            "text-align" => self.text_align = TextAlign::from_str(value).ok(),
+            "text-alignment" => self.text_alignment = TextAlign::from_str(value).ok(),
+            "text-color" => self.text_color = value.parse().ok(),
+            "margin" => self.margin = parse_sides(value),
+            "anchor" => self.anchor = Some(value.trim().to_string()),
+            "format" => self.format = Some(value.to_string()),
+            "output" => self.output = Some(value.trim().to_string()),
+            "max-visible" => self.max_visible = value.trim().parse().ok(),
+            "max-history" => self.max_history = parse_u32(value),
+            "actions" => self.actions = parse_bool(value),
+            "history" => self.history = parse_bool(value),
+            "invisible" => self.invisible = parse_bool(value),
+            "on-button-left" => self.on_button_left = Some(value.trim().to_string()),
+            "on-button-middle" => self.on_button_middle = Some(value.trim().to_string()),
+            "on-button-right" => self.on_button_right = Some(value.trim().to_string()),
+            "on-touch" => self.on_touch = Some(value.trim().to_string()),
+            "on-notify" => self.on_notify = Some(value.trim().to_string()),
+            "include" => self.include = Some(value.trim().to_string()),
+            _ => {
+                // unknown key — keep it ignored for now
+            }
+        }
+    }
+
+    /// Convert the typed config back into key/value string pairs. Only
+    /// populated fields are emitted.
+    pub fn to_kv_pairs(&self) -> Vec<(String, String)> {
+        let mut out = Vec::new();
+        if let Some(v) = &self.sort { out.push(("sort".to_string(), v.clone())); }
+        if let Some(v) = &self.layer { out.push(("layer".to_string(), format!("{:?}", v).to_lowercase())); }
+        if let Some(v) = &self.background_color { out.push(("background-color".to_string(), v.to_string())); }
+        if let Some(v) = &self.width { out.push(("width".to_string(), format!("{}", v))); }
+        if let Some(v) = &self.height { out.push(("height".to_string(), format!("{}", v))); }
+        if let Some(v) = &self.border_size { out.push(("border-size".to_string(), format!("{}", v))); }
+        if let Some(v) = &self.border_color { out.push(("border-color".to_string(), v.to_string())); }
+        if let Some(v) = &self.border_radius { out.push(("border-radius".to_string(), sides_to_str(v))); }
+        if let Some(v) = &self.icons { out.push(("icons".to_string(), bool_to_str(*v))); }
+        if let Some(v) = &self.max_icon_size { out.push(("max-icon-size".to_string(), format!("{}", v))); }
+        if let Some(v) = &self.default_timeout { out.push(("default-timeout".to_string(), format!("{}", v))); }
+        if let Some(v) = &self.ignore_timeout { out.push(("ignore-timeout".to_string(), bool_to_str(*v))); }
+        if let Some(v) = &self.font { out.push(("font".to_string(), v.clone())); }
+        if let Some(v) = &self.outer_margin { out.push(("outer-margin".to_string(), sides_to_str(v))); }
+        if let Some(v) = &self.padding { out.push(("padding".to_string(), sides_to_str(v))); }
+        if let Some(v) = &self.markup { out.push(("markup".to_string(), bool_to_str(*v))); }
+        if let Some(v) = &self.progress_color { out.push(("progress-color".to_string(), v.clone())); }
+        if let Some(v) = &self.progress_background_color { out.push(("progress-background-color".to_string(), v.to_string())); }
+        if let Some(v) = &self.icon_path { out.push(("icon-path".to_string(), v.clone())); }
+        if let Some(v) = &self.icon_location { out.push(("icon-location".to_string(), format!("{:?}", v).to_lowercase())); }
+        if let Some(v) = &self.icon_border_radius { out.push(("icon-border-radius".to_string(), format!("{}", v))); }
+        if let Some(v) = &self.group_by { out.push(("group-by".to_string(), v.clone())); }
+        if let Some(v) = &self.layout { out.push(("layout".to_string(), format!("{:?}", v).to_lowercase())); }
+        if let Some(v) = &self.text_align { out.push(("text-align".to_string(), format!("{:?}", v).to_lowercase())); }
+        if let Some(v) = &self.text_alignment { out.push(("text-alignment".to_string(), format!("{:?}", v).to_lowercase())); }
+        if let Some(v) = &self.text_color { out.push(("text-color".to_string(), v.to_string())); }
+        if let Some(v) = &self.margin { out.push(("margin".to_string(), sides_to_str(v))); }
+        if let Some(v) = &self.anchor { out.push(("anchor".to_string(), v.clone())); }
+        if let Some(v) = &self.format { out.push(("format".to_string(), v.clone())); }
+        if let Some(v) = &self.output { out.push(("output".to_string(), v.clone())); }
+        if let Some(v) = &self.max_visible { out.push(("max-visible".to_string(), format!("{}", v))); }
+        if let Some(v) = &self.max_history { out.push(("max-history".to_string(), format!("{}", v))); }
+        if let Some(v) = &self.actions { out.push(("actions".to_string(), bool_to_str(*v))); }
+        if let Some(v) = &self.history { out.push(("history".to_string(), bool_to_str(*v))); }
+        if let Some(v) = &self.invisible { out.push(("invisible".to_string(), bool_to_str(*v))); }
+        if let Some(v) = &self.on_button_left { out.push(("on-button-left".to_string(), v.clone())); }
+        if let Some(v) = &self.on_button_middle { out.push(("on-button-middle".to_string(), v.clone())); }
+        if let Some(v) = &self.on_button_right { out.push(("on-button-right".to_string(), v.clone())); }
+        if let Some(v) = &self.on_touch { out.push(("on-touch".to_string(), v.clone())); }
+        if let Some(v) = &self.on_notify { out.push(("on-notify".to_string(), v.clone())); }
+        if let Some(v) = &self.include { out.push(("include".to_string(), v.clone())); }
+        out
+    }
+}
+
+/// Return a list of known mako configuration keys with a short description.
+pub fn known_keys() -> Vec<(&'static str, &'static str)> {
+    vec![
+        ("sort", "Sort order: -time, +time, -priority or +priority"),
+        ("layer", "Window layer: overlay, top, bottom, background"),
+        ("background-color", "Background color (#rrggbb or named)") ,
+        ("width", "Notification width in pixels"),
+        ("height", "Notification height in pixels"),
+        ("border-size", "Border width in pixels"),
+        ("border-color", "Border color (#rrggbb)") ,
+        ("border-radius", "Corner radius in pixels (1 to 4 values, top-left first)"),
+        ("icons", "Show icons: 1 or 0"),
+        ("max-icon-size", "Maximum icon size in pixels"),
+        ("default-timeout", "Default timeout in milliseconds"),
+        ("ignore-timeout", "Ignore per-notification timeout: 1 or 0"),
+        ("font", "Font description, e.g. 'monospace 10'"),
+        ("format", "Notification text: %-specifiers (%a, %s, %b, ...) and Pango markup"),
+        ("outer-margin", "Outer margin in pixels"),
+        ("padding", "Padding in pixels"),
+        ("markup", "Enable markup rendering: 1 or 0"),
+        ("progress-color", "Progress bar color"),
+        ("progress-background-color", "Progress background color"),
+        ("icon-path", "Search paths for icons (colon separated)") ,
+        ("icon-location", "Icon position: left, right, top, bottom, top-left, ..."),
+    ("anchor", "Anchor position: top-right, top-center, top-left, bottom-right, bottom-center, bottom-left, center-right, center-left, center"),
+    ("anchor-point", "Alias for anchor; same values as anchor"),
+        ("icon-border-radius", "Icon corner radius in pixels"),
+        ("group-by", "Group by these fields, comma separated: app-name, app-icon, summary, body, urgency, category, desktop-entry, actionable, expiring, none"),
+        ("layout", "Layout hint: normal, overlay, center (no longer read by mako)"),
+        ("text-align", "Old name for text-alignment: left, center, right"),
+        ("text-alignment", "Text alignment: left, center, right"),
+        ("text-color", "Text color (#rrggbb[aa])"),
+        ("margin", "Margin around each notification in pixels (1 to 4 values, like CSS)"),
+        ("max-visible", "Most notifications shown at once; -1 for no limit"),
+        ("max-history", "How many dismissed notifications to keep for restore"),
+        ("output", "Output (monitor) to show notifications on, e.g. DP-1"),
+        ("actions", "Let notifications carry actions: 1 or 0"),
+        ("history", "Keep dismissed notifications for restore: 1 or 0"),
+        ("invisible", "Hide matching notifications while keeping them: 1 or 0"),
+        ("on-button-left", "Left click: none, invoke-default-action, dismiss, dismiss-all, dismiss-group or exec <command>"),
+        ("on-button-middle", "Middle click: none, invoke-default-action, dismiss, dismiss-all, dismiss-group or exec <command>"),
+        ("on-button-right", "Right click: none, invoke-default-action, dismiss, dismiss-all, dismiss-group or exec <command>"),
+        ("on-touch", "Touch: none, invoke-default-action, dismiss, dismiss-all, dismiss-group or exec <command>"),
+        ("on-notify", "When a notification arrives: none, ... or exec <command>, e.g. to play a sound"),
+        ("include", "Read another config file here (path)"),
+        // Last, after every real key.
+        ("<custom>", "Create a custom key name (type after selecting this)"),
+    ]
+}
+
+/// The reference entry for one key, as in mako(5).
+pub struct KeyDoc {
+    pub key: &'static str,
+    /// What the value is, e.g. "color" or "pixels".
+    pub kind: &'static str,
+    pub description: &'static str,
+    pub examples: &'static [&'static str],
+}
+
+/// Reference entries for the keys in `known_keys`, shown in the docs pane.
+pub const KEY_DOCS: &[KeyDoc] = &[
+    KeyDoc {
+        key: "sort",
+        kind: "one of -time, +time, -priority, +priority",
+        description: "The order notifications are stacked in. A leading - sorts descending (newest or most urgent first), + ascending.",
+        examples: &["-time", "+priority"],
+    },
+    KeyDoc {
+        key: "layer",
+        kind: "one of overlay, top, bottom, background",
+        description: "The layer-shell layer notifications are drawn on. overlay shows them above fullscreen windows; top keeps them below.",
+        examples: &["overlay"],
+    },
+    KeyDoc {
+        key: "background-color",
+        kind: "color",
+        description: "The background of the notification, as #RRGGBB or #RRGGBBAA.",
+        examples: &["#285577FF", "#1e1e2ecc"],
+    },
+    KeyDoc {
+        key: "text-color",
+        kind: "color",
+        description: "The color of the notification text, as #RRGGBB or #RRGGBBAA.",
+        examples: &["#FFFFFFFF", "#cdd6f4"],
+    },
+    KeyDoc {
+        key: "width",
+        kind: "pixels",
+        description: "The width of each notification.",
+        examples: &["300", "400"],
+    },
+    KeyDoc {
+        key: "height",
+        kind: "pixels",
+        description: "The maximum height of each notification; longer text is cut off.",
+        examples: &["100", "150"],
+    },
+    KeyDoc {
+        key: "outer-margin",
+        kind: "directional pixels (1 to 4 values)",
+        description: "The margin between the whole stack of notifications and the edges of the output, like CSS: top, right, bottom and left.",
+        examples: &["0", "10,20"],
+    },
+    KeyDoc {
+        key: "margin",
+        kind: "directional pixels (1 to 4 values)",
+        description: "The margin around each notification, like CSS: one value for every side, or top, right, bottom and left.",
+        examples: &["10", "5,10", "5,10,5,10"],
+    },
+    KeyDoc {
+        key: "padding",
+        kind: "directional pixels (1 to 4 values)",
+        description: "The space between the border and the content of each notification, like CSS.",
+        examples: &["5", "8,12"],
+    },
+    KeyDoc {
+        key: "border-size",
+        kind: "pixels",
+        description: "The width of the border around each notification.",
+        examples: &["2", "0"],
+    },
+    KeyDoc {
+        key: "border-color",
+        kind: "color",
+        description: "The color of the border, as #RRGGBB or #RRGGBBAA.",
+        examples: &["#4C7899FF", "#bf616a"],
+    },
+    KeyDoc {
+        key: "border-radius",
+        kind: "pixels per corner (1 to 4 values)",
+        description: "The corner radius of each notification: one value for every corner, or top-left, top-right, bottom-right and bottom-left, filled in like CSS.",
+        examples: &["0", "8", "8,8,0,0"],
+    },
+    KeyDoc {
+        key: "progress-color",
+        kind: "[over|source] color",
+        description: "The color of the progress bar drawn for notifications with a value hint. over draws it on top of the background, source replaces it.",
+        examples: &["over #5588AAFF", "source #44475a"],
+    },
+    KeyDoc {
+        key: "progress-background-color",
+        kind: "color",
+        description: "The color of the part of the progress bar that isn't filled yet.",
+        examples: &["#00000033"],
+    },
+    KeyDoc {
+        key: "icons",
+        kind: "boolean",
+        description: "Whether to show icons in notifications.",
+        examples: &["1", "0"],
+    },
+    KeyDoc {
+        key: "max-icon-size",
+        kind: "pixels",
+        description: "Icons larger than this are scaled down to fit.",
+        examples: &["64", "48"],
+    },
+    KeyDoc {
+        key: "icon-path",
+        kind: "path list (colon separated)",
+        description: "Directories to look up icons in, searched in order; each may hold an icon theme.",
+        examples: &["/usr/share/icons/Papirus-Dark", "~/.icons:/usr/share/icons/hicolor"],
+    },
+    KeyDoc {
+        key: "icon-location",
+        kind: "one of left, right, top, bottom",
+        description: "Where the icon goes relative to the text.",
+        examples: &["left", "top"],
+    },
+    KeyDoc {
+        key: "icon-border-radius",
+        kind: "pixels",
+        description: "The corner radius of icons.",
+        examples: &["0", "4"],
+    },
+    KeyDoc {
+        key: "font",
+        kind: "Pango font description",
+        description: "The font for the notification text: a family, optional styles and a size.",
+        examples: &["monospace 10", "Inter Bold 11"],
+    },
+    KeyDoc {
+        key: "markup",
+        kind: "boolean",
+        description: "Whether to render Pango markup in format and in notification bodies.",
+        examples: &["1", "0"],
+    },
+    KeyDoc {
+        key: "format",
+        kind: "format string",
+        description: "The text of each notification. %a is the app name, %s the summary, %b the body, %g the group count and %i the id; %h and %t count hidden and total notifications in [hidden]. \\n is a line break and Pango markup applies.",
+        examples: &["<b>%s</b>\\n%b", "<i>%a</i>: %s"],
+    },
+    KeyDoc {
+        key: "actions",
+        kind: "boolean",
+        description: "Whether notifications may carry actions, to be invoked by a binding or makoctl.",
+        examples: &["1", "0"],
+    },
+    KeyDoc {
+        key: "history",
+        kind: "boolean",
+        description: "Whether dismissed notifications are kept, so makoctl restore can bring them back.",
+        examples: &["1", "0"],
+    },
+    KeyDoc {
+        key: "default-timeout",
+        kind: "milliseconds",
+        description: "How long notifications stay when they don't ask for a timeout. 0 keeps them until dismissed.",
+        examples: &["5000", "0"],
+    },
+    KeyDoc {
+        key: "ignore-timeout",
+        kind: "boolean",
+        description: "Use default-timeout even for notifications that ask for their own timeout.",
+        examples: &["1", "0"],
+    },
+    KeyDoc {
+        key: "group-by",
+        kind: "comma separated fields",
+        description: "Notifications equal in every listed field are shown as one group; %g in format counts them. none turns grouping off.",
+        examples: &["app-name", "app-name,summary"],
+    },
+    KeyDoc {
+        key: "max-visible",
+        kind: "number",
+        description: "The most notifications shown at once; the rest are summed up by [hidden]. -1 for no limit.",
+        examples: &["5", "-1"],
+    },
+    KeyDoc {
+        key: "max-history",
+        kind: "number",
+        description: "How many dismissed notifications are kept for makoctl restore.",
+        examples: &["5", "20"],
+    },
+    KeyDoc {
+        key: "output",
+        kind: "output name",
+        description: "The output to show notifications on. Empty lets the compositor pick, usually the focused one.",
+        examples: &["DP-1", "eDP-1"],
+    },
+    KeyDoc {
+        key: "anchor",
+        kind: "position",
+        description: "Where on the output the stack of notifications is placed.",
+        examples: &["top-right", "bottom-center"],
+    },
+    KeyDoc {
+        key: "anchor-point",
+        kind: "position",
+        description: "Another name for anchor.",
+        examples: &["top-right"],
+    },
+    KeyDoc {
+        key: "text-alignment",
+        kind: "one of left, center, right",
+        description: "How the text is aligned inside each notification.",
+        examples: &["left", "center"],
+    },
+    KeyDoc {
+        key: "text-align",
+        kind: "one of left, center, right",
+        description: "Another name for text-alignment.",
+        examples: &["center"],
+    },
+    KeyDoc {
+        key: "invisible",
+        kind: "boolean",
+        description: "Hide matching notifications while still counting them, e.g. in a do-not-disturb [mode=...] section.",
+        examples: &["1"],
+    },
+    KeyDoc {
+        key: "on-button-left",
+        kind: "binding",
+        description: "What a left click does: none, invoke-default-action, dismiss, dismiss-all, dismiss-group, or exec followed by a shell command.",
+        examples: &["invoke-default-action", "exec makoctl menu wofi -d"],
+    },
+    KeyDoc {
+        key: "on-button-middle",
+        kind: "binding",
+        description: "What a middle click does; takes the same actions as on-button-left.",
+        examples: &["dismiss-all"],
+    },
+    KeyDoc {
+        key: "on-button-right",
+        kind: "binding",
+        description: "What a right click does; takes the same actions as on-button-left.",
+        examples: &["dismiss"],
+    },
+    KeyDoc {
+        key: "on-touch",
+        kind: "binding",
+        description: "What a tap does; takes the same actions as on-button-left.",
+        examples: &["dismiss"],
+    },
+    KeyDoc {
+        key: "on-notify",
+        kind: "binding",
+        description: "Run when a notification is shown; usually exec, e.g. to play a sound.",
+        examples: &["exec mpv /usr/share/sounds/freedesktop/stereo/message.oga"],
+    },
+    KeyDoc {
+        key: "layout",
+        kind: "text",
+        description: "A layout hint some builds accept; not part of mako(5).",
+        examples: &["normal"],
+    },
+    KeyDoc {
+        key: "include",
+        kind: "path",
+        description: "Read another config file at this point, as if its lines were here.",
+        examples: &["~/.config/mako/colors"],
+    },
+];
+
+/// The reference entry for `key`, if it is a known key.
+pub fn key_doc(key: &str) -> Option<&'static KeyDoc> {
+    KEY_DOCS.iter().find(|d| d.key == key)
+}
+
+/// Keys older configs use: old names, with the key mako reads now, and
+/// keys it no longer reads at all (`None`).
+pub const DEPRECATED_KEYS: &[(&str, Option<&str>)] = &[
+    ("text-align", Some("text-alignment")),
+    ("layout", None),
+];
+
+/// What deprecated `key` became: `Some(Some(new))` for a renamed key,
+/// `Some(None)` for a dropped one, `None` if it isn't deprecated.
+pub fn deprecated(key: &str) -> Option<Option<&'static str>> {
+    DEPRECATED_KEYS.iter().find(|(k, _)| *k == key).map(|(_, new)| *new)
+}
+
+/// The mako release that first read each key, as `(major, minor)`, for
+/// keys added since 1.4. Older keys work with any mako still shipped.
+pub const KEYS_SINCE: &[(&str, (u32, u32))] = &[
+    ("layer", (1, 4)),
+    ("max-history", (1, 4)),
+    ("history", (1, 4)),
+    ("outer-margin", (1, 5)),
+    ("icon-location", (1, 5)),
+    ("on-touch", (1, 5)),
+    ("on-notify", (1, 6)),
+    ("invisible", (1, 6)),
+    ("include", (1, 7)),
+    ("icon-border-radius", (1, 8)),
+];
+
+/// The mako release that first read `key`, if it is newer than 1.4.
+pub fn since(key: &str) -> Option<(u32, u32)> {
+    KEYS_SINCE.iter().find(|(k, _)| *k == key).map(|(_, v)| *v)
+}
+
+/// Whether a mako of `version` reads `key`.
+pub fn supported(key: &str, version: (u32, u32)) -> bool {
+    since(key).is_none_or(|since| version >= since)
+}
+
+/// Keys mako only reads before the first criteria section: mako(5)'s
+/// global configuration options.
+pub const GLOBAL_ONLY_KEYS: &[&str] = &["sort", "max-history"];
+
+/// Keys only worth setting for some notifications: `invisible` in the
+/// global params hides every one of them.
+pub const SECTION_ONLY_KEYS: &[&str] = &["invisible"];
+
+/// Whether `key` is worth offering in a criteria section (`in_section`)
+/// or in the global params.
+pub fn fits_scope(key: &str, in_section: bool) -> bool {
+    if in_section { !GLOBAL_ONLY_KEYS.contains(&key) } else { !SECTION_ONLY_KEYS.contains(&key) }
+}
+
+/// What a key is about, for ordering and grouping the params list.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Category {
+    Appearance,
+    Behavior,
+    Icons,
+    Bindings,
+    /// Keys mako(5) doesn't list.
+    Custom,
+}
+
+impl Category {
+    pub const ALL: &[Category] = &[Category::Appearance, Category::Behavior, Category::Icons, Category::Bindings, Category::Custom];
+
+    /// The i18n id of the category's name.
+    pub fn label_id(self) -> &'static str {
+        match self {
+            Category::Appearance => "category.appearance",
+            Category::Behavior => "category.behavior",
+            Category::Icons => "category.icons",
+            Category::Bindings => "category.bindings",
+            Category::Custom => "category.custom",
+        }
+    }
+}
+
+/// The category `key` belongs in.
+pub fn category(key: &str) -> Category {
+    match key {
+        "icons" | "max-icon-size" | "icon-path" | "icon-location" | "icon-border-radius" => Category::Icons,
+        _ if key.starts_with("on-") && known_keys().iter().any(|(k, _)| *k == key) => Category::Bindings,
+        "sort" | "layer" | "anchor" | "anchor-point" | "output" | "default-timeout" | "ignore-timeout" | "group-by"
+        | "max-visible" | "max-history" | "actions" | "history" | "invisible" | "include" => Category::Behavior,
+        _ if known_keys().iter().any(|(k, _)| *k == key) && key != "<custom>" => Category::Appearance,
+        _ => Category::Custom,
+    }
+}
+
+/// Keys whose value is a colon-separated list of directories.
+pub fn is_path_list_key(key: &str) -> bool {
+    key == "icon-path"
+}
+
+/// What separates the entries of a list-valued key, for keys that are
+/// lists: the directories of `icon-path`, the fields of `group-by`.
+pub fn list_separator(key: &str) -> Option<char> {
+    match key {
+        "icon-path" => Some(':'),
+        "group-by" => Some(','),
+        _ => None,
+    }
+}
+
+/// Keys whose value is a duration in milliseconds.
+pub fn is_duration_key(key: &str) -> bool {
+    key == "default-timeout"
+}
+
+/// What to store for `value` typed for `key`: durations written with a
+/// unit become milliseconds and colors mako's lowercase `#rrggbb[aa]`.
+/// Anything that doesn't parse is kept as typed.
+pub fn normalize_value(key: &str, value: &str) -> String {
+    if is_duration_key(key)
+        && let Some(ms) = parse_duration_ms(value)
+    {
+        return ms.to_string();
+    }
+    if is_color_key(key)
+        && let Ok(color) = value.parse::<Color>()
+    {
+        return color.to_string();
+    }
+    if key == "progress-color" {
+        let v = value.trim();
+        let (op, color) = match v.split_once(' ') {
+            Some((op @ ("over" | "source"), color)) => (Some(op), color),
+            _ => (None, v),
+        };
+        if let Ok(color) = color.parse::<Color>() {
+            return op.map_or_else(|| color.to_string(), |op| format!("{} {}", op, color));
+        }
+    }
+    value.to_string()
+}
+
+/// Parse a duration as plain milliseconds or with units: `500ms`, `5s`,
+/// `1.5s`, `2m`, `1h`, or combined like `1m30s`.
+pub fn parse_duration_ms(s: &str) -> Option<u64> {
+    let s: String = s.chars().filter(|c| !c.is_whitespace()).collect::<String>().to_lowercase();
+    if let Ok(ms) = s.parse::<u64>() {
+        return Some(ms);
+    }
+    let mut total = 0.0;
+    let mut rest = s.as_str();
+    while !rest.is_empty() {
+        let num_len = rest.find(|c: char| !c.is_ascii_digit() && c != '.').unwrap_or(rest.len());
+        let n: f64 = rest[..num_len].parse().ok()?;
+        rest = &rest[num_len..];
+        let unit_len = rest.find(|c: char| c.is_ascii_digit() || c == '.').unwrap_or(rest.len());
+        let factor = match &rest[..unit_len] {
+            "ms" => 1.0,
+            "s" | "sec" => 1000.0,
+            "m" | "min" => 60_000.0,
+            "h" => 3_600_000.0,
+            _ => return None,
+        };
+        rest = &rest[unit_len..];
+        total += n * factor;
+    }
+    (total >= 0.0 && !s.is_empty()).then_some(total.round() as u64)
+}
+
+/// `90000` -> `1m 30s`, `1500` -> `1.5s`, `250` -> `250ms`.
+pub fn format_duration_ms(ms: u64) -> String {
+    let (h, rest) = (ms / 3_600_000, ms % 3_600_000);
+    let (m, rest) = (rest / 60_000, rest % 60_000);
+    let (s, millis) = (rest / 1000, rest % 1000);
+    let mut parts = Vec::new();
+    if h > 0 {
+        parts.push(format!("{}h", h));
+    }
+    if m > 0 {
+        parts.push(format!("{}m", m));
+    }
+    match (s, millis) {
+        (0, 0) => {}
+        (s, 0) => parts.push(format!("{}s", s)),
+        (0, millis) if parts.is_empty() => parts.push(format!("{}ms", millis)),
+        (s, millis) => parts.push(format!("{}.{}s", s, format!("{:03}", millis).trim_end_matches('0'))),
+    }
+    if parts.is_empty() {
+        parts.push("0ms".to_string());
+    }
+    parts.join(" ")
+}
+
+/// For a given key, return a small set of allowed values when applicable.
+pub fn allowed_values(key: &str) -> Option<Vec<&'static str>> {
+    match key {
+        "layer" => Some(vec!["overlay", "top", "bottom", "background"]),
+        "sort" => Some(vec!["-time", "+time", "-priority", "+priority"]),
+        "icons" | "ignore-timeout" | "markup" | "actions" | "history" | "invisible" => Some(vec!["1", "0", "true", "false"]),
+        "icon-location" => Some(vec!["left", "right", "top", "bottom", "top-left", "top-right", "bottom-left", "bottom-right", "center"]),
+        "text-align" | "text-alignment" => Some(vec!["left", "center", "right"]),
+        "layout" => Some(vec!["normal", "overlay", "center"]),
+        "anchor" | "anchor-point" => Some(vec![
+            "top-right", "top-center", "top-left", "bottom-right", "bottom-center", "bottom-left", "center-right", "center-left", "center",
+        ]),
+        _ => None,
+    }
+}
+
+/// The `anchor` positions as the screen they stand for: rows top to
+/// bottom, each left to right.
+pub const ANCHOR_GRID: [&str; 9] =
+    ["top-left", "top-center", "top-right", "center-left", "center", "center-right", "bottom-left", "bottom-center", "bottom-right"];
+
+/// Check `value` (already normalized) the way mako would parse it for
+/// `key`, so a typo can't produce a config mako refuses to load. Keys
+/// without a known type accept anything.
+pub fn validate(key: &str, value: &str) -> Result<(), String> {
+    let ok = match key {
+        "width" | "height" | "border-size" | "max-icon-size" | "icon-border-radius" => parse_px(value).is_some(),
+        "outer-margin" | "margin" | "padding" | "border-radius" => parse_sides(value).is_some(),
+        "default-timeout" | "max-history" => parse_u32(value).is_some(),
+        "max-visible" => value.trim().parse::<i32>().is_ok(),
+        "icons" | "ignore-timeout" | "markup" | "actions" | "history" | "invisible" => parse_bool(value).is_some(),
+        "on-button-left" | "on-button-middle" | "on-button-right" | "on-touch" | "on-notify" => {
+            let v = value.trim();
+            BINDING_ACTIONS.contains(&v) || v.strip_prefix("exec ").is_some_and(|cmd| !cmd.trim().is_empty())
+        }
+        "group-by" => value.split(',').all(|field| GROUP_BY_FIELDS.contains(&field.trim())),
+        _ if is_color_key(key) => is_color(value),
+        "progress-color" => {
+            let v = value.trim();
+            is_color(v.strip_prefix("over ").or_else(|| v.strip_prefix("source ")).unwrap_or(v))
+        }
+        _ => match allowed_values(key) {
+            Some(vals) => vals.contains(&value.trim()),
+            None => true,
+        },
+    };
+    if ok {
+        return Ok(());
+    }
+    let expected = match key {
+        "outer-margin" | "margin" | "padding" => tr("validate.margin"),
+        "border-radius" => tr("validate.corners"),
+        "icons" | "ignore-timeout" | "markup" | "actions" | "history" | "invisible" => tr("validate.bool"),
+        "on-button-left" | "on-button-middle" | "on-button-right" | "on-touch" | "on-notify" => tr("validate.binding"),
+        "group-by" => tr("validate.group_by"),
+        _ if is_color_key(key) => return Err(trf("validate.invalid_color", &[&value, &key, &color_problem(value)])),
+        "progress-color" => {
+            let v = value.trim();
+            let color = v.strip_prefix("over ").or_else(|| v.strip_prefix("source ")).unwrap_or(v);
+            return Err(trf("validate.invalid_color", &[&value, &key, &color_problem(color)]));
+        }
+        "default-timeout" => tr("validate.duration"),
+        "max-visible" | "max-history" => tr("validate.number"),
+        _ if allowed_values(key).is_some() => return Err(trf("validate.one_of", &[&value, &key])),
+        _ => tr("validate.px"),
+    };
+    Err(trf("validate.invalid", &[&value, &key, &expected]))
+}
+
+/// What a section criterion takes: any text (which can also be matched
+/// as a regular expression), one of a few words, or nothing at all.
+pub enum CriterionKind {
+    Text,
+    Choice(&'static [&'static str]),
+    Flag,
+}
+
+/// The fields a section header can test, in the order the criteria
+/// builder lists them.
+pub const CRITERIA: &[(&str, CriterionKind)] = &[
+    ("app-name", CriterionKind::Text),
+    ("app-icon", CriterionKind::Text),
+    ("summary", CriterionKind::Text),
+    ("body", CriterionKind::Text),
+    ("urgency", CriterionKind::Choice(&["low", "normal", "critical"])),
+    ("category", CriterionKind::Text),
+    ("desktop-entry", CriterionKind::Text),
+    ("actionable", CriterionKind::Choice(&["true", "false"])),
+    ("expiring", CriterionKind::Choice(&["true", "false"])),
+    ("mode", CriterionKind::Text),
+    ("grouped", CriterionKind::Choice(&["true", "false"])),
+    ("group-index", CriterionKind::Text),
+    ("hidden", CriterionKind::Flag),
+    ("output", CriterionKind::Text),
+    (
+        "anchor",
+        CriterionKind::Choice(&[
+            "top-right", "top-center", "top-left", "bottom-right", "bottom-center", "bottom-left", "center-right", "center-left", "center",
+        ]),
+    ),
+];
+
+/// What a click, touch or `on-notify` can do, besides `exec <command>`.
+pub const BINDING_ACTIONS: &[&str] = &["none", "invoke-default-action", "dismiss", "dismiss-all", "dismiss-group"];
+
+/// The fields `group-by` can combine.
+pub const GROUP_BY_FIELDS: &[&str] =
+    &["app-name", "app-icon", "summary", "body", "urgency", "category", "desktop-entry", "actionable", "expiring", "none"];
+
+/// How much one `+`/`-` press changes a numeric key, if it is one.
+pub fn step(key: &str) -> Option<i64> {
+    Some(match key {
+        "width" | "height" => 10,
+        "max-icon-size" => 8,
+        "default-timeout" => 1000,
+        "border-size" | "border-radius" | "icon-border-radius" | "outer-margin" | "margin" | "padding"
+        | "max-visible" | "max-history" => 1,
+        _ => return None,
+    })
+}
+
+/// `value` moved by `steps` times the key's step, never below zero (or
+/// -1, meaning unlimited, for `max-visible`). A `px` suffix is kept and
+/// every part of a comma-separated margin moves together.
+pub fn step_value(key: &str, value: &str, steps: i64) -> Option<String> {
+    let by = step(key)? * steps;
+    let min = if key == "max-visible" { -1 } else { 0 };
+    let parts = value
+        .split(',')
+        .map(|part| {
+            let part = part.trim();
+            let (num, suffix) = part.strip_suffix("px").map_or((part, ""), |n| (n, "px"));
+            let n: i64 = num.trim().parse().ok()?;
+            Some(format!("{}{}", (n + by).max(min), suffix))
+        })
+        .collect::<Option<Vec<_>>>()?;
+    Some(parts.join(","))
+}
+
+/// mako's color syntax: `#rrggbb` or `#rrggbbaa`.
+fn is_color(s: &str) -> bool {
+    s.trim().strip_prefix('#').is_some_and(|hex| matches!(hex.len(), 6 | 8)) && s.parse::<Color>().is_ok()
+}
+
+/// Keys whose value is a single color.
+fn is_color_key(key: &str) -> bool {
+    matches!(key, "background-color" | "border-color" | "text-color" | "progress-background-color")
+}
+
+/// Keys whose value is or holds a color: the single-color ones and
+/// `progress-color`.
+pub fn takes_color(key: &str) -> bool {
+    is_color_key(key) || key == "progress-color"
+}
+
+/// Why `value` isn't a color mako reads.
+fn color_problem(value: &str) -> String {
+    match value.parse::<Color>() {
+        Ok(color) => trf("color.short", &[&color]),
+        Err(e) => e,
+    }
+}
+
+/// mako's built-in defaults, as documented in mako(5), for the keys that
+/// have one.
+pub const DEFAULTS: &[(&str, &str)] = &[
+    ("sort", "-time"),
+    ("layer", "top"),
+    ("anchor", "top-right"),
+    ("font", "monospace 10"),
+    ("background-color", "#285577FF"),
+    ("text-color", "#FFFFFFFF"),
+    ("width", "300"),
+    ("height", "100"),
+    ("outer-margin", "0"),
+    ("margin", "10"),
+    ("padding", "5"),
+    ("border-size", "2"),
+    ("border-color", "#4C7899FF"),
+    ("border-radius", "0"),
+    ("progress-color", "over #5588AAFF"),
+    ("icons", "1"),
+    ("max-icon-size", "64"),
+    ("icon-location", "left"),
+    ("icon-border-radius", "0"),
+    ("markup", "1"),
+    ("actions", "1"),
+    ("history", "1"),
+    ("format", "<b>%s</b>\\n%b"),
+    ("default-timeout", "0"),
+    ("ignore-timeout", "0"),
+    ("max-visible", "5"),
+    ("max-history", "5"),
+    ("text-alignment", "left"),
+    ("on-button-left", "invoke-default-action"),
+    ("on-button-middle", "none"),
+    ("on-button-right", "dismiss"),
+    ("on-touch", "dismiss"),
+];
+
+/// mako's default for `key`, if it has one. `anchor-point` shares
+/// `anchor`'s and `text-align` `text-alignment`'s.
+pub fn default_value(key: &str) -> Option<&'static str> {
+    let key = match key {
+        "anchor-point" => "anchor",
+        "text-align" => "text-alignment",
+        _ => key,
+    };
+    DEFAULTS.iter().find(|(k, _)| *k == key).map(|(_, v)| *v)
+}
+
+/// A config file listing every default, commented out, as a clean
+/// starting point.
+pub fn default_config_text() -> String {
+    let mut out = String::from("# mako configuration. Every line below is mako's default;\n# uncomment and change what you want to customize.\n\n");
+    for (key, value) in DEFAULTS {
+        out.push_str(&format!("#{}={}\n", key, value));
+    }
+    out
+}
+
+/// Whether `value` is what mako would use for `key` anyway. Colors match
+/// regardless of case and an opaque alpha, booleans in any spelling,
+/// and sizes with or without `px`.
+pub fn is_default(key: &str, value: &str) -> bool {
+    let Some(default) = default_value(key) else { return false };
+    let (value, default) = (value.trim(), default.trim());
+    if let (Some(a), Some(b)) = (parse_bool(value), parse_bool(default)) {
+        return a == b;
+    }
+    if let (Some(a), Some(b)) = (parse_px(value), parse_px(default)) {
+        return a == b;
+    }
+    if let (Some(a), Some(b)) = (parse_sides(value), parse_sides(default)) {
+        return expand_sides(&a) == expand_sides(&b);
+    }
+    let canon = |v: &str| {
+        let v = v.to_lowercase();
+        match v.strip_suffix("ff") {
+            Some(rgb) if v.starts_with('#') && v.len() == 9 => rgb.to_string(),
+            _ => v,
+        }
+    };
+    canon(value) == canon(default)
+}
+
+/// A size in pixels, with or without a `px` suffix.
+pub fn parse_px(s: &str) -> Option<u32> {
+    let s = s.trim();
+    let s = s.strip_suffix("px").unwrap_or(s);
+    s.parse::<u32>().ok()
+}
+
+/// `10` or `5,10`: one to four pixel sizes, as mako takes for margins.
+pub fn parse_sides(s: &str) -> Option<Vec<u32>> {
+    let sides: Option<Vec<u32>> = s.split(',').map(parse_px).collect();
+    sides.filter(|v| (1..=4).contains(&v.len()))
+}
+
+/// One to four sides the way CSS reads them, as top, right, bottom and
+/// left: a missing right copies the top, bottom the top, left the right.
+pub fn expand_sides(sides: &[u32]) -> [u32; 4] {
+    match *sides {
+        [all] => [all; 4],
+        [vertical, horizontal] => [vertical, horizontal, vertical, horizontal],
+        [top, horizontal, bottom] => [top, horizontal, bottom, horizontal],
+        [top, right, bottom, left, ..] => [top, right, bottom, left],
+        [] => [0; 4],
+    }
+}
+
+/// Top, right, bottom and left as the shortest value mako reads the same.
+pub fn sides_value([top, right, bottom, left]: [u32; 4]) -> String {
+    let sides: &[u32] = if left != right {
+        &[top, right, bottom, left]
+    } else if top != bottom {
+        &[top, right, bottom]
+    } else if top != right {
+        &[top, right]
+    } else {
+        &[top]
+    };
+    sides_to_str(sides)
+}
+
+fn sides_to_str(sides: &[u32]) -> String {
+    sides.iter().map(u32::to_string).collect::<Vec<_>>().join(",")
+}
+
+/// A whole number.
+pub fn parse_u32(s: &str) -> Option<u32> {
+    s.trim().parse::<u32>().ok()
+}
+
+/// mako's `1`/`0`, or `true`/`false`, `yes`/`no`, `on`/`off`.
+pub fn parse_bool(s: &str) -> Option<bool> {
+    match s.trim().to_lowercase().as_str() {
+        "1" | "true" | "yes" | "on" => Some(true),
+        "0" | "false" | "no" | "off" => Some(false),
+        _ => None,
+    }
+}
+
+fn bool_to_str(b: bool) -> String { if b { "1".to_string() } else { "0".to_string() } }
+
+// Debug helpers to make enum formatting stable when converting to strings
+impl std::fmt::Debug for Layer {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Layer::Overlay => write!(f, "overlay"),
+            Layer::Bottom => write!(f, "bottom"),
+            Layer::Top => write!(f, "top"),
+            Layer::Background => write!(f, "background"),
+            Layer::Normal => write!(f, "normal"),
+        }
+    }
+}
+
+impl std::fmt::Debug for LayoutKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            LayoutKind::Normal => write!(f, "normal"),
+            LayoutKind::Overlay => write!(f, "overlay"),
+            LayoutKind::Center => write!(f, "center"),
+            LayoutKind::Other(s) => write!(f, "{}", s),
+        }
+    }
+}
+
+impl std::fmt::Debug for IconLocation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            IconLocation::Left => write!(f, "left"),
+            IconLocation::Right => write!(f, "right"),
+            IconLocation::Top => write!(f, "top"),
+            IconLocation::Bottom => write!(f, "bottom"),
+            IconLocation::TopLeft => write!(f, "top-left"),
+            IconLocation::TopRight => write!(f, "top-right"),
+            IconLocation::BottomLeft => write!(f, "bottom-left"),
+            IconLocation::BottomRight => write!(f, "bottom-right"),
+            IconLocation::Center => write!(f, "center"),
+            IconLocation::Other(s) => write!(f, "{}", s),
+        }
+    }
+}
+
+impl std::fmt::Debug for TextAlign {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TextAlign::Left => write!(f, "left"),
+            TextAlign::Center => write!(f, "center"),
+            TextAlign::Right => write!(f, "right"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn validate_uses_each_keys_type() {
+        assert!(validate("border-size", "2px").is_ok());
+        assert!(validate("border-size", "purple unicorn").is_err());
+        assert!(validate("padding", "5,10").is_ok());
+        assert!(validate("padding", "1,2,3,4,5").is_err());
+        assert!(validate("border-radius", "8,8,0,0").is_ok());
+        assert!(validate("border-radius", "8,,0").is_err());
+        assert!(validate("background-color", "#285577ee").is_ok());
+        assert!(validate("background-color", "blue").is_err());
+        assert!(validate("progress-color", "over #5588aa").is_ok());
+        assert!(validate("icons", "maybe").is_err());
+        assert!(validate("layer", "overlay").is_ok());
+        assert!(validate("layer", "sideways").is_err());
+        assert!(validate("font", "anything at all").is_ok());
+        assert!(validate("on-button-left", "dismiss-group").is_ok());
+        assert!(validate("on-notify", "exec mpv ~/ding.ogg").is_ok());
+        assert!(validate("on-touch", "exec ").is_err());
+        assert!(validate("on-button-right", "close").is_err());
+        assert!(validate("group-by", "app-name,summary").is_ok());
+        assert!(validate("group-by", "app-name,colour").is_err());
+        assert!(validate("sort", "+priority").is_ok());
+    }
+
+    #[test]
+    fn the_custom_key_entry_comes_last() {
+        assert_eq!(known_keys().last().map(|(k, _)| *k), Some("<custom>"));
+    }
+
+    #[test]
+    fn every_known_key_is_documented() {
+        for (key, _) in known_keys().into_iter().filter(|(k, _)| *k != "<custom>") {
+            let doc = key_doc(key).unwrap_or_else(|| panic!("{} has no docs", key));
+            assert!(!doc.examples.is_empty(), "{}", key);
+        }
+    }
+
+    #[test]
+    fn keys_fall_into_categories() {
+        assert_eq!(category("font"), Category::Appearance);
+        assert_eq!(category("default-timeout"), Category::Behavior);
+        assert_eq!(category("icon-path"), Category::Icons);
+        assert_eq!(category("on-button-left"), Category::Bindings);
+        assert_eq!(category("on-my-own"), Category::Custom);
+    }
+
+    #[test]
+    fn typed_config_round_trips_every_known_key() {
+        let mut cfg = MakoConfig::new();
+        let pairs = [
+            ("text-color", "#ffffff"),
+            ("max-visible", "-1"),
+            ("on-button-middle", "exec makoctl menu wofi -d"),
+            ("invisible", "1"),
+            ("layer", "background"),
+            ("text-alignment", "center"),
+        ];
+        for (key, value) in pairs {
+            cfg.set_from_kv(key, value);
+        }
+        let out = cfg.to_kv_pairs();
+        for (key, value) in pairs {
+            assert!(out.contains(&(key.to_string(), value.to_string())), "{}", key);
+        }
+        let untyped: Vec<_> = known_keys().into_iter().map(|(k, _)| k).filter(|k| !k.starts_with('<')).filter(|k| {
+            let mut one = MakoConfig::new();
+            one.set_from_kv(k, default_value(k).or_else(|| key_doc(k).map(|d| d.examples[0])).unwrap_or("1"));
+            one.to_kv_pairs().is_empty()
+        }).collect();
+        assert_eq!(untyped, ["anchor-point"]);
+    }
+
+    #[test]
+    fn config_params_round_trip_through_the_typed_model() {
+        let cfg: crate::Config = "width=300px\npadding=5,10\nicons=true\nwidth=350\nlayer=sideways\nmy-key=1\nborder-radius=8,8px,0\n".parse().unwrap();
+        let typed = MakoConfig::from_params(&cfg.params);
+        assert_eq!(typed.width, Some(350));
+        assert_eq!(typed.padding, Some(vec![5, 10]));
+        assert!(typed.layer.is_none());
+        let params = typed.to_params();
+        let pairs: Vec<(&str, &str)> = params.iter().map(|p| (p.key.as_str(), p.value.as_str())).collect();
+        assert_eq!(pairs, [("width", "350"), ("border-radius", "8,8,0"), ("icons", "1"), ("padding", "5,10")]);
+        assert_eq!(MakoConfig::from_params(&params).to_kv_pairs(), typed.to_kv_pairs());
+        assert!(MakoConfig::from_params(&[Param::new("margin", "1,2,3,4,5")]).margin.is_none());
+    }
+
+    #[test]
+    fn colors_parse_strictly_and_print_lowercase() {
+        assert_eq!("#FFF".parse(), Ok(Color { r: 255, g: 255, b: 255, a: 255 }));
+        assert_eq!("#285577FF".parse::<Color>().map(|c| c.to_string()), Ok("#285577".to_string()));
+        assert_eq!("#28557780".parse::<Color>().map(|c| c.to_string()), Ok("#28557780".to_string()));
+        assert_eq!("285577".parse::<Color>(), Err(tr("color.no_hash").to_string()));
+        assert_eq!("#28557g".parse::<Color>(), Err(trf("color.bad_digit", &[&'g'])));
+        assert_eq!("#2855".parse::<Color>(), Err(trf("color.bad_length", &[&4])));
+        assert!(validate("border-color", "#fff").unwrap_err().contains("#ffffff"));
+        assert_eq!(normalize_value("border-color", "#ABC"), "#aabbcc");
+        assert_eq!(normalize_value("progress-color", "over #5588AA"), "over #5588aa");
+        assert_eq!(normalize_value("border-color", "red"), "red");
+        let mut cfg = MakoConfig::new();
+        cfg.set_from_kv("text-color", "#FFFFFFFF");
+        cfg.set_from_kv("border-color", "purple");
+        assert_eq!(cfg.to_kv_pairs(), [("text-color".to_string(), "#ffffff".to_string())]);
+    }
+
+    #[test]
+    fn numbers_step_by_key() {
+        assert_eq!(step_value("width", "300", 1).as_deref(), Some("310"));
+        assert_eq!(step_value("border-size", "2px", -1).as_deref(), Some("1px"));
+        assert_eq!(step_value("border-size", "0", -1).as_deref(), Some("0"));
+        assert_eq!(step_value("padding", "5,10", 1).as_deref(), Some("6,11"));
+        assert_eq!(step_value("default-timeout", "5000", -1).as_deref(), Some("4000"));
+        assert_eq!(step_value("max-visible", "0", -2).as_deref(), Some("-1"));
+        assert_eq!(step_value("width", "wide", 1), None);
+        assert_eq!(step_value("font", "10", 1), None);
+    }
+
+    #[test]
+    fn durations_parse_with_units() {
+        assert_eq!(parse_duration_ms("5000"), Some(5000));
+        assert_eq!(parse_duration_ms("5s"), Some(5000));
+        assert_eq!(parse_duration_ms("1.5s"), Some(1500));
+        assert_eq!(parse_duration_ms("2m"), Some(120_000));
+        assert_eq!(parse_duration_ms("1m 30s"), Some(90_000));
+        assert_eq!(parse_duration_ms("250ms"), Some(250));
+        assert_eq!(parse_duration_ms("1H"), Some(3_600_000));
+        assert_eq!(parse_duration_ms("5x"), None);
+        assert_eq!(parse_duration_ms("s"), None);
+        assert_eq!(parse_duration_ms(""), None);
+    }
+
+    #[test]
+    fn defaults_match_loosely() {
+        assert!(is_default("background-color", "#285577"));
+        assert!(is_default("background-color", "#285577ff"));
+        assert!(!is_default("background-color", "#28557780"));
+        assert!(is_default("icons", "true"));
+        assert!(is_default("padding", "5px"));
+        assert!(is_default("margin", "10,10px,10"));
+        assert!(is_default("anchor-point", "top-right"));
+        assert!(is_default("format", "<b>%s</b>\\n%b"));
+        assert!(!is_default("font", "monospace 12"));
+        assert!(!is_default("my-key", ""));
+    }
+
+    #[test]
+    fn sides_expand_like_css_and_shorten_back() {
+        assert_eq!(expand_sides(&[5]), [5, 5, 5, 5]);
+        assert_eq!(expand_sides(&[5, 10]), [5, 10, 5, 10]);
+        assert_eq!(expand_sides(&[1, 2, 3]), [1, 2, 3, 2]);
+        assert_eq!(expand_sides(&[1, 2, 3, 4]), [1, 2, 3, 4]);
+        for value in ["5", "5,10", "1,2,3", "1,2,3,4"] {
+            assert_eq!(sides_value(expand_sides(&parse_sides(value).unwrap())), value);
+        }
+        assert_eq!(sides_value([0, 8, 0, 8]), "0,8");
+    }
+
+    #[test]
+    fn durations_format_compactly() {
+        assert_eq!(format_duration_ms(5000), "5s");
+        assert_eq!(format_duration_ms(1500), "1.5s");
+        assert_eq!(format_duration_ms(90_000), "1m 30s");
+        assert_eq!(format_duration_ms(250), "250ms");
+        assert_eq!(format_duration_ms(3_661_000), "1h 1m 1s");
+    }
+}
@@ -0,0 +1,37 @@
+//! Reading, validating and writing config files for the mako notification
+//! daemon.
+//!
+//! [`Config`] is the document model: it parses any file without failing,
+//! skipping lines it doesn't understand, and writes the params and
+//! `[criteria]` sections back in the same order. [`keys`] knows what mako's options mean:
+//! their defaults, documentation, allowed values and how to validate them.
+//! [`check`] reports every problem in a file, with line numbers.
+//!
+//! ```
+//! use mako_config::{validate, Config};
+//!
+//! let mut cfg: Config = "font=monospace 10\n\n[urgency=high]\nborder-color=#ff0000\n".parse().unwrap();
+//! assert_eq!(cfg.sections[0].params[0].value, "#ff0000");
+//!
+//! assert!(validate("default-timeout", "5000").is_ok());
+//! assert!(validate("border-color", "red").is_err());
+//!
+//! cfg.set(0, "default-timeout", "5000".to_string());
+//! assert!(cfg.to_string().contains("default-timeout=5000"));
+//! ```
+//!
+//! Nothing here talks to a running mako; `Config::save` only writes the
+//! file, and it is up to the caller to run `makoctl reload`.
+//!
+//! The types don't implement serde's traits. The config text is their
+//! stable serialized form: `to_string()` writes it and `parse()` reads it
+//! back.
+
+pub mod check;
+pub mod config;
+pub mod i18n;
+pub mod keys;
+
+pub use check::{check, Problem, Severity};
+pub use config::{config_dir, write_atomic, Condition, Config, Criteria, Param, Section};
+pub use keys::{default_value, is_default, key_doc, known_keys, validate, KeyDoc};
@@ -0,0 +1,97 @@
+//! What the editor did this session, for the log view: saves, reloads,
+//! notifications and the problems left in each saved config. Unlike the
+//! footer, which has room for one line of the last reload, it keeps
+//! everything `makoctl` printed.
+
+use std::collections::VecDeque;
+use std::time::SystemTime;
+
+/// Entries kept; older ones are dropped.
+const LIMIT: usize = 500;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Kind {
+    Save,
+    Reload,
+    Notify,
+    Problem,
+}
+
+impl Kind {
+    /// Message id of the label the log view shows.
+    pub fn label_id(self) -> &'static str {
+        match self {
+            Kind::Save => "log.save",
+            Kind::Reload => "log.reload",
+            Kind::Notify => "log.notify",
+            Kind::Problem => "log.problem",
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Entry {
+    pub at: SystemTime,
+    pub kind: Kind,
+    pub failed: bool,
+    /// What happened. Lines after the first carry details, such as the
+    /// stderr of a failed reload.
+    pub text: String,
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct Activity {
+    entries: VecDeque<Entry>,
+}
+
+impl Activity {
+    pub fn push(&mut self, kind: Kind, failed: bool, text: impl Into<String>) {
+        if self.entries.len() == LIMIT {
+            self.entries.pop_front();
+        }
+        self.entries.push_back(Entry { at: SystemTime::now(), kind, failed, text: text.into() });
+    }
+
+    /// Log `result`, with `ok` as the text when it has none of its own.
+    pub fn push_result(&mut self, kind: Kind, result: &Result<String, String>, ok: &str) {
+        match result {
+            Ok(out) if out.trim().is_empty() => self.push(kind, false, ok),
+            Ok(out) => self.push(kind, false, format!("{}\n{}", ok, out.trim_end())),
+            Err(e) => self.push(kind, true, e.as_str()),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// The rows of the log view, newest entry first: each line of an
+    /// entry, with whether it is the entry's first line.
+    pub fn rows(&self) -> Vec<(&Entry, &str, bool)> {
+        self.entries
+            .iter()
+            .rev()
+            .flat_map(|e| e.text.lines().enumerate().map(move |(i, line)| (e, line, i == 0)))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rows_list_newest_first_with_details_under_their_entry() {
+        let mut log = Activity::default();
+        log.push(Kind::Save, false, "Saved config");
+        log.push_result(Kind::Reload, &Err("line 3: bad\nline 4: worse".to_string()), "Reloaded");
+        log.push_result(Kind::Reload, &Ok(String::new()), "Reloaded");
+        let rows: Vec<(&str, bool)> = log.rows().into_iter().map(|(_, line, first)| (line, first)).collect();
+        assert_eq!(rows, [("Reloaded", true), ("line 3: bad", true), ("line 4: worse", false), ("Saved config", true)]);
+        for _ in 0..LIMIT {
+            log.push(Kind::Notify, false, "Sent");
+        }
+        assert_eq!(log.entries.len(), LIMIT);
+        assert!(log.rows().iter().all(|(e, _, _)| e.kind == Kind::Notify));
+    }
+}
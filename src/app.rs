@@ -0,0 +1,4226 @@
+use std::{
+    fs, mem,
+    path::PathBuf,
+    time::{Duration, Instant, UNIX_EPOCH},
+};
+
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers, MouseButton, MouseEvent, MouseEventKind};
+use ratatui::{
+    layout::{Position, Rect},
+    widgets::ListState,
+};
+
+use crate::activity::{Activity, Kind};
+use crate::browser::Browser;
+use crate::command_line::{self, ExCommand};
+use crate::check::{self, Problem};
+use crate::config::{Backup, Condition, Config, Criteria, Param, Section};
+use crate::diff;
+use crate::fonts::{self, FontPicker};
+use crate::format;
+use crate::fuzzy;
+use crate::git;
+use crate::i18n::{tr, trf};
+use crate::keymap::Command;
+use crate::line_edit;
+use crate::list_editor::ListEditor;
+use crate::logging;
+use crate::mako_config::{allowed_values, ANCHOR_GRID, category, Category, CriterionKind, CRITERIA, default_value, deprecated, is_default, is_path_list_key, fits_scope, known_keys, list_separator, expand_sides, parse_sides, sides_value, normalize_value, step_value, takes_color, validate};
+use crate::plugin::Plugins;
+use crate::settings::{IdleMode, Settings};
+use crate::snapshots;
+use crate::snippet;
+use crate::status::Status;
+use crate::suggest;
+use crate::swaync;
+use crate::dunst::{self, Mapping};
+use crate::notifications::{Notification, TestNotification};
+use crate::themes::PRESETS;
+use crate::trash::Trash;
+
+/// What the Global and Sections tabs show: the params list of
+/// `App::section`, or a prompt over it. Each prompt owns the state it
+/// shows in the footer.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ParamsScreen {
+    List,
+    EditValue { idx: usize, input: String },
+    AddKey { input: String },
+    AddCustomKey { input: String },
+    /// `key` was typed as a custom key but looks like a misspelling of one
+    /// of `suggestions`. `idx` is the highlighted row; the row after the
+    /// suggestions keeps `key` as typed.
+    DidYouMean { key: String, suggestions: Vec<String>, idx: usize },
+    AddValue { key: String, input: String },
+    /// Choosing a value for `key` from the only ones mako accepts, with
+    /// the highlighted one. `idx` is the param being edited, or `None`
+    /// when adding `key`.
+    PickValue { idx: Option<usize>, key: String, choice: usize },
+    /// Picking where `anchor` puts the notifications on a 3x3 screen,
+    /// `cell` into `ANCHOR_GRID`. `idx` as for `PickValue`.
+    PickAnchor { idx: Option<usize>, key: String, cell: usize },
+    /// Editing a margin or padding side by side: top, right, bottom and
+    /// left, with `side` the one being typed; `typed` once a digit has
+    /// been, until then the first replaces the size. For `border-radius`
+    /// the four are corners, clockwise from the top left. `idx` as for
+    /// `PickValue`.
+    EditSides { idx: Option<usize>, key: String, sides: [u32; 4], side: usize, typed: bool },
+    ConfirmDelete { idx: usize },
+    /// Typing a search; the list shows matches as they are typed.
+    Search { input: String },
+    /// Typing the criteria of a new section, e.g. `urgency=critical`.
+    AddSection { input: String },
+    /// Building the criteria of a new section field by field, with the
+    /// highlighted row of `mako_config::CRITERIA`.
+    BuildCriteria { criteria: Criteria, idx: usize },
+    /// Typing the value of text field `idx` in the criteria builder.
+    EditCriterion { criteria: Criteria, idx: usize, input: String },
+    /// Throwing the whole config away for mako's defaults. Only goes ahead
+    /// once `input` spells out the confirmation word.
+    ConfirmResetAll { input: String },
+    /// Copying param `idx`; `input` is the key the copy gets.
+    Duplicate { idx: usize, input: String },
+    /// Typing a `:` command.
+    CommandLine { input: String },
+    /// Typing the value for every marked param; `*` stands for each one's
+    /// current value.
+    BulkEdit { input: String },
+    /// Confirming `change` to the marked params.
+    ConfirmBulk { change: BulkChange },
+    /// Picking directories for a path-list value. `back` is the prompt
+    /// this was opened from; it gets the picked value on Tab.
+    BrowsePath { back: Box<ParamsScreen>, browser: Browser },
+    /// Building a `font` value from the installed families. `back` is the
+    /// prompt this was opened from; it gets the built value on Enter.
+    PickFont { back: Box<ParamsScreen>, picker: FontPicker },
+    /// Editing a list-valued value one entry per row. `back` is the prompt
+    /// this was opened from; it gets the joined value on Enter.
+    EditList { back: Box<ParamsScreen>, list: ListEditor },
+}
+
+/// What happens to the marked params once confirmed.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum BulkChange {
+    Delete,
+    /// Param index and new value for each, from the typed `pattern`.
+    Set { pattern: String, values: Vec<(usize, String)> },
+    /// `:s/from/to/`: section, param index and new value for each param
+    /// it changes, and how many matches it replaces.
+    Replace { from: String, to: String, count: usize, values: Vec<(usize, usize, String)> },
+}
+
+/// A screen opened over the tabs, whichever is showing, until it is
+/// closed. Each variant owns the state it shows.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Overlay {
+    /// Quitting with changes that aren't on disk: save, discard or stay.
+    ConfirmQuit,
+    /// The editor settings screen, with the highlighted row.
+    Settings { idx: usize },
+    EditSetting { idx: usize, input: String },
+    /// Browsing deleted params, newest first, with the highlighted row.
+    Trash { idx: usize },
+    /// Picking a backup of the config file to restore, newest first.
+    Backups { idx: usize, backups: Vec<Backup> },
+    /// Picking a snapshot to restore, newest first, previewed as a diff
+    /// from the current config.
+    Snapshots { idx: usize, snapshots: Vec<snapshots::Snapshot> },
+    /// Picking a commit of the config to restore, newest first, or why its
+    /// history couldn't be read; `None` while git is still being asked.
+    GitLog { idx: usize, commits: Option<Result<Vec<git::Commit>, String>> },
+    /// The notifications in mako's history, from `history`, with the
+    /// highlighted row.
+    History { idx: usize },
+    /// Browsing the built-in theme presets, with the highlighted one.
+    Themes { idx: usize },
+    /// Reading the diff of the config on disk against the one in memory,
+    /// scrolled to row `idx`.
+    Diff { idx: usize },
+    /// Reading the activity log, scrolled to row `idx`.
+    Log { idx: usize },
+    /// The problems `check` finds in the config as it would be written,
+    /// with the highlighted one.
+    Problems { idx: usize, problems: Vec<Problem> },
+    /// Typing the path of a dunstrc to import.
+    ImportPath { input: String },
+    /// Pasting a mako config snippet to merge in, lines and all.
+    PasteConfig { input: String },
+    /// Reviewing what a dunstrc or swaync config would become before
+    /// applying it; `source` names it in the change that applies it.
+    ImportReview { idx: usize, mappings: Vec<Mapping>, source: &'static str },
+    /// Composing `test_notification`, with the highlighted field.
+    Compose { idx: usize },
+    EditCompose { idx: usize, input: String },
+}
+
+impl ParamsScreen {
+    /// The text being typed in this prompt, if it has one.
+    pub fn input_mut(&mut self) -> Option<&mut String> {
+        match self {
+            ParamsScreen::EditValue { input, .. }
+            | ParamsScreen::AddKey { input }
+            | ParamsScreen::AddCustomKey { input }
+            | ParamsScreen::ConfirmResetAll { input }
+            | ParamsScreen::AddSection { input }
+            | ParamsScreen::EditCriterion { input, .. }
+            | ParamsScreen::Search { input }
+            | ParamsScreen::AddValue { input, .. }
+            | ParamsScreen::Duplicate { input, .. }
+            | ParamsScreen::CommandLine { input }
+            | ParamsScreen::BulkEdit { input }
+            | ParamsScreen::EditList { list: ListEditor { input: Some(input), .. }, .. } => Some(input),
+            ParamsScreen::List
+            | ParamsScreen::ConfirmDelete { .. }
+            | ParamsScreen::DidYouMean { .. }
+            | ParamsScreen::PickValue { .. }
+            | ParamsScreen::PickAnchor { .. }
+            | ParamsScreen::EditSides { .. }
+            | ParamsScreen::BuildCriteria { .. }
+            | ParamsScreen::ConfirmBulk { .. }
+            | ParamsScreen::BrowsePath { .. }
+            | ParamsScreen::PickFont { .. }
+            | ParamsScreen::EditList { .. } => None,
+        }
+    }
+}
+
+impl Overlay {
+    /// The text being typed in this overlay, if it has any.
+    pub fn input_mut(&mut self) -> Option<&mut String> {
+        match self {
+            Overlay::EditSetting { input, .. }
+            | Overlay::EditCompose { input, .. }
+            | Overlay::ImportPath { input }
+            | Overlay::PasteConfig { input } => Some(input),
+            Overlay::ConfirmQuit
+            | Overlay::Settings { .. }
+            | Overlay::Trash { .. }
+            | Overlay::Backups { .. }
+            | Overlay::Snapshots { .. }
+            | Overlay::GitLog { .. }
+            | Overlay::History { .. }
+            | Overlay::Compose { .. }
+            | Overlay::ImportReview { .. }
+            | Overlay::Themes { .. }
+            | Overlay::Diff { .. }
+            | Overlay::Log { .. }
+            | Overlay::Problems { .. } => None,
+        }
+    }
+}
+
+/// Side effect requested by a transition. The state machine itself never
+/// writes to the filesystem or spawns processes; the caller does that.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Action {
+    None,
+    Quit,
+    /// Save the config (and reload mako), then quit if that worked.
+    SaveAndQuit,
+    /// The config changed: save it (and the trash), then notify about
+    /// `key = value` and reload mako.
+    Save { key: String, value: String },
+    /// Write the config the user asked for with `Command::Write`, then
+    /// report the changes in `App::pending` as `Save` would.
+    Write,
+    /// With `Settings::live_apply`, a change was added to `App::pending`:
+    /// write them all like `Write` once `live_apply_ms` has passed since
+    /// the last such write.
+    LiveApply,
+    /// The editor settings changed and should be written to disk.
+    SaveSettings,
+    /// The trash changed without the config changing.
+    SaveTrash,
+    /// The config was reset: back up the file, replace it with the
+    /// commented defaults and reload mako.
+    ResetConfig,
+    /// Ask mako for the notifications it is showing and store them in
+    /// `notifications`.
+    ListNotifications,
+    /// Dismiss notification `id`, or every notification for `None`, then
+    /// list them again.
+    Dismiss { id: Option<u32> },
+    /// Invoke `action` of notification `id`, then list the notifications
+    /// again.
+    Invoke { id: u32, action: String },
+    /// Send `test_notification`.
+    SendTest,
+    /// Send the test notification with its progress going from 0 to 100.
+    SendProgress,
+    /// Ask mako for its notification history and store it in `history`.
+    ListHistory,
+    /// Put the most recent notification in the history back on screen,
+    /// then list the history again.
+    RestoreHistory,
+    /// Ask git for the config's history and hand it to `set_git_log`.
+    ListGitLog,
+    /// Ask mako which modes are on and store them in `modes`.
+    ListModes,
+    /// Turn mode `name` on or off, then list the modes again.
+    ToggleMode { name: String },
+    /// Put `text` on the system clipboard.
+    Copy { text: String },
+    /// Read the system clipboard and hand it to `handle_paste`.
+    PasteClipboard,
+    /// Run `makoctl reload`.
+    Reload,
+    /// Load the config at `path` and hand it to `App::open`.
+    OpenProfile { path: PathBuf },
+}
+
+/// Everything the UI needs to draw a frame and react to input.
+pub struct App {
+    pub cfg: Config,
+    /// What the Global and Sections tabs show.
+    pub params_screen: ParamsScreen,
+    /// The screen open over the tabs, which gets every key until closed.
+    pub overlay: Option<Overlay>,
+    pub list_state: ListState,
+    /// Selection in the known-keys chooser used while adding a key.
+    pub key_list_state: ListState,
+    /// Outcome of the most recent `makoctl reload`.
+    pub last_reload: Option<Result<String, String>>,
+    /// Reloads started in the background and not finished yet.
+    pub reloading: usize,
+    /// Frame of the spinner shown while `reloading`.
+    pub spinner: usize,
+    /// Failures reported by plugin post-save actions after the last save.
+    pub hook_errors: Vec<String>,
+    /// Result of the last action that isn't a reload, e.g. saving settings.
+    pub message: Option<Result<String, String>>,
+    pub plugins: Plugins,
+    pub settings: Settings,
+    /// Deleted params that can be restored.
+    pub trash: Trash,
+    /// Daemon and file state for the header, refreshed by the runtime.
+    pub status: Status,
+    /// The config as last loaded or saved, to tell whether it has unsaved
+    /// changes.
+    pub saved_text: String,
+    /// Changes held back by `Settings::deferred_write` or `live_apply` since
+    /// the last write, as `(key, value)`.
+    pub pending: Vec<(String, String)>,
+    /// Image file to show inline and the cells to show it in, as laid out
+    /// by the last frame. Set by the renderer; drawn by the runtime.
+    pub image_preview: Option<(PathBuf, Rect)>,
+    /// Hide params set to mako's default, leaving only what's customized.
+    pub only_customized: bool,
+    /// List the known keys the section doesn't set too, as `Row::Unset`.
+    pub show_unset: bool,
+    /// Show the docs pane for the selected key next to the list.
+    pub show_docs: bool,
+    /// Drop the header and boxes around the footer, for small popup
+    /// terminals (`--compact`); short terminals get this regardless.
+    pub compact: bool,
+    pub sort: SortOrder,
+    /// Categories whose params are hidden under their header.
+    pub collapsed: Vec<Category>,
+    /// The category header selected instead of a param, when
+    /// `list_state` has no selection.
+    pub header: Option<Category>,
+    /// The unset key selected instead, likewise.
+    pub unset: Option<&'static str>,
+    /// The params `y` last yanked, for `p` to paste into any section.
+    pub yanked: Vec<Param>,
+    /// The tab on screen. Global and Sections show the params of
+    /// `section`; Sections lists the sections instead while it is 0.
+    pub tab: Tab,
+    /// The highlighted row of the Sections tab's list, 0 for the first
+    /// section. Opening a section moves it there, so the tab comes back
+    /// to the section last edited.
+    pub section_list: usize,
+    pub modes_screen: ModesScreen,
+    pub notifications_screen: NotificationsScreen,
+    /// Params of the section marked for a bulk delete or edit, in the order
+    /// they were marked.
+    pub marked: Vec<usize>,
+    /// The section being edited, as numbered by `Config::params_of`. The
+    /// list, its selection and every param index in `params_screen` refer
+    /// to it.
+    pub section: usize,
+    /// The rows of the params list as drawn by the last frame, and the
+    /// position in `rows` of its top row. Set by the renderer;
+    /// used to tell which param a click landed on.
+    pub list_hitbox: Option<(Rect, usize)>,
+    /// The param last clicked and when, to spot double-clicks.
+    last_click: Option<(usize, Instant)>,
+    /// Scroll offset of the help overlay while it is open.
+    pub help: Option<u16>,
+    /// Only params whose key or value contains this (ignoring case) are
+    /// listed.
+    pub search: Option<String>,
+    /// States before each change, newest last, each with the key it
+    /// changed.
+    undo_stack: Vec<(Snapshot, String)>,
+    /// States undone, newest last, for redo.
+    redo_stack: Vec<(Snapshot, String)>,
+    /// What `makoctl list` last reported, for the notifications view.
+    pub notifications: Result<Vec<Notification>, String>,
+    /// What `makoctl history` last reported, for the history view.
+    pub history: Result<Vec<Notification>, String>,
+    /// Saves, reloads and notifications this session, for the log view.
+    pub activity: Activity,
+    /// The modes `makoctl mode` last reported as on.
+    pub modes: Result<Vec<String>, String>,
+    /// The installed font families, for checking `font`. `None` when
+    /// fontconfig couldn't be asked.
+    pub fonts: Option<Vec<String>>,
+    /// The installed mako's version, for flagging keys it doesn't read.
+    /// `None` when `mako --version` couldn't be asked.
+    pub mako_version: Option<(u32, u32)>,
+    /// The test notification being composed, kept between visits.
+    pub test_notification: TestNotification,
+    /// A value Enter refused because it doesn't validate. Pressing Enter
+    /// again on the same value saves it anyway.
+    pub refused: Option<String>,
+    /// Where the cursor is in the prompt being typed, as the number of
+    /// characters after it (see `line_edit`). Back at the end whenever the
+    /// prompt changes.
+    pub cursor: usize,
+}
+
+/// How the params list is ordered. Only the view changes: the file keeps
+/// its order unless `Command::ApplySort` writes the shown one.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum SortOrder {
+    #[default]
+    File,
+    Alphabetical,
+    Category,
+}
+
+impl SortOrder {
+    /// The order `Command::Sort` switches to next.
+    pub fn next(self) -> Self {
+        match self {
+            SortOrder::File => SortOrder::Alphabetical,
+            SortOrder::Alphabetical => SortOrder::Category,
+            SortOrder::Category => SortOrder::File,
+        }
+    }
+
+    /// The i18n id of the order's name.
+    pub fn label_id(self) -> &'static str {
+        match self {
+            SortOrder::File => "sort.file",
+            SortOrder::Alphabetical => "sort.alphabetical",
+            SortOrder::Category => "sort.category",
+        }
+    }
+
+    /// `indices` of `params` put in this order. Sorts are stable, so
+    /// repeated keys keep their file order.
+    fn apply(self, params: &[Param], indices: &mut [usize]) {
+        match self {
+            SortOrder::File => {}
+            SortOrder::Alphabetical => indices.sort_by(|&a, &b| params[a].key.cmp(&params[b].key)),
+            SortOrder::Category => indices.sort_by_key(|&i| category(&params[i].key)),
+        }
+    }
+}
+
+/// The screens Tab and Shift-Tab cycle through. Each keeps its own state
+/// in `App` while another is showing: Global and Sections share
+/// `ParamsScreen`, as both edit the params of `App::section`, and an
+/// `Overlay` opens over any of them.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Tab {
+    /// The section-less params at the top of the file.
+    Global,
+    /// The criteria sections' params.
+    Sections,
+    Modes,
+    Notifications,
+}
+
+impl Tab {
+    pub const ALL: &[Tab] = &[Tab::Global, Tab::Sections, Tab::Modes, Tab::Notifications];
+
+    /// The i18n id of the tab's name.
+    pub fn label_id(self) -> &'static str {
+        match self {
+            Tab::Global => "tab.global",
+            Tab::Sections => "tab.sections",
+            Tab::Modes => "tab.modes",
+            Tab::Notifications => "tab.notifications",
+        }
+    }
+}
+
+/// What the Modes tab shows.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ModesScreen {
+    /// The mako modes, from `App::mode_rows`, with the highlighted one.
+    List { idx: usize },
+    /// Typing the name of a new mode to style.
+    Add { input: String },
+}
+
+/// What the Notifications tab shows.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum NotificationsScreen {
+    /// The notifications mako is showing, from `App::notifications`, with
+    /// the highlighted row.
+    List { idx: usize },
+    /// The actions of notification `id`, row `notification` of the list,
+    /// with the highlighted one.
+    Actions { notification: usize, id: u32, actions: Vec<(String, String)>, idx: usize },
+}
+
+/// A row of the params list: a category header (in category order), a
+/// param, by index into the section, or a known key the section doesn't
+/// set (with `App::show_unset`).
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Row {
+    Header(Category),
+    Param(usize),
+    Unset(&'static str),
+}
+
+/// Two clicks on the same row within this long make a double-click.
+const DOUBLE_CLICK: Duration = Duration::from_millis(400);
+
+/// Unchanged lines shown around each change in the diff view.
+pub const DIFF_CONTEXT: usize = 3;
+
+/// How many changes undo remembers.
+const UNDO_LIMIT: usize = 100;
+
+/// The largest margin side the sides editor types, well past any screen.
+const MAX_SIDE: u32 = 9999;
+
+/// What undo brings back: the config and where the list was.
+struct Snapshot {
+    cfg: Config,
+    section: usize,
+    selected: Option<usize>,
+}
+
+impl App {
+    pub fn new(cfg: Config) -> Self {
+        let mut list_state = ListState::default();
+        if !cfg.params.is_empty() {
+            list_state.select(Some(0));
+        }
+        let mut key_list_state = ListState::default();
+        key_list_state.select(Some(0));
+        let saved_text = cfg.to_string();
+        App {
+            cfg,
+            params_screen: ParamsScreen::List,
+            overlay: None,
+            list_state,
+            key_list_state,
+            last_reload: None,
+            reloading: 0,
+            spinner: 0,
+            hook_errors: Vec::new(),
+            message: None,
+            plugins: Plugins::default(),
+            settings: Settings::default(),
+            trash: Trash::default(),
+            status: Status::default(),
+            saved_text,
+            pending: Vec::new(),
+            image_preview: None,
+            only_customized: false,
+            show_unset: false,
+            show_docs: false,
+            compact: false,
+            sort: SortOrder::File,
+            collapsed: Vec::new(),
+            header: None,
+            unset: None,
+            yanked: Vec::new(),
+            marked: Vec::new(),
+            tab: Tab::Global,
+            section_list: 0,
+            modes_screen: ModesScreen::List { idx: 0 },
+            notifications_screen: NotificationsScreen::List { idx: 0 },
+            section: 0,
+            search: None,
+            help: None,
+            list_hitbox: None,
+            last_click: None,
+            undo_stack: Vec::new(),
+            redo_stack: Vec::new(),
+            refused: None,
+            cursor: 0,
+            notifications: Ok(Vec::new()),
+            history: Err(tr("docs.history_unread").to_string()),
+            activity: Activity::default(),
+            fonts: None,
+            mako_version: None,
+            modes: Ok(Vec::new()),
+            test_notification: TestNotification::default(),
+        }
+    }
+
+    pub fn handle_key(&mut self, key: KeyEvent) -> Action {
+        if let Some(scroll) = &mut self.help {
+            match key.code {
+                KeyCode::Esc | KeyCode::Char('?' | 'q') => self.help = None,
+                KeyCode::Down | KeyCode::Char('j') => *scroll += 1,
+                KeyCode::Up | KeyCode::Char('k') => *scroll = scroll.saturating_sub(1),
+                KeyCode::PageDown => *scroll += 10,
+                KeyCode::PageUp => *scroll = scroll.saturating_sub(10),
+                _ => {}
+            }
+            return Action::None;
+        }
+        if self.params_showing() {
+            // Messages shown in the params view last until the next key.
+            self.message = None;
+            if is_ctrl(key) && key.code == KeyCode::Char('r') {
+                let action = self.redo();
+                return self.defer(action);
+            }
+            if self.settings.keys.command(key.code) == Some(Command::Undo) {
+                let action = self.undo();
+                return self.defer(action);
+            }
+        }
+        // Ctrl-V (Ctrl-Shift-V too, where the terminal passes it on) pastes
+        // the clipboard into any prompt.
+        if is_ctrl(key) && matches!(key.code, KeyCode::Char('v' | 'V')) && self.input_mut().is_some() {
+            return Action::PasteClipboard;
+        }
+        let before = Snapshot { cfg: self.cfg.clone(), section: self.section, selected: self.list_state.selected() };
+        let prompt = self.prompt();
+        let action = self.transition(key);
+        if self.prompt() != prompt {
+            self.cursor = 0;
+        }
+        let changed = match &action {
+            Action::Save { key, .. } => Some(key.clone()),
+            Action::ResetConfig => Some(tr("undo.reset").to_string()),
+            _ => None,
+        };
+        if let Some(key) = changed {
+            if self.undo_stack.len() == UNDO_LIMIT {
+                self.undo_stack.remove(0);
+            }
+            self.undo_stack.push((before, key));
+            self.redo_stack.clear();
+            // Indices shift with the params they point at.
+            self.marked.clear();
+        }
+        self.defer(action)
+    }
+
+    /// With `deferred_write` on, hold a save back in `pending` instead of
+    /// writing the file and reloading mako on every change; with
+    /// `live_apply`, hold it back until the runtime's next live write.
+    fn defer(&mut self, action: Action) -> Action {
+        match action {
+            Action::Save { key, value } if self.settings.deferred_write => {
+                self.pending.push((key, value));
+                Action::None
+            }
+            Action::Save { key, value } if self.settings.live_apply => {
+                self.pending.push((key, value));
+                Action::LiveApply
+            }
+            action => action,
+        }
+    }
+
+    /// Go back to the config before the last change, and save that.
+    fn undo(&mut self) -> Action {
+        let Some((snapshot, key)) = self.undo_stack.pop() else {
+            self.message = Some(Err(tr("undo.nothing").to_string()));
+            return Action::None;
+        };
+        let current = self.restore(snapshot);
+        self.marked.clear();
+        self.redo_stack.push((current, key.clone()));
+        self.message = Some(Ok(trf("undo.undone", &[&key])));
+        self.restored_action(key)
+    }
+
+    /// Redo the last undone change, and save that.
+    fn redo(&mut self) -> Action {
+        let Some((snapshot, key)) = self.redo_stack.pop() else {
+            self.message = Some(Err(tr("undo.nothing_to_redo").to_string()));
+            return Action::None;
+        };
+        let current = self.restore(snapshot);
+        self.marked.clear();
+        self.undo_stack.push((current, key.clone()));
+        self.message = Some(Ok(trf("undo.redone", &[&key])));
+        self.restored_action(key)
+    }
+
+    /// Put `snapshot` in place, returning the state it replaced.
+    fn restore(&mut self, snapshot: Snapshot) -> Snapshot {
+        let current = Snapshot { cfg: self.cfg.clone(), section: self.section, selected: self.list_state.selected() };
+        self.cfg = snapshot.cfg;
+        self.section = snapshot.section.min(self.cfg.sections.len());
+        self.tab = if self.section > 0 { Tab::Sections } else { Tab::Global };
+        let len = self.cfg.params_of(self.section).len();
+        self.list_state.select(snapshot.selected.filter(|_| len > 0).map(|i| i.min(len - 1)));
+        current
+    }
+
+    /// The save for a config restored by undo or redo, naming `key` with its
+    /// value now.
+    fn restored_action(&self, key: String) -> Action {
+        let params = self.cfg.params_of(self.section);
+        let value = params.iter().rev().find(|p| p.key == key).map(|p| p.value.clone());
+        Action::Save { key, value: value.unwrap_or_else(|| "<deleted>".to_string()) }
+    }
+
+    fn transition(&mut self, key: KeyEvent) -> Action {
+        if self.overlay.is_some() {
+            return self.overlay_key(key);
+        }
+        if self.params_screen == ParamsScreen::List {
+            // Tab and Shift-Tab leave any tab but from its own prompts.
+            let on_list = match self.tab {
+                Tab::Modes => matches!(self.modes_screen, ModesScreen::List { .. }),
+                Tab::Notifications => matches!(self.notifications_screen, NotificationsScreen::List { .. }),
+                Tab::Global | Tab::Sections => true,
+            };
+            if on_list && matches!(key.code, KeyCode::Tab | KeyCode::BackTab) {
+                return self.step_tab(if key.code == KeyCode::Tab { 1 } else { -1 });
+            }
+            match self.tab {
+                Tab::Modes => return self.modes_key(key),
+                Tab::Notifications => return self.notifications_key(key),
+                Tab::Sections if self.section == 0 => return self.section_list_key(key),
+                Tab::Global | Tab::Sections => {}
+            }
+        }
+        match &mut self.params_screen {
+            ParamsScreen::List
+                if self.list_state.selected().is_none()
+                    && matches!(key.code, KeyCode::Enter | KeyCode::Left | KeyCode::Right | KeyCode::Char(' ')) =>
+            {
+                if let Some(name) = self.unset {
+                    // Promote it, starting at the value mako uses now.
+                    if key.code == KeyCode::Enter {
+                        self.params_screen = value_prompt(name.to_string(), None, default_value(name).unwrap_or_default().to_string());
+                    }
+                } else if let Some(category) = self.header {
+                    let collapsed = self.collapsed.contains(&category);
+                    let collapse = match key.code {
+                        KeyCode::Left => true,
+                        KeyCode::Right => false,
+                        _ => !collapsed,
+                    };
+                    self.collapsed.retain(|&c| c != category);
+                    if collapse {
+                        self.collapsed.push(category);
+                    }
+                }
+            }
+            ParamsScreen::List => match self.settings.keys.command(key.code) {
+                Some(Command::Quit) if self.is_dirty() => self.overlay = Some(Overlay::ConfirmQuit),
+                Some(Command::Quit) => return Action::Quit,
+                Some(Command::Down) => self.step_selection(1),
+                Some(Command::Up) => self.step_selection(-1),
+                Some(Command::Top) => self.move_selection(isize::MIN),
+                Some(Command::Bottom) => self.move_selection(isize::MAX),
+                None if key.code == KeyCode::Home => self.move_selection(isize::MIN),
+                None if key.code == KeyCode::End => self.move_selection(isize::MAX),
+                None if key.code == KeyCode::PageDown => self.move_selection(self.page_rows()),
+                None if key.code == KeyCode::PageUp => self.move_selection(-self.page_rows()),
+                Some(Command::Mark) => {
+                    if let Some(i) = self.list_state.selected() {
+                        match self.marked.iter().position(|&m| m == i) {
+                            Some(at) => {
+                                self.marked.remove(at);
+                            }
+                            None => self.marked.push(i),
+                        }
+                        self.step_selection(1);
+                    }
+                }
+                None if key.code == KeyCode::Esc && !self.marked.is_empty() => self.marked.clear(),
+                Some(Command::Edit) if !self.marked.is_empty() => {
+                    let params = self.cfg.params_of(self.section);
+                    let first = &params[self.marked[0]].value;
+                    let same = self.marked.iter().all(|&i| params[i].value == *first);
+                    self.params_screen = ParamsScreen::BulkEdit { input: if same { first.clone() } else { String::new() } };
+                }
+                Some(Command::Delete) if !self.marked.is_empty() => self.params_screen = ParamsScreen::ConfirmBulk { change: BulkChange::Delete },
+                Some(Command::Edit) => {
+                    if let Some(i) = self.list_state.selected() {
+                        let p = &self.cfg.params_of(self.section)[i];
+                        self.params_screen = value_prompt(p.key.clone(), Some(i), p.value.clone());
+                    }
+                }
+                Some(Command::Add) => {
+                    self.key_list_state.select(Some(0));
+                    self.params_screen = ParamsScreen::AddKey { input: String::new() };
+                }
+                Some(Command::Delete) => {
+                    if let Some(i) = self.list_state.selected() {
+                        self.params_screen = ParamsScreen::ConfirmDelete { idx: i };
+                    } else if self.section > 0 && self.cfg.params_of(self.section).is_empty() {
+                        // An empty section goes without asking; there is
+                        // nothing in it to lose.
+                        let removed = self.cfg.sections.remove(self.section - 1);
+                        self.switch_section(self.section - 1);
+                        return Action::Save { key: format!("[{}]", removed.criteria), value: "<deleted>".to_string() };
+                    }
+                }
+                Some(Command::NextSection) => self.switch_section((self.section + 1) % self.cfg.section_count()),
+                Some(Command::PrevSection) => {
+                    let count = self.cfg.section_count();
+                    self.switch_section((self.section + count - 1) % count);
+                }
+                Some(Command::AddSection) => self.params_screen = ParamsScreen::BuildCriteria { criteria: Criteria::default(), idx: 0 },
+                Some(Command::Help) => self.help = Some(0),
+                Some(Command::Search) => {
+                    self.params_screen = ParamsScreen::Search { input: self.search.take().unwrap_or_default() };
+                }
+                Some(Command::NextMatch) if self.search.is_some() => self.step_selection(1),
+                Some(Command::PrevMatch) if self.search.is_some() => self.step_selection(-1),
+                None if key.code == KeyCode::Esc && self.search.is_some() => {
+                    self.search = None;
+                    self.step_selection(0);
+                }
+                // Up the breadcrumb, from a section to the global params.
+                None if key.code == KeyCode::Esc && self.section > 0 => self.switch_section(0),
+                Some(Command::Settings) => {
+                    self.message = None;
+                    self.overlay = Some(Overlay::Settings { idx: 0 });
+                }
+                Some(Command::Trash) => self.overlay = Some(Overlay::Trash { idx: 0 }),
+                Some(Command::Backups) => self.overlay = Some(Overlay::Backups { idx: 0, backups: self.cfg.backups() }),
+                Some(Command::Snapshots) => self.overlay = Some(Overlay::Snapshots { idx: 0, snapshots: snapshots::list(&self.cfg.path) }),
+                Some(Command::GitLog) => {
+                    self.overlay = Some(Overlay::GitLog { idx: 0, commits: None });
+                    return Action::ListGitLog;
+                }
+                Some(Command::Compose) => self.overlay = Some(Overlay::Compose { idx: 0 }),
+                Some(Command::ProgressPreview) => return Action::SendProgress,
+                Some(Command::Themes) => self.overlay = Some(Overlay::Themes { idx: 0 }),
+                Some(Command::Write) if self.is_dirty() => return Action::Write,
+                Some(Command::Write) => self.message = Some(Ok(tr("footer.nothing_to_write").to_string())),
+                Some(Command::Diff) => self.overlay = Some(Overlay::Diff { idx: 0 }),
+                Some(Command::Log) => self.overlay = Some(Overlay::Log { idx: 0 }),
+                Some(Command::CommandLine) => self.params_screen = ParamsScreen::CommandLine { input: String::new() },
+                Some(Command::Validate) => self.overlay = Some(Overlay::Problems { idx: 0, problems: check::check(&self.cfg.to_string()) }),
+                Some(Command::AutoReload) => {
+                    self.settings.auto_reload = !self.settings.auto_reload;
+                    return Action::SaveSettings;
+                }
+                Some(Command::Copy) if !self.marked.is_empty() => {
+                    let mut marked = mem::take(&mut self.marked);
+                    marked.sort_unstable();
+                    self.yanked = marked.into_iter().map(|i| self.cfg.params_of(self.section)[i].clone()).collect();
+                    let text = self.yanked.iter().map(|p| format!("{}={}\n", p.key, p.value)).collect();
+                    return Action::Copy { text };
+                }
+                Some(Command::Copy) => {
+                    if let Some(p) = self.list_state.selected().and_then(|i| self.cfg.params_of(self.section).get(i)) {
+                        self.yanked = vec![p.clone()];
+                        return Action::Copy { text: p.value.clone() };
+                    }
+                }
+                Some(Command::Paste) if self.yanked.is_empty() => {
+                    self.message = Some(Err(trf("footer.nothing_yanked", &[&self.settings.keys.key(Command::Copy)])));
+                }
+                Some(Command::Paste) => return self.paste_yanked(),
+                Some(Command::ImportDunst) => {
+                    // Offer swaync's config to those who have it instead,
+                    // and nothing without a home directory to look in.
+                    let path = dunst::default_path().ok().map(|dunst| match swaync::default_path() {
+                        Ok(swaync) if !dunst.exists() && swaync.exists() => swaync,
+                        _ => dunst,
+                    });
+                    self.overlay = Some(Overlay::ImportPath { input: path.as_deref().map(dunst::display_path).unwrap_or_default() });
+                }
+                // Not Ctrl-V, which pastes into prompts.
+                Some(Command::PasteConfig) if !is_ctrl(key) => self.overlay = Some(Overlay::PasteConfig { input: String::new() }),
+                Some(Command::Notifications) => {
+                    self.tab = Tab::Notifications;
+                    return Action::ListNotifications;
+                }
+                Some(Command::History) => {
+                    self.overlay = Some(Overlay::History { idx: 0 });
+                    return Action::ListHistory;
+                }
+                Some(Command::Modes) => {
+                    self.tab = Tab::Modes;
+                    return Action::ListModes;
+                }
+                Some(Command::Duplicate) => {
+                    if let Some(i) = self.list_state.selected() {
+                        self.params_screen = ParamsScreen::Duplicate { idx: i, input: self.cfg.params_of(self.section)[i].key.clone() };
+                    }
+                }
+                Some(Command::Reset) => {
+                    if let Some(i) = self.list_state.selected() {
+                        let key = self.cfg.params_of(self.section)[i].key.clone();
+                        // With no other line setting a key mako knows, it
+                        // falls back to its default anyway, so the line can go.
+                        let known = known_keys().iter().any(|(k, _)| *k == key && *k != "<custom>");
+                        let others = (0..self.cfg.section_count())
+                            .flat_map(|n| self.cfg.params_of(n).iter().enumerate().map(move |(j, p)| (n, j, p)))
+                            .filter(|&(n, j, p)| p.key == key && (n, j) != (self.section, i))
+                            .count();
+                        if known && others == 0 {
+                            let removed = self.cfg.params_of_mut(self.section).remove(i);
+                            self.trash.push(removed);
+                            let len = self.cfg.params_of(self.section).len();
+                            self.list_state.select((len > 0).then(|| i.min(len - 1)));
+                            self.message = Some(Ok(trf("reset.key_removed", &[&key])));
+                            return Action::Save { key, value: "<reset>".to_string() };
+                        }
+                        match default_value(&key) {
+                            Some(default) => {
+                                self.cfg.params_of_mut(self.section)[i].value = default.to_string();
+                                self.message = Some(Ok(trf("reset.key_done", &[&key, &default])));
+                                return Action::Save { key, value: default.to_string() };
+                            }
+                            None => self.message = Some(Err(trf("reset.no_default", &[&key]))),
+                        }
+                    }
+                }
+                Some(cmd @ (Command::Increment | Command::Decrement)) => {
+                    if let Some(i) = self.list_state.selected() {
+                        let p = &self.cfg.params_of(self.section)[i];
+                        let steps = if cmd == Command::Increment { 1 } else { -1 };
+                        match step_value(&p.key, &p.value, steps) {
+                            Some(value) => {
+                                let key = p.key.clone();
+                                self.cfg.params_of_mut(self.section)[i].value = value.clone();
+                                return Action::Save { key, value };
+                            }
+                            None => self.message = Some(Err(trf("step.not_numeric", &[&p.value]))),
+                        }
+                    }
+                }
+                Some(Command::MoveUp | Command::MoveDown) if self.sort != SortOrder::File => {
+                    self.message = Some(Err(trf("sort.move_needs_file_order", &[&self.settings.keys.key(Command::Sort)])));
+                }
+                Some(Command::MoveUp) => return self.move_selected(-1),
+                Some(Command::MoveDown) => return self.move_selected(1),
+                Some(Command::Sort) => {
+                    self.sort = self.sort.next();
+                    self.step_selection(0);
+                }
+                Some(Command::ApplySort) => return self.apply_sort(),
+                Some(Command::ResetAll) => self.params_screen = ParamsScreen::ConfirmResetAll { input: String::new() },
+                Some(Command::Docs) => {
+                    self.show_docs = !self.show_docs;
+                    if self.docs_show_history() {
+                        return Action::ListHistory;
+                    }
+                }
+                Some(Command::Migrate) => {
+                    let (renamed, removed) = self.cfg.migrate();
+                    if renamed == 0 && removed.is_empty() {
+                        self.message = Some(Ok(tr("migrate.nothing").to_string()));
+                        return Action::None;
+                    }
+                    let dropped = removed.len();
+                    for p in removed {
+                        self.trash.push(p);
+                    }
+                    self.step_selection(0);
+                    self.message = Some(Ok(trf("migrate.done", &[&renamed, &dropped])));
+                    return Action::Save { key: "<migrate>".to_string(), value: format!("{} renamed, {} removed", renamed, dropped) };
+                }
+                Some(Command::Customized) => {
+                    self.only_customized = !self.only_customized;
+                    self.step_selection(0);
+                }
+                Some(Command::ShowUnset) => {
+                    self.show_unset = !self.show_unset;
+                    self.step_selection(0);
+                }
+                _ => {}
+            },
+            ParamsScreen::EditValue { idx, input } => match key.code {
+                KeyCode::Char('o') if is_ctrl(key) && is_path_list_key(&self.cfg.params_of(self.section)[*idx].key) => {
+                    self.open_browser();
+                }
+                KeyCode::Char('o') if is_ctrl(key) && self.cfg.params_of(self.section)[*idx].key == "font" => self.open_font_picker(),
+                KeyCode::Char('l') if is_ctrl(key) => {
+                    if let Some(separator) = list_separator(&self.cfg.params_of(self.section)[*idx].key) {
+                        self.open_list_editor(separator);
+                    }
+                }
+                KeyCode::Tab if format::is_format_key(&self.cfg.params_of(self.section)[*idx].key) => {
+                    if let Some(completed) = format::complete(input) {
+                        *input = completed;
+                    }
+                }
+                KeyCode::Esc => {
+                    self.refused = None;
+                    self.params_screen = ParamsScreen::List;
+                }
+                KeyCode::Enter => {
+                    let idx = *idx;
+                    if let Some(p) = self.cfg.params_of(self.section).get(idx) {
+                        let value = normalize_value(&p.key, input);
+                        if validate(&p.key, &self.cfg.expand(&value)).is_err() && self.refused.as_ref() != Some(&value) {
+                            self.refused = Some(value);
+                            return Action::None;
+                        }
+                    }
+                    let value = input.clone();
+                    self.params_screen = ParamsScreen::List;
+                    self.refused = None;
+                    if idx < self.cfg.params_of(self.section).len() {
+                        let value = normalize_value(&self.cfg.params_of(self.section)[idx].key, &value);
+                        self.cfg.params_of_mut(self.section)[idx].value = value.clone();
+                        return Action::Save { key: self.cfg.params_of(self.section)[idx].key.clone(), value };
+                    }
+                }
+                _ if line_edit::edit(input, &mut self.cursor, key) => {}
+                _ => {}
+            },
+            ParamsScreen::AddKey { input } => match key.code {
+                KeyCode::Esc => self.params_screen = ParamsScreen::List,
+                KeyCode::Enter => {
+                    // Only accept a selection from the filtered list. If '<custom>' is
+                    // selected, open the custom-key prompt instead.
+                    let filtered = filtered_known_keys(input, &self.plugins, self.section);
+                    self.params_screen = match self.key_list_state.selected().and_then(|i| filtered.get(i)) {
+                        Some((k, _)) if k == "<custom>" => ParamsScreen::AddCustomKey { input: String::new() },
+                        Some((k, _)) => value_prompt(k.clone(), None, String::new()),
+                        None => ParamsScreen::List,
+                    };
+                }
+                KeyCode::Down | KeyCode::Char('j') => {
+                    let len = filtered_known_keys(input, &self.plugins, self.section).len();
+                    if len > 0 {
+                        let i = self.key_list_state.selected().unwrap_or(0);
+                        self.key_list_state.select(Some((i + 1) % len));
+                    }
+                }
+                KeyCode::Up | KeyCode::Char('k') => {
+                    let len = filtered_known_keys(input, &self.plugins, self.section).len();
+                    if len > 0 {
+                        let i = self.key_list_state.selected().unwrap_or(0);
+                        self.key_list_state.select(Some(if i == 0 { len - 1 } else { i - 1 }));
+                    }
+                }
+                KeyCode::Backspace => {
+                    line_edit::pop(input);
+                    self.key_list_state.select(Some(0));
+                }
+                KeyCode::Char(c) => {
+                    input.push(c);
+                    self.key_list_state.select(Some(0));
+                }
+                _ => {}
+            },
+            ParamsScreen::AddCustomKey { input } => match key.code {
+                KeyCode::Esc => self.params_screen = ParamsScreen::List,
+                KeyCode::Enter => {
+                    let keyname = input.trim().to_string();
+                    let known = filtered_known_keys("", &self.plugins, self.section);
+                    let candidates = known.iter().map(|(k, _)| k.as_str()).filter(|k| *k != "<custom>");
+                    let is_known = candidates.clone().any(|k| k == keyname);
+                    let suggestions =
+                        suggest::did_you_mean(&keyname, candidates.chain(self.cfg.params_of(self.section).iter().map(|p| p.key.as_str())));
+                    self.params_screen = if keyname.is_empty() {
+                        ParamsScreen::List
+                    } else if is_known || suggestions.is_empty() {
+                        value_prompt(keyname, None, String::new())
+                    } else {
+                        ParamsScreen::DidYouMean { key: keyname, suggestions, idx: 0 }
+                    };
+                }
+                _ if line_edit::edit(input, &mut self.cursor, key) => {}
+                _ => {}
+            },
+            ParamsScreen::DidYouMean { key: typed, suggestions, idx } => {
+                let len = suggestions.len() + 1;
+                match key.code {
+                    KeyCode::Esc => self.params_screen = ParamsScreen::AddCustomKey { input: typed.clone() },
+                    KeyCode::Down | KeyCode::Char('j') => *idx = (*idx + 1) % len,
+                    KeyCode::Up | KeyCode::Char('k') => *idx = if *idx == 0 { len - 1 } else { *idx - 1 },
+                    KeyCode::Enter => {
+                        let key = suggestions.get(*idx).unwrap_or(typed).clone();
+                        self.params_screen = value_prompt(key, None, String::new());
+                    }
+                    _ => {}
+                }
+            }
+            ParamsScreen::AddValue { key: key_str, input } => match key.code {
+                KeyCode::Char('o') if is_ctrl(key) && is_path_list_key(key_str) => self.open_browser(),
+                KeyCode::Char('o') if is_ctrl(key) && key_str == "font" => self.open_font_picker(),
+                KeyCode::Char('l') if is_ctrl(key) => {
+                    if let Some(separator) = list_separator(key_str) {
+                        self.open_list_editor(separator);
+                    }
+                }
+                KeyCode::Tab if format::is_format_key(key_str) => {
+                    if let Some(completed) = format::complete(input) {
+                        *input = completed;
+                    }
+                }
+                KeyCode::Esc => {
+                    self.refused = None;
+                    self.params_screen = ParamsScreen::List;
+                }
+                KeyCode::Enter => {
+                    let key_str = key_str.clone();
+                    let val = normalize_value(&key_str, input);
+                    if validate(&key_str, &self.cfg.expand(&val)).is_err() && self.refused.as_ref() != Some(&val) {
+                        self.refused = Some(val);
+                        return Action::None;
+                    }
+                    self.refused = None;
+                    self.params_screen = ParamsScreen::List;
+                    if !key_str.trim().is_empty() {
+                        self.cfg.add_param(self.section, key_str.clone(), val.clone());
+                        // select the newly added item
+                        self.list_state.select(Some(self.cfg.params_of(self.section).len() - 1));
+                        return Action::Save { key: key_str, value: val };
+                    }
+                }
+                _ if line_edit::edit(input, &mut self.cursor, key) => {}
+                _ => {}
+            },
+            ParamsScreen::PickValue { idx, key: key_str, choice } => {
+                let values = allowed_values(key_str).unwrap_or_default();
+                match key.code {
+                    KeyCode::Esc | KeyCode::Char('q') => self.params_screen = ParamsScreen::List,
+                    KeyCode::Down | KeyCode::Char('j') => *choice = (*choice + 1) % values.len(),
+                    KeyCode::Up | KeyCode::Char('k') => *choice = if *choice == 0 { values.len() - 1 } else { *choice - 1 },
+                    KeyCode::Enter => {
+                        let (idx, key_str, value) = (*idx, key_str.clone(), values[*choice].to_string());
+                        return self.pick(idx, key_str, value);
+                    }
+                    _ => {}
+                }
+            }
+            ParamsScreen::PickAnchor { idx, key: key_str, cell } => {
+                let (row, col) = (*cell / 3, *cell % 3);
+                match key.code {
+                    KeyCode::Esc | KeyCode::Char('q') => self.params_screen = ParamsScreen::List,
+                    KeyCode::Up | KeyCode::Char('k') => *cell = row.saturating_sub(1) * 3 + col,
+                    KeyCode::Down | KeyCode::Char('j') => *cell = (row + 1).min(2) * 3 + col,
+                    KeyCode::Left | KeyCode::Char('h') => *cell = row * 3 + col.saturating_sub(1),
+                    KeyCode::Right | KeyCode::Char('l') => *cell = row * 3 + (col + 1).min(2),
+                    KeyCode::Enter => {
+                        let (idx, key_str, value) = (*idx, key_str.clone(), ANCHOR_GRID[*cell].to_string());
+                        return self.pick(idx, key_str, value);
+                    }
+                    _ => {}
+                }
+            }
+            ParamsScreen::EditSides { idx, key: key_str, sides, side, typed } => match key.code {
+                KeyCode::Esc => self.params_screen = ParamsScreen::List,
+                KeyCode::Up | KeyCode::Right | KeyCode::Down | KeyCode::Left | KeyCode::Tab | KeyCode::BackTab => {
+                    // Corners go round a 2x2 grid: the arrows keep to the
+                    // row or column they don't move along.
+                    let corners = key_str == "border-radius";
+                    *side = match key.code {
+                        KeyCode::Tab => (*side + 1) % 4,
+                        KeyCode::BackTab => (*side + 3) % 4,
+                        KeyCode::Up if corners => [0, 1, 1, 0][*side],
+                        KeyCode::Down if corners => [3, 2, 2, 3][*side],
+                        KeyCode::Left if corners => [0, 0, 3, 3][*side],
+                        KeyCode::Right if corners => [1, 1, 2, 2][*side],
+                        KeyCode::Up => 0,
+                        KeyCode::Right => 1,
+                        KeyCode::Down => 2,
+                        _ => 3,
+                    };
+                    *typed = false;
+                }
+                KeyCode::Char(c) if c.is_ascii_digit() => {
+                    let kept = if *typed { sides[*side] * 10 } else { 0 };
+                    sides[*side] = (kept + c.to_digit(10).unwrap_or(0)).min(MAX_SIDE);
+                    *typed = true;
+                }
+                KeyCode::Backspace => {
+                    sides[*side] /= 10;
+                    *typed = true;
+                }
+                KeyCode::Char('+') => sides[*side] = (sides[*side] + 1).min(MAX_SIDE),
+                KeyCode::Char('-') => sides[*side] = sides[*side].saturating_sub(1),
+                KeyCode::Enter => {
+                    let (idx, key_str, value) = (*idx, key_str.clone(), sides_value(*sides));
+                    return self.pick(idx, key_str, value);
+                }
+                _ => {}
+            },
+            ParamsScreen::ConfirmDelete { idx } => match key.code {
+                KeyCode::Char('y') | KeyCode::Char('Y') => {
+                    let idx = *idx;
+                    self.params_screen = ParamsScreen::List;
+                    if idx < self.cfg.params_of(self.section).len() {
+                        let removed = self.cfg.params_of(self.section)[idx].key.clone();
+                        self.trash.push(self.cfg.params_of(self.section)[idx].clone());
+                        self.cfg.params_of_mut(self.section).remove(idx);
+                        if self.cfg.params_of(self.section).is_empty() {
+                            self.list_state.select(None);
+                        } else {
+                            self.list_state.select(Some(idx.saturating_sub(1)));
+                        }
+                        return Action::Save { key: removed, value: "<deleted>".to_string() };
+                    }
+                }
+                KeyCode::Char('n') | KeyCode::Char('N') | KeyCode::Esc => self.params_screen = ParamsScreen::List,
+                _ => {}
+            },
+            ParamsScreen::Search { input } => {
+                match key.code {
+                    KeyCode::Esc => {
+                        self.params_screen = ParamsScreen::List;
+                        self.search = None;
+                    }
+                    KeyCode::Enter => {
+                        let query = mem::take(input);
+                        self.params_screen = ParamsScreen::List;
+                        self.search = Some(query).filter(|q| !q.is_empty());
+                    }
+                    KeyCode::Backspace => line_edit::pop(input),
+                    KeyCode::Char(c) => input.push(c),
+                    _ => {}
+                }
+                self.step_selection(0);
+            }
+            ParamsScreen::AddSection { input } => match key.code {
+                KeyCode::Esc => self.params_screen = ParamsScreen::List,
+                KeyCode::Enter => {
+                    let Ok(criteria) = input.parse::<Criteria>();
+                    return self.add_section(criteria);
+                }
+                KeyCode::Backspace => line_edit::pop(input),
+                KeyCode::Char(c) => input.push(c),
+                _ => {}
+            },
+            ParamsScreen::BuildCriteria { criteria, idx } => {
+                let (field, kind) = &CRITERIA[*idx];
+                match key.code {
+                    KeyCode::Esc | KeyCode::Char('q') => self.params_screen = ParamsScreen::List,
+                    KeyCode::Down | KeyCode::Char('j') => *idx = (*idx + 1) % CRITERIA.len(),
+                    KeyCode::Up | KeyCode::Char('k') => *idx = if *idx == 0 { CRITERIA.len() - 1 } else { *idx - 1 },
+                    KeyCode::Enter | KeyCode::Char(' ') => match kind {
+                        CriterionKind::Text => {
+                            let input = criteria.get(field).and_then(|c| c.value.clone()).unwrap_or_default();
+                            self.params_screen = ParamsScreen::EditCriterion { criteria: criteria.clone(), idx: *idx, input };
+                        }
+                        // Choices cycle through their words and then back to unset.
+                        CriterionKind::Choice(values) => {
+                            let next = match criteria.get(field).and_then(|c| c.value.as_deref()) {
+                                Some(v) => values.iter().position(|w| *w == v).and_then(|i| values.get(i + 1)),
+                                None => values.first(),
+                            };
+                            let condition = next.map(|v| Condition { key: field.to_string(), regex: false, value: Some(v.to_string()) });
+                            criteria.set(field, condition);
+                        }
+                        CriterionKind::Flag => {
+                            let condition = criteria.get(field).is_none().then(|| Condition { key: field.to_string(), regex: false, value: None });
+                            criteria.set(field, condition);
+                        }
+                    },
+                    KeyCode::Char('r') => {
+                        if let Some(c) = criteria.get(field).filter(|_| matches!(kind, CriterionKind::Text)) {
+                            let c = Condition { regex: !c.regex, ..c.clone() };
+                            criteria.set(field, Some(c));
+                        }
+                    }
+                    KeyCode::Char('x') | KeyCode::Delete | KeyCode::Backspace => criteria.set(field, None),
+                    KeyCode::Char('/') => self.params_screen = ParamsScreen::AddSection { input: criteria.to_string() },
+                    KeyCode::Char('s') => {
+                        let criteria = mem::take(criteria);
+                        return self.add_section(criteria);
+                    }
+                    _ => {}
+                }
+            }
+            ParamsScreen::EditCriterion { criteria, idx, input } => match key.code {
+                KeyCode::Esc => self.params_screen = ParamsScreen::BuildCriteria { criteria: mem::take(criteria), idx: *idx },
+                KeyCode::Enter => {
+                    let field = CRITERIA[*idx].0;
+                    let regex = criteria.get(field).is_some_and(|c| c.regex);
+                    let condition = (!input.is_empty()).then(|| Condition { key: field.to_string(), regex, value: Some(input.clone()) });
+                    criteria.set(field, condition);
+                    self.params_screen = ParamsScreen::BuildCriteria { criteria: mem::take(criteria), idx: *idx };
+                }
+                _ if line_edit::edit(input, &mut self.cursor, key) => {}
+                _ => {}
+            },
+            ParamsScreen::ConfirmResetAll { input } => match key.code {
+                KeyCode::Esc => self.params_screen = ParamsScreen::List,
+                KeyCode::Enter => {
+                    let confirmed = input.trim() == tr("reset.confirm_word");
+                    self.params_screen = ParamsScreen::List;
+                    if confirmed {
+                        self.cfg.params.clear();
+                        self.cfg.sections.clear();
+                        self.section = 0;
+                        self.tab = Tab::Global;
+                        self.list_state.select(None);
+                        self.message = Some(Ok(tr("reset.all_done").to_string()));
+                        return Action::ResetConfig;
+                    }
+                }
+                KeyCode::Backspace => line_edit::pop(input),
+                KeyCode::Char(c) => input.push(c),
+                _ => {}
+            },
+            ParamsScreen::Duplicate { idx, input } => match key.code {
+                KeyCode::Esc => self.params_screen = ParamsScreen::List,
+                KeyCode::Enter => {
+                    let (idx, new_key) = (*idx, input.trim().to_string());
+                    self.params_screen = ParamsScreen::List;
+                    if idx < self.cfg.params_of(self.section).len() && !new_key.is_empty() {
+                        let value = self.cfg.params_of(self.section)[idx].value.clone();
+                        self.cfg.params_of_mut(self.section).insert(idx + 1, Param::new(new_key.clone(), value.clone()));
+                        self.list_state.select(Some(idx + 1));
+                        return Action::Save { key: new_key, value };
+                    }
+                }
+                KeyCode::Backspace => line_edit::pop(input),
+                KeyCode::Char(c) => input.push(c),
+                _ => {}
+            },
+            ParamsScreen::BulkEdit { input } => match key.code {
+                KeyCode::Esc => self.params_screen = ParamsScreen::List,
+                KeyCode::Enter => {
+                    let pattern = input.trim().to_string();
+                    let params = self.cfg.params_of(self.section);
+                    let mut values = Vec::new();
+                    for &i in &self.marked {
+                        let p = &params[i];
+                        let value = normalize_value(&p.key, &pattern.replace('*', &p.value));
+                        if let Err(e) = validate(&p.key, &self.cfg.expand(&value)) {
+                            self.message = Some(Err(trf("bulk.invalid", &[&p.key, &e])));
+                            return Action::None;
+                        }
+                        values.push((i, value));
+                    }
+                    self.message = None;
+                    self.params_screen = ParamsScreen::ConfirmBulk { change: BulkChange::Set { pattern, values } };
+                }
+                _ if line_edit::edit(input, &mut self.cursor, key) => {}
+                _ => {}
+            },
+            ParamsScreen::ConfirmBulk { change } => match key.code {
+                KeyCode::Char('y' | 'Y') => {
+                    let change = mem::replace(change, BulkChange::Delete);
+                    self.params_screen = ParamsScreen::List;
+                    return self.apply_bulk(change);
+                }
+                KeyCode::Char('n' | 'N') | KeyCode::Esc => self.params_screen = ParamsScreen::List,
+                _ => {}
+            },
+            ParamsScreen::CommandLine { input } => match key.code {
+                KeyCode::Esc => self.params_screen = ParamsScreen::List,
+                KeyCode::Backspace if input.is_empty() => self.params_screen = ParamsScreen::List,
+                KeyCode::Enter => {
+                    let line = mem::take(input);
+                    self.params_screen = ParamsScreen::List;
+                    match command_line::parse(&line) {
+                        Ok(cmd) => return self.run_ex(cmd),
+                        Err(e) => self.message = Some(Err(e)),
+                    }
+                }
+                _ if line_edit::edit(input, &mut self.cursor, key) => {}
+                _ => {}
+            },
+            ParamsScreen::BrowsePath { back, browser } => match key.code {
+                KeyCode::Esc => self.params_screen = mem::replace(back.as_mut(), ParamsScreen::List),
+                KeyCode::Tab => {
+                    let value = browser.value();
+                    let mut back = mem::replace(back.as_mut(), ParamsScreen::List);
+                    if let Some(input) = back.input_mut() {
+                        *input = value;
+                    }
+                    self.params_screen = back;
+                }
+                KeyCode::Down | KeyCode::Char('j') => browser.down(),
+                KeyCode::Up | KeyCode::Char('k') => browser.up(),
+                KeyCode::Enter | KeyCode::Right | KeyCode::Char('l') => browser.enter(),
+                KeyCode::Left | KeyCode::Backspace | KeyCode::Char('h') => browser.parent(),
+                KeyCode::Char(' ') => browser.toggle(),
+                _ => {}
+            },
+            ParamsScreen::PickFont { back, picker } => match key.code {
+                KeyCode::Esc => self.params_screen = mem::replace(back.as_mut(), ParamsScreen::List),
+                KeyCode::Enter => {
+                    if let Some(value) = picker.value() {
+                        let mut back = mem::replace(back.as_mut(), ParamsScreen::List);
+                        if let Some(input) = back.input_mut() {
+                            *input = value;
+                        }
+                        self.params_screen = back;
+                    }
+                }
+                KeyCode::Tab | KeyCode::BackTab => {
+                    picker.field = match picker.field {
+                        fonts::Field::Family => fonts::Field::Size,
+                        fonts::Field::Size => fonts::Field::Family,
+                    }
+                }
+                KeyCode::Down => picker.down(),
+                KeyCode::Up => picker.up(),
+                KeyCode::Right => picker.cycle_style(1),
+                KeyCode::Left => picker.cycle_style(-1),
+                KeyCode::Backspace => picker.backspace(),
+                KeyCode::Char(c) => picker.push(c),
+                _ => {}
+            },
+            ParamsScreen::EditList { list: ListEditor { input: Some(input), .. }, .. } => match key.code {
+                // `input` borrows from the list, so reach it again through the screen.
+                KeyCode::Esc | KeyCode::Enter => {
+                    if let ParamsScreen::EditList { list, .. } = &mut self.params_screen {
+                        match key.code {
+                            KeyCode::Esc => list.cancel(),
+                            _ => list.commit(),
+                        }
+                    }
+                }
+                _ if line_edit::edit(input, &mut self.cursor, key) => {}
+                _ => {}
+            },
+            ParamsScreen::EditList { back, list } => match key.code {
+                KeyCode::Esc => self.params_screen = mem::replace(back.as_mut(), ParamsScreen::List),
+                KeyCode::Enter => {
+                    let value = list.value();
+                    let mut back = mem::replace(back.as_mut(), ParamsScreen::List);
+                    if let Some(input) = back.input_mut() {
+                        *input = value;
+                    }
+                    self.params_screen = back;
+                }
+                KeyCode::Down | KeyCode::Char('j') => list.down(),
+                KeyCode::Up | KeyCode::Char('k') => list.up(),
+                KeyCode::Char('J') => list.move_by(1),
+                KeyCode::Char('K') => list.move_by(-1),
+                KeyCode::Char('a') => {
+                    list.add();
+                    self.cursor = 0;
+                }
+                KeyCode::Char('e') => {
+                    list.edit();
+                    self.cursor = 0;
+                }
+                KeyCode::Char('d') | KeyCode::Delete => list.remove(),
+                _ => {}
+            },
+        }
+        Action::None
+    }
+
+    /// Keys on the overlay open over the tabs.
+    fn overlay_key(&mut self, key: KeyEvent) -> Action {
+        let Some(overlay) = &mut self.overlay else { return Action::None };
+        match overlay {
+            Overlay::ConfirmQuit => match key.code {
+                KeyCode::Char('s' | 'S' | 'y' | 'Y') => {
+                    self.overlay = None;
+                    return Action::SaveAndQuit;
+                }
+                KeyCode::Char('d' | 'D') => {
+                    self.overlay = None;
+                    return Action::Quit;
+                }
+                KeyCode::Char('c' | 'C' | 'n' | 'N') | KeyCode::Esc => self.overlay = None,
+                _ => {}
+            },
+            Overlay::Settings { idx } => {
+                let len = self.settings.fields().len();
+                match key.code {
+                    KeyCode::Esc | KeyCode::Char('q') => self.overlay = None,
+                    KeyCode::Down | KeyCode::Char('j') => *idx = (*idx + 1) % len,
+                    KeyCode::Up | KeyCode::Char('k') => *idx = if *idx == 0 { len - 1 } else { *idx - 1 },
+                    KeyCode::Enter => {
+                        let input = self.settings.fields()[*idx].1.clone();
+                        self.message = None;
+                        self.overlay = Some(Overlay::EditSetting { idx: *idx, input });
+                    }
+                    _ => {}
+                }
+            }
+            Overlay::EditSetting { idx, input } => match key.code {
+                KeyCode::Esc => self.overlay = Some(Overlay::Settings { idx: *idx }),
+                KeyCode::Enter => {
+                    let field = self.settings.fields()[*idx].0.clone();
+                    match self.settings.set(&field, input) {
+                        Ok(()) => {
+                            self.cfg.palette = self.settings.palette.clone();
+                            self.overlay = Some(Overlay::Settings { idx: *idx });
+                            return Action::SaveSettings;
+                        }
+                        Err(e) => self.message = Some(Err(e)),
+                    }
+                }
+                KeyCode::Backspace => line_edit::pop(input),
+                KeyCode::Char(c) => input.push(c),
+                _ => {}
+            },
+            Overlay::Themes { idx } => match key.code {
+                KeyCode::Esc | KeyCode::Char('q') => self.overlay = None,
+                KeyCode::Down | KeyCode::Char('j') => *idx = (*idx + 1) % PRESETS.len(),
+                KeyCode::Up | KeyCode::Char('k') => *idx = if *idx == 0 { PRESETS.len() - 1 } else { *idx - 1 },
+                KeyCode::Enter => {
+                    let preset = &PRESETS[*idx];
+                    preset.apply(&mut self.cfg);
+                    self.overlay = None;
+                    self.switch_section(0);
+                    self.message = Some(Ok(trf("themes.applied", &[&preset.name])));
+                    return Action::Save { key: "theme".to_string(), value: preset.name.to_string() };
+                }
+                _ => {}
+            },
+            Overlay::ImportPath { input } => match key.code {
+                KeyCode::Esc => self.overlay = None,
+                KeyCode::Enter => {
+                    let path = expand_tilde(input.trim());
+                    let imported = if swaync::is_swaync(&path) {
+                        swaync::read(&path).map(|mappings| (mappings, "swaync"))
+                    } else {
+                        fs::read_to_string(&path)
+                            .map(|text| (dunst::import(&text), "dunstrc"))
+                            .map_err(|e| trf("import.read_failed", &[&path.display(), &e]))
+                    };
+                    match imported {
+                        Ok((mappings, source)) => self.overlay = Some(Overlay::ImportReview { idx: 0, mappings, source }),
+                        Err(e) => self.message = Some(Err(e)),
+                    }
+                }
+                KeyCode::Backspace => line_edit::pop(input),
+                KeyCode::Char(c) => input.push(c),
+                _ => {}
+            },
+            Overlay::PasteConfig { input } => match key.code {
+                KeyCode::Esc => self.overlay = None,
+                KeyCode::Char('d') if is_ctrl(key) => {
+                    let mappings = snippet::review(input, &self.cfg);
+                    if mappings.is_empty() {
+                        self.message = Some(Err(tr("prompt.paste_empty").to_string()));
+                    } else {
+                        self.message = None;
+                        self.overlay = Some(Overlay::ImportReview { idx: 0, mappings, source: "pasted text" });
+                    }
+                }
+                KeyCode::Enter => input.push('\n'),
+                KeyCode::Backspace => line_edit::pop(input),
+                KeyCode::Char(c) if !is_ctrl(key) => input.push(c),
+                _ => {}
+            },
+            Overlay::ImportReview { idx, mappings, source } => match key.code {
+                KeyCode::Esc | KeyCode::Char('q') => self.overlay = None,
+                KeyCode::Down | KeyCode::Char('j') if !mappings.is_empty() => *idx = (*idx + 1) % mappings.len(),
+                KeyCode::Up | KeyCode::Char('k') if !mappings.is_empty() => {
+                    *idx = if *idx == 0 { mappings.len() - 1 } else { *idx - 1 };
+                }
+                KeyCode::Char(' ') => {
+                    if let Some(m) = mappings.get_mut(*idx).filter(|m| m.param.is_some()) {
+                        m.enabled = !m.enabled;
+                    }
+                }
+                KeyCode::Enter => {
+                    let applied = dunst::apply(mappings, &mut self.cfg);
+                    let source = *source;
+                    self.overlay = None;
+                    self.switch_section(0);
+                    self.message = Some(Ok(trf("import.done", &[&applied, &source])));
+                    if applied > 0 {
+                        return Action::Save { key: source.to_string(), value: "<imported>".to_string() };
+                    }
+                }
+                _ => {}
+            },
+            Overlay::Compose { idx } => {
+                let len = self.test_notification.fields().len();
+                match key.code {
+                    KeyCode::Esc | KeyCode::Char('q') => self.overlay = None,
+                    KeyCode::Down | KeyCode::Char('j') => *idx = (*idx + 1) % len,
+                    KeyCode::Up | KeyCode::Char('k') => *idx = if *idx == 0 { len - 1 } else { *idx - 1 },
+                    KeyCode::Enter => {
+                        let input = self.test_notification.fields()[*idx].1.clone();
+                        self.message = None;
+                        self.overlay = Some(Overlay::EditCompose { idx: *idx, input });
+                    }
+                    KeyCode::Char('s') => return Action::SendTest,
+                    KeyCode::Char('p') => return Action::SendProgress,
+                    _ => {}
+                }
+            }
+            Overlay::EditCompose { idx, input } => match key.code {
+                KeyCode::Esc => self.overlay = Some(Overlay::Compose { idx: *idx }),
+                KeyCode::Enter => {
+                    let field = self.test_notification.fields()[*idx].0;
+                    match self.test_notification.set(field, input) {
+                        Ok(()) => self.overlay = Some(Overlay::Compose { idx: *idx }),
+                        Err(e) => self.message = Some(Err(e)),
+                    }
+                }
+                KeyCode::Backspace => line_edit::pop(input),
+                KeyCode::Char(c) => input.push(c),
+                _ => {}
+            },
+            Overlay::Trash { idx } => match key.code {
+                KeyCode::Esc | KeyCode::Char('q') => self.overlay = None,
+                KeyCode::Down | KeyCode::Char('j') if !self.trash.items.is_empty() => {
+                    *idx = (*idx + 1) % self.trash.items.len();
+                }
+                KeyCode::Up | KeyCode::Char('k') if !self.trash.items.is_empty() => {
+                    *idx = if *idx == 0 { self.trash.items.len() - 1 } else { *idx - 1 };
+                }
+                KeyCode::Enter | KeyCode::Char('r') => {
+                    if let Some(param) = self.trash.take_newest(*idx) {
+                        self.overlay = None;
+                        let action = Action::Save { key: param.key.clone(), value: param.value.clone() };
+                        self.cfg.params_of_mut(self.section).push(param);
+                        self.list_state.select(Some(self.cfg.params_of(self.section).len() - 1));
+                        return action;
+                    }
+                }
+                KeyCode::Char('x') => {
+                    self.trash.take_newest(*idx);
+                    *idx = (*idx).min(self.trash.items.len().saturating_sub(1));
+                    return Action::SaveTrash;
+                }
+                _ => {}
+            },
+            Overlay::History { idx } => {
+                let count = self.history.as_ref().map_or(0, Vec::len);
+                match key.code {
+                    KeyCode::Esc | KeyCode::Char('q') => self.overlay = None,
+                    KeyCode::Char(c) if c == self.settings.keys.key(Command::History) => self.overlay = None,
+                    KeyCode::Down | KeyCode::Char('j') if count > 0 => *idx = (*idx + 1) % count,
+                    KeyCode::Up | KeyCode::Char('k') if count > 0 => *idx = if *idx == 0 { count - 1 } else { *idx - 1 },
+                    KeyCode::Char('r') => return Action::ListHistory,
+                    KeyCode::Char('R') if count > 0 => return Action::RestoreHistory,
+                    KeyCode::Char('s') => {
+                        if let Some(n) = self.history.as_ref().ok().and_then(|list| list.get(*idx)) {
+                            self.test_notification = TestNotification::like(n);
+                            return Action::SendTest;
+                        }
+                    }
+                    _ => {}
+                }
+            }
+            Overlay::Backups { idx, backups } => match key.code {
+                KeyCode::Esc | KeyCode::Char('q') => self.overlay = None,
+                KeyCode::Down | KeyCode::Char('j') if !backups.is_empty() => *idx = (*idx + 1) % backups.len(),
+                KeyCode::Up | KeyCode::Char('k') if !backups.is_empty() => {
+                    *idx = if *idx == 0 { backups.len() - 1 } else { *idx - 1 };
+                }
+                KeyCode::Enter => {
+                    let Some(backup) = backups.get(*idx).cloned() else { return Action::None };
+                    self.overlay = None;
+                    let name = backup.path.file_name().unwrap_or_default().to_string_lossy().into_owned();
+                    match self.cfg.restore(&backup) {
+                        Ok(()) => {
+                            self.switch_section(0);
+                            self.message = Some(Ok(trf("backups.restored", &[&name])));
+                            return Action::Save { key: name, value: "<restored>".to_string() };
+                        }
+                        Err(e) => self.message = Some(Err(trf("backups.read_failed", &[&name, &e]))),
+                    }
+                }
+                _ => {}
+            },
+            Overlay::Snapshots { idx, snapshots } => match key.code {
+                KeyCode::Esc | KeyCode::Char('q') => self.overlay = None,
+                KeyCode::Down | KeyCode::Char('j') if !snapshots.is_empty() => *idx = (*idx + 1) % snapshots.len(),
+                KeyCode::Up | KeyCode::Char('k') if !snapshots.is_empty() => {
+                    *idx = if *idx == 0 { snapshots.len() - 1 } else { *idx - 1 };
+                }
+                KeyCode::Enter => {
+                    let Some(snapshot) = snapshots.get(*idx) else { return Action::None };
+                    let Ok(cfg) = snapshot.text.parse::<Config>();
+                    let millis = snapshot.taken.duration_since(UNIX_EPOCH).unwrap_or_default().as_millis();
+                    let name = trf("snapshots.name", &[&logging::timestamp(millis)]);
+                    self.overlay = None;
+                    self.cfg.params = cfg.params;
+                    self.cfg.sections = cfg.sections;
+                    self.switch_section(0);
+                    self.message = Some(Ok(trf("backups.restored", &[&name])));
+                    return Action::Save { key: name, value: "<restored>".to_string() };
+                }
+                _ => {}
+            },
+            Overlay::GitLog { idx, commits } => {
+                let commits = commits.as_ref().and_then(|c| c.as_ref().ok());
+                let len = commits.map_or(0, Vec::len);
+                match key.code {
+                    KeyCode::Esc | KeyCode::Char('q') => self.overlay = None,
+                    KeyCode::Down | KeyCode::Char('j') if len > 0 => *idx = (*idx + 1) % len,
+                    KeyCode::Up | KeyCode::Char('k') if len > 0 => *idx = if *idx == 0 { len - 1 } else { *idx - 1 },
+                    KeyCode::Enter => {
+                        let Some(commit) = commits.and_then(|c| c.get(*idx)) else { return Action::None };
+                        let Ok(cfg) = commit.text.parse::<Config>();
+                        let name = trf("git.name", &[&commit.short_hash()]);
+                        self.overlay = None;
+                        self.cfg.params = cfg.params;
+                        self.cfg.sections = cfg.sections;
+                        self.switch_section(0);
+                        self.message = Some(Ok(trf("backups.restored", &[&name])));
+                        return Action::Save { key: name, value: "<restored>".to_string() };
+                    }
+                    _ => {}
+                }
+            }
+            Overlay::Diff { idx } => {
+                let rows = diff::unified(&self.saved_text, &self.cfg.to_string(), DIFF_CONTEXT).len();
+                match key.code {
+                    KeyCode::Esc | KeyCode::Char('q') => self.overlay = None,
+                    _ if self.settings.keys.command(key.code) == Some(Command::Write) && rows > 0 => {
+                        self.overlay = None;
+                        return Action::Write;
+                    }
+                    KeyCode::Down | KeyCode::Char('j') => *idx = (*idx + 1).min(rows.saturating_sub(1)),
+                    KeyCode::Up | KeyCode::Char('k') => *idx = idx.saturating_sub(1),
+                    KeyCode::PageDown => *idx = (*idx + 10).min(rows.saturating_sub(1)),
+                    KeyCode::PageUp => *idx = idx.saturating_sub(10),
+                    _ => {}
+                }
+            }
+            Overlay::Log { idx } => {
+                let rows = self.activity.rows().len();
+                match key.code {
+                    KeyCode::Esc | KeyCode::Char('q') => self.overlay = None,
+                    KeyCode::Char(c) if c == self.settings.keys.key(Command::Log) => self.overlay = None,
+                    KeyCode::Down | KeyCode::Char('j') => *idx = (*idx + 1).min(rows.saturating_sub(1)),
+                    KeyCode::Up | KeyCode::Char('k') => *idx = idx.saturating_sub(1),
+                    KeyCode::PageDown => *idx = (*idx + 10).min(rows.saturating_sub(1)),
+                    KeyCode::PageUp => *idx = idx.saturating_sub(10),
+                    KeyCode::Home => *idx = 0,
+                    KeyCode::End => *idx = rows.saturating_sub(1),
+                    _ => {}
+                }
+            }
+            Overlay::Problems { idx, problems } => match key.code {
+                KeyCode::Esc | KeyCode::Char('q') => self.overlay = None,
+                KeyCode::Down | KeyCode::Char('j') => *idx = (*idx + 1).min(problems.len().saturating_sub(1)),
+                KeyCode::Up | KeyCode::Char('k') => *idx = idx.saturating_sub(1),
+                KeyCode::Enter => {
+                    if let Some((n, param)) = problems.get(*idx).and_then(|p| self.cfg.locate_line(p.line)) {
+                        self.overlay = None;
+                        self.reveal(n, param);
+                    }
+                }
+                _ => {}
+            },
+        }
+        Action::None
+    }
+
+    /// Open the list editor on the current prompt's value.
+    fn open_list_editor(&mut self, separator: char) {
+        let list = ListEditor::new(self.params_screen.input_mut().map(|s| s.as_str()).unwrap_or(""), separator);
+        let back = mem::replace(&mut self.params_screen, ParamsScreen::List);
+        self.params_screen = ParamsScreen::EditList { back: Box::new(back), list };
+    }
+
+    /// Open the font picker on the current prompt's value, if fontconfig
+    /// listed the installed fonts.
+    fn open_font_picker(&mut self) {
+        let Some(families) = self.fonts.clone() else {
+            self.message = Some(Err(tr("fonts.unavailable").to_string()));
+            return;
+        };
+        let picker = FontPicker::new(families, self.params_screen.input_mut().map(|s| s.as_str()).unwrap_or(""));
+        let back = mem::replace(&mut self.params_screen, ParamsScreen::List);
+        self.params_screen = ParamsScreen::PickFont { back: Box::new(back), picker };
+    }
+
+    /// Open the path browser on the current prompt's value.
+    fn open_browser(&mut self) {
+        let browser = Browser::new(self.params_screen.input_mut().map(|s| s.as_str()).unwrap_or(""));
+        let back = mem::replace(&mut self.params_screen, ParamsScreen::List);
+        self.params_screen = ParamsScreen::BrowsePath { back: Box::new(back), browser };
+    }
+
+    /// Go to the section with `criteria`, adding it first if there is
+    /// none. Empty criteria add nothing.
+    fn add_section(&mut self, criteria: Criteria) -> Action {
+        self.params_screen = ParamsScreen::List;
+        if criteria.conditions.is_empty() {
+            return Action::None;
+        }
+        // Adding a section that already exists just goes there.
+        if let Some(i) = self.cfg.sections.iter().position(|s| s.criteria == criteria) {
+            self.switch_section(i + 1);
+            return Action::None;
+        }
+        let key = format!("[{}]", criteria);
+        self.cfg.sections.push(Section { criteria, params: Vec::new() });
+        self.switch_section(self.cfg.sections.len());
+        Action::Save { key, value: String::new() }
+    }
+
+    /// Set the picked `value`: on param `idx`, or as a new `key`.
+    fn pick(&mut self, idx: Option<usize>, key: String, value: String) -> Action {
+        self.params_screen = ParamsScreen::List;
+        match idx {
+            Some(i) => self.cfg.params_of_mut(self.section)[i].value = value.clone(),
+            None => {
+                self.cfg.add_param(self.section, key.clone(), value.clone());
+                self.list_state.select(Some(self.cfg.params_of(self.section).len() - 1));
+            }
+        }
+        Action::Save { key, value }
+    }
+
+    /// The key the docs pane is about: the one being edited, else the
+    /// highlighted row's.
+    pub fn docs_key(&self) -> Option<&str> {
+        let selected = self.list_state.selected().and_then(|i| self.cfg.params_of(self.section).get(i));
+        self.editing_key().or(selected.map(|p| p.key.as_str())).or(self.unset)
+    }
+
+    /// Whether the docs pane is showing `history` or `max-history`, which
+    /// it gives the size of mako's history for.
+    pub fn docs_show_history(&self) -> bool {
+        self.show_docs && matches!(self.docs_key(), Some("history" | "max-history"))
+    }
+
+    /// Whether the notifications tab is listing what mako shows, which
+    /// follows mako on its own.
+    pub fn notifications_showing(&self) -> bool {
+        self.overlay.is_none() && self.params_screen == ParamsScreen::List && self.tab == Tab::Notifications
+    }
+
+    /// Whether anything on screen follows the outside world, so the runtime
+    /// should keep refreshing it: the header's daemon status, the
+    /// notifications list, or the history size in the docs pane. Never with
+    /// `IdleMode::Block`.
+    pub fn wants_refresh(&self) -> bool {
+        self.settings.idle_mode == IdleMode::Poll && (!self.compact || self.notifications_showing() || self.docs_show_history())
+    }
+
+    /// The key whose value is being typed, in the edit and add-value
+    /// prompts.
+    pub fn editing_key(&self) -> Option<&str> {
+        match &self.params_screen {
+            ParamsScreen::EditValue { idx, .. } => self.cfg.params_of(self.section).get(*idx).map(|p| p.key.as_str()),
+            ParamsScreen::AddValue { key, .. } | ParamsScreen::EditSides { key, .. } => Some(key),
+            _ => None,
+        }
+    }
+
+    /// Whether the config differs from what is on disk, e.g. because the
+    /// last save failed.
+    pub fn is_dirty(&self) -> bool {
+        self.cfg.to_string() != self.saved_text
+    }
+
+    /// Record that the config was just written to disk.
+    pub fn mark_saved(&mut self) {
+        self.saved_text = self.cfg.to_string();
+        self.pending.clear();
+    }
+
+    /// A mouse event: in the params list a click selects the row under
+    /// it, a double-click edits it and the wheel moves the selection. The
+    /// wheel also scrolls the help overlay.
+    pub fn handle_mouse(&mut self, event: MouseEvent) -> Action {
+        if let Some(scroll) = &mut self.help {
+            match event.kind {
+                MouseEventKind::ScrollDown => *scroll += 1,
+                MouseEventKind::ScrollUp => *scroll = scroll.saturating_sub(1),
+                _ => {}
+            }
+            return Action::None;
+        }
+        if !self.params_showing() {
+            return Action::None;
+        }
+        match event.kind {
+            MouseEventKind::ScrollDown => self.step_selection(1),
+            MouseEventKind::ScrollUp => self.step_selection(-1),
+            MouseEventKind::Down(MouseButton::Left) => {
+                let Some((area, top)) = self.list_hitbox else { return Action::None };
+                if !area.contains(Position { x: event.column, y: event.row }) {
+                    return Action::None;
+                }
+                let idx = match self.rows().get(top + usize::from(event.row - area.y)) {
+                    Some(&Row::Param(idx)) => idx,
+                    Some(&Row::Header(c)) => {
+                        self.select_row(Row::Header(c));
+                        return self.handle_key(KeyEvent::from(KeyCode::Enter));
+                    }
+                    Some(&Row::Unset(k)) => {
+                        self.select_row(Row::Unset(k));
+                        return Action::None;
+                    }
+                    None => return Action::None,
+                };
+                let now = Instant::now();
+                let double = self.last_click.is_some_and(|(i, at)| i == idx && now.duration_since(at) <= DOUBLE_CLICK);
+                self.list_state.select(Some(idx));
+                if double {
+                    self.last_click = None;
+                    return self.handle_key(KeyEvent::from(KeyCode::Enter));
+                }
+                self.last_click = Some((idx, now));
+            }
+            _ => {}
+        }
+        Action::None
+    }
+
+    /// Insert pasted text into the active prompt in one go. Config values
+    /// are single-line, so line breaks are folded into spaces (a trailing
+    /// one, as copied from a terminal, is dropped). Outside a prompt a
+    /// paste is ignored rather than replayed as key presses.
+    pub fn handle_paste(&mut self, text: &str) -> Action {
+        // The config buffer keeps the lines.
+        if let Some(Overlay::PasteConfig { input }) = &mut self.overlay {
+            input.push_str(&text.replace("\r\n", "\n").replace('\r', "\n"));
+            return Action::None;
+        }
+        let text = text.trim_end_matches(['\r', '\n']).replace("\r\n", " ").replace(['\r', '\n'], " ");
+        let add_key = matches!(self.params_screen, ParamsScreen::AddKey { .. });
+        let cursor = self.cursor;
+        if let Some(input) = self.input_mut() {
+            line_edit::insert_str(input, cursor, &text);
+            if add_key {
+                self.key_list_state.select(Some(0));
+            }
+        }
+        Action::None
+    }
+
+    /// Indices of the params the list shows, in order.
+    pub fn visible_params(&self) -> Vec<usize> {
+        let params = self.cfg.params_of(self.section);
+        let query = match &self.params_screen {
+            ParamsScreen::Search { input } => Some(input.as_str()),
+            _ => self.search.as_deref(),
+        }
+        .map(str::to_lowercase);
+        let mut visible: Vec<usize> = (0..params.len())
+            .filter(|&i| !self.only_customized || !is_default(&params[i].key, &params[i].value))
+            .filter(|&i| {
+                query.as_deref().is_none_or(|q| params[i].key.to_lowercase().contains(q) || params[i].value.to_lowercase().contains(q))
+            })
+            .collect();
+        self.sort.apply(params, &mut visible);
+        visible
+    }
+
+    /// With `show_unset`, the known keys the section doesn't set that the
+    /// search matches, in `known_keys` order.
+    pub fn unset_keys(&self) -> Vec<&'static str> {
+        if !self.show_unset {
+            return Vec::new();
+        }
+        let params = self.cfg.params_of(self.section);
+        let query = match &self.params_screen {
+            ParamsScreen::Search { input } => Some(input.as_str()),
+            _ => self.search.as_deref(),
+        }
+        .map(str::to_lowercase);
+        known_keys()
+            .into_iter()
+            .map(|(k, _)| k)
+            .filter(|k| *k != "<custom>" && deprecated(k).is_none() && !params.iter().any(|p| p.key == *k))
+            .filter(|k| query.as_deref().is_none_or(|q| k.contains(q)))
+            .collect()
+    }
+
+    /// The rows of the params list: the visible params, under a header per
+    /// category in category order, leaving out collapsed categories'
+    /// params.
+    pub fn rows(&self) -> Vec<Row> {
+        let visible = self.visible_params();
+        let unset = self.unset_keys();
+        if self.sort != SortOrder::Category {
+            return visible.into_iter().map(Row::Param).chain(unset.into_iter().map(Row::Unset)).collect();
+        }
+        let params = self.cfg.params_of(self.section);
+        let mut rows = Vec::new();
+        for &c in Category::ALL {
+            let members: Vec<usize> = visible.iter().copied().filter(|&i| category(&params[i].key) == c).collect();
+            let unset_members: Vec<&'static str> = unset.iter().copied().filter(|k| category(k) == c).collect();
+            if members.is_empty() && unset_members.is_empty() {
+                continue;
+            }
+            rows.push(Row::Header(c));
+            if !self.collapsed.contains(&c) {
+                rows.extend(members.into_iter().map(Row::Param));
+                rows.extend(unset_members.into_iter().map(Row::Unset));
+            }
+        }
+        rows
+    }
+
+    /// Where the selection is in `rows`: the selected param, else the
+    /// selected header.
+    pub fn selected_row(&self, rows: &[Row]) -> Option<usize> {
+        match (self.list_state.selected(), self.header, self.unset) {
+            (Some(i), _, _) => rows.iter().position(|&r| r == Row::Param(i)),
+            (None, Some(c), _) => rows.iter().position(|&r| r == Row::Header(c)),
+            (None, None, Some(k)) => rows.iter().position(|&r| r == Row::Unset(k)),
+            (None, None, None) => None,
+        }
+    }
+
+    /// Select `row`.
+    fn select_row(&mut self, row: Row) {
+        match row {
+            Row::Header(c) => {
+                self.list_state.select(None);
+                self.header = Some(c);
+                self.unset = None;
+            }
+            Row::Param(i) => {
+                self.list_state.select(Some(i));
+                self.header = None;
+                self.unset = None;
+            }
+            Row::Unset(k) => {
+                self.list_state.select(None);
+                self.header = None;
+                self.unset = Some(k);
+            }
+        }
+    }
+
+    /// Rewrite the section's params in the order the list shows them, and
+    /// go back to file order, which now is the same.
+    fn apply_sort(&mut self) -> Action {
+        if self.sort == SortOrder::File {
+            self.message = Some(Ok(tr("sort.nothing_to_apply").to_string()));
+            return Action::None;
+        }
+        let params = self.cfg.params_of_mut(self.section);
+        let mut order: Vec<usize> = (0..params.len()).collect();
+        self.sort.apply(params, &mut order);
+        let sorted: Vec<Param> = order.iter().map(|&i| params[i].clone()).collect();
+        *params = sorted;
+        let selected = self.list_state.selected().and_then(|i| order.iter().position(|&o| o == i));
+        self.list_state.select(selected);
+        let label = tr(self.sort.label_id());
+        self.sort = SortOrder::File;
+        self.step_selection(0);
+        self.message = Some(Ok(trf("sort.applied", &[&label])));
+        Action::Save { key: "<order>".to_string(), value: label.to_string() }
+    }
+
+    /// Whether the params list is on screen with no prompt over it: the
+    /// Global tab, or a section opened from the Sections tab.
+    pub fn params_showing(&self) -> bool {
+        self.params_screen == ParamsScreen::List
+            && self.overlay.is_none()
+            && match self.tab {
+                Tab::Global => true,
+                Tab::Sections => self.section > 0,
+                Tab::Modes | Tab::Notifications => false,
+            }
+    }
+
+    /// The text being typed in the open prompt: the overlay's, the params
+    /// screen's, or the Modes tab's new mode.
+    pub fn input_mut(&mut self) -> Option<&mut String> {
+        match (&mut self.overlay, &mut self.params_screen, self.tab, &mut self.modes_screen) {
+            (Some(overlay), ..) => overlay.input_mut(),
+            (None, ParamsScreen::List, Tab::Modes, ModesScreen::Add { input }) => Some(input),
+            (None, screen, ..) => screen.input_mut(),
+        }
+    }
+
+    /// Which prompt is open, to put the cursor back at the end when it
+    /// changes.
+    fn prompt(&self) -> (Option<mem::Discriminant<Overlay>>, mem::Discriminant<ParamsScreen>, mem::Discriminant<ModesScreen>) {
+        (self.overlay.as_ref().map(mem::discriminant), mem::discriminant(&self.params_screen), mem::discriminant(&self.modes_screen))
+    }
+
+    /// Go `by` tabs along, wrapping around and skipping Sections while the
+    /// config has none.
+    fn step_tab(&mut self, by: isize) -> Action {
+        let tabs: Vec<Tab> = Tab::ALL.iter().copied().filter(|&t| t != Tab::Sections || !self.cfg.sections.is_empty()).collect();
+        let pos = tabs.iter().position(|&t| t == self.tab).unwrap_or(0) as isize;
+        let tab = tabs[(pos + by).rem_euclid(tabs.len() as isize) as usize];
+        match tab {
+            Tab::Global => self.switch_section(0),
+            Tab::Sections => {
+                let idx = self.section_list.min(self.cfg.sections.len() - 1);
+                self.switch_section(0);
+                self.section_list = idx;
+            }
+            Tab::Modes => {
+                self.tab = tab;
+                return Action::ListModes;
+            }
+            Tab::Notifications => {
+                self.tab = tab;
+                return Action::ListNotifications;
+            }
+        }
+        self.tab = tab;
+        Action::None
+    }
+
+    /// Back from the Modes or Notifications tab, or the Sections tab's
+    /// list, to the params last shown.
+    fn back_to_params(&mut self) {
+        self.tab = if self.section > 0 { Tab::Sections } else { Tab::Global };
+    }
+
+    /// Keys on the Sections tab's list of sections.
+    fn section_list_key(&mut self, key: KeyEvent) -> Action {
+        let count = self.cfg.sections.len();
+        let idx = &mut self.section_list;
+        match key.code {
+            KeyCode::Esc | KeyCode::Char('q') => self.back_to_params(),
+            KeyCode::Down | KeyCode::Char('j') if count > 0 => *idx = (*idx + 1) % count,
+            KeyCode::Up | KeyCode::Char('k') if count > 0 => *idx = if *idx == 0 { count - 1 } else { *idx - 1 },
+            KeyCode::Enter | KeyCode::Right if *idx < count => {
+                let n = *idx + 1;
+                self.switch_section(n);
+            }
+            KeyCode::Char(c) if c == self.settings.keys.key(Command::AddSection) => {
+                self.params_screen = ParamsScreen::BuildCriteria { criteria: Criteria::default(), idx: 0 };
+            }
+            _ => {}
+        }
+        Action::None
+    }
+
+    /// Keys on the Modes tab.
+    fn modes_key(&mut self, key: KeyEvent) -> Action {
+        match &mut self.modes_screen {
+            ModesScreen::List { idx } => {
+                let (idx, rows) = (*idx, self.mode_rows());
+                match key.code {
+                    KeyCode::Esc | KeyCode::Char('q') => self.back_to_params(),
+                    KeyCode::Char(c) if c == self.settings.keys.key(Command::Modes) => self.back_to_params(),
+                    KeyCode::Down | KeyCode::Char('j') if !rows.is_empty() => {
+                        self.modes_screen = ModesScreen::List { idx: (idx + 1) % rows.len() };
+                    }
+                    KeyCode::Up | KeyCode::Char('k') if !rows.is_empty() => {
+                        self.modes_screen = ModesScreen::List { idx: if idx == 0 { rows.len() - 1 } else { idx - 1 } };
+                    }
+                    KeyCode::Char('r') => return Action::ListModes,
+                    KeyCode::Char(' ' | 't') => {
+                        if let Some(name) = rows.get(idx) {
+                            return Action::ToggleMode { name: name.clone() };
+                        }
+                    }
+                    KeyCode::Enter => {
+                        if let Some(name) = rows.get(idx) {
+                            return self.add_section(Criteria::mode(name));
+                        }
+                    }
+                    KeyCode::Char('a') => self.modes_screen = ModesScreen::Add { input: String::new() },
+                    _ => {}
+                }
+            }
+            ModesScreen::Add { input } => match key.code {
+                KeyCode::Esc => self.modes_screen = ModesScreen::List { idx: 0 },
+                KeyCode::Enter => {
+                    let name = input.trim().to_string();
+                    self.modes_screen = ModesScreen::List { idx: 0 };
+                    if !name.is_empty() {
+                        return self.add_section(Criteria::mode(&name));
+                    }
+                }
+                _ if line_edit::edit(input, &mut self.cursor, key) => {}
+                _ => {}
+            },
+        }
+        Action::None
+    }
+
+    /// Keys on the Notifications tab.
+    fn notifications_key(&mut self, key: KeyEvent) -> Action {
+        match &mut self.notifications_screen {
+            NotificationsScreen::List { idx } => {
+                let count = self.notifications.as_ref().map_or(0, Vec::len);
+                match key.code {
+                    KeyCode::Esc | KeyCode::Char('q') => self.back_to_params(),
+                    KeyCode::Char(c) if c == self.settings.keys.key(Command::Notifications) => self.back_to_params(),
+                    KeyCode::Down | KeyCode::Char('j') if count > 0 => *idx = (*idx + 1) % count,
+                    KeyCode::Up | KeyCode::Char('k') if count > 0 => *idx = if *idx == 0 { count - 1 } else { *idx - 1 },
+                    KeyCode::Char('r') => return Action::ListNotifications,
+                    KeyCode::Char('x') => {
+                        if let Some(n) = self.notifications.as_ref().ok().and_then(|list| list.get(*idx)) {
+                            return Action::Dismiss { id: Some(n.id) };
+                        }
+                    }
+                    KeyCode::Char('X') if count > 0 => return Action::Dismiss { id: None },
+                    KeyCode::Char('a') => match self.notifications.as_ref().ok().and_then(|list| list.get(*idx)) {
+                        Some(n) if n.actions.is_empty() => self.message = Some(Err(trf("footer.no_actions", &[&n.summary]))),
+                        Some(n) => {
+                            self.notifications_screen =
+                                NotificationsScreen::Actions { notification: *idx, id: n.id, actions: n.actions.clone(), idx: 0 };
+                        }
+                        None => {}
+                    },
+                    _ => {}
+                }
+            }
+            NotificationsScreen::Actions { notification, id, actions, idx } => match key.code {
+                KeyCode::Esc | KeyCode::Char('q') => self.notifications_screen = NotificationsScreen::List { idx: *notification },
+                KeyCode::Down | KeyCode::Char('j') => *idx = (*idx + 1) % actions.len(),
+                KeyCode::Up | KeyCode::Char('k') => *idx = if *idx == 0 { actions.len() - 1 } else { *idx - 1 },
+                KeyCode::Enter => {
+                    let action = Action::Invoke { id: *id, action: actions[*idx].0.clone() };
+                    self.notifications_screen = NotificationsScreen::List { idx: *notification };
+                    return action;
+                }
+                _ => {}
+            },
+        }
+        Action::None
+    }
+
+    /// Edit section `n` instead, starting at its first param.
+    pub fn switch_section(&mut self, n: usize) {
+        self.section = n.min(self.cfg.sections.len());
+        if self.section > 0 {
+            self.section_list = self.section - 1;
+            self.tab = Tab::Sections;
+        } else {
+            self.tab = Tab::Global;
+        }
+        self.marked.clear();
+        self.list_state = ListState::default();
+        self.list_state.select(Some(0));
+        self.header = None;
+        self.unset = None;
+        self.step_selection(0);
+    }
+
+    /// Show section `n` with param `idx` selected (the first one if
+    /// `None`), clearing the filters and expanding the category that would
+    /// hide it.
+    fn reveal(&mut self, n: usize, idx: Option<usize>) {
+        self.switch_section(n);
+        let Some(i) = idx else { return };
+        self.search = None;
+        self.only_customized = false;
+        let key = self.cfg.params_of(self.section)[i].key.clone();
+        self.collapsed.retain(|&c| c != category(&key));
+        self.select_row(Row::Param(i));
+    }
+
+    /// Swap the selected param with the visible one `by` rows away,
+    /// keeping it selected. Nothing happens at either end of the list.
+    fn move_selected(&mut self, by: isize) -> Action {
+        let visible = self.visible_params();
+        let Some(pos) = self.list_state.selected().and_then(|i| visible.iter().position(|&v| v == i)) else {
+            return Action::None;
+        };
+        let Some(&other) = pos.checked_add_signed(by).and_then(|p| visible.get(p)) else { return Action::None };
+        let params = self.cfg.params_of_mut(self.section);
+        params.swap(visible[pos], other);
+        let moved = params[other].clone();
+        self.list_state.select(Some(other));
+        Action::Save { key: moved.key, value: moved.value }
+    }
+
+    /// Move the selection `by` visible rows, wrapping around. With `by` 0
+    /// this only makes sure a visible param is selected.
+    fn step_selection(&mut self, by: isize) {
+        let rows = self.rows();
+        if rows.is_empty() {
+            self.list_state.select(None);
+            self.header = None;
+            self.unset = None;
+            return;
+        }
+        let selected = self.list_state.selected();
+        let pos = match self.selected_row(&rows) {
+            Some(pos) => (pos as isize + by).rem_euclid(rows.len() as isize) as usize,
+            // The selection is hidden: take the next visible param.
+            None => rows.iter().position(|&r| matches!(r, Row::Param(v) if v >= selected.unwrap_or(0))).unwrap_or(0),
+        };
+        self.select_row(rows[pos]);
+    }
+
+    /// Move the selection `by` rows, stopping at either end rather than
+    /// wrapping around like `step_selection`.
+    fn move_selection(&mut self, by: isize) {
+        let rows = self.rows();
+        if rows.is_empty() {
+            return;
+        }
+        let pos = self.selected_row(&rows).unwrap_or(0) as isize;
+        self.select_row(rows[pos.saturating_add(by).clamp(0, rows.len() as isize - 1) as usize]);
+    }
+
+    /// How far PageUp/PageDown move: the params list's height as last
+    /// drawn, less a row kept in view for context.
+    fn page_rows(&self) -> isize {
+        self.list_hitbox.map_or(10, |(area, _)| (area.height as isize - 1).max(1))
+    }
+
+    /// The terminal was resized. Clamp every selection to its list and
+    /// reset scroll offsets so the next frame scrolls each list afresh for
+    /// the new height instead of keeping an offset computed for the old one.
+    pub fn handle_resize(&mut self) {
+        let params = self.cfg.params_of(self.section).len();
+        self.list_state.select(match self.list_state.selected() {
+            _ if params == 0 => None,
+            Some(i) => Some(i.min(params - 1)),
+            None if self.header.is_some() || self.unset.is_some() => None,
+            None => Some(0),
+        });
+        *self.list_state.offset_mut() = 0;
+        if let ParamsScreen::AddKey { input } = &self.params_screen {
+            let keys = filtered_known_keys(input, &self.plugins, self.section).len();
+            let i = self.key_list_state.selected().unwrap_or(0);
+            self.key_list_state.select(Some(i.min(keys.saturating_sub(1))));
+        }
+        *self.key_list_state.offset_mut() = 0;
+        let settings = self.settings.fields().len();
+        match &mut self.overlay {
+            Some(Overlay::Settings { idx } | Overlay::EditSetting { idx, .. }) => *idx = (*idx).min(settings - 1),
+            Some(Overlay::Trash { idx }) => *idx = (*idx).min(self.trash.items.len().saturating_sub(1)),
+            _ => {}
+        }
+        match &mut self.params_screen {
+            ParamsScreen::EditValue { idx, .. } | ParamsScreen::ConfirmDelete { idx } | ParamsScreen::Duplicate { idx, .. } if *idx >= params => {
+                self.params_screen = ParamsScreen::List
+            }
+            ParamsScreen::PickValue { idx: Some(idx), .. }
+            | ParamsScreen::PickAnchor { idx: Some(idx), .. }
+            | ParamsScreen::EditSides { idx: Some(idx), .. }
+                if *idx >= params =>
+            {
+                self.params_screen = ParamsScreen::List
+            }
+            _ => {}
+        }
+    }
+
+    /// The modes view's rows: the modes that are on, then those the config
+    /// styles but that are off.
+    pub fn mode_rows(&self) -> Vec<String> {
+        let mut rows = self.modes.clone().unwrap_or_default();
+        for name in self.cfg.modes() {
+            if !rows.iter().any(|r| r == name) {
+                rows.push(name.to_string());
+            }
+        }
+        rows
+    }
+
+    /// Store what `makoctl mode` returned, keeping the highlighted row in
+    /// range.
+    pub fn set_modes(&mut self, modes: Result<Vec<String>, String>) {
+        self.modes = modes;
+        let count = self.mode_rows().len();
+        if let ModesScreen::List { idx } = &mut self.modes_screen {
+            *idx = (*idx).min(count.saturating_sub(1));
+        }
+    }
+
+    /// Store what `makoctl list` returned, keeping the highlighted row in
+    /// range as notifications come and go. Returns whether it differs from
+    /// what was there.
+    pub fn set_notifications(&mut self, notifications: Result<Vec<Notification>, String>) -> bool {
+        let count = notifications.as_ref().map_or(0, Vec::len);
+        if let NotificationsScreen::List { idx } = &mut self.notifications_screen {
+            *idx = (*idx).min(count.saturating_sub(1));
+        }
+        std::mem::replace(&mut self.notifications, notifications) != self.notifications
+    }
+
+    /// Put the yanked params into the current section after the selected
+    /// one; a key the section already has takes the yanked value instead.
+    fn paste_yanked(&mut self) -> Action {
+        let mut at = self.list_state.selected().map_or(self.cfg.params_of(self.section).len(), |i| i + 1);
+        let mut last = 0;
+        for p in self.yanked.clone() {
+            let params = self.cfg.params_of_mut(self.section);
+            match params.iter().position(|q| q.key == p.key) {
+                Some(i) => {
+                    params[i].value = p.value;
+                    last = i;
+                }
+                None => {
+                    params.insert(at, p);
+                    last = at;
+                    at += 1;
+                }
+            }
+        }
+        self.list_state.select(Some(last));
+        let keys: Vec<&str> = self.yanked.iter().map(|p| p.key.as_str()).collect();
+        let value = match self.yanked.as_slice() {
+            [p] => p.value.clone(),
+            _ => "<pasted>".to_string(),
+        };
+        Action::Save { key: keys.join(", "), value }
+    }
+
+    /// Delete or change every marked param at once, as one change to undo.
+    fn apply_bulk(&mut self, change: BulkChange) -> Action {
+        let mut marked = mem::take(&mut self.marked);
+        let keys: Vec<String> = marked.iter().map(|&i| self.cfg.params_of(self.section)[i].key.clone()).collect();
+        let value = match change {
+            BulkChange::Delete => {
+                marked.sort_unstable_by(|a, b| b.cmp(a));
+                for i in marked {
+                    let removed = self.cfg.params_of_mut(self.section).remove(i);
+                    self.trash.push(removed);
+                }
+                let len = self.cfg.params_of(self.section).len();
+                self.list_state.select((len > 0).then(|| self.list_state.selected().unwrap_or(0).min(len - 1)));
+                "<deleted>".to_string()
+            }
+            BulkChange::Set { pattern, values } => {
+                for (i, value) in values {
+                    self.cfg.params_of_mut(self.section)[i].value = value;
+                }
+                pattern
+            }
+            BulkChange::Replace { to, values, .. } => {
+                let mut keys: Vec<String> = Vec::new();
+                for (n, i, value) in values {
+                    let p = &mut self.cfg.params_of_mut(n)[i];
+                    p.value = value;
+                    if !keys.contains(&p.key) {
+                        keys.push(p.key.clone());
+                    }
+                }
+                return Action::Save { key: keys.join(", "), value: to };
+            }
+        };
+        Action::Save { key: keys.join(", "), value }
+    }
+
+    /// Carry out a `:` command, as the key bindings it stands for would.
+    fn run_ex(&mut self, cmd: ExCommand) -> Action {
+        match cmd {
+            ExCommand::Write if self.is_dirty() => return Action::Write,
+            ExCommand::Write => self.message = Some(Ok(tr("footer.nothing_to_write").to_string())),
+            ExCommand::Quit { force: false } if self.is_dirty() => self.overlay = Some(Overlay::ConfirmQuit),
+            ExCommand::Quit { .. } => return Action::Quit,
+            ExCommand::WriteQuit if self.is_dirty() => return Action::SaveAndQuit,
+            ExCommand::WriteQuit => return Action::Quit,
+            ExCommand::Set { key, value } => {
+                let value = normalize_value(&key, &value);
+                if let Err(e) = validate(&key, &self.cfg.expand(&value)) {
+                    self.message = Some(Err(e));
+                    return Action::None;
+                }
+                self.cfg.set(self.section, &key, value.clone());
+                let idx = self.cfg.params_of(self.section).iter().rposition(|p| p.key == key);
+                self.reveal(self.section, idx);
+                return Action::Save { key, value };
+            }
+            ExCommand::Delete { key } => {
+                let (kept, removed): (Vec<Param>, Vec<Param>) =
+                    mem::take(self.cfg.params_of_mut(self.section)).into_iter().partition(|p| p.key != key);
+                *self.cfg.params_of_mut(self.section) = kept;
+                if removed.is_empty() {
+                    self.message = Some(Err(trf("cli.not_set", &[&key])));
+                    return Action::None;
+                }
+                for p in removed {
+                    self.trash.push(p);
+                }
+                let len = self.cfg.params_of(self.section).len();
+                self.list_state.select((len > 0).then(|| self.list_state.selected().unwrap_or(0).min(len - 1)));
+                return Action::Save { key, value: "<deleted>".to_string() };
+            }
+            ExCommand::Profile { .. } if self.is_dirty() => self.message = Some(Err(tr("cmdline.unsaved").to_string())),
+            ExCommand::Profile { name } => match self.settings.profiles.iter().find(|(n, _)| *n == name) {
+                Some((_, path)) => return Action::OpenProfile { path: path.clone() },
+                None => {
+                    let names: Vec<&str> = self.settings.profiles.iter().map(|(n, _)| n.as_str()).collect();
+                    self.message = Some(Err(trf("cmdline.no_profile", &[&name, &names.join(", ")])));
+                }
+            },
+            ExCommand::Reload => return Action::Reload,
+            ExCommand::Replace { from, to, all_sections, colors } => {
+                let sections = if all_sections { 0..self.cfg.section_count() } else { self.section..self.section + 1 };
+                let mut values = Vec::new();
+                let mut count = 0;
+                for n in sections {
+                    for (i, p) in self.cfg.params_of(n).iter().enumerate() {
+                        let matches = p.value.matches(&from).count();
+                        if matches == 0 || colors && !takes_color(&p.key) {
+                            continue;
+                        }
+                        let value = normalize_value(&p.key, &p.value.replace(&from, &to));
+                        if let Err(e) = validate(&p.key, &self.cfg.expand(&value)) {
+                            self.message = Some(Err(trf("bulk.invalid", &[&p.key, &e])));
+                            return Action::None;
+                        }
+                        count += matches;
+                        values.push((n, i, value));
+                    }
+                }
+                if values.is_empty() {
+                    self.message = Some(Err(trf("cmdline.no_match", &[&from])));
+                } else {
+                    self.params_screen = ParamsScreen::ConfirmBulk { change: BulkChange::Replace { from, to, count, values } };
+                }
+            }
+        }
+        Action::None
+    }
+
+    /// Edit `cfg` from now on, e.g. another profile's file, starting at its
+    /// global params like the editor does at startup. Settings, the trash
+    /// and the log carry over.
+    pub fn open(&mut self, mut cfg: Config) {
+        cfg.palette = self.settings.palette.clone();
+        self.list_state.select((!cfg.params.is_empty()).then_some(0));
+        self.saved_text = cfg.to_string();
+        self.cfg = cfg;
+        self.params_screen = ParamsScreen::List;
+        self.section = 0;
+        self.tab = Tab::Global;
+        self.section_list = 0;
+        self.header = None;
+        self.unset = None;
+        self.marked.clear();
+        self.search = None;
+        self.collapsed.clear();
+        self.pending.clear();
+        self.undo_stack.clear();
+        self.redo_stack.clear();
+        self.refused = None;
+    }
+
+    /// Log that `what` was saved, and the problems `check` finds in the
+    /// file as written.
+    pub fn log_saved(&mut self, what: String) {
+        self.activity.push(Kind::Save, false, what);
+        let problems = check::check(&self.cfg.to_string());
+        if !problems.is_empty() {
+            let failed = problems.iter().any(|p| p.severity == check::Severity::Error);
+            let lines: Vec<String> = problems.iter().map(|p| p.to_string()).collect();
+            self.activity.push(Kind::Problem, failed, format!("{}\n{}", trf("log.problems", &[&problems.len()]), lines.join("\n")));
+        }
+    }
+
+    /// Fill the git log the user opened with what `git::history` returned,
+    /// unless they closed it in the meantime.
+    pub fn set_git_log(&mut self, history: Result<Vec<git::Commit>, String>) {
+        if let Some(Overlay::GitLog { commits, .. }) = &mut self.overlay {
+            *commits = Some(history);
+        }
+    }
+
+    /// Store what `makoctl history` returned, like `set_notifications`.
+    pub fn set_history(&mut self, history: Result<Vec<Notification>, String>) -> bool {
+        let count = history.as_ref().map_or(0, Vec::len);
+        if let Some(Overlay::History { idx }) = &mut self.overlay {
+            *idx = (*idx).min(count.saturating_sub(1));
+        }
+        std::mem::replace(&mut self.history, history) != self.history
+    }
+}
+
+/// `~/x` -> `$HOME/x`, as typed in path prompts.
+fn expand_tilde(path: &str) -> PathBuf {
+    match (path.strip_prefix("~/"), home::home_dir()) {
+        (Some(rest), Some(home)) => home.join(rest),
+        _ => PathBuf::from(path),
+    }
+}
+
+/// The prompt for a value of `key`: a pick list when mako only accepts
+/// certain values, otherwise a text prompt starting at `current`. `idx`
+/// is the param being edited, or `None` when adding one.
+fn value_prompt(key: String, idx: Option<usize>, current: String) -> ParamsScreen {
+    if key == "anchor" || key == "anchor-point" {
+        let value = if current.trim().is_empty() { default_value("anchor").unwrap_or_default() } else { current.trim() };
+        let cell = ANCHOR_GRID.iter().position(|v| *v == value).unwrap_or(2);
+        return ParamsScreen::PickAnchor { idx, key, cell };
+    }
+    if matches!(key.as_str(), "margin" | "outer-margin" | "padding" | "border-radius") {
+        let value = if current.trim().is_empty() { default_value(&key).unwrap_or_default() } else { &current };
+        // A value mako wouldn't read is left to the text prompt to fix.
+        if let Some(sides) = parse_sides(value) {
+            return ParamsScreen::EditSides { idx, key, sides: expand_sides(&sides), side: 0, typed: false };
+        }
+    }
+    match (allowed_values(&key), idx) {
+        (Some(values), _) => ParamsScreen::PickValue { choice: values.iter().position(|v| *v == current.trim()).unwrap_or(0), idx, key },
+        (None, Some(idx)) => ParamsScreen::EditValue { idx, input: current },
+        (None, None) => ParamsScreen::AddValue { key, input: current },
+    }
+}
+
+/// Whether Ctrl is held, as for Ctrl-O (open the path browser).
+fn is_ctrl(key: KeyEvent) -> bool {
+    key.modifiers.contains(KeyModifiers::CONTROL)
+}
+
+/// Known keys (built-in followed by plugin-provided ones) matching `input`:
+/// keys it fuzzy-matches (see `fuzzy::score`) best first, then keys whose
+/// description contains it, ignoring case. Used both to render the chooser
+/// and to resolve the highlighted row, so the two always agree.
+pub fn filtered_known_keys(input: &str, plugins: &Plugins, section: usize) -> Vec<(String, String)> {
+    let filter = input.to_lowercase();
+    let mut matches: Vec<(i32, (String, String))> = known_keys()
+        .into_iter()
+        .filter(|(k, _)| fits_scope(k, section > 0))
+        .map(|(k, d)| (k.to_string(), d.to_string()))
+        .chain(plugins.known_keys())
+        .filter_map(|(k, d)| match fuzzy::score(&filter, &k) {
+            Some((score, _)) => Some((score, (k, d))),
+            None if d.to_lowercase().contains(&filter) => Some((i32::MIN, (k, d))),
+            None => None,
+        })
+        .collect();
+    // Stable, so equally good matches keep the list's order.
+    matches.sort_by_key(|(score, _)| std::cmp::Reverse(*score));
+    matches.into_iter().map(|(_, key)| key).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::Param;
+    use crate::mako_config::Category;
+    use crate::plugin::Plugin;
+
+    fn press(app: &mut App, code: KeyCode) -> Action {
+        app.handle_key(KeyEvent::from(code))
+    }
+
+    fn type_str(app: &mut App, s: &str) {
+        for c in s.chars() {
+            press(app, KeyCode::Char(c));
+        }
+    }
+
+    fn app_with(params: &[(&str, &str)]) -> App {
+        App::new(Config { params: params.iter().map(|(k, v)| Param::new(*k, *v)).collect(), ..Config::default() })
+    }
+
+    #[test]
+    fn new_selects_first_param_when_present() {
+        assert_eq!(app_with(&[("font", "mono 10")]).list_state.selected(), Some(0));
+        assert_eq!(app_with(&[]).list_state.selected(), None);
+    }
+
+    #[test]
+    fn normal_q_quits() {
+        let mut app = app_with(&[]);
+        assert_eq!(press(&mut app, KeyCode::Char('q')), Action::Quit);
+    }
+
+    #[test]
+    fn quitting_with_unsaved_changes_asks_first() {
+        let mut app = app_with(&[("font", "mono 10")]);
+        app.cfg.params[0].value = "mono 12".into();
+        assert_eq!(press(&mut app, KeyCode::Char('q')), Action::None);
+        assert_eq!(app.overlay, Some(Overlay::ConfirmQuit));
+        press(&mut app, KeyCode::Esc);
+        assert_eq!(app.overlay, None);
+        press(&mut app, KeyCode::Char('q'));
+        assert_eq!(press(&mut app, KeyCode::Char('s')), Action::SaveAndQuit);
+        press(&mut app, KeyCode::Char('q'));
+        assert_eq!(press(&mut app, KeyCode::Char('d')), Action::Quit);
+        app.mark_saved();
+        assert_eq!(press(&mut app, KeyCode::Char('q')), Action::Quit);
+    }
+
+    #[test]
+    fn deferred_write_holds_saves_until_w() {
+        let mut app = app_with(&[("border-size", "2")]);
+        app.settings.deferred_write = true;
+        press(&mut app, KeyCode::Char('w'));
+        assert_eq!(app.message, Some(Ok(tr("footer.nothing_to_write").to_string())));
+        assert_eq!(press(&mut app, KeyCode::Char('+')), Action::None);
+        assert_eq!(press(&mut app, KeyCode::Char('+')), Action::None);
+        assert_eq!(app.cfg.params[0].value, "4");
+        assert_eq!(app.pending.len(), 2);
+        assert!(app.is_dirty());
+        assert_eq!(press(&mut app, KeyCode::Char('u')), Action::None);
+        assert_eq!(app.pending.last(), Some(&("border-size".to_string(), "3".to_string())));
+        assert_eq!(press(&mut app, KeyCode::Char('w')), Action::Write);
+        app.mark_saved();
+        assert!(app.pending.is_empty());
+        app.settings.deferred_write = false;
+        assert!(matches!(press(&mut app, KeyCode::Char('+')), Action::Save { .. }));
+    }
+
+    #[test]
+    fn live_apply_hands_steps_to_the_runtime_to_batch() {
+        let mut app = app_with(&[("width", "300")]);
+        app.settings.live_apply = true;
+        assert_eq!(press(&mut app, KeyCode::Char('+')), Action::LiveApply);
+        assert_eq!(press(&mut app, KeyCode::Char('+')), Action::LiveApply);
+        assert_eq!(app.cfg.params[0].value, "320");
+        assert_eq!(app.pending.len(), 2);
+        app.settings.deferred_write = true;
+        assert_eq!(press(&mut app, KeyCode::Char('-')), Action::None);
+    }
+
+    #[test]
+    fn log_view_scrolls_every_line_of_the_activity() {
+        let mut app = app_with(&[("border-size", "2")]);
+        app.log_saved("border-size = 3".to_string());
+        app.activity.push(Kind::Reload, true, "config:1: bad\nconfig:2: worse");
+        press(&mut app, KeyCode::Char('E'));
+        assert_eq!(app.overlay, Some(Overlay::Log { idx: 0 }));
+        press(&mut app, KeyCode::End);
+        assert_eq!(app.overlay, Some(Overlay::Log { idx: 2 }));
+        press(&mut app, KeyCode::Down);
+        assert_eq!(app.overlay, Some(Overlay::Log { idx: 2 }));
+        press(&mut app, KeyCode::Char('E'));
+        assert_eq!(app.overlay, None);
+    }
+
+    #[test]
+    fn command_line_sets_deletes_and_quits() {
+        let mut app = app_with(&[("width", "300"), ("height", "100")]);
+        press(&mut app, KeyCode::Char(':'));
+        type_str(&mut app, "set border-color=#f00");
+        assert!(matches!(press(&mut app, KeyCode::Enter), Action::Save { .. }));
+        assert_eq!(app.cfg.params[2].value, "#ff0000");
+        assert_eq!(app.list_state.selected(), Some(2));
+        press(&mut app, KeyCode::Char(':'));
+        type_str(&mut app, "set width=wide");
+        assert_eq!(press(&mut app, KeyCode::Enter), Action::None);
+        assert!(matches!(app.message, Some(Err(_))));
+        press(&mut app, KeyCode::Char(':'));
+        type_str(&mut app, "d width");
+        assert!(matches!(press(&mut app, KeyCode::Enter), Action::Save { .. }));
+        assert_eq!(app.cfg.params.len(), 2);
+        assert_eq!(app.trash.items.len(), 1);
+        press(&mut app, KeyCode::Char(':'));
+        type_str(&mut app, "profile work");
+        assert_eq!(press(&mut app, KeyCode::Enter), Action::None);
+        assert_eq!(app.message, Some(Err(tr("cmdline.unsaved").to_string())));
+        press(&mut app, KeyCode::Char(':'));
+        type_str(&mut app, "q");
+        press(&mut app, KeyCode::Enter);
+        assert_eq!(app.overlay, Some(Overlay::ConfirmQuit));
+        press(&mut app, KeyCode::Esc);
+        app.mark_saved();
+        app.settings.profiles.push(("work".to_string(), PathBuf::from("/tmp/work")));
+        press(&mut app, KeyCode::Char(':'));
+        type_str(&mut app, "profile work");
+        assert_eq!(press(&mut app, KeyCode::Enter), Action::OpenProfile { path: PathBuf::from("/tmp/work") });
+        press(&mut app, KeyCode::Char(':'));
+        type_str(&mut app, "wq");
+        assert_eq!(press(&mut app, KeyCode::Enter), Action::Quit);
+    }
+
+    #[test]
+    fn space_marks_params_for_one_bulk_edit_or_delete() {
+        let mut app = app_with(&[("background-color", "#ffffff"), ("text-color", "#000000"), ("width", "300")]);
+        press(&mut app, KeyCode::Char(' '));
+        press(&mut app, KeyCode::Char(' '));
+        assert_eq!(app.marked, [0, 1]);
+        assert_eq!(app.list_state.selected(), Some(2));
+        press(&mut app, KeyCode::Char('e'));
+        assert_eq!(app.params_screen, ParamsScreen::BulkEdit { input: String::new() });
+        type_str(&mut app, "*cc");
+        press(&mut app, KeyCode::Enter);
+        assert!(matches!(app.params_screen, ParamsScreen::ConfirmBulk { .. }));
+        press(&mut app, KeyCode::Char('n'));
+        assert_eq!(app.marked, [0, 1]);
+        press(&mut app, KeyCode::Char('e'));
+        type_str(&mut app, "*cc");
+        press(&mut app, KeyCode::Enter);
+        let action = press(&mut app, KeyCode::Char('y'));
+        assert_eq!(action, Action::Save { key: "background-color, text-color".to_string(), value: "*cc".to_string() });
+        assert_eq!(app.cfg.params[0].value, "#ffffffcc");
+        assert_eq!(app.cfg.params[1].value, "#000000cc");
+        assert!(app.marked.is_empty());
+
+        // A value that doesn't suit every marked key stays in the prompt.
+        app.list_state.select(Some(1));
+        press(&mut app, KeyCode::Char(' '));
+        press(&mut app, KeyCode::Char(' '));
+        press(&mut app, KeyCode::Char('e'));
+        type_str(&mut app, "wide");
+        assert_eq!(press(&mut app, KeyCode::Enter), Action::None);
+        assert!(matches!(app.params_screen, ParamsScreen::BulkEdit { .. }));
+        press(&mut app, KeyCode::Esc);
+        press(&mut app, KeyCode::Char('d'));
+        assert!(matches!(press(&mut app, KeyCode::Char('y')), Action::Save { .. }));
+        assert_eq!(app.cfg.params.len(), 1);
+        assert_eq!(app.trash.items.len(), 2);
+        assert_eq!(app.list_state.selected(), Some(0));
+
+        press(&mut app, KeyCode::Char(' '));
+        press(&mut app, KeyCode::Esc);
+        assert!(app.marked.is_empty());
+    }
+
+    #[test]
+    fn o_cycles_the_list_order_and_shift_o_writes_it() {
+        let mut app = app_with(&[("on-touch", "dismiss"), ("width", "300"), ("icons", "1"), ("anchor", "top-left")]);
+        press(&mut app, KeyCode::Char('o'));
+        assert_eq!(app.sort, SortOrder::Alphabetical);
+        assert_eq!(app.visible_params(), vec![3, 2, 0, 1]);
+        press(&mut app, KeyCode::Char('o'));
+        assert_eq!(app.visible_params(), vec![1, 3, 2, 0]);
+        assert_eq!(app.list_state.selected(), Some(0));
+        assert_eq!(press(&mut app, KeyCode::Char('J')), Action::None);
+        assert_eq!(app.cfg.params[0].key, "on-touch");
+        assert!(matches!(press(&mut app, KeyCode::Char('O')), Action::Save { .. }));
+        let keys: Vec<&str> = app.cfg.params.iter().map(|p| p.key.as_str()).collect();
+        assert_eq!(keys, ["width", "anchor", "icons", "on-touch"]);
+        assert_eq!(app.sort, SortOrder::File);
+        assert_eq!(app.list_state.selected(), Some(3));
+        assert_eq!(press(&mut app, KeyCode::Char('O')), Action::None);
+    }
+
+    #[test]
+    fn category_order_groups_params_under_collapsible_headers() {
+        let mut app = app_with(&[("on-touch", "dismiss"), ("width", "300"), ("height", "100")]);
+        app.sort = SortOrder::Category;
+        let (appearance, bindings) = (Row::Header(Category::Appearance), Row::Header(Category::Bindings));
+        assert_eq!(app.rows(), vec![appearance, Row::Param(1), Row::Param(2), bindings, Row::Param(0)]);
+        press(&mut app, KeyCode::Char('k'));
+        assert_eq!((app.list_state.selected(), app.header), (None, Some(Category::Bindings)));
+        assert_eq!(press(&mut app, KeyCode::Enter), Action::None);
+        assert_eq!(app.rows(), vec![appearance, Row::Param(1), Row::Param(2), bindings]);
+        press(&mut app, KeyCode::Char('k'));
+        press(&mut app, KeyCode::Char('k'));
+        press(&mut app, KeyCode::Char('k'));
+        assert_eq!(app.header, Some(Category::Appearance));
+        press(&mut app, KeyCode::Left);
+        press(&mut app, KeyCode::Left);
+        assert_eq!(app.rows(), vec![appearance, bindings]);
+        press(&mut app, KeyCode::Right);
+        press(&mut app, KeyCode::Char('j'));
+        assert_eq!(app.list_state.selected(), Some(1));
+    }
+
+    #[test]
+    fn g_migrates_old_keys() {
+        let mut app = app_with(&[("text-align", "center"), ("layout", "normal"), ("font", "mono 10")]);
+        app.list_state.select(Some(2));
+        assert!(matches!(press(&mut app, KeyCode::Char('U')), Action::Save { .. }));
+        let keys: Vec<&str> = app.cfg.params.iter().map(|p| p.key.as_str()).collect();
+        assert_eq!(keys, ["text-alignment", "font"]);
+        assert_eq!(app.trash.items.last().map(|p| p.key.as_str()), Some("layout"));
+        assert_eq!(app.list_state.selected(), Some(0));
+        assert_eq!(press(&mut app, KeyCode::Char('U')), Action::None);
+        assert_eq!(app.message, Some(Ok(tr("migrate.nothing").to_string())));
+    }
+
+    #[test]
+    fn l_lists_problems_and_enter_goes_to_their_line() {
+        let mut app = app_with(&[("font", "mono 10"), ("width", "wide")]);
+        app.cfg.sections.push(Section { criteria: "urgency=critical".parse().unwrap(), params: vec![Param::new("border-size", "x")] });
+        app.search = Some("font".to_string());
+        press(&mut app, KeyCode::Char('L'));
+        let Some(Overlay::Problems { problems, .. }) = &app.overlay else { panic!("{:?}", app.overlay) };
+        assert_eq!(problems.iter().map(|p| p.line).collect::<Vec<_>>(), [2, 5]);
+        press(&mut app, KeyCode::Down);
+        press(&mut app, KeyCode::Enter);
+        assert_eq!(app.overlay, None);
+        assert_eq!((app.section, app.list_state.selected()), (1, Some(0)));
+        press(&mut app, KeyCode::Char('L'));
+        press(&mut app, KeyCode::Enter);
+        assert_eq!((app.section, app.list_state.selected(), app.search.as_deref()), (0, Some(1), None));
+        assert_eq!(app.cfg.locate_line(4), Some((1, None)));
+        assert_eq!(app.cfg.locate_line(6), None);
+    }
+
+    #[test]
+    fn i_toggles_the_docs_pane() {
+        let mut app = app_with(&[("font", "mono 10")]);
+        press(&mut app, KeyCode::Char('i'));
+        assert!(app.show_docs);
+        press(&mut app, KeyCode::Char('i'));
+        assert!(!app.show_docs);
+    }
+
+    #[test]
+    fn diff_view_scrolls_the_changes_and_writes_them() {
+        let mut app = app_with(&[("border-size", "2")]);
+        app.settings.deferred_write = true;
+        press(&mut app, KeyCode::Char('D'));
+        assert_eq!(app.overlay, Some(Overlay::Diff { idx: 0 }));
+        assert_eq!(press(&mut app, KeyCode::Char('w')), Action::None);
+        press(&mut app, KeyCode::Esc);
+        press(&mut app, KeyCode::Char('+'));
+        press(&mut app, KeyCode::Char('D'));
+        for _ in 0..5 {
+            press(&mut app, KeyCode::Down);
+        }
+        // Header, the removed line and the added one.
+        assert_eq!(app.overlay, Some(Overlay::Diff { idx: 2 }));
+        assert_eq!(press(&mut app, KeyCode::Char('w')), Action::Write);
+        assert_eq!(app.overlay, None);
+    }
+
+    #[test]
+    fn normal_navigation_wraps() {
+        let mut app = app_with(&[("a", "1"), ("b", "2"), ("c", "3")]);
+        press(&mut app, KeyCode::Char('k'));
+        assert_eq!(app.list_state.selected(), Some(2));
+        press(&mut app, KeyCode::Down);
+        assert_eq!(app.list_state.selected(), Some(0));
+        press(&mut app, KeyCode::Char('j'));
+        assert_eq!(app.list_state.selected(), Some(1));
+        press(&mut app, KeyCode::Up);
+        assert_eq!(app.list_state.selected(), Some(0));
+    }
+
+    #[test]
+    fn normal_navigation_on_empty_config_is_a_no_op() {
+        let mut app = app_with(&[]);
+        press(&mut app, KeyCode::Char('j'));
+        press(&mut app, KeyCode::Char('k'));
+        assert_eq!(app.list_state.selected(), None);
+        assert_eq!(app.params_screen, ParamsScreen::List);
+    }
+
+    #[test]
+    fn plus_and_minus_step_numbers_and_save() {
+        let mut app = app_with(&[("width", "300"), ("font", "mono 10")]);
+        assert_eq!(press(&mut app, KeyCode::Char('+')), Action::Save { key: "width".into(), value: "310".into() });
+        assert_eq!(press(&mut app, KeyCode::Char('h')), Action::Save { key: "width".into(), value: "300".into() });
+        assert_eq!(press(&mut app, KeyCode::Left), Action::Save { key: "width".into(), value: "290".into() });
+        press(&mut app, KeyCode::Char('j'));
+        assert_eq!(press(&mut app, KeyCode::Char('-')), Action::None);
+        assert!(matches!(app.message, Some(Err(_))));
+        assert_eq!(app.cfg.params[1].value, "mono 10");
+    }
+
+    #[test]
+    fn shift_j_and_k_reorder_params() {
+        let mut app = app_with(&[("a", "1"), ("b", "2"), ("c", "3")]);
+        assert_eq!(press(&mut app, KeyCode::Char('J')), Action::Save { key: "a".into(), value: "1".into() });
+        assert_eq!(app.cfg.to_string(), "b=2\na=1\nc=3\n");
+        assert_eq!(app.list_state.selected(), Some(1));
+        press(&mut app, KeyCode::Char('J'));
+        assert_eq!(press(&mut app, KeyCode::Char('J')), Action::None);
+        press(&mut app, KeyCode::Char('K'));
+        press(&mut app, KeyCode::Char('K'));
+        assert_eq!(app.cfg.to_string(), "a=1\nb=2\nc=3\n");
+        assert_eq!(press(&mut app, KeyCode::Char('u')), Action::Save { key: "a".into(), value: "1".into() });
+        assert_eq!(app.cfg.to_string(), "b=2\na=1\nc=3\n");
+    }
+
+    #[test]
+    fn docs_on_the_history_keys_ask_for_the_history() {
+        let mut app = app_with(&[("width", "300"), ("max-history", "20")]);
+        assert_eq!(press(&mut app, KeyCode::Char('i')), Action::None);
+        assert!(!app.docs_show_history());
+        press(&mut app, KeyCode::Char('i'));
+        press(&mut app, KeyCode::Char('j'));
+        assert_eq!(press(&mut app, KeyCode::Char('i')), Action::ListHistory);
+        assert_eq!(app.docs_key(), Some("max-history"));
+        assert!(app.docs_show_history());
+    }
+
+    #[test]
+    fn history_view_resends_and_restores() {
+        let mut app = app_with(&[]);
+        assert_eq!(press(&mut app, KeyCode::Char('H')), Action::ListHistory);
+        assert_eq!(press(&mut app, KeyCode::Char('R')), Action::None);
+        let n = |id, app_name: &str| Notification {
+            id,
+            app_name: app_name.into(),
+            summary: "New mail".into(),
+            body: "from Sam".into(),
+            category: "email.arrived".into(),
+            urgency: "critical",
+            actions: Vec::new(),
+        };
+        app.set_history(Ok(vec![n(7, "thunderbird"), n(5, "firefox")]));
+        press(&mut app, KeyCode::Char('j'));
+        assert_eq!(press(&mut app, KeyCode::Char('s')), Action::SendTest);
+        let sent = &app.test_notification;
+        assert_eq!((sent.app_name.as_str(), sent.urgency.as_str(), sent.category.as_str()), ("firefox", "critical", "email.arrived"));
+        assert_eq!(sent.body, "from Sam");
+        assert_eq!(press(&mut app, KeyCode::Char('R')), Action::RestoreHistory);
+        app.set_history(Ok(vec![n(5, "firefox")]));
+        assert_eq!(app.overlay, Some(Overlay::History { idx: 0 }));
+        press(&mut app, KeyCode::Char('H'));
+        assert_eq!(app.overlay, None);
+    }
+
+    #[test]
+    fn notifications_view_asks_for_the_list_and_follows_it() {
+        let mut app = app_with(&[]);
+        assert_eq!(press(&mut app, KeyCode::Char('m')), Action::ListNotifications);
+        let n = |id| Notification {
+            id,
+            app_name: "mail".into(),
+            summary: "hi".into(),
+            body: String::new(),
+            category: String::new(),
+            urgency: "normal",
+            actions: Vec::new(),
+        };
+        app.set_notifications(Ok(vec![n(1), n(2), n(3)]));
+        press(&mut app, KeyCode::Char('k'));
+        assert_eq!(app.notifications_screen, NotificationsScreen::List { idx: 2 });
+        app.set_notifications(Ok(vec![n(1)]));
+        assert_eq!(app.notifications_screen, NotificationsScreen::List { idx: 0 });
+        assert_eq!(press(&mut app, KeyCode::Char('r')), Action::ListNotifications);
+        assert_eq!(press(&mut app, KeyCode::Char('x')), Action::Dismiss { id: Some(1) });
+        assert_eq!(press(&mut app, KeyCode::Char('X')), Action::Dismiss { id: None });
+        press(&mut app, KeyCode::Char('a'));
+        assert!(matches!(app.message, Some(Err(_))));
+        let actions = vec![("default".to_string(), "Open".to_string()), ("reply".to_string(), "Reply".to_string())];
+        app.set_notifications(Ok(vec![Notification { actions: actions.clone(), ..n(9) }]));
+        press(&mut app, KeyCode::Char('a'));
+        assert_eq!(app.notifications_screen, NotificationsScreen::Actions { notification: 0, id: 9, actions, idx: 0 });
+        press(&mut app, KeyCode::Char('k'));
+        assert_eq!(press(&mut app, KeyCode::Enter), Action::Invoke { id: 9, action: "reply".into() });
+        assert_eq!(app.notifications_screen, NotificationsScreen::List { idx: 0 });
+        app.set_notifications(Ok(Vec::new()));
+        assert_eq!(press(&mut app, KeyCode::Char('x')), Action::None);
+        press(&mut app, KeyCode::Char('m'));
+        assert_eq!((&app.params_screen, app.tab), (&ParamsScreen::List, Tab::Global));
+    }
+
+    #[test]
+    fn composer_edits_fields_and_sends() {
+        let mut app = app_with(&[]);
+        press(&mut app, KeyCode::Char('T'));
+        press(&mut app, KeyCode::Char('j'));
+        press(&mut app, KeyCode::Char('j'));
+        press(&mut app, KeyCode::Enter);
+        assert_eq!(app.overlay, Some(Overlay::EditCompose { idx: 2, input: "normal".into() }));
+        app.overlay = Some(Overlay::EditCompose { idx: 2, input: "loud".into() });
+        press(&mut app, KeyCode::Enter);
+        assert!(matches!(app.message, Some(Err(_))));
+        app.overlay = Some(Overlay::EditCompose { idx: 2, input: "critical".into() });
+        press(&mut app, KeyCode::Enter);
+        assert_eq!(app.overlay, Some(Overlay::Compose { idx: 2 }));
+        assert_eq!(app.test_notification.urgency, "critical");
+        assert_eq!(press(&mut app, KeyCode::Char('s')), Action::SendTest);
+        assert_eq!(press(&mut app, KeyCode::Char('p')), Action::SendProgress);
+        press(&mut app, KeyCode::Esc);
+        assert_eq!(press(&mut app, KeyCode::Char('B')), Action::SendProgress);
+        press(&mut app, KeyCode::Char('T'));
+        assert_eq!(app.test_notification.urgency, "critical");
+    }
+
+    #[test]
+    fn dunstrc_import_is_reviewed_then_applied() {
+        let dir = std::env::temp_dir().join(format!("mako-tui-dunst-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("dunstrc");
+        fs::write(&path, "[global]\nfont = Sans 9\nframe_width = 3\nshrink = yes\n").unwrap();
+        let mut app = app_with(&[("font", "mono 10")]);
+        press(&mut app, KeyCode::Char('I'));
+        app.overlay = Some(Overlay::ImportPath { input: path.display().to_string() });
+        press(&mut app, KeyCode::Enter);
+        let Some(Overlay::ImportReview { mappings, .. }) = &app.overlay else { panic!("{:?}", app.overlay) };
+        assert_eq!(mappings.len(), 3);
+        press(&mut app, KeyCode::Char('j'));
+        press(&mut app, KeyCode::Char(' '));
+        assert_eq!(press(&mut app, KeyCode::Enter), Action::Save { key: "dunstrc".into(), value: "<imported>".into() });
+        assert_eq!(app.cfg.to_string(), "font=Sans 9\n");
+        fs::remove_dir_all(&dir).unwrap();
+
+        app.overlay = Some(Overlay::ImportPath { input: "/nonexistent/dunstrc".into() });
+        press(&mut app, KeyCode::Enter);
+        assert!(matches!(app.overlay, Some(Overlay::ImportPath { .. })));
+        assert!(matches!(app.message, Some(Err(_))));
+    }
+
+    #[test]
+    fn swaync_config_and_style_are_imported_through_the_same_review() {
+        let dir = std::env::temp_dir().join(format!("mako-tui-swaync-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(dir.join("config.json"), r#"{"positionX": "left", "positionY": "bottom", "timeout": 4}"#).unwrap();
+        fs::write(dir.join("style.css"), ".notification { border-radius: 6px; }").unwrap();
+        let mut app = app_with(&[]);
+        app.overlay = Some(Overlay::ImportPath { input: dir.join("config.json").display().to_string() });
+        press(&mut app, KeyCode::Enter);
+        assert_eq!(press(&mut app, KeyCode::Enter), Action::Save { key: "swaync".into(), value: "<imported>".into() });
+        assert_eq!(app.cfg.to_string(), "anchor=bottom-left\ndefault-timeout=4000\nborder-radius=6\n");
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn keys_with_fixed_values_are_picked_from_a_list() {
+        let mut app = app_with(&[("font", "mono 10"), ("text-align", "center")]);
+        press(&mut app, KeyCode::Char('j'));
+        press(&mut app, KeyCode::Enter);
+        assert_eq!(app.params_screen, ParamsScreen::PickValue { idx: Some(1), key: "text-align".into(), choice: 1 });
+        press(&mut app, KeyCode::Down);
+        assert_eq!(press(&mut app, KeyCode::Enter), Action::Save { key: "text-align".into(), value: "right".into() });
+        press(&mut app, KeyCode::Char('a'));
+        type_str(&mut app, "layer");
+        press(&mut app, KeyCode::Enter);
+        assert_eq!(app.params_screen, ParamsScreen::PickValue { idx: None, key: "layer".into(), choice: 0 });
+        press(&mut app, KeyCode::Char('k'));
+        assert_eq!(press(&mut app, KeyCode::Enter), Action::Save { key: "layer".into(), value: "background".into() });
+        assert_eq!(app.list_state.selected(), Some(2));
+    }
+
+    #[test]
+    fn margins_are_edited_side_by_side_and_saved_in_short_form() {
+        let mut app = app_with(&[("padding", "5,10")]);
+        press(&mut app, KeyCode::Enter);
+        assert_eq!(app.params_screen, ParamsScreen::EditSides { idx: Some(0), key: "padding".into(), sides: [5, 10, 5, 10], side: 0, typed: false });
+        type_str(&mut app, "12");
+        press(&mut app, KeyCode::Down);
+        type_str(&mut app, "12");
+        press(&mut app, KeyCode::Tab);
+        press(&mut app, KeyCode::Char('+'));
+        assert_eq!(app.params_screen, ParamsScreen::EditSides { idx: Some(0), key: "padding".into(), sides: [12, 10, 12, 11], side: 3, typed: false });
+        press(&mut app, KeyCode::Char('-'));
+        assert_eq!(press(&mut app, KeyCode::Enter), Action::Save { key: "padding".into(), value: "12,10".into() });
+        app.cfg.params[0].value = "wide".into();
+        press(&mut app, KeyCode::Enter);
+        assert_eq!(app.params_screen, ParamsScreen::EditValue { idx: 0, input: "wide".into() });
+        press(&mut app, KeyCode::Esc);
+        press(&mut app, KeyCode::Char('a'));
+        type_str(&mut app, "outer-margin");
+        press(&mut app, KeyCode::Enter);
+        press(&mut app, KeyCode::Left);
+        type_str(&mut app, "8");
+        assert_eq!(press(&mut app, KeyCode::Enter), Action::Save { key: "outer-margin".into(), value: "0,0,0,8".into() });
+    }
+
+    #[test]
+    fn border_radius_corners_are_picked_round_the_notification() {
+        let mut app = app_with(&[("border-radius", "8")]);
+        press(&mut app, KeyCode::Enter);
+        press(&mut app, KeyCode::Down);
+        press(&mut app, KeyCode::Right);
+        type_str(&mut app, "0");
+        press(&mut app, KeyCode::Left);
+        type_str(&mut app, "0");
+        press(&mut app, KeyCode::Up);
+        assert_eq!(app.params_screen, ParamsScreen::EditSides { idx: Some(0), key: "border-radius".into(), sides: [8, 8, 0, 0], side: 0, typed: false });
+        assert_eq!(press(&mut app, KeyCode::Enter), Action::Save { key: "border-radius".into(), value: "8,8,0,0".into() });
+    }
+
+    #[test]
+    fn anchor_is_picked_on_a_grid_starting_at_the_current_cell() {
+        let mut app = app_with(&[("anchor", "bottom-left")]);
+        press(&mut app, KeyCode::Enter);
+        assert_eq!(app.params_screen, ParamsScreen::PickAnchor { idx: Some(0), key: "anchor".into(), cell: 6 });
+        press(&mut app, KeyCode::Down);
+        press(&mut app, KeyCode::Left);
+        assert_eq!(app.params_screen, ParamsScreen::PickAnchor { idx: Some(0), key: "anchor".into(), cell: 6 });
+        press(&mut app, KeyCode::Up);
+        press(&mut app, KeyCode::Char('l'));
+        press(&mut app, KeyCode::Right);
+        press(&mut app, KeyCode::Right);
+        assert_eq!(press(&mut app, KeyCode::Enter), Action::Save { key: "anchor".into(), value: "center-right".into() });
+        assert_eq!(app.cfg.params[0].value, "center-right");
+        press(&mut app, KeyCode::Char('a'));
+        type_str(&mut app, "anchor-point");
+        press(&mut app, KeyCode::Enter);
+        assert_eq!(app.params_screen, ParamsScreen::PickAnchor { idx: None, key: "anchor-point".into(), cell: 2 });
+    }
+
+    #[test]
+    fn clipboard_copy_and_paste_are_left_to_the_runtime() {
+        let mut app = app_with(&[("font", "mono 10")]);
+        assert_eq!(press(&mut app, KeyCode::Char('y')), Action::Copy { text: "mono 10".into() });
+        let ctrl_v = KeyEvent::new(KeyCode::Char('v'), KeyModifiers::CONTROL);
+        assert_eq!(app.handle_key(ctrl_v), Action::None);
+        press(&mut app, KeyCode::Char('a'));
+        press(&mut app, KeyCode::Char('j'));
+        assert_eq!(app.handle_key(ctrl_v), Action::PasteClipboard);
+        assert_eq!(app.handle_key(KeyEvent::new(KeyCode::Char('V'), KeyModifiers::CONTROL | KeyModifiers::SHIFT)), Action::PasteClipboard);
+        assert_eq!(app.params_screen, ParamsScreen::AddKey { input: String::new() });
+    }
+
+    #[test]
+    fn value_prompts_edit_at_the_cursor() {
+        let mut app = app_with(&[("font", "mono 10")]);
+        press(&mut app, KeyCode::Enter);
+        press(&mut app, KeyCode::Left);
+        press(&mut app, KeyCode::Left);
+        type_str(&mut app, "space ");
+        app.handle_key(KeyEvent::new(KeyCode::Char('w'), KeyModifiers::CONTROL));
+        app.handle_paste("bold ");
+        assert_eq!(app.params_screen, ParamsScreen::EditValue { idx: 0, input: "mono bold 10".into() });
+        press(&mut app, KeyCode::Esc);
+        press(&mut app, KeyCode::Enter);
+        press(&mut app, KeyCode::Backspace);
+        assert_eq!(app.params_screen, ParamsScreen::EditValue { idx: 0, input: "mono 1".into() });
+    }
+
+    #[test]
+    fn yanked_params_paste_into_another_section() {
+        let mut app = app_with(&[("border-color", "#ff0000"), ("border-size", "3"), ("width", "300")]);
+        app.cfg.sections.push(Section { criteria: "urgency=critical".parse().unwrap(), params: vec![Param::new("border-size", "1")] });
+        press(&mut app, KeyCode::Char('p'));
+        assert!(matches!(app.message, Some(Err(_))));
+        press(&mut app, KeyCode::Char(' '));
+        press(&mut app, KeyCode::Char(' '));
+        assert_eq!(press(&mut app, KeyCode::Char('y')), Action::Copy { text: "border-color=#ff0000\nborder-size=3\n".into() });
+        assert!(app.marked.is_empty());
+        press(&mut app, KeyCode::Char(']'));
+        let action = press(&mut app, KeyCode::Char('p'));
+        assert_eq!(action, Action::Save { key: "border-color, border-size".into(), value: "<pasted>".into() });
+        assert_eq!(app.cfg.sections[0].params, [Param::new("border-size", "3"), Param::new("border-color", "#ff0000")]);
+        assert_eq!(app.list_state.selected(), Some(0));
+
+        press(&mut app, KeyCode::Char('['));
+        app.list_state.select(Some(2));
+        assert_eq!(press(&mut app, KeyCode::Char('y')), Action::Copy { text: "300".into() });
+        press(&mut app, KeyCode::Char('p'));
+        assert_eq!(app.cfg.params.len(), 3);
+        assert_eq!(app.cfg.params[2].value, "300");
+    }
+
+    #[test]
+    fn theme_gallery_applies_the_highlighted_preset() {
+        let mut app = app_with(&[("anchor", "bottom-right"), ("background-color", "#000000")]);
+        press(&mut app, KeyCode::Char('P'));
+        press(&mut app, KeyCode::Char('j'));
+        assert_eq!(press(&mut app, KeyCode::Enter), Action::Save { key: "theme".into(), value: "Dracula".into() });
+        assert_eq!(app.cfg.params[0].value, "bottom-right");
+        assert_eq!(app.cfg.params[1].value, "#282a36");
+        assert_eq!(app.params_screen, ParamsScreen::List);
+        assert!(matches!(press(&mut app, KeyCode::Char('u')), Action::Save { key, .. } if key == "theme"));
+        assert_eq!(app.cfg.to_string(), "anchor=bottom-right\nbackground-color=#000000\n");
+    }
+
+    #[test]
+    fn invalid_values_are_refused_until_forced() {
+        let mut app = app_with(&[("border-size", "2")]);
+        press(&mut app, KeyCode::Char('e'));
+        press(&mut app, KeyCode::Backspace);
+        type_str(&mut app, "purple unicorn");
+        assert_eq!(press(&mut app, KeyCode::Enter), Action::None);
+        assert!(matches!(app.params_screen, ParamsScreen::EditValue { .. }));
+        assert_eq!(app.cfg.params[0].value, "2");
+        for _ in 0.."purple unicorn".len() {
+            press(&mut app, KeyCode::Backspace);
+        }
+        type_str(&mut app, "3px");
+        assert_eq!(press(&mut app, KeyCode::Enter), Action::Save { key: "border-size".into(), value: "3px".into() });
+
+        press(&mut app, KeyCode::Char('e'));
+        type_str(&mut app, "x");
+        press(&mut app, KeyCode::Enter);
+        assert_eq!(press(&mut app, KeyCode::Enter), Action::Save { key: "border-size".into(), value: "3pxx".into() });
+        assert_eq!(app.refused, None);
+    }
+
+    #[test]
+    fn edit_and_delete_need_a_selection() {
+        let mut app = app_with(&[]);
+        press(&mut app, KeyCode::Char('e'));
+        assert_eq!(app.params_screen, ParamsScreen::List);
+        press(&mut app, KeyCode::Char('d'));
+        assert_eq!(app.params_screen, ParamsScreen::List);
+    }
+
+    #[test]
+    fn edit_prefills_current_value() {
+        let mut app = app_with(&[("font", "mono 10")]);
+        press(&mut app, KeyCode::Enter);
+        assert_eq!(app.params_screen, ParamsScreen::EditValue { idx: 0, input: "mono 10".to_string() });
+    }
+
+    #[test]
+    fn edit_commit_updates_value_and_saves() {
+        let mut app = app_with(&[("font", "mono 10")]);
+        press(&mut app, KeyCode::Char('e'));
+        press(&mut app, KeyCode::Backspace);
+        press(&mut app, KeyCode::Backspace);
+        type_str(&mut app, "12");
+        let action = press(&mut app, KeyCode::Enter);
+        assert_eq!(action, Action::Save { key: "font".to_string(), value: "mono 12".to_string() });
+        assert_eq!(app.cfg.params[0].value, "mono 12");
+        assert_eq!(app.params_screen, ParamsScreen::List);
+    }
+
+    #[test]
+    fn edit_escape_discards_input() {
+        let mut app = app_with(&[("font", "mono 10")]);
+        press(&mut app, KeyCode::Char('e'));
+        type_str(&mut app, "xyz");
+        assert_eq!(press(&mut app, KeyCode::Esc), Action::None);
+        assert_eq!(app.cfg.params[0].value, "mono 10");
+        assert_eq!(app.params_screen, ParamsScreen::List);
+    }
+
+    #[test]
+    fn edit_commit_with_stale_index_does_not_save() {
+        let mut app = app_with(&[]);
+        app.params_screen = ParamsScreen::EditValue { idx: 3, input: "x".to_string() };
+        assert_eq!(press(&mut app, KeyCode::Enter), Action::None);
+        assert_eq!(app.params_screen, ParamsScreen::List);
+    }
+
+    #[test]
+    fn add_key_typing_filters_and_resets_selection() {
+        let mut app = app_with(&[]);
+        press(&mut app, KeyCode::Char('a'));
+        press(&mut app, KeyCode::Down);
+        assert_eq!(app.key_list_state.selected(), Some(1));
+        type_str(&mut app, "border-c");
+        assert_eq!(app.params_screen, ParamsScreen::AddKey { input: "border-c".to_string() });
+        assert_eq!(app.key_list_state.selected(), Some(0));
+        assert_eq!(filtered_known_keys("border-c", &app.plugins, 0)[0].0, "border-color");
+        assert_eq!(filtered_known_keys("bgc", &app.plugins, 0)[0].0, "background-color");
+    }
+
+    #[test]
+    fn add_key_offers_the_keys_that_fit_the_scope() {
+        let mut app = app_with(&[]);
+        app.cfg.sections.push(Section { criteria: "mode=dnd".parse().unwrap(), params: Vec::new() });
+        let keys = |app: &App| filtered_known_keys("", &app.plugins, app.section).into_iter().map(|(k, _)| k).collect::<Vec<_>>();
+        let global = keys(&app);
+        assert!(global.iter().any(|k| k == "sort") && !global.iter().any(|k| k == "invisible"));
+        app.switch_section(1);
+        let section = keys(&app);
+        assert!(!section.iter().any(|k| k == "sort" || k == "max-history") && section.iter().any(|k| k == "invisible"));
+        press(&mut app, KeyCode::Char('a'));
+        type_str(&mut app, "invis");
+        press(&mut app, KeyCode::Enter);
+        assert!(matches!(&app.params_screen, ParamsScreen::PickValue { key, .. } if key == "invisible"));
+    }
+
+    #[test]
+    fn add_key_navigation_wraps_over_filtered_list() {
+        let mut app = app_with(&[]);
+        press(&mut app, KeyCode::Char('a'));
+        let len = filtered_known_keys("", &app.plugins, 0).len();
+        press(&mut app, KeyCode::Up);
+        assert_eq!(app.key_list_state.selected(), Some(len - 1));
+        press(&mut app, KeyCode::Down);
+        assert_eq!(app.key_list_state.selected(), Some(0));
+    }
+
+    #[test]
+    fn add_key_enter_moves_to_value_prompt() {
+        let mut app = app_with(&[]);
+        press(&mut app, KeyCode::Char('a'));
+        type_str(&mut app, "border-color");
+        press(&mut app, KeyCode::Enter);
+        assert_eq!(app.params_screen, ParamsScreen::AddValue { key: "border-color".to_string(), input: String::new() });
+    }
+
+    #[test]
+    fn adding_a_key_to_an_empty_section_lands_in_that_section() {
+        let mut app = app_with(&[("font", "monospace 10")]);
+        app.cfg.sections.push(Section { criteria: "app-name=foo".parse().unwrap(), params: Vec::new() });
+        app.switch_section(1);
+        press(&mut app, KeyCode::Char('a'));
+        type_str(&mut app, "format");
+        press(&mut app, KeyCode::Enter);
+        type_str(&mut app, "%s");
+        press(&mut app, KeyCode::Enter);
+        assert_eq!(app.params_screen, ParamsScreen::List);
+        assert_eq!(app.cfg.sections[0].params, vec![Param::new("format", "%s")]);
+        assert_eq!(app.cfg.params, vec![Param::new("font", "monospace 10")]);
+        assert_eq!(app.list_state.selected(), Some(0));
+    }
+
+    #[test]
+    fn add_key_enter_on_empty_filtered_list_returns_to_normal() {
+        let mut app = app_with(&[]);
+        press(&mut app, KeyCode::Char('a'));
+        type_str(&mut app, "zzzz-no-such-key");
+        assert!(filtered_known_keys("zzzz-no-such-key", &app.plugins, 0).is_empty());
+        assert_eq!(press(&mut app, KeyCode::Enter), Action::None);
+        assert_eq!(app.params_screen, ParamsScreen::List);
+    }
+
+    struct ExtraKey;
+
+    impl Plugin for ExtraKey {
+        fn name(&self) -> &str {
+            "extra"
+        }
+
+        fn known_keys(&self) -> Vec<(String, String)> {
+            vec![("x-corp-accent".to_string(), "Corporate accent color".to_string())]
+        }
+    }
+
+    #[test]
+    fn add_key_offers_plugin_keys() {
+        let mut app = app_with(&[]);
+        app.plugins.register(Box::new(ExtraKey));
+        press(&mut app, KeyCode::Char('a'));
+        type_str(&mut app, "corporate");
+        press(&mut app, KeyCode::Enter);
+        assert_eq!(app.params_screen, ParamsScreen::AddValue { key: "x-corp-accent".to_string(), input: String::new() });
+    }
+
+    #[test]
+    fn add_key_custom_entry_opens_custom_prompt() {
+        let mut app = app_with(&[]);
+        press(&mut app, KeyCode::Char('a'));
+        type_str(&mut app, "<custom>");
+        press(&mut app, KeyCode::Enter);
+        assert_eq!(app.params_screen, ParamsScreen::AddCustomKey { input: String::new() });
+    }
+
+    #[test]
+    fn add_key_escape_cancels() {
+        let mut app = app_with(&[]);
+        press(&mut app, KeyCode::Char('a'));
+        press(&mut app, KeyCode::Esc);
+        assert_eq!(app.params_screen, ParamsScreen::List);
+    }
+
+    #[test]
+    fn custom_key_blank_name_returns_to_normal() {
+        let mut app = app_with(&[]);
+        app.params_screen = ParamsScreen::AddCustomKey { input: String::new() };
+        type_str(&mut app, "   ");
+        press(&mut app, KeyCode::Enter);
+        assert_eq!(app.params_screen, ParamsScreen::List);
+    }
+
+    #[test]
+    fn custom_key_name_is_trimmed() {
+        let mut app = app_with(&[]);
+        app.params_screen = ParamsScreen::AddCustomKey { input: String::new() };
+        type_str(&mut app, " my-key ");
+        press(&mut app, KeyCode::Enter);
+        assert_eq!(app.params_screen, ParamsScreen::AddValue { key: "my-key".to_string(), input: String::new() });
+    }
+
+    #[test]
+    fn custom_key_close_to_a_known_key_offers_suggestions() {
+        let mut app = app_with(&[("my-colour", "x")]);
+        app.params_screen = ParamsScreen::AddCustomKey { input: String::new() };
+        type_str(&mut app, "boarder-color");
+        press(&mut app, KeyCode::Enter);
+        let ParamsScreen::DidYouMean { suggestions, .. } = &app.params_screen else { panic!("{:?}", app.params_screen) };
+        assert_eq!(suggestions[0], "border-color");
+        press(&mut app, KeyCode::Enter);
+        assert_eq!(app.params_screen, ParamsScreen::AddValue { key: "border-color".to_string(), input: String::new() });
+
+        // The last row keeps the name as typed; Esc goes back to typing.
+        app.params_screen = ParamsScreen::AddCustomKey { input: String::new() };
+        type_str(&mut app, "my-color");
+        press(&mut app, KeyCode::Enter);
+        press(&mut app, KeyCode::Up);
+        press(&mut app, KeyCode::Enter);
+        assert_eq!(app.params_screen, ParamsScreen::AddValue { key: "my-color".to_string(), input: String::new() });
+        app.params_screen = ParamsScreen::AddCustomKey { input: String::new() };
+        type_str(&mut app, "my-color");
+        press(&mut app, KeyCode::Enter);
+        press(&mut app, KeyCode::Esc);
+        assert_eq!(app.params_screen, ParamsScreen::AddCustomKey { input: "my-color".to_string() });
+    }
+
+    #[test]
+    fn custom_key_escape_cancels() {
+        let mut app = app_with(&[]);
+        app.params_screen = ParamsScreen::AddCustomKey { input: "x".to_string() };
+        press(&mut app, KeyCode::Esc);
+        assert_eq!(app.params_screen, ParamsScreen::List);
+    }
+
+    #[test]
+    fn add_value_appends_selects_and_saves() {
+        let mut app = app_with(&[("font", "mono 10")]);
+        app.params_screen = ParamsScreen::AddValue { key: "width".to_string(), input: String::new() };
+        type_str(&mut app, "300");
+        let action = press(&mut app, KeyCode::Enter);
+        assert_eq!(action, Action::Save { key: "width".to_string(), value: "300".to_string() });
+        assert_eq!(app.cfg.params.len(), 2);
+        assert_eq!(app.cfg.params[1].key, "width");
+        assert_eq!(app.list_state.selected(), Some(1));
+        assert_eq!(app.params_screen, ParamsScreen::List);
+    }
+
+    #[test]
+    fn add_value_escape_adds_nothing() {
+        let mut app = app_with(&[]);
+        app.params_screen = ParamsScreen::AddValue { key: "width".to_string(), input: "300".to_string() };
+        press(&mut app, KeyCode::Esc);
+        assert!(app.cfg.params.is_empty());
+        assert_eq!(app.params_screen, ParamsScreen::List);
+    }
+
+    #[test]
+    fn confirm_delete_removes_and_moves_selection_up() {
+        let mut app = app_with(&[("a", "1"), ("b", "2")]);
+        press(&mut app, KeyCode::Char('j'));
+        press(&mut app, KeyCode::Char('d'));
+        assert_eq!(app.params_screen, ParamsScreen::ConfirmDelete { idx: 1 });
+        let action = press(&mut app, KeyCode::Char('y'));
+        assert_eq!(action, Action::Save { key: "b".to_string(), value: "<deleted>".to_string() });
+        assert_eq!(app.cfg.params.len(), 1);
+        assert_eq!(app.list_state.selected(), Some(0));
+    }
+
+    #[test]
+    fn deleting_the_last_param_clears_selection() {
+        let mut app = app_with(&[("a", "1")]);
+        press(&mut app, KeyCode::Char('d'));
+        press(&mut app, KeyCode::Char('Y'));
+        assert!(app.cfg.params.is_empty());
+        assert_eq!(app.list_state.selected(), None);
+        assert_eq!(app.params_screen, ParamsScreen::List);
+    }
+
+    #[test]
+    fn rebound_keys_replace_the_defaults() {
+        let mut app = app_with(&[("a", "1")]);
+        app.settings.keys.bind(Command::Delete, 'x');
+        press(&mut app, KeyCode::Char('d'));
+        assert_eq!(app.params_screen, ParamsScreen::List);
+        press(&mut app, KeyCode::Char('x'));
+        assert_eq!(app.params_screen, ParamsScreen::ConfirmDelete { idx: 0 });
+    }
+
+    #[test]
+    fn the_ticker_only_runs_while_something_on_screen_follows_mako() {
+        let mut app = app_with(&[("font", "mono 10")]);
+        assert!(app.wants_refresh());
+        app.compact = true;
+        assert!(!app.wants_refresh());
+        app.tab = Tab::Notifications;
+        assert!(app.wants_refresh());
+        app.settings.idle_mode = IdleMode::Block;
+        assert!(!app.wants_refresh());
+    }
+
+    #[test]
+    fn shift_a_toggles_auto_reload_and_saves_the_setting() {
+        let mut app = app_with(&[]);
+        assert_eq!(press(&mut app, KeyCode::Char('A')), Action::SaveSettings);
+        assert!(!app.settings.auto_reload);
+        assert_eq!(press(&mut app, KeyCode::Char('A')), Action::SaveSettings);
+        assert!(app.settings.auto_reload);
+    }
+
+    #[test]
+    fn settings_screen_edits_and_saves_a_setting() {
+        let mut app = app_with(&[]);
+        press(&mut app, KeyCode::Char('s'));
+        assert_eq!(app.overlay, Some(Overlay::Settings { idx: 0 }));
+        let idx = app.settings.fields().iter().position(|(k, _)| k == "backup_retention").unwrap();
+        for _ in 0..idx {
+            press(&mut app, KeyCode::Char('j'));
+        }
+        press(&mut app, KeyCode::Enter);
+        assert_eq!(app.overlay, Some(Overlay::EditSetting { idx, input: "5".to_string() }));
+        press(&mut app, KeyCode::Backspace);
+        type_str(&mut app, "9");
+        assert_eq!(press(&mut app, KeyCode::Enter), Action::SaveSettings);
+        assert_eq!(app.settings.backup_retention, 9);
+        assert_eq!(app.overlay, Some(Overlay::Settings { idx }));
+        press(&mut app, KeyCode::Esc);
+        assert_eq!(app.overlay, None);
+    }
+
+    #[test]
+    fn invalid_setting_stays_in_the_prompt() {
+        let mut app = app_with(&[]);
+        app.overlay = Some(Overlay::EditSetting { idx: 0, input: "maybe".to_string() });
+        assert_eq!(press(&mut app, KeyCode::Enter), Action::None);
+        assert!(matches!(app.message, Some(Err(_))));
+        assert!(app.settings.auto_reload);
+        assert_eq!(app.overlay, Some(Overlay::EditSetting { idx: 0, input: "maybe".to_string() }));
+    }
+
+    #[test]
+    fn confirm_delete_can_be_cancelled() {
+        for code in [KeyCode::Char('n'), KeyCode::Char('N'), KeyCode::Esc] {
+            let mut app = app_with(&[("a", "1")]);
+            press(&mut app, KeyCode::Char('d'));
+            assert_eq!(press(&mut app, code), Action::None);
+            assert_eq!(app.cfg.params.len(), 1);
+            assert_eq!(app.params_screen, ParamsScreen::List);
+        }
+    }
+
+    #[test]
+    fn paste_goes_into_the_prompt_in_one_piece() {
+        let mut app = app_with(&[("font", "")]);
+        press(&mut app, KeyCode::Char('e'));
+        assert_eq!(app.handle_paste("Noto Sans\r\nBold 11\n"), Action::None);
+        assert_eq!(app.params_screen, ParamsScreen::EditValue { idx: 0, input: "Noto Sans Bold 11".to_string() });
+    }
+
+    #[test]
+    fn paste_outside_a_prompt_triggers_no_bindings() {
+        let mut app = app_with(&[("a", "1")]);
+        assert_eq!(app.handle_paste("qdy"), Action::None);
+        assert_eq!(app.params_screen, ParamsScreen::List);
+        assert_eq!(app.cfg.params.len(), 1);
+    }
+
+    #[test]
+    fn page_keys_and_g_move_within_the_list_without_wrapping() {
+        let params: Vec<(String, String)> = (0..30).map(|i| (format!("key-{}", i), i.to_string())).collect();
+        let params: Vec<(&str, &str)> = params.iter().map(|(k, v)| (k.as_str(), v.as_str())).collect();
+        let mut app = app_with(&params);
+        app.list_hitbox = Some((Rect::new(1, 1, 40, 8), 0));
+        press(&mut app, KeyCode::PageDown);
+        assert_eq!(app.list_state.selected(), Some(7));
+        press(&mut app, KeyCode::Char('G'));
+        assert_eq!(app.list_state.selected(), Some(29));
+        press(&mut app, KeyCode::PageDown);
+        assert_eq!(app.list_state.selected(), Some(29));
+        press(&mut app, KeyCode::PageUp);
+        assert_eq!(app.list_state.selected(), Some(22));
+        press(&mut app, KeyCode::Char('g'));
+        assert_eq!(app.list_state.selected(), Some(0));
+        press(&mut app, KeyCode::End);
+        assert_eq!(app.list_state.selected(), Some(29));
+    }
+
+    #[test]
+    fn resize_clamps_selection_and_resets_scroll() {
+        let mut app = app_with(&[("a", "1"), ("b", "2")]);
+        app.list_state.select(Some(7));
+        *app.list_state.offset_mut() = 5;
+        app.params_screen = ParamsScreen::ConfirmDelete { idx: 7 };
+        app.handle_resize();
+        assert_eq!(app.list_state.selected(), Some(1));
+        assert_eq!(app.list_state.offset(), 0);
+        assert_eq!(app.params_screen, ParamsScreen::List);
+    }
+
+    #[test]
+    fn font_picker_builds_the_value_in_the_prompt() {
+        let mut app = app_with(&[("font", "Inter 10")]);
+        let ctrl_o = KeyEvent::new(KeyCode::Char('o'), KeyModifiers::CONTROL);
+        press(&mut app, KeyCode::Char('e'));
+        app.handle_key(ctrl_o);
+        assert_eq!(app.message, Some(Err(tr("fonts.unavailable").to_string())));
+        app.fonts = Some(vec!["DejaVu Sans".to_string(), "Inter".to_string()]);
+        app.handle_key(ctrl_o);
+        assert!(matches!(app.params_screen, ParamsScreen::PickFont { .. }));
+        type_str(&mut app, "dj");
+        press(&mut app, KeyCode::Right);
+        press(&mut app, KeyCode::Tab);
+        press(&mut app, KeyCode::Backspace);
+        type_str(&mut app, "2");
+        press(&mut app, KeyCode::Enter);
+        assert_eq!(app.params_screen, ParamsScreen::EditValue { idx: 0, input: "DejaVu Sans Bold 12".to_string() });
+    }
+
+    #[test]
+    fn list_editor_rebuilds_the_value_in_the_prompt() {
+        let mut app = app_with(&[("icon-path", "/a:/b:/c")]);
+        press(&mut app, KeyCode::Char('e'));
+        app.handle_key(KeyEvent::new(KeyCode::Char('l'), KeyModifiers::CONTROL));
+        assert!(matches!(app.params_screen, ParamsScreen::EditList { .. }));
+        press(&mut app, KeyCode::Char('j'));
+        press(&mut app, KeyCode::Char('K'));
+        press(&mut app, KeyCode::Char('a'));
+        // Typing goes to the new entry, not to the list's own keys.
+        type_str(&mut app, "/new");
+        assert_eq!(app.params_screen.input_mut().map(|s| s.clone()), Some("/new".to_string()));
+        press(&mut app, KeyCode::Enter);
+        press(&mut app, KeyCode::Char('j'));
+        press(&mut app, KeyCode::Char('d'));
+        press(&mut app, KeyCode::Enter);
+        assert_eq!(app.params_screen, ParamsScreen::EditValue { idx: 0, input: "/b:/new:/c".to_string() });
+        press(&mut app, KeyCode::Enter);
+        assert_eq!(app.cfg.params_of(app.section)[0].value, "/b:/new:/c");
+    }
+
+    #[test]
+    fn path_browser_picks_directories_into_the_prompt() {
+        let root = std::env::temp_dir().join(format!("mako-tui-browse-{}", std::process::id()));
+        std::fs::create_dir_all(root.join("hicolor")).unwrap();
+        std::fs::write(root.join("hicolor/index.theme"), "[Icon Theme]\nName=Hicolor\n").unwrap();
+        let mut app = app_with(&[("icon-path", root.to_str().unwrap())]);
+        press(&mut app, KeyCode::Char('e'));
+        app.handle_key(KeyEvent::new(KeyCode::Char('o'), KeyModifiers::CONTROL));
+        let ParamsScreen::BrowsePath { browser, .. } = &app.params_screen else { panic!("{:?}", app.params_screen) };
+        assert_eq!(browser.dir, root);
+        assert_eq!(browser.themes, vec!["Hicolor".to_string()]);
+        // `..` is first, then `hicolor`. Going up and back down lands on
+        // the directory we came from.
+        press(&mut app, KeyCode::Char('j'));
+        press(&mut app, KeyCode::Char(' '));
+        press(&mut app, KeyCode::Char('h'));
+        press(&mut app, KeyCode::Char('l'));
+        let ParamsScreen::BrowsePath { browser, .. } = &app.params_screen else { panic!() };
+        assert_eq!(browser.dir, root);
+        press(&mut app, KeyCode::Tab);
+        let expected = root.join("hicolor").display().to_string();
+        assert_eq!(app.params_screen, ParamsScreen::EditValue { idx: 0, input: format!("{}:{}", root.display(), expected) });
+        std::fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[test]
+    fn deleted_params_can_be_restored_from_the_trash() {
+        let mut app = app_with(&[("format", "<b>%s</b>\\n%b"), ("font", "mono 10")]);
+        press(&mut app, KeyCode::Char('d'));
+        press(&mut app, KeyCode::Char('y'));
+        assert_eq!(app.cfg.params.len(), 1);
+        press(&mut app, KeyCode::Char('t'));
+        assert_eq!(app.overlay, Some(Overlay::Trash { idx: 0 }));
+        let action = press(&mut app, KeyCode::Enter);
+        assert_eq!(action, Action::Save { key: "format".to_string(), value: "<b>%s</b>\\n%b".to_string() });
+        assert_eq!(app.overlay, None);
+        assert_eq!(app.cfg.params[1].key, "format");
+        assert_eq!(app.list_state.selected(), Some(1));
+        assert!(app.trash.items.is_empty());
+    }
+
+    #[test]
+    fn trash_lists_newest_first_and_discards() {
+        let mut app = app_with(&[]);
+        app.trash.push(Param::new("a", "1"));
+        app.trash.push(Param::new("b", "2"));
+        press(&mut app, KeyCode::Char('t'));
+        press(&mut app, KeyCode::Char('j'));
+        assert_eq!(press(&mut app, KeyCode::Char('x')), Action::SaveTrash);
+        assert_eq!(app.trash.items.iter().map(|p| p.key.as_str()).collect::<Vec<_>>(), vec!["b"]);
+        assert_eq!(app.overlay, Some(Overlay::Trash { idx: 0 }));
+    }
+
+    #[test]
+    fn edits_are_dirty_until_marked_saved() {
+        let mut app = app_with(&[("font", "mono 10")]);
+        assert!(!app.is_dirty());
+        press(&mut app, KeyCode::Char('e'));
+        type_str(&mut app, "0");
+        press(&mut app, KeyCode::Enter);
+        assert!(app.is_dirty());
+        app.mark_saved();
+        assert!(!app.is_dirty());
+    }
+
+    #[test]
+    fn timeouts_typed_with_units_are_stored_as_milliseconds() {
+        let mut app = app_with(&[("default-timeout", "")]);
+        press(&mut app, KeyCode::Char('e'));
+        type_str(&mut app, "1m30s");
+        assert_eq!(press(&mut app, KeyCode::Enter), Action::Save { key: "default-timeout".to_string(), value: "90000".to_string() });
+        assert_eq!(app.cfg.params[0].value, "90000");
+    }
+
+    #[test]
+    fn customized_filter_hides_defaults_and_navigation_skips_them() {
+        let mut app = app_with(&[("font", "mono 12"), ("padding", "5"), ("icons", "true"), ("width", "400")]);
+        app.list_state.select(Some(1));
+        press(&mut app, KeyCode::Char('f'));
+        assert_eq!(app.visible_params(), vec![0, 3]);
+        // The hidden selection moves to the next visible param.
+        assert_eq!(app.list_state.selected(), Some(3));
+        press(&mut app, KeyCode::Char('j'));
+        assert_eq!(app.list_state.selected(), Some(0));
+        press(&mut app, KeyCode::Char('k'));
+        assert_eq!(app.list_state.selected(), Some(3));
+        assert_eq!(app.cfg.without_defaults().to_string(), "font=mono 12\nwidth=400\n");
+
+        press(&mut app, KeyCode::Char('f'));
+        assert_eq!(app.visible_params().len(), 4);
+    }
+
+    #[test]
+    fn reset_sets_the_selected_key_to_its_default() {
+        let mut app = app_with(&[("padding", "12"), ("my-key", "x"), ("width", "500")]);
+        app.cfg.sections.push(Section { criteria: "urgency=low".parse().unwrap(), params: vec![Param::new("padding", "2")] });
+        assert_eq!(
+            press(&mut app, KeyCode::Char('r')),
+            Action::Save { key: "padding".to_string(), value: "5".to_string() }
+        );
+        assert_eq!(app.cfg.params[0].value, "5");
+        press(&mut app, KeyCode::Char('j'));
+        assert_eq!(press(&mut app, KeyCode::Char('r')), Action::None);
+        assert!(matches!(app.message, Some(Err(_))));
+        assert_eq!(app.cfg.params[1].value, "x");
+        // Nothing else sets width, so resetting it drops the line.
+        press(&mut app, KeyCode::Char('j'));
+        assert_eq!(press(&mut app, KeyCode::Char('r')), Action::Save { key: "width".into(), value: "<reset>".into() });
+        assert_eq!(app.cfg.params.len(), 2);
+        assert_eq!(app.list_state.selected(), Some(1));
+        assert_eq!(git::message(&[("width".into(), "<reset>".into())]), "reset width");
+    }
+
+    #[test]
+    fn reset_all_needs_the_confirmation_word() {
+        let mut app = app_with(&[("padding", "12")]);
+        press(&mut app, KeyCode::Char('R'));
+        type_str(&mut app, "yes");
+        assert_eq!(press(&mut app, KeyCode::Enter), Action::None);
+        assert_eq!(app.cfg.params.len(), 1);
+
+        press(&mut app, KeyCode::Char('R'));
+        type_str(&mut app, "reset");
+        assert_eq!(press(&mut app, KeyCode::Enter), Action::ResetConfig);
+        assert!(app.cfg.params.is_empty());
+        assert_eq!(app.list_state.selected(), None);
+    }
+
+    #[test]
+    fn reset_to_defaults_backs_up_and_writes_commented_defaults() {
+        let dir = std::env::temp_dir().join(format!("mako-tui-reset-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        let mut cfg = Config::new(dir.join("config"));
+        cfg.params.push(Param::new("padding", "12"));
+        cfg.save().unwrap();
+        cfg.reset_to_defaults(0).unwrap();
+        assert_eq!(std::fs::read_to_string(cfg.backup_path(1)).unwrap(), "padding=12\n");
+        let text = std::fs::read_to_string(&cfg.path).unwrap();
+        assert!(text.contains("#padding=5\n"));
+        assert!(Config::load_from(cfg.path.clone()).unwrap().params.is_empty());
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn sections_survive_a_round_trip() {
+        let text = "font=mono 10\n\n[urgency=critical]\nborder-color=#ff0000\n\n[app-name=\"Google Chrome\" summary~=^Update grouped]\nformat=%s\n";
+        let Ok(cfg) = text.parse::<Config>();
+        assert_eq!(cfg.params.len(), 1);
+        assert_eq!(cfg.sections.len(), 2);
+        let conditions = &cfg.sections[1].criteria.conditions;
+        assert_eq!(conditions[0].value.as_deref(), Some("Google Chrome"));
+        assert!(conditions[1].regex);
+        assert_eq!(conditions[2].value, None);
+        assert_eq!(cfg.to_string(), text);
+    }
+
+    #[test]
+    fn edits_apply_to_the_current_section() {
+        let Ok(cfg) = "font=mono 10\n[urgency=critical]\nborder-color=#ff0000\n".parse::<Config>();
+        let mut app = App::new(cfg);
+        press(&mut app, KeyCode::Char(']'));
+        assert_eq!(app.section, 1);
+        assert_eq!(app.list_state.selected(), Some(0));
+        press(&mut app, KeyCode::Char('e'));
+        press(&mut app, KeyCode::Backspace);
+        type_str(&mut app, "1");
+        press(&mut app, KeyCode::Enter);
+        assert_eq!(app.cfg.sections[0].params[0].value, "#ff0001");
+        assert_eq!(app.cfg.params[0].value, "mono 10");
+        press(&mut app, KeyCode::Char(']'));
+        assert_eq!(app.section, 0);
+        press(&mut app, KeyCode::Char('['));
+        assert_eq!(app.section, 1);
+    }
+
+    #[test]
+    fn sections_can_be_added_and_removed_when_empty() {
+        let mut app = app_with(&[("font", "mono 10")]);
+        press(&mut app, KeyCode::Char('S'));
+        press(&mut app, KeyCode::Char('/'));
+        type_str(&mut app, "app-name=firefox");
+        assert_eq!(press(&mut app, KeyCode::Enter), Action::Save { key: "[app-name=firefox]".to_string(), value: String::new() });
+        assert_eq!(app.section, 1);
+        assert_eq!(app.list_state.selected(), None);
+        assert!(app.cfg.to_string().ends_with("\n[app-name=firefox]\n"));
+
+        // Adding it again only switches to it.
+        press(&mut app, KeyCode::Char(']'));
+        press(&mut app, KeyCode::Char('S'));
+        press(&mut app, KeyCode::Char('/'));
+        type_str(&mut app, " app-name=firefox ");
+        assert_eq!(press(&mut app, KeyCode::Enter), Action::None);
+        assert_eq!(app.section, 1);
+
+        assert!(matches!(press(&mut app, KeyCode::Char('d')), Action::Save { .. }));
+        assert!(app.cfg.sections.is_empty());
+        assert_eq!(app.section, 0);
+        assert_eq!(app.list_state.selected(), Some(0));
+    }
+
+    #[test]
+    fn modes_view_toggles_modes_and_edits_their_sections() {
+        let mut app = App::new("font=mono 10\n[mode=do-not-disturb]\ninvisible=1\n".parse().unwrap());
+        assert_eq!(press(&mut app, KeyCode::Char('M')), Action::ListModes);
+        app.set_modes(Ok(vec!["default".into()]));
+        assert_eq!(app.mode_rows(), ["default", "do-not-disturb"]);
+        press(&mut app, KeyCode::Char('j'));
+        assert_eq!(press(&mut app, KeyCode::Char(' ')), Action::ToggleMode { name: "do-not-disturb".into() });
+        assert_eq!(press(&mut app, KeyCode::Enter), Action::None);
+        assert_eq!((app.section, &app.params_screen), (1, &ParamsScreen::List));
+
+        press(&mut app, KeyCode::Char('M'));
+        press(&mut app, KeyCode::Char('a'));
+        type_str(&mut app, "gaming");
+        assert_eq!(press(&mut app, KeyCode::Enter), Action::Save { key: "[mode=gaming]".into(), value: String::new() });
+        assert_eq!(app.section, 2);
+        assert_eq!(app.cfg.modes(), ["do-not-disturb", "gaming"]);
+    }
+
+    #[test]
+    fn criteria_builder_combines_fields_into_one_header() {
+        let mut app = app_with(&[("font", "mono 10")]);
+        press(&mut app, KeyCode::Char('S'));
+        press(&mut app, KeyCode::Enter);
+        type_str(&mut app, "Google Chrome");
+        press(&mut app, KeyCode::Enter);
+        for _ in 0..4 {
+            press(&mut app, KeyCode::Down);
+        }
+        press(&mut app, KeyCode::Enter);
+        press(&mut app, KeyCode::Enter);
+        press(&mut app, KeyCode::Up);
+        press(&mut app, KeyCode::Up);
+        press(&mut app, KeyCode::Enter);
+        type_str(&mut app, "^Down");
+        press(&mut app, KeyCode::Enter);
+        press(&mut app, KeyCode::Char('r'));
+        let ParamsScreen::BuildCriteria { criteria, .. } = &app.params_screen else { panic!("{:?}", app.params_screen) };
+        assert_eq!(criteria.to_string(), "app-name=\"Google Chrome\" urgency=normal summary~=^Down");
+        assert_eq!(
+            press(&mut app, KeyCode::Char('s')),
+            Action::Save { key: "[app-name=\"Google Chrome\" urgency=normal summary~=^Down]".into(), value: String::new() }
+        );
+        assert_eq!(app.section, 1);
+        let Ok(parsed) = app.cfg.sections[0].criteria.to_string().parse::<Criteria>();
+        assert_eq!(parsed, app.cfg.sections[0].criteria);
+    }
+
+    #[test]
+    fn undo_and_redo_walk_through_changes() {
+        let mut app = app_with(&[("font", "mono 10"), ("width", "300")]);
+        press(&mut app, KeyCode::Char('e'));
+        type_str(&mut app, "0");
+        press(&mut app, KeyCode::Enter);
+        press(&mut app, KeyCode::Char('j'));
+        press(&mut app, KeyCode::Char('d'));
+        press(&mut app, KeyCode::Char('y'));
+        assert_eq!(app.cfg.to_string(), "font=mono 100\n");
+
+        assert_eq!(press(&mut app, KeyCode::Char('u')), Action::Save { key: "width".to_string(), value: "300".to_string() });
+        assert_eq!(app.cfg.to_string(), "font=mono 100\nwidth=300\n");
+        assert_eq!(app.list_state.selected(), Some(1));
+        assert_eq!(press(&mut app, KeyCode::Char('u')), Action::Save { key: "font".to_string(), value: "mono 10".to_string() });
+        assert_eq!(press(&mut app, KeyCode::Char('u')), Action::None);
+        assert!(matches!(app.message, Some(Err(_))));
+
+        let redo = KeyEvent::new(KeyCode::Char('r'), KeyModifiers::CONTROL);
+        assert_eq!(app.handle_key(redo), Action::Save { key: "font".to_string(), value: "mono 100".to_string() });
+        assert_eq!(app.cfg.to_string(), "font=mono 100\nwidth=300\n");
+
+        // A new change forgets what could be redone.
+        press(&mut app, KeyCode::Char('r'));
+        assert_eq!(app.handle_key(redo), Action::None);
+    }
+
+    #[test]
+    fn search_filters_by_key_or_value_and_n_jumps_between_matches() {
+        let mut app = app_with(&[("font", "mono 10"), ("border-color", "#fff"), ("width", "300"), ("text-color", "#FFF")]);
+        press(&mut app, KeyCode::Char('/'));
+        type_str(&mut app, "COLOR");
+        assert_eq!(app.visible_params(), vec![1, 3]);
+        assert_eq!(app.list_state.selected(), Some(1));
+        press(&mut app, KeyCode::Enter);
+        assert_eq!(app.search.as_deref(), Some("COLOR"));
+        press(&mut app, KeyCode::Char('n'));
+        assert_eq!(app.list_state.selected(), Some(3));
+        press(&mut app, KeyCode::Char('n'));
+        assert_eq!(app.list_state.selected(), Some(1));
+        press(&mut app, KeyCode::Char('N'));
+        assert_eq!(app.list_state.selected(), Some(3));
+
+        // Values match too, and Esc brings everything back.
+        press(&mut app, KeyCode::Char('/'));
+        for _ in 0..5 {
+            press(&mut app, KeyCode::Backspace);
+        }
+        type_str(&mut app, "300");
+        assert_eq!(app.visible_params(), vec![2]);
+        press(&mut app, KeyCode::Esc);
+        assert_eq!(app.search, None);
+        assert_eq!(app.visible_params().len(), 4);
+        assert_eq!(app.list_state.selected(), Some(2));
+    }
+
+    #[test]
+    fn help_overlay_swallows_keys_until_closed() {
+        let mut app = app_with(&[("font", "mono 10")]);
+        press(&mut app, KeyCode::Char('?'));
+        assert_eq!(app.help, Some(0));
+        press(&mut app, KeyCode::Char('j'));
+        press(&mut app, KeyCode::Char('d'));
+        assert_eq!(app.help, Some(1));
+        assert_eq!(app.params_screen, ParamsScreen::List);
+        assert_eq!(press(&mut app, KeyCode::Char('q')), Action::None);
+        assert_eq!(app.help, None);
+        assert_eq!(press(&mut app, KeyCode::Char('q')), Action::Quit);
+    }
+
+    #[test]
+    fn clicks_select_rows_and_double_clicks_edit() {
+        let mut app = app_with(&[("font", "mono 10"), ("width", "300"), ("height", "100")]);
+        // Rows start at y=5 and the list is scrolled down by one.
+        app.list_hitbox = Some((Rect::new(1, 5, 40, 3), 1));
+        let mouse = |kind, row| MouseEvent { kind, column: 3, row, modifiers: KeyModifiers::NONE };
+        let click = |row| mouse(MouseEventKind::Down(MouseButton::Left), row);
+
+        app.handle_mouse(click(5));
+        assert_eq!(app.list_state.selected(), Some(1));
+        app.handle_mouse(click(6));
+        assert_eq!(app.list_state.selected(), Some(2));
+        app.handle_mouse(click(9));
+        assert_eq!(app.list_state.selected(), Some(2));
+        app.handle_mouse(click(6));
+        assert_eq!(app.params_screen, ParamsScreen::EditValue { idx: 2, input: "100".to_string() });
+
+        app.params_screen = ParamsScreen::List;
+        app.handle_mouse(mouse(MouseEventKind::ScrollDown, 0));
+        assert_eq!(app.list_state.selected(), Some(0));
+        app.handle_mouse(mouse(MouseEventKind::ScrollUp, 0));
+        assert_eq!(app.list_state.selected(), Some(2));
+    }
+
+    #[test]
+    fn backups_are_listed_and_restored() {
+        let dir = std::env::temp_dir().join(format!("mako-tui-backups-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        let mut cfg = Config::new(dir.join("config"));
+        for width in ["100", "200", "300"] {
+            cfg.params = vec![Param::new("width", width)];
+            cfg.save_with_backups(5).unwrap();
+        }
+        let mut app = App::new(cfg);
+        press(&mut app, KeyCode::Char('b'));
+        let Some(Overlay::Backups { backups, .. }) = &app.overlay else { panic!("{:?}", app.overlay) };
+        assert_eq!(backups.iter().map(|b| (b.n, b.keys)).collect::<Vec<_>>(), vec![(1, 1), (2, 1)]);
+        press(&mut app, KeyCode::Char('j'));
+        assert!(matches!(press(&mut app, KeyCode::Enter), Action::Save { .. }));
+        assert_eq!(app.cfg.to_string(), "width=100\n");
+        assert_eq!(app.overlay, None);
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn a_snapshot_is_restored_as_one_change() {
+        let mut app = app_with(&[("width", "300")]);
+        let taken = UNIX_EPOCH + Duration::from_secs(951_782_400);
+        let snapshot = snapshots::Snapshot {
+            path: PathBuf::from("/tmp/snap"),
+            taken,
+            text: "width=100\n[urgency=critical]\nborder-size=4\n".to_string(),
+            keys: 2,
+        };
+        app.overlay = Some(Overlay::Snapshots { idx: 0, snapshots: vec![snapshot] });
+        let action = press(&mut app, KeyCode::Enter);
+        assert_eq!(action, Action::Save { key: "snapshot 2000-02-29T00:00:00.000Z".into(), value: "<restored>".into() });
+        assert_eq!(app.cfg.params, [Param::new("width", "100")]);
+        assert_eq!(app.cfg.sections.len(), 1);
+        press(&mut app, KeyCode::Char('u'));
+        assert_eq!(app.cfg.to_string(), "width=300\n");
+    }
+
+    #[test]
+    fn a_commit_is_restored_and_saved_under_its_hash() {
+        let mut app = app_with(&[("width", "300")]);
+        let commit = |hash: &str, text: &str| git::Commit {
+            hash: hash.to_string(),
+            time: UNIX_EPOCH,
+            subject: "set width".to_string(),
+            text: text.to_string(),
+        };
+        let commits = vec![commit("1234567890", "width=300\n"), commit("abcdef0123", "width=100\n")];
+        assert_eq!(press(&mut app, KeyCode::Char('C')), Action::ListGitLog);
+        app.set_git_log(Ok(commits));
+        press(&mut app, KeyCode::Char('j'));
+        let action = press(&mut app, KeyCode::Enter);
+        assert_eq!(action, Action::Save { key: "commit abcdef0".into(), value: "<restored>".into() });
+        assert_eq!(app.cfg.params, [Param::new("width", "100")]);
+        assert_eq!(git::message(&[("commit abcdef0".into(), "<restored>".into())]), "restore commit abcdef0");
+
+        app.overlay = Some(Overlay::GitLog { idx: 0, commits: Some(Err("not a git repository".to_string())) });
+        assert_eq!(press(&mut app, KeyCode::Enter), Action::None);
+        press(&mut app, KeyCode::Esc);
+        assert_eq!(app.overlay, None);
+    }
+
+    #[test]
+    fn atomic_writes_replace_a_symlinks_target() {
+        let dir = std::env::temp_dir().join(format!("mako-tui-atomic-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("real"), "old").unwrap();
+        std::os::unix::fs::symlink(dir.join("real"), dir.join("link")).unwrap();
+        crate::config::write_atomic(&dir.join("link"), "new").unwrap();
+        assert!(std::fs::symlink_metadata(dir.join("link")).unwrap().file_type().is_symlink());
+        assert_eq!(std::fs::read_to_string(dir.join("real")).unwrap(), "new");
+        assert!(!dir.join("real.tmp").exists());
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn duplicate_copies_the_value_under_a_new_key() {
+        let mut app = app_with(&[("border-color", "#285577"), ("font", "mono 10")]);
+        press(&mut app, KeyCode::Char('c'));
+        assert_eq!(app.params_screen, ParamsScreen::Duplicate { idx: 0, input: "border-color".to_string() });
+        for _ in 0.."border-color".len() {
+            press(&mut app, KeyCode::Backspace);
+        }
+        type_str(&mut app, "progress-color");
+        let action = press(&mut app, KeyCode::Enter);
+        assert_eq!(action, Action::Save { key: "progress-color".to_string(), value: "#285577".to_string() });
+        assert_eq!(app.cfg.params[1].key, "progress-color");
+        assert_eq!(app.cfg.params[1].value, "#285577");
+        assert_eq!(app.list_state.selected(), Some(1));
+    }
+
+    #[test]
+    fn palette_names_are_expanded_on_save_and_kept_symbolic() {
+        let mut app = app_with(&[("width", "300")]);
+        app.settings.set("palette.accent", "#88c0d0").unwrap();
+        app.open(app.cfg.clone());
+        press(&mut app, KeyCode::Char(':'));
+        type_str(&mut app, "set border-color=@accent");
+        assert!(matches!(press(&mut app, KeyCode::Enter), Action::Save { .. }));
+        assert_eq!(app.cfg.params[1].value, "@accent");
+        let text = app.cfg.to_string();
+        assert!(text.contains("# template: border-color=@accent\nborder-color=#88c0d0\n"), "{}", text);
+        let Ok(cfg) = text.parse::<Config>();
+        assert_eq!(cfg.params[1].value, "@accent");
+        press(&mut app, KeyCode::Char(':'));
+        type_str(&mut app, "set text-color=@fg");
+        assert_eq!(press(&mut app, KeyCode::Enter), Action::None);
+    }
+
+    #[test]
+    fn replace_counts_matches_and_waits_for_confirmation() {
+        let mut app = app_with(&[("background-color", "#1d1f21"), ("format", "#1d1f21 %s")]);
+        app.cfg.sections.push(Section { criteria: "urgency=low".parse().unwrap(), params: vec![Param::new("border-color", "#1d1f21")] });
+        press(&mut app, KeyCode::Char(':'));
+        type_str(&mut app, "%s/#1d1f21/#282828/ colors");
+        press(&mut app, KeyCode::Enter);
+        let ParamsScreen::ConfirmBulk { change: BulkChange::Replace { count, .. } } = &app.params_screen else { panic!("{:?}", app.params_screen) };
+        assert_eq!(*count, 2);
+        let action = press(&mut app, KeyCode::Char('y'));
+        assert_eq!(action, Action::Save { key: "background-color, border-color".into(), value: "#282828".into() });
+        assert_eq!(app.cfg.params[0].value, "#282828");
+        assert_eq!(app.cfg.params[1].value, "#1d1f21 %s");
+        assert_eq!(app.cfg.sections[0].params[0].value, "#282828");
+
+        press(&mut app, KeyCode::Char(':'));
+        type_str(&mut app, "s/#282828/grey/");
+        press(&mut app, KeyCode::Enter);
+        assert_eq!(app.params_screen, ParamsScreen::List);
+        assert!(matches!(app.message, Some(Err(_))));
+        press(&mut app, KeyCode::Char(':'));
+        type_str(&mut app, "s/#1d1f21/#000000/");
+        press(&mut app, KeyCode::Enter);
+        press(&mut app, KeyCode::Char('n'));
+        assert_eq!(app.cfg.params[1].value, "#1d1f21 %s");
+    }
+
+    #[test]
+    fn unset_keys_are_listed_with_defaults_and_enter_adds_one() {
+        let mut app = app_with(&[("width", "300")]);
+        assert!(app.rows().iter().all(|r| matches!(r, Row::Param(_))));
+        press(&mut app, KeyCode::Char('F'));
+        let rows = app.rows();
+        assert_eq!(rows[0], Row::Param(0));
+        assert!(rows.contains(&Row::Unset("height")) && !rows.contains(&Row::Unset("width")));
+        app.sort = SortOrder::Category;
+        assert!(app.rows().contains(&Row::Unset("layer")));
+
+        app.select_row(Row::Unset("height"));
+        press(&mut app, KeyCode::Enter);
+        assert_eq!(app.params_screen, ParamsScreen::AddValue { key: "height".to_string(), input: default_value("height").unwrap().to_string() });
+        let action = press(&mut app, KeyCode::Enter);
+        assert_eq!(action, Action::Save { key: "height".into(), value: default_value("height").unwrap().into() });
+        assert!(!app.rows().contains(&Row::Unset("height")));
+        assert_eq!(app.list_state.selected(), Some(1));
+
+        press(&mut app, KeyCode::Char('F'));
+        assert_eq!(app.rows().len(), 3);
+    }
+
+    #[test]
+    fn pasted_config_is_reviewed_against_the_config_and_merged() {
+        let mut app = app_with(&[("width", "300"), ("border-size", "2")]);
+        press(&mut app, KeyCode::Char('v'));
+        app.handle_paste("width=300\r\nborder-size=4\r\n[urgency=critical]\r\n");
+        type_str(&mut app, "border-color=#bf616a");
+        let ctrl_d = KeyEvent::new(KeyCode::Char('d'), KeyModifiers::CONTROL);
+        app.handle_key(ctrl_d);
+        let Some(Overlay::ImportReview { mappings, source, .. }) = &app.overlay else { panic!("{:?}", app.overlay) };
+        assert_eq!(mappings.iter().map(|m| m.enabled).collect::<Vec<_>>(), [false, true, true]);
+        assert_eq!(*source, "pasted text");
+        press(&mut app, KeyCode::Char('j'));
+        press(&mut app, KeyCode::Char(' '));
+        let action = press(&mut app, KeyCode::Enter);
+        assert_eq!(action, Action::Save { key: "pasted text".into(), value: "<imported>".into() });
+        assert_eq!(app.cfg.params_of(0), &[Param::new("width", "300"), Param::new("border-size", "2")]);
+        assert_eq!(app.cfg.sections[0].params, [Param::new("border-color", "#bf616a")]);
+
+        press(&mut app, KeyCode::Char('v'));
+        type_str(&mut app, "# only a comment");
+        app.handle_key(ctrl_d);
+        assert!(matches!(app.overlay, Some(Overlay::PasteConfig { .. })));
+        assert!(matches!(app.message, Some(Err(_))));
+    }
+
+    #[test]
+    fn tab_cycles_the_screens_and_skips_sections_until_there_are_some() {
+        let mut app = app_with(&[("width", "300")]);
+        assert_eq!(app.tab, Tab::Global);
+        assert_eq!(press(&mut app, KeyCode::Tab), Action::ListModes);
+        assert_eq!(app.tab, Tab::Modes);
+        assert_eq!(press(&mut app, KeyCode::BackTab), Action::None);
+        assert_eq!(app.tab, Tab::Global);
+
+        app.cfg.sections.push(Section { criteria: "urgency=low".parse().unwrap(), params: vec![Param::new("width", "200")] });
+        app.cfg.sections.push(Section { criteria: "urgency=critical".parse().unwrap(), params: Vec::new() });
+        press(&mut app, KeyCode::Tab);
+        assert_eq!((app.tab, app.section, app.section_list), (Tab::Sections, 0, 0));
+        press(&mut app, KeyCode::Enter);
+        assert_eq!((app.tab, app.section), (Tab::Sections, 1));
+        press(&mut app, KeyCode::Char(']'));
+        press(&mut app, KeyCode::Tab);
+        assert_eq!(press(&mut app, KeyCode::Tab), Action::ListNotifications);
+        assert_eq!(app.tab, Tab::Notifications);
+        press(&mut app, KeyCode::Tab);
+        assert_eq!((app.tab, app.section), (Tab::Global, 0));
+        press(&mut app, KeyCode::BackTab);
+        press(&mut app, KeyCode::BackTab);
+        press(&mut app, KeyCode::BackTab);
+        assert_eq!((app.tab, app.section, app.section_list), (Tab::Sections, 0, 1));
+    }
+
+    #[test]
+    fn an_overlay_takes_every_key_and_leaves_the_tab_as_it_was() {
+        let mut app = app_with(&[("width", "300")]);
+        press(&mut app, KeyCode::Char(':'));
+        type_str(&mut app, "q");
+        app.cfg.params[0].value = "310".into();
+        press(&mut app, KeyCode::Enter);
+        assert_eq!((&app.overlay, &app.params_screen), (&Some(Overlay::ConfirmQuit), &ParamsScreen::List));
+        assert_eq!(press(&mut app, KeyCode::Tab), Action::None);
+        assert_eq!(app.tab, Tab::Global);
+        press(&mut app, KeyCode::Esc);
+        assert_eq!(app.overlay, None);
+        assert_eq!(press(&mut app, KeyCode::Tab), Action::ListModes);
+    }
+
+    #[test]
+    fn a_section_picked_from_the_list_scopes_edits_until_esc() {
+        let mut app = app_with(&[("width", "300")]);
+        app.cfg.sections.push(Section { criteria: "urgency=low".parse().unwrap(), params: Vec::new() });
+        app.cfg.sections.push(Section { criteria: "urgency=critical".parse().unwrap(), params: Vec::new() });
+        press(&mut app, KeyCode::Tab);
+        press(&mut app, KeyCode::Char('j'));
+        press(&mut app, KeyCode::Enter);
+        assert_eq!((&app.params_screen, app.section), (&ParamsScreen::List, 2));
+        press(&mut app, KeyCode::Char('a'));
+        type_str(&mut app, "width");
+        press(&mut app, KeyCode::Enter);
+        type_str(&mut app, "400");
+        assert!(matches!(press(&mut app, KeyCode::Enter), Action::Save { .. }));
+        assert_eq!(app.cfg.sections[1].params, vec![Param::new("width", "400")]);
+        assert_eq!(app.cfg.params, vec![Param::new("width", "300")]);
+        assert!(app.cfg.sections[0].params.is_empty());
+        press(&mut app, KeyCode::Esc);
+        assert_eq!((&app.params_screen, app.section, app.tab), (&ParamsScreen::List, 0, Tab::Global));
+    }
+
+    #[test]
+    fn each_tab_keeps_its_screen_and_its_keys_to_itself() {
+        let mut app = app_with(&[("width", "300")]);
+        press(&mut app, KeyCode::Char('+'));
+        app.set_modes(Ok(vec!["default".to_string(), "dnd".to_string()]));
+        press(&mut app, KeyCode::Tab);
+        press(&mut app, KeyCode::Char('j'));
+        assert_eq!(app.modes_screen, ModesScreen::List { idx: 1 });
+        // Undo is the params screen's; here it is just a key.
+        assert_eq!(press(&mut app, KeyCode::Char('u')), Action::None);
+        assert_eq!(app.cfg.params[0].value, "310");
+        press(&mut app, KeyCode::BackTab);
+        assert_eq!(press(&mut app, KeyCode::Tab), Action::ListModes);
+        assert_eq!(app.modes_screen, ModesScreen::List { idx: 1 });
+
+        press(&mut app, KeyCode::Char('a'));
+        press(&mut app, KeyCode::Tab);
+        assert_eq!(app.tab, Tab::Modes);
+        app.handle_paste("work");
+        assert_eq!(app.modes_screen, ModesScreen::Add { input: "work".to_string() });
+        press(&mut app, KeyCode::Enter);
+        assert_eq!((app.tab, app.section), (Tab::Sections, 1));
+        assert_eq!(app.cfg.sections[0].criteria, Criteria::mode("work"));
+    }
+}
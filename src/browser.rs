@@ -0,0 +1,137 @@
+use std::{fs, path::PathBuf};
+
+use home::home_dir;
+
+use crate::i18n::trf;
+use crate::icons;
+
+/// One row of the browser: a file or directory in the current directory.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Entry {
+    pub name: String,
+    pub path: PathBuf,
+    pub is_dir: bool,
+}
+
+/// A small file browser for building colon-separated path lists such as
+/// `icon-path`. Directories can be entered and picked; files are listed so
+/// icons can be previewed.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Browser {
+    pub dir: PathBuf,
+    /// Contents of `dir`, directories first, with `..` on top when there is
+    /// a parent.
+    pub entries: Vec<Entry>,
+    pub selected: usize,
+    /// The paths picked so far, in order; this is the value being built.
+    pub picked: Vec<PathBuf>,
+    /// Icon themes found directly in `dir`.
+    pub themes: Vec<String>,
+    /// Why `dir` couldn't be listed, if it couldn't.
+    pub error: Option<String>,
+}
+
+impl Browser {
+    /// Start from an existing `a:b:c` value, in the first picked directory
+    /// that exists, or else in the system icon directory or home.
+    pub fn new(value: &str) -> Self {
+        let picked: Vec<PathBuf> = value.split(':').map(str::trim).filter(|p| !p.is_empty()).map(PathBuf::from).collect();
+        let start = picked
+            .iter()
+            .find(|p| p.is_dir())
+            .cloned()
+            .or_else(|| Some(PathBuf::from("/usr/share/icons")).filter(|p| p.is_dir()))
+            .or_else(home_dir)
+            .unwrap_or_else(|| PathBuf::from("/"));
+        let mut browser =
+            Browser { dir: PathBuf::new(), entries: Vec::new(), selected: 0, picked, themes: Vec::new(), error: None };
+        browser.open(start);
+        browser
+    }
+
+    /// List `dir` and make it the current directory.
+    pub fn open(&mut self, dir: PathBuf) {
+        self.entries.clear();
+        self.selected = 0;
+        self.error = None;
+        match fs::read_dir(&dir) {
+            Ok(rd) => {
+                let mut entries: Vec<Entry> = rd
+                    .filter_map(Result::ok)
+                    .map(|e| Entry { name: e.file_name().to_string_lossy().into_owned(), path: e.path(), is_dir: e.path().is_dir() })
+                    .filter(|e| !e.name.starts_with('.'))
+                    .collect();
+                entries.sort_by(|a, b| b.is_dir.cmp(&a.is_dir).then_with(|| a.name.cmp(&b.name)));
+                if let Some(parent) = dir.parent() {
+                    entries.insert(0, Entry { name: "..".to_string(), path: parent.to_path_buf(), is_dir: true });
+                }
+                self.entries = entries;
+            }
+            Err(e) => self.error = Some(trf("browser.read_failed", &[&dir.display(), &e])),
+        }
+        self.themes = icons::themes_in(&dir);
+        self.dir = dir;
+    }
+
+    pub fn selected_entry(&self) -> Option<&Entry> {
+        self.entries.get(self.selected)
+    }
+
+    pub fn down(&mut self) {
+        if !self.entries.is_empty() {
+            self.selected = (self.selected + 1) % self.entries.len();
+        }
+    }
+
+    pub fn up(&mut self) {
+        if !self.entries.is_empty() {
+            self.selected = if self.selected == 0 { self.entries.len() - 1 } else { self.selected - 1 };
+        }
+    }
+
+    /// Enter the highlighted directory.
+    pub fn enter(&mut self) {
+        if let Some(e) = self.selected_entry()
+            && e.is_dir
+        {
+            let path = e.path.clone();
+            self.open(path);
+        }
+    }
+
+    /// Go to the parent directory, keeping the directory we came from
+    /// highlighted.
+    pub fn parent(&mut self) {
+        let Some(parent) = self.dir.parent().map(PathBuf::from) else { return };
+        let from = self.dir.clone();
+        self.open(parent);
+        if let Some(i) = self.entries.iter().position(|e| e.path == from) {
+            self.selected = i;
+        }
+    }
+
+    /// Add the highlighted directory to the picked paths, or remove it if
+    /// it is already there. On `..` this picks the current directory.
+    pub fn toggle(&mut self) {
+        let path = match self.selected_entry() {
+            Some(e) if e.name == ".." => self.dir.clone(),
+            Some(e) if e.is_dir => e.path.clone(),
+            _ => return,
+        };
+        match self.picked.iter().position(|p| *p == path) {
+            Some(i) => {
+                self.picked.remove(i);
+            }
+            None => self.picked.push(path),
+        }
+    }
+
+    pub fn is_picked(&self, entry: &Entry) -> bool {
+        self.picked.contains(&entry.path)
+    }
+
+    /// The picked paths as a colon-separated value.
+    pub fn value(&self) -> String {
+        self.picked.iter().map(|p| p.display().to_string()).collect::<Vec<_>>().join(":")
+    }
+}
@@ -0,0 +1,199 @@
+//! `get`, `set`, `list`, `delete` and `apply`: edit the config from
+//! scripts and key bindings without opening the editor.
+//!
+//! ```text
+//! mako-tui get font
+//! mako-tui set border-radius 8
+//! mako-tui --section urgency=critical set border-color '#ff0000'
+//! mako-tui list
+//! mako-tui delete max-icon-size
+//! mako-tui apply ~/.config/mako-editor/themes/nord.toml
+//! mako-tui validate ~/dotfiles/mako/config
+//! ```
+
+use std::path::Path;
+
+use crate::config::{Config, Criteria};
+use crate::i18n::{tr, trf};
+use crate::mako_config::{normalize_value, validate};
+use crate::check::{self, Severity};
+use crate::themes;
+
+/// What a subcommand did.
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct Outcome {
+    /// Text for stdout.
+    pub output: String,
+    /// The changes made, as `(key, value)` with `<deleted>` for a deleted
+    /// key like `App::pending`; the config should be saved unless empty.
+    pub changes: Vec<(String, String)>,
+}
+
+/// Whether `arg` names one of the subcommands, so the caller knows to
+/// hand the arguments to `run` instead of starting the editor.
+pub fn is_subcommand(arg: &str) -> bool {
+    matches!(arg, "get" | "set" | "list" | "delete" | "apply" | "--section")
+}
+
+/// Run a subcommand against `cfg`. `args` start at the subcommand, or
+/// at `--section CRITERIA` to work in a section instead of the global
+/// params. `set` and `apply` check values like the editor does; `--force`
+/// saves them anyway.
+pub fn run(cfg: &mut Config, args: &[String]) -> Result<Outcome, String> {
+    let mut args: Vec<&str> = args.iter().map(String::as_str).collect();
+    let force = args.contains(&"--force");
+    args.retain(|a| *a != "--force");
+    let section = match args.as_slice() {
+        ["--section", criteria, rest @ ..] => {
+            let n = find_section(cfg, criteria)?;
+            args = rest.to_vec();
+            n
+        }
+        _ => 0,
+    };
+    let params = cfg.params_of(section);
+    match args.as_slice() {
+        ["get", key] => match params.iter().rev().find(|p| p.key == *key) {
+            Some(p) => Ok(Outcome { output: format!("{}\n", p.value), changes: Vec::new() }),
+            None => Err(trf("cli.not_set", &[key])),
+        },
+        ["list"] => Ok(Outcome {
+            output: params.iter().map(|p| format!("{}={}\n", p.key, p.value)).collect(),
+            changes: Vec::new(),
+        }),
+        ["set", key, value @ ..] if !value.is_empty() => {
+            let value = normalize_value(key, &value.join(" "));
+            if !force && let Err(e) = validate(key, &cfg.expand(&value)) {
+                return Err(trf("cli.invalid", &[&e]));
+            }
+            cfg.set(section, key, value.clone());
+            Ok(Outcome { output: String::new(), changes: vec![(key.to_string(), value)] })
+        }
+        // A theme sets its keys in the sections it names, whatever
+        // `--section` says, adding sections the config lacks.
+        ["apply", theme] => {
+            let theme = themes::load(theme)?;
+            let sections = theme.sections.iter().map(|s| (Some(&s.criteria), &s.params));
+            let mut changes = Vec::new();
+            for (criteria, params) in std::iter::once((None, &theme.params)).chain(sections) {
+                let n = criteria.map_or(0, |c| cfg.section_for(c));
+                for p in params {
+                    let value = normalize_value(&p.key, &p.value);
+                    if !force && let Err(e) = validate(&p.key, &cfg.expand(&value)) {
+                        return Err(trf("cli.invalid", &[&format!("{}: {}", p.key, e)]));
+                    }
+                    cfg.set(n, &p.key, value.clone());
+                    changes.push((p.key.clone(), value));
+                }
+            }
+            Ok(Outcome { output: String::new(), changes })
+        }
+        ["delete", key] => {
+            let params = cfg.params_of_mut(section);
+            let before = params.len();
+            params.retain(|p| p.key != *key);
+            if params.len() == before {
+                return Err(trf("cli.not_set", &[key]));
+            }
+            Ok(Outcome { output: String::new(), changes: vec![(key.to_string(), "<deleted>".to_string())] })
+        }
+        _ => Err(tr("cli.usage").to_string()),
+    }
+}
+
+/// `validate`: one `PATH:LINE: SEVERITY: MESSAGE` line per problem in
+/// `text`, read from `path`, and a summary. The flag is whether any of the
+/// problems is an error, which makes the command fail.
+pub fn validate_report(path: &Path, text: &str) -> (String, bool) {
+    let problems = check::check(text);
+    let mut out: String = problems.iter().map(|p| format!("{}:{}\n", path.display(), p)).collect();
+    let errors = problems.iter().filter(|p| p.severity == Severity::Error).count();
+    let warnings = problems.len() - errors;
+    out.push_str(&trf("cli.validate_summary", &[&path.display(), &errors, &warnings]));
+    out.push('\n');
+    (out, errors > 0)
+}
+
+/// The number `Config::params_of` uses for the section with these
+/// criteria.
+fn find_section(cfg: &Config, criteria: &str) -> Result<usize, String> {
+    let wanted: Criteria = criteria.parse().map_err(|_| trf("cli.no_section", &[&criteria]))?;
+    cfg.sections
+        .iter()
+        .position(|s| s.criteria == wanted)
+        .map(|i| i + 1)
+        .ok_or_else(|| trf("cli.no_section", &[&criteria]))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn run_str(cfg: &mut Config, args: &str) -> Result<Outcome, String> {
+        run(cfg, &args.split(' ').map(String::from).collect::<Vec<_>>())
+    }
+
+    #[test]
+    fn get_set_list_and_delete() {
+        let mut cfg: Config = "font=mono 10\nwidth=300\nwidth=350\n[urgency=high]\nborder-color=#ff0000\n".parse().unwrap();
+        assert_eq!(run_str(&mut cfg, "get width").unwrap().output, "350\n");
+        assert!(run_str(&mut cfg, "get height").is_err());
+        assert_eq!(run_str(&mut cfg, "set width 320").unwrap().changes, [("width".to_string(), "320".to_string())]);
+        assert_eq!(cfg.params[1].value, "300");
+        assert_eq!(cfg.params[2].value, "320");
+        run_str(&mut cfg, "set font monospace 12").unwrap();
+        assert_eq!(run_str(&mut cfg, "get font").unwrap().output, "monospace 12\n");
+        assert!(run_str(&mut cfg, "set border-size thick").is_err());
+        run_str(&mut cfg, "set border-size thick --force").unwrap();
+        let forced = run_str(&mut cfg, "set --force border-size thick").unwrap();
+        assert_eq!(forced.changes, [("border-size".to_string(), "thick".to_string())]);
+        run_str(&mut cfg, "set default-timeout 5s").unwrap();
+        assert_eq!(run_str(&mut cfg, "get default-timeout").unwrap().output, "5000\n");
+        assert_eq!(run_str(&mut cfg, "delete width").unwrap().changes, [("width".to_string(), "<deleted>".to_string())]);
+        assert!(run_str(&mut cfg, "delete width").is_err());
+        assert_eq!(
+            run_str(&mut cfg, "list").unwrap().output,
+            "font=monospace 12\nborder-size=thick\ndefault-timeout=5000\n"
+        );
+    }
+
+    #[test]
+    fn section_option_works_in_that_section() {
+        let mut cfg: Config = "font=mono 10\n[urgency=high]\nborder-color=#ff0000\n".parse().unwrap();
+        assert_eq!(run_str(&mut cfg, "--section urgency=high get border-color").unwrap().output, "#ff0000\n");
+        run_str(&mut cfg, "--section urgency=high set width 500").unwrap();
+        assert_eq!(cfg.sections[0].params.len(), 2);
+        assert_eq!(cfg.params.len(), 1);
+        assert!(run_str(&mut cfg, "--section urgency=low list").is_err());
+        assert!(run_str(&mut cfg, "frobnicate").is_err());
+    }
+
+    #[test]
+    fn apply_merges_a_theme_into_its_sections() {
+        let dir = std::env::temp_dir().join(format!("mako-tui-apply-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let theme = dir.join("red.toml");
+        std::fs::write(&theme, "border-color = \"#f00\"\n\n[\"urgency=critical\"]\nborder-size = 4\n").unwrap();
+        let mut cfg: Config = "default-timeout=5000\nborder-color=#ffffff\n".parse().unwrap();
+        let changes = run_str(&mut cfg, &format!("apply {}", theme.display())).unwrap().changes;
+        assert_eq!(changes, [("border-color".to_string(), "#ff0000".to_string()), ("border-size".to_string(), "4".to_string())]);
+        assert_eq!(cfg.to_string(), "default-timeout=5000\nborder-color=#ff0000\n\n[urgency=critical]\nborder-size=4\n");
+        std::fs::write(&theme, "border-size = thick\n").unwrap();
+        assert!(run_str(&mut cfg, &format!("apply {}", theme.display())).is_err());
+        run_str(&mut cfg, "apply nord").unwrap();
+        assert_eq!(cfg.params[1].value, "#88c0d0");
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn validate_lists_problems_and_fails_on_errors() {
+        let path = Path::new("config");
+        let (out, failed) = validate_report(path, "font=mono 10\nwidth=wide\nmy-key=1\n");
+        let lines: Vec<&str> = out.lines().collect();
+        assert!(lines[0].starts_with("config:2: error: "), "{}", out);
+        assert!(lines[1].starts_with("config:3: warning: "), "{}", out);
+        assert_eq!(lines.len(), 3);
+        assert!(failed);
+        assert!(!validate_report(path, "my-key=1\n").1);
+    }
+}
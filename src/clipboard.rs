@@ -0,0 +1,56 @@
+//! The Wayland clipboard, through `wl-copy` and `wl-paste` from
+//! wl-clipboard.
+
+#[cfg(feature = "clipboard")]
+use std::{
+    io::Write,
+    process::{Command, Stdio},
+};
+
+#[cfg(feature = "clipboard")]
+use crate::i18n::trf;
+#[cfg(feature = "clipboard")]
+use crate::logging;
+#[cfg(not(feature = "clipboard"))]
+use crate::i18n::tr;
+
+/// The text on the clipboard, without the trailing newline many programs
+/// copy along with it.
+#[cfg(feature = "clipboard")]
+pub fn paste() -> Result<String, String> {
+    match logging::output(Command::new("wl-paste").arg("--no-newline")) {
+        Ok(output) if output.status.success() => Ok(String::from_utf8_lossy(&output.stdout).trim_end_matches(['\r', '\n']).to_string()),
+        Ok(output) => Err(String::from_utf8_lossy(&output.stderr).trim().to_string()),
+        Err(e) => Err(trf("error.clipboard_exec", &[&"wl-paste", &e])),
+    }
+}
+
+#[cfg(not(feature = "clipboard"))]
+pub fn paste() -> Result<String, String> {
+    Err(tr("error.clipboard_disabled").to_string())
+}
+
+/// Put `text` on the clipboard. `wl-copy` stays in the background to serve
+/// it, so this only waits for it to read its input.
+#[cfg(feature = "clipboard")]
+pub fn copy(text: &str) -> Result<(), String> {
+    let mut child = Command::new("wl-copy")
+        .stdin(Stdio::piped())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()
+        .map_err(|e| trf("error.clipboard_exec", &[&"wl-copy", &e]))?;
+    if let Some(mut stdin) = child.stdin.take() {
+        stdin.write_all(text.as_bytes()).map_err(|e| trf("error.clipboard_exec", &[&"wl-copy", &e]))?;
+    }
+    match child.wait() {
+        Ok(status) if status.success() => Ok(()),
+        Ok(status) => Err(trf("error.clipboard_failed", &[&"wl-copy", &status])),
+        Err(e) => Err(trf("error.clipboard_exec", &[&"wl-copy", &e])),
+    }
+}
+
+#[cfg(not(feature = "clipboard"))]
+pub fn copy(_text: &str) -> Result<(), String> {
+    Err(tr("error.clipboard_disabled").to_string())
+}
@@ -0,0 +1,133 @@
+//! The `:` command line: vim-style commands for what otherwise takes a key
+//! binding per mode.
+//!
+//! ```text
+//! :w            write the config
+//! :q  :q!       quit (asking about unsaved changes) / quit anyway
+//! :wq  :x       write and quit
+//! :set key=value
+//! :d key        delete key (to the trash)
+//! :profile name edit another profile's file
+//! :reload       makoctl reload
+//! :s/old/new/   replace text in the open section's values
+//! :%s/old/new/  ... in every section's (`colors` after it: color keys only)
+//! ```
+
+use crate::i18n::{tr, trf};
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ExCommand {
+    Write,
+    Quit { force: bool },
+    WriteQuit,
+    Set { key: String, value: String },
+    Delete { key: String },
+    Profile { name: String },
+    Reload,
+    /// Replace every `from` in values with `to`: in every section with
+    /// `all_sections`, else the open one, and only in color keys with
+    /// `colors`.
+    Replace { from: String, to: String, all_sections: bool, colors: bool },
+}
+
+/// Read one command line, without its leading `:`.
+pub fn parse(line: &str) -> Result<ExCommand, String> {
+    let line = line.trim();
+    if let Some(rest) = line.strip_prefix('%').unwrap_or(line).strip_prefix('s')
+        && rest.starts_with(|c: char| c.is_ascii_punctuation() && c != '\\')
+    {
+        return parse_replace(rest, line.starts_with('%'));
+    }
+    let (name, arg) = line.split_once(char::is_whitespace).map_or((line, ""), |(n, a)| (n, a.trim()));
+    match (name, arg) {
+        ("w" | "write", "") => Ok(ExCommand::Write),
+        ("q" | "quit", "") => Ok(ExCommand::Quit { force: false }),
+        ("q!" | "quit!", "") => Ok(ExCommand::Quit { force: true }),
+        ("wq" | "x", "") => Ok(ExCommand::WriteQuit),
+        ("reload", "") => Ok(ExCommand::Reload),
+        ("set" | "se", arg) => match arg.split_once('=') {
+            Some((key, value)) if !key.trim().is_empty() => {
+                Ok(ExCommand::Set { key: key.trim().to_string(), value: value.trim().to_string() })
+            }
+            _ => Err(tr("cmdline.usage_set").to_string()),
+        },
+        ("d" | "delete", key) if !key.is_empty() && !key.contains(char::is_whitespace) => {
+            Ok(ExCommand::Delete { key: key.to_string() })
+        }
+        ("d" | "delete", _) => Err(tr("cmdline.usage_delete").to_string()),
+        ("profile", name) if !name.is_empty() => Ok(ExCommand::Profile { name: name.to_string() }),
+        ("profile", _) => Err(tr("cmdline.usage_profile").to_string()),
+        _ => Err(trf("cmdline.unknown", &[&line])),
+    }
+}
+
+/// `/old/new/[g] [colors]`, after the `s`: any punctuation can stand in
+/// for `/`, and a backslash puts it in `old` or `new` as itself. The `g`
+/// vim wants is allowed, though every match is replaced either way.
+fn parse_replace(s: &str, all_sections: bool) -> Result<ExCommand, String> {
+    let mut chars = s.chars();
+    let delim = chars.next().unwrap_or('/');
+    let mut fields = vec![String::new()];
+    let mut escaped = false;
+    for c in chars {
+        match c {
+            _ if escaped => {
+                if c != delim {
+                    fields.last_mut().unwrap().push('\\');
+                }
+                fields.last_mut().unwrap().push(c);
+                escaped = false;
+            }
+            '\\' => escaped = true,
+            _ if c == delim && fields.len() < 3 => fields.push(String::new()),
+            _ => fields.last_mut().unwrap().push(c),
+        }
+    }
+    let usage = || tr("cmdline.usage_replace").to_string();
+    let [from, to, flags] = <[String; 3]>::try_from(fields).map_err(|_| usage())?;
+    let flags = flags.trim();
+    let colors = match flags.strip_prefix('g').unwrap_or(flags).trim() {
+        "" => false,
+        "colors" => true,
+        _ => return Err(usage()),
+    };
+    if from.is_empty() {
+        return Err(usage());
+    }
+    Ok(ExCommand::Replace { from, to, all_sections, colors })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reads_each_command() {
+        assert_eq!(parse("w"), Ok(ExCommand::Write));
+        assert_eq!(parse(" q! "), Ok(ExCommand::Quit { force: true }));
+        assert_eq!(parse("x"), Ok(ExCommand::WriteQuit));
+        assert_eq!(
+            parse("set border-color = #ff0000"),
+            Ok(ExCommand::Set { key: "border-color".to_string(), value: "#ff0000".to_string() })
+        );
+        assert_eq!(parse("set font=Inter Bold 10").map(|c| matches!(c, ExCommand::Set { .. })), Ok(true));
+        assert_eq!(parse("d width"), Ok(ExCommand::Delete { key: "width".to_string() }));
+        assert_eq!(parse("profile work"), Ok(ExCommand::Profile { name: "work".to_string() }));
+        assert_eq!(parse("reload"), Ok(ExCommand::Reload));
+        assert!(parse("set width").is_err());
+        assert!(parse("d").is_err());
+        assert!(parse("w extra").is_err());
+        assert!(parse("frobnicate").is_err());
+
+        let replace = |from: &str, to: &str, all_sections, colors| {
+            Ok(ExCommand::Replace { from: from.to_string(), to: to.to_string(), all_sections, colors })
+        };
+        assert_eq!(parse("%s/#1d1f21/#282828/"), replace("#1d1f21", "#282828", true, false));
+        assert_eq!(parse("s/Inter 10/Inter 11/g"), replace("Inter 10", "Inter 11", false, false));
+        assert_eq!(parse("%s|a/b|c\\|d| colors"), replace("a/b", "c|d", true, true));
+        assert_eq!(parse("s/x//"), replace("x", "", false, false));
+        assert!(parse("%s/x/y").is_err());
+        assert!(parse("s//y/").is_err());
+        assert!(parse("%s/x/y/ fonts").is_err());
+    }
+}
@@ -0,0 +1,161 @@
+//! `completions {bash,zsh,fish}`: shell completion scripts for the
+//! command line. Key names for `get`, `set` and `delete`, and the values
+//! `set` accepts, come from `completions keys` and `completions values
+//! KEY` when the shell completes, so keys added to the config (or to the
+//! tables in a newer build) show up without regenerating the script.
+//!
+//! ```text
+//! mako-tui completions bash > ~/.local/share/bash-completion/completions/mako-tui
+//! mako-tui completions zsh > ~/.zfunc/_mako-tui
+//! mako-tui completions fish > ~/.config/fish/completions/mako-tui.fish
+//! ```
+
+use crate::config::Config;
+use crate::i18n::tr;
+use crate::mako_config::{allowed_values, deprecated, known_keys};
+
+const SUBCOMMANDS: &str = "get set list delete apply validate completions";
+const OPTIONS: &str =
+    "--config --section --force --log-level --compact --headless --size --export-minimal --export-nix --refresh-keys";
+const LOG_LEVELS: &str = "off error warn info debug trace";
+const SHELLS: &str = "bash zsh fish";
+
+/// What `completions ARGS` prints.
+pub fn run(cfg: &Config, args: &[String]) -> Result<String, String> {
+    match args.iter().map(String::as_str).collect::<Vec<_>>().as_slice() {
+        ["bash"] => Ok(fill(BASH)),
+        ["zsh"] => Ok(fill(ZSH)),
+        ["fish"] => Ok(fish()),
+        ["keys"] => Ok(keys(cfg).iter().map(|k| format!("{}\n", k)).collect()),
+        ["values", key] => Ok(allowed_values(key).unwrap_or_default().iter().map(|v| format!("{}\n", v)).collect()),
+        _ => Err(tr("cli.usage").to_string()),
+    }
+}
+
+/// `script` with the word lists filled in.
+fn fill(script: &str) -> String {
+    script
+        .replace("@SUBCOMMANDS@", SUBCOMMANDS)
+        .replace("@OPTIONS@", OPTIONS)
+        .replace("@LEVELS@", LOG_LEVELS)
+        .replace("@SHELLS@", SHELLS)
+}
+
+/// Every key mako reads, then any others the config sets.
+fn keys(cfg: &Config) -> Vec<String> {
+    let mut out: Vec<String> = known_keys()
+        .into_iter()
+        .map(|(k, _)| k)
+        .filter(|k| *k != "<custom>" && deprecated(k).is_none())
+        .map(String::from)
+        .collect();
+    for p in cfg.params.iter().chain(cfg.sections.iter().flat_map(|s| &s.params)) {
+        if !out.contains(&p.key) {
+            out.push(p.key.clone());
+        }
+    }
+    out
+}
+
+const BASH: &str = r#"# bash completion for mako-tui
+_mako_tui() {
+    local cur="${COMP_WORDS[COMP_CWORD]}" prev="${COMP_WORDS[COMP_CWORD-1]}"
+    case "$prev" in
+        --config|--headless|apply|validate|--refresh-keys)
+            COMPREPLY=($(compgen -f -- "$cur")); return ;;
+        --log-level)
+            COMPREPLY=($(compgen -W "@LEVELS@" -- "$cur")); return ;;
+        --section|--size)
+            return ;;
+        get|set|delete)
+            COMPREPLY=($(compgen -W "$(mako-tui completions keys 2>/dev/null)" -- "$cur")); return ;;
+        completions)
+            COMPREPLY=($(compgen -W "@SHELLS@" -- "$cur")); return ;;
+    esac
+    if [[ $COMP_CWORD -ge 3 && "${COMP_WORDS[COMP_CWORD-2]}" == set ]]; then
+        COMPREPLY=($(compgen -W "$(mako-tui completions values "$prev" 2>/dev/null)" -- "$cur"))
+    elif [[ "$cur" == -* ]]; then
+        COMPREPLY=($(compgen -W "@OPTIONS@" -- "$cur"))
+    else
+        COMPREPLY=($(compgen -W "@SUBCOMMANDS@" -- "$cur"))
+    fi
+}
+complete -F _mako_tui mako-tui
+"#;
+
+const ZSH: &str = r#"#compdef mako-tui
+
+_mako_tui() {
+    case ${words[CURRENT-1]} in
+        --config|--headless|apply|validate|--refresh-keys) _files; return ;;
+        --log-level) compadd -- @LEVELS@; return ;;
+        --section|--size) return ;;
+        get|set|delete) compadd -- ${(f)"$(mako-tui completions keys 2>/dev/null)"}; return ;;
+        completions) compadd -- @SHELLS@; return ;;
+    esac
+    if (( CURRENT > 3 )) && [[ ${words[CURRENT-2]} == set ]]; then
+        compadd -- ${(f)"$(mako-tui completions values ${words[CURRENT-1]} 2>/dev/null)"}
+    elif [[ $PREFIX == -* ]]; then
+        compadd -- @OPTIONS@
+    else
+        compadd -- @SUBCOMMANDS@
+    fi
+}
+
+_mako_tui "$@"
+"#;
+
+fn fish() -> String {
+    let mut out = String::from(
+        "# fish completion for mako-tui\n\
+         function __mako_tui_set_key\n\
+         \x20   set -l tokens (commandline -opc)\n\
+         \x20   test (count $tokens) -ge 3; and test $tokens[-2] = set; and echo $tokens[-1]\n\
+         end\n\n\
+         complete -c mako-tui -f\n",
+    );
+    out.push_str(&format!("complete -c mako-tui -n __fish_use_subcommand -a '{}'\n", SUBCOMMANDS));
+    for option in OPTIONS.split(' ') {
+        let name = &option[2..];
+        let extra = match name {
+            "config" | "headless" | "refresh-keys" => " -r -F".to_string(),
+            "log-level" => format!(" -x -a '{}'", LOG_LEVELS),
+            "section" | "size" => " -x".to_string(),
+            _ => String::new(),
+        };
+        out.push_str(&format!("complete -c mako-tui -l {}{}\n", name, extra));
+    }
+    out.push_str(&format!(
+        "complete -c mako-tui -n '__fish_prev_arg_in get set delete' -a '(mako-tui completions keys 2>/dev/null)'\n\
+         complete -c mako-tui -n __mako_tui_set_key -a '(mako-tui completions values (__mako_tui_set_key) 2>/dev/null)'\n\
+         complete -c mako-tui -n '__fish_prev_arg_in apply validate' -F\n\
+         complete -c mako-tui -n '__fish_prev_arg_in completions' -a '{}'\n",
+        SHELLS
+    ));
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn run_str(cfg: &Config, args: &str) -> Result<String, String> {
+        run(cfg, &args.split(' ').map(String::from).collect::<Vec<_>>())
+    }
+
+    #[test]
+    fn scripts_cover_every_subcommand_and_ask_for_keys() {
+        let cfg: Config = "width=300\nmy-future-key=1\n".parse().unwrap();
+        for shell in SHELLS.split(' ') {
+            let script = run_str(&cfg, shell).unwrap();
+            assert!(script.split('@').skip(1).all(|s| !s.starts_with(|c: char| c.is_ascii_uppercase())), "{}", shell);
+            assert!(script.contains("mako-tui completions keys"), "{}", shell);
+            assert!(SUBCOMMANDS.split(' ').all(|s| script.contains(s)), "{}", shell);
+        }
+        let keys = run_str(&cfg, "keys").unwrap();
+        assert!(keys.lines().any(|k| k == "border-radius"));
+        assert_eq!(keys.lines().last(), Some("my-future-key"));
+        assert_eq!(run_str(&cfg, "values layer").unwrap(), "overlay\ntop\nbottom\nbackground\n");
+        assert!(run_str(&cfg, "powershell").is_err());
+    }
+}
@@ -0,0 +1,203 @@
+//! Talking to the running mako: `makoctl`, and the notifications D-Bus
+//! interface through `gdbus`.
+
+#[cfg(feature = "makoctl")]
+use std::process::Command;
+
+use crate::notifications::{parse_list, Notification, TestNotification};
+
+#[cfg(feature = "makoctl")]
+use crate::i18n::trf;
+#[cfg(feature = "makoctl")]
+use crate::logging;
+#[cfg(feature = "makoctl")]
+use crate::notifications::parse_notify_reply;
+#[cfg(not(feature = "makoctl"))]
+use crate::i18n::tr;
+
+/// Announce a saved change with a desktop notification.
+pub fn notify(key: &str, value: &str) -> Result<(), String> {
+    notify_changes(&[(key.to_string(), value.to_string())])
+}
+
+/// Like `notify`, with one `key = value` line per change.
+pub fn notify_changes(changes: &[(String, String)]) -> Result<(), String> {
+    let body: Vec<String> = changes.iter().map(|(key, value)| format!("{} = {}", key, value)).collect();
+    let n = TestNotification {
+        summary: "Mako Config Updated".to_string(),
+        body: body.join("\n"),
+        app_name: String::new(),
+        ..TestNotification::default()
+    };
+    send_notification(&n)
+}
+
+/// Show `n`, `n.count` times, by calling `Notify` on the session bus, so
+/// its hints reach mako as they would from an application.
+#[cfg(feature = "makoctl")]
+pub fn send_notification(n: &TestNotification) -> Result<(), String> {
+    let args = n.gdbus_args();
+    for _ in 0..n.count.max(1) {
+        match logging::output(Command::new("gdbus").args(&args)) {
+            Ok(output) if output.status.success() => {}
+            Ok(output) => return Err(trf("error.notify_failed", &[&String::from_utf8_lossy(&output.stderr).trim()])),
+            Err(e) => return Err(trf("error.gdbus_exec", &[&e])),
+        }
+    }
+    Ok(())
+}
+
+#[cfg(not(feature = "makoctl"))]
+pub fn send_notification(_n: &TestNotification) -> Result<(), String> {
+    Err(tr("error.makoctl_disabled").to_string())
+}
+
+/// What the progress preview steps through, in percent.
+#[cfg(feature = "makoctl")]
+const PROGRESS_STEPS: [u8; 11] = [0, 10, 20, 30, 40, 50, 60, 70, 80, 90, 100];
+
+/// How long each step of the progress preview stays up.
+#[cfg(feature = "makoctl")]
+const PROGRESS_STEP: std::time::Duration = std::time::Duration::from_millis(300);
+
+/// Fill a progress bar: send `n` with the `value` hint going from 0 to
+/// 100, each step replacing the last, so `progress-color` and
+/// `progress-background-color` can be watched filling up. Blocks for the
+/// few seconds that takes.
+#[cfg(feature = "makoctl")]
+pub fn send_progress(n: &TestNotification) -> Result<(), String> {
+    let mut n = TestNotification { replaces: 0, ..n.clone() };
+    for (i, value) in PROGRESS_STEPS.into_iter().enumerate() {
+        if i > 0 {
+            std::thread::sleep(PROGRESS_STEP);
+        }
+        n.progress = Some(value);
+        match logging::output(Command::new("gdbus").args(n.gdbus_args())) {
+            Ok(output) if output.status.success() => {
+                n.replaces = parse_notify_reply(&String::from_utf8_lossy(&output.stdout)).unwrap_or(n.replaces);
+            }
+            Ok(output) => return Err(trf("error.notify_failed", &[&String::from_utf8_lossy(&output.stderr).trim()])),
+            Err(e) => return Err(trf("error.gdbus_exec", &[&e])),
+        }
+    }
+    Ok(())
+}
+
+#[cfg(not(feature = "makoctl"))]
+pub fn send_progress(_n: &TestNotification) -> Result<(), String> {
+    Err(tr("error.makoctl_disabled").to_string())
+}
+
+/// The installed mako's version, from `mako --version`.
+#[cfg(feature = "makoctl")]
+pub fn mako_version() -> Result<(u32, u32), String> {
+    match logging::output(Command::new("mako").arg("--version")) {
+        Ok(output) if output.status.success() => {
+            let out = String::from_utf8_lossy(&output.stdout);
+            parse_version(&out).ok_or_else(|| trf("error.mako_version", &[&out.trim()]))
+        }
+        Ok(output) => Err(String::from_utf8_lossy(&output.stderr).trim().to_string()),
+        Err(e) => Err(trf("error.mako_exec", &[&e])),
+    }
+}
+
+#[cfg(not(feature = "makoctl"))]
+pub fn mako_version() -> Result<(u32, u32), String> {
+    Err(tr("error.makoctl_disabled").to_string())
+}
+
+/// `(1, 9)` from `mako 1.9.0`, or from a bare `1.9` or `v1.9-12-gabc`.
+#[cfg_attr(not(feature = "makoctl"), allow(dead_code))]
+fn parse_version(s: &str) -> Option<(u32, u32)> {
+    let word = s.split_whitespace().find(|w| w.trim_start_matches('v').starts_with(|c: char| c.is_ascii_digit()))?;
+    let mut parts = word.trim_start_matches('v').split(|c: char| !c.is_ascii_digit());
+    Some((parts.next()?.parse().ok()?, parts.next()?.parse().ok()?))
+}
+
+/// The `makoctl` commands the editor uses, each with its output parsed.
+/// Errors are what makoctl printed, or why it couldn't be run.
+pub struct MakoCtl;
+
+impl MakoCtl {
+    /// `makoctl reload`, returning whatever it printed.
+    pub fn reload() -> Result<String, String> {
+        Self::run(&["reload"]).map(|out| out.trim().to_string())
+    }
+
+    /// The notifications on screen. mako 1.9 and later print text unless
+    /// given `-j`; older versions only speak JSON.
+    pub fn list() -> Result<Vec<Notification>, String> {
+        match parse_list(&Self::run(&["list"])?) {
+            Ok(list) => Ok(list),
+            Err(_) => parse_list(&Self::run(&["list", "-j"])?),
+        }
+    }
+
+    /// The notifications in mako's history, most recent first, read the
+    /// same way as `list`.
+    pub fn history() -> Result<Vec<Notification>, String> {
+        match parse_list(&Self::run(&["history"])?) {
+            Ok(list) => Ok(list),
+            Err(_) => parse_list(&Self::run(&["history", "-j"])?),
+        }
+    }
+
+    /// `makoctl restore`: put the most recent notification in the history
+    /// back on screen.
+    pub fn restore() -> Result<(), String> {
+        Self::run(&["restore"]).map(drop)
+    }
+
+    /// `makoctl dismiss -n ID`.
+    pub fn dismiss(id: u32) -> Result<(), String> {
+        Self::run(&["dismiss", "-n", &id.to_string()]).map(drop)
+    }
+
+    /// `makoctl invoke -n ID ACTION`: do what the notification's button
+    /// `action` does.
+    pub fn invoke(id: u32, action: &str) -> Result<(), String> {
+        Self::run(&["invoke", "-n", &id.to_string(), action]).map(drop)
+    }
+
+    /// `makoctl dismiss --all`.
+    pub fn dismiss_all() -> Result<(), String> {
+        Self::run(&["dismiss", "--all"]).map(drop)
+    }
+
+    /// `makoctl mode`: the modes that are on, one per line.
+    pub fn modes() -> Result<Vec<String>, String> {
+        Self::run(&["mode"]).map(|out| out.lines().map(str::trim).filter(|l| !l.is_empty()).map(String::from).collect())
+    }
+
+    /// `makoctl mode -t NAME`: turn mode `name` on, or off if it is on.
+    pub fn toggle_mode(name: &str) -> Result<(), String> {
+        Self::run(&["mode", "-t", name]).map(drop)
+    }
+
+    #[cfg(feature = "makoctl")]
+    fn run(args: &[&str]) -> Result<String, String> {
+        match logging::output(Command::new("makoctl").args(args)) {
+            Ok(output) if output.status.success() => Ok(String::from_utf8_lossy(&output.stdout).into_owned()),
+            Ok(output) => Err(String::from_utf8_lossy(&output.stderr).trim().to_string()),
+            Err(e) => Err(trf("error.makoctl_exec", &[&e])),
+        }
+    }
+
+    #[cfg(not(feature = "makoctl"))]
+    fn run(_args: &[&str]) -> Result<String, String> {
+        Err(tr("error.makoctl_disabled").to_string())
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reads_the_version_mako_prints() {
+        assert_eq!(parse_version("mako 1.9.0\n"), Some((1, 9)));
+        assert_eq!(parse_version("v1.10-3-gabc123"), Some((1, 10)));
+        assert_eq!(parse_version("mako (unknown)"), None);
+    }
+}
@@ -0,0 +1,115 @@
+//! Line diffs between the config on disk and the one being edited, for
+//! reviewing changes before they are written.
+
+/// What a row of a diff shows.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Kind {
+    /// A `@@ -a,b +c,d @@` hunk header.
+    Hunk,
+    Same,
+    Removed,
+    Added,
+}
+
+/// A unified diff from `old` to `new`: one row per line, prefixed with
+/// ` `, `-` or `+`, grouped into hunks with `context` unchanged lines around
+/// each change. Empty when the two are the same.
+pub fn unified(old: &str, new: &str, context: usize) -> Vec<(Kind, String)> {
+    let old: Vec<&str> = old.lines().collect();
+    let new: Vec<&str> = new.lines().collect();
+    let ops = ops(&old, &new);
+    let changes: Vec<usize> = (0..ops.len()).filter(|&i| ops[i].0 != Kind::Same).collect();
+    let mut out = Vec::new();
+    let mut i = 0;
+    while i < changes.len() {
+        // A hunk runs until the gap to the next change is too wide to share
+        // context.
+        let mut j = i;
+        while j + 1 < changes.len() && changes[j + 1] - changes[j] <= 2 * context + 1 {
+            j += 1;
+        }
+        let start = changes[i].saturating_sub(context);
+        let end = (changes[j] + context + 1).min(ops.len());
+        let hunk = &ops[start..end];
+        let count = |kind: Kind| hunk.iter().filter(|op| op.0 == Kind::Same || op.0 == kind).count();
+        let (old_count, new_count) = (count(Kind::Removed), count(Kind::Added));
+        // Like diff(1), an empty side names the line before it.
+        let line = |n: usize, count: usize| if count == 0 { n } else { n + 1 };
+        out.push((
+            Kind::Hunk,
+            format!(
+                "@@ -{},{} +{},{} @@",
+                line(ops[start].1, old_count),
+                old_count,
+                line(ops[start].2, new_count),
+                new_count
+            ),
+        ));
+        for (kind, _, _, text) in hunk {
+            let prefix = match kind {
+                Kind::Removed => '-',
+                Kind::Added => '+',
+                _ => ' ',
+            };
+            out.push((*kind, format!("{}{}", prefix, text)));
+        }
+        i = j + 1;
+    }
+    out
+}
+
+/// The edit script from `old` to `new` through their longest common
+/// subsequence, as `(kind, lines of old before, lines of new before, text)`.
+/// Removals come before additions where lines were replaced.
+fn ops<'a>(old: &[&'a str], new: &[&'a str]) -> Vec<(Kind, usize, usize, &'a str)> {
+    let (n, m) = (old.len(), new.len());
+    // lcs[i][j]: length of the longest common subsequence of old[i..], new[j..].
+    let mut lcs = vec![vec![0usize; m + 1]; n + 1];
+    for i in (0..n).rev() {
+        for j in (0..m).rev() {
+            lcs[i][j] = if old[i] == new[j] { lcs[i + 1][j + 1] + 1 } else { lcs[i + 1][j].max(lcs[i][j + 1]) };
+        }
+    }
+    let (mut i, mut j) = (0, 0);
+    let mut out = Vec::new();
+    while i < n || j < m {
+        if i < n && j < m && old[i] == new[j] {
+            out.push((Kind::Same, i, j, old[i]));
+            i += 1;
+            j += 1;
+        } else if i < n && (j == m || lcs[i + 1][j] >= lcs[i][j + 1]) {
+            out.push((Kind::Removed, i, j, old[i]));
+            i += 1;
+        } else {
+            out.push((Kind::Added, i, j, new[j]));
+            j += 1;
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn text(rows: &[(Kind, String)]) -> Vec<&str> {
+        rows.iter().map(|(_, line)| line.as_str()).collect()
+    }
+
+    #[test]
+    fn changed_lines_are_removed_then_added_with_context() {
+        let old = "a=1\nb=2\nc=3\nd=4\ne=5\nf=6\ng=7\n";
+        let new = "a=1\nb=2\nc=3\nd=40\ne=5\nf=6\ng=7\nh=8\n";
+        let rows = unified(old, new, 1);
+        assert_eq!(text(&rows), ["@@ -3,3 +3,3 @@", " c=3", "-d=4", "+d=40", " e=5", "@@ -7,1 +7,2 @@", " g=7", "+h=8"]);
+        assert_eq!(rows[2].0, Kind::Removed);
+        assert_eq!(rows[3].0, Kind::Added);
+        assert!(unified(old, old, 3).is_empty());
+    }
+
+    #[test]
+    fn new_and_emptied_files_name_the_line_before() {
+        assert_eq!(text(&unified("", "a=1\n", 3)), ["@@ -0,0 +1,1 @@", "+a=1"]);
+        assert_eq!(text(&unified("a=1\n", "", 3)), ["@@ -1,1 +0,0 @@", "-a=1"]);
+    }
+}
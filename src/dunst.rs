@@ -0,0 +1,325 @@
+//! Importing a dunstrc: dunst keys with a mako equivalent are translated,
+//! the rest are listed with the reason they were left out.
+
+use std::{io, path::{Path, PathBuf}};
+
+use home::home_dir;
+
+use crate::config::{Condition, Config, Criteria, Param};
+use crate::i18n::tr;
+
+/// One dunst setting and what it becomes in mako.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Mapping {
+    /// Where it came from, e.g. `[urgency_critical] frame_color = #ff0000`.
+    pub from: String,
+    /// The mako section it goes to; `None` for the global params.
+    pub section: Option<Criteria>,
+    /// The mako param, or `None` when there is no equivalent.
+    pub param: Option<Param>,
+    /// Why there is no param (an `import.*` message id).
+    pub skipped: Option<&'static str>,
+    /// Whether it is applied; the review screen toggles this.
+    pub enabled: bool,
+}
+
+/// Where dunst reads its config: `$XDG_CONFIG_HOME/dunst/dunstrc`.
+pub fn default_path() -> io::Result<PathBuf> {
+    Ok(crate::settings::config_dir()?.join("dunst/dunstrc"))
+}
+
+/// Translate a dunstrc. `[global]` and `[urgency_normal]` become global
+/// params, `[urgency_low]`/`[urgency_critical]` become urgency sections,
+/// and rules become sections on what they match (app name, summary, body,
+/// category, urgency).
+pub fn import(text: &str) -> Vec<Mapping> {
+    let mut out = Vec::new();
+    let mut section = String::new();
+    let mut keys = Vec::new();
+    for line in text.lines() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') || line.starts_with(';') {
+            continue;
+        }
+        if let Some(name) = line.strip_prefix('[').and_then(|l| l.strip_suffix(']')) {
+            out.extend(import_section(&section, &std::mem::take(&mut keys)));
+            section = name.trim().to_string();
+        } else if let Some((key, value)) = line.split_once('=') {
+            keys.push((key.trim().to_string(), unquote(value.trim()).to_string()));
+        }
+    }
+    out.extend(import_section(&section, &keys));
+    out
+}
+
+fn import_section(section: &str, keys: &[(String, String)]) -> Vec<Mapping> {
+    let criteria = match section {
+        "global" | "urgency_normal" => None,
+        "urgency_low" | "urgency_critical" => Some(urgency(&section["urgency_".len()..])),
+        _ => return import_rule(section, keys),
+    };
+    keys.iter().flat_map(|(k, v)| import_key(section, criteria.clone(), k, v, keys)).collect()
+}
+
+/// Apply the enabled mappings to `cfg`, each setting (rather than adding
+/// to) its key. Returns how many were applied.
+pub fn apply(mappings: &[Mapping], cfg: &mut Config) -> usize {
+    let mut applied = 0;
+    for m in mappings.iter().filter(|m| m.enabled) {
+        let Some(param) = &m.param else { continue };
+        let n = match &m.section {
+            Some(criteria) => cfg.section_for(criteria),
+            None => 0,
+        };
+        cfg.set(n, &param.key, param.value.clone());
+        applied += 1;
+    }
+    applied
+}
+
+fn urgency(level: &str) -> Criteria {
+    Criteria { conditions: vec![Condition { key: "urgency".to_string(), regex: false, value: Some(level.to_string()) }] }
+}
+
+fn unquote(s: &str) -> &str {
+    s.strip_prefix('"').and_then(|s| s.strip_suffix('"')).unwrap_or(s)
+}
+
+fn mapping(section: &str, criteria: &Option<Criteria>, key: &str, value: &str, param: Option<Param>, skipped: Option<&'static str>) -> Mapping {
+    Mapping {
+        from: format!("[{}] {} = {}", section, key, value),
+        section: criteria.clone(),
+        enabled: param.is_some(),
+        param,
+        skipped,
+    }
+}
+
+/// The mako params for one dunst key. Most keys map to one param, a few
+/// (`geometry`, `offset`, `icon_position`) to several or none.
+/// `keys` are the section's other keys, for the pairs dunst splits and
+/// mako doesn't.
+fn import_key(section: &str, criteria: Option<Criteria>, key: &str, value: &str, keys: &[(String, String)]) -> Vec<Mapping> {
+    let one = |mako: &str, v: String| vec![mapping(section, &criteria, key, value, Some(Param::new(mako, v)), None)];
+    let skip = |why| vec![mapping(section, &criteria, key, value, None, Some(why))];
+    match key {
+        "font" => one("font", value.to_string()),
+        "width" => one("width", value.to_string()),
+        "height" => one("height", value.to_string()),
+        "background" => one("background-color", value.to_string()),
+        "foreground" => one("text-color", value.to_string()),
+        "frame_color" => one("border-color", value.to_string()),
+        "frame_width" => one("border-size", value.to_string()),
+        "corner_radius" => one("border-radius", value.to_string()),
+        "highlight" => one("progress-color", format!("over {}", value)),
+        "format" => one("format", value.to_string()),
+        "max_icon_size" | "icon_size" => one("max-icon-size", value.to_string()),
+        "icon_path" => one("icon-path", value.to_string()),
+        "layer" => one("layer", value.to_string()),
+        "notification_limit" => one("max-visible", if value == "0" { "-1".to_string() } else { value.to_string() }),
+        "alignment" => one("text-alignment", value.to_string()),
+        "markup" => one("markup", if value == "no" { "0" } else { "1" }.to_string()),
+        "timeout" => match dunst_seconds(value) {
+            Some(ms) => one("default-timeout", ms.to_string()),
+            None => skip("import.bad_value"),
+        },
+        // mako's padding takes both directions: `vertical,horizontal`.
+        "padding" => match keys.iter().find(|(k, _)| k == "horizontal_padding") {
+            Some((_, h)) => one("padding", format!("{},{}", value, h)),
+            None => one("padding", value.to_string()),
+        },
+        "horizontal_padding" if keys.iter().any(|(k, _)| k == "padding") => skip("import.merged_padding"),
+        "horizontal_padding" => one("padding", format!("0,{}", value)),
+        "icon_position" => match value {
+            "off" => one("icons", "0".to_string()),
+            "left" | "right" | "top" => one("icon-location", value.to_string()),
+            _ => skip("import.bad_value"),
+        },
+        "origin" => match anchor(value) {
+            Some(a) => one("anchor", a.to_string()),
+            None => skip("import.bad_value"),
+        },
+        "offset" => match value.split_once(['x', ',']) {
+            Some((x, y)) => one("outer-margin", format!("{},{}", y.trim(), x.trim())),
+            None => skip("import.bad_value"),
+        },
+        // Old dunst: `WIDTHxHEIGHT+X+Y`, where a negative X counts from
+        // the right and a negative Y from the bottom.
+        "geometry" => match parse_geometry(value) {
+            Some((w, x, y)) => {
+                let mut out = Vec::new();
+                if w > 0 {
+                    out.extend(one("width", w.to_string()));
+                }
+                let v = if y < 0 { "bottom" } else { "top" };
+                let h = if x < 0 { "right" } else { "left" };
+                out.extend(one("anchor", format!("{}-{}", v, h)));
+                out.extend(one("outer-margin", format!("{},{}", y.abs(), x.abs())));
+                out
+            }
+            None => skip("import.bad_value"),
+        },
+        _ => skip("import.no_equivalent"),
+    }
+}
+
+/// A dunst rule section: its match keys become the criteria, its style
+/// keys params in that section.
+fn import_rule(section: &str, keys: &[(String, String)]) -> Vec<Mapping> {
+    let mut conditions = Vec::new();
+    for (key, value) in keys {
+        let mako = match key.as_str() {
+            "appname" => "app-name",
+            "summary" => "summary",
+            "body" => "body",
+            "category" => "category",
+            "urgency" => "urgency",
+            _ => continue,
+        };
+        // dunst matches shell-style globs, mako exact values or regexes.
+        let regex = value.contains(['*', '?']);
+        let value = if regex { format!("^{}$", value.replace('.', "\\.").replace('*', ".*").replace('?', ".")) } else { value.clone() };
+        conditions.push(Condition { key: mako.to_string(), regex, value: Some(value) });
+    }
+    let criteria = Criteria { conditions };
+    keys.iter()
+        .filter(|(k, _)| !matches!(k.as_str(), "appname" | "summary" | "body" | "category" | "urgency"))
+        .flat_map(|(k, v)| {
+            if criteria.conditions.is_empty() {
+                vec![mapping(section, &None, k, v, None, Some("import.unmatched_rule"))]
+            } else {
+                import_key(section, Some(criteria.clone()), k, v, keys)
+            }
+        })
+        .collect()
+}
+
+/// dunst timeouts: plain seconds or with a unit (`10s`, `500ms`, `1m`).
+fn dunst_seconds(value: &str) -> Option<u64> {
+    match value.trim().parse::<f64>() {
+        Ok(secs) if secs >= 0.0 => Some((secs * 1000.0).round() as u64),
+        Ok(_) => None,
+        Err(_) => crate::mako_config::parse_duration_ms(value),
+    }
+}
+
+fn anchor(origin: &str) -> Option<&'static str> {
+    Some(match origin {
+        "top-left" => "top-left",
+        "top-center" => "top-center",
+        "top-right" => "top-right",
+        "bottom-left" => "bottom-left",
+        "bottom-center" => "bottom-center",
+        "bottom-right" => "bottom-right",
+        "left-center" => "center-left",
+        "right-center" => "center-right",
+        "center" => "center",
+        _ => return None,
+    })
+}
+
+fn parse_geometry(value: &str) -> Option<(u32, i64, i64)> {
+    let split = value.find(['+', '-']).unwrap_or(value.len());
+    let (size, offsets) = value.split_at(split);
+    let width = match size.split('x').next()? {
+        "" => 0,
+        w => w.parse().ok()?,
+    };
+    let mut nums = Vec::new();
+    let mut rest = offsets;
+    while !rest.is_empty() {
+        let end = rest[1..].find(['+', '-']).map_or(rest.len(), |i| i + 1);
+        nums.push(rest[..end].parse::<i64>().ok()?);
+        rest = &rest[end..];
+    }
+    match nums.as_slice() {
+        [x, y] => Some((width, *x, *y)),
+        [] => Some((width, 0, 0)),
+        _ => None,
+    }
+}
+
+/// `[section] key = value → key=value`, for the review list.
+pub fn describe(m: &Mapping) -> String {
+    match (&m.param, &m.section) {
+        (Some(p), Some(c)) => format!("[{}] {}={}", c, p.key, p.value),
+        (Some(p), None) => format!("{}={}", p.key, p.value),
+        (None, _) => tr(m.skipped.unwrap_or("import.no_equivalent")).to_string(),
+    }
+}
+
+/// `path`, e.g. `~/.config/dunst/dunstrc`, shortened with `~` for
+/// prompts.
+pub fn display_path(path: &Path) -> String {
+    match home_dir().and_then(|h| path.strip_prefix(h).ok().map(|p| p.to_path_buf())) {
+        Some(rel) => format!("~/{}", rel.display()),
+        None => path.display().to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DUNSTRC: &str = r##"
+[global]
+    font = "Monospace 10"
+    geometry = "300x5-30+20"
+    frame_width = 2
+    padding = 8
+    horizontal_padding = 10
+    frame_color = "#aaaaaa"
+    separator_color = frame
+    icon_position = off
+
+[urgency_normal]
+    background = "#285577"
+    timeout = 10
+
+[urgency_critical]
+    frame_color = "#ff0000"
+    timeout = 0
+
+[spotify]
+    appname = Spotify*
+    background = "#1db954"
+"##;
+
+    #[test]
+    fn maps_comparable_keys() {
+        let mappings = import(DUNSTRC);
+        let params: Vec<String> = mappings.iter().filter(|m| m.enabled).map(describe).collect();
+        assert_eq!(
+            params,
+            [
+                "font=Monospace 10",
+                "width=300",
+                "anchor=top-right",
+                "outer-margin=20,30",
+                "border-size=2",
+                "padding=8,10",
+                "border-color=#aaaaaa",
+                "icons=0",
+                "background-color=#285577",
+                "default-timeout=10000",
+                "[urgency=critical] border-color=#ff0000",
+                "[urgency=critical] default-timeout=0",
+                "[app-name~=^Spotify.*$] background-color=#1db954",
+            ]
+        );
+        let skipped: Vec<&str> = mappings.iter().filter(|m| !m.enabled).map(|m| m.from.as_str()).collect();
+        assert_eq!(skipped, ["[global] horizontal_padding = 10", "[global] separator_color = frame"]);
+    }
+
+    #[test]
+    fn apply_sets_keys_and_creates_sections() {
+        let mut cfg: Config = "font=mono 8\n".parse().unwrap();
+        let mut mappings = import(DUNSTRC);
+        mappings[1].enabled = false;
+        assert_eq!(apply(&mappings, &mut cfg), 12);
+        assert_eq!(cfg.params[0].value, "Monospace 10");
+        assert!(!cfg.params.iter().any(|p| p.key == "width"));
+        assert_eq!(cfg.sections.len(), 2);
+        assert_eq!(cfg.sections[0].criteria.to_string(), "urgency=critical");
+    }
+}
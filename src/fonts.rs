@@ -0,0 +1,235 @@
+//! The installed font families, from fontconfig's `fc-list`, and the
+//! families a Pango font description such as `DejaVu Sans, Bold 10` asks
+//! for.
+
+#[cfg(feature = "fontconfig")]
+use std::process::Command;
+
+use crate::fuzzy;
+use crate::line_edit;
+#[cfg(feature = "fontconfig")]
+use crate::i18n::trf;
+#[cfg(feature = "fontconfig")]
+use crate::logging;
+#[cfg(not(feature = "fontconfig"))]
+use crate::i18n::tr;
+
+/// Family names fontconfig resolves to some installed font, so they are
+/// never reported missing.
+const GENERIC: &[&str] = &["sans-serif", "sans", "serif", "monospace", "mono", "system-ui", "cursive", "fantasy", "emoji", "math"];
+
+/// Words Pango reads as style, weight, variant or stretch options after the
+/// family list.
+const STYLE_WORDS: &[&str] = &[
+    "normal", "roman", "oblique", "italic", "small-caps", "all-small-caps", "petite-caps", "all-petite-caps", "unicase",
+    "title-caps", "thin", "ultra-light", "extra-light", "light", "semi-light", "demi-light", "book", "regular", "medium",
+    "semi-bold", "demi-bold", "bold", "ultra-bold", "extra-bold", "heavy", "black", "ultra-heavy", "extra-heavy",
+    "ultra-black", "extra-black", "ultra-condensed", "extra-condensed", "condensed", "semi-condensed", "semi-expanded",
+    "expanded", "extra-expanded", "ultra-expanded", "not-rotated", "south", "upside-down", "north", "rotated-left", "east",
+    "rotated-right", "west",
+];
+
+/// Every installed family, sorted and without duplicates. A font with
+/// several names (`fc-list` prints them comma-separated) lists each.
+#[cfg(feature = "fontconfig")]
+pub fn families() -> Result<Vec<String>, String> {
+    match logging::output(Command::new("fc-list").args([":", "family"])) {
+        Ok(output) if output.status.success() => {
+            let mut out: Vec<String> = String::from_utf8_lossy(&output.stdout)
+                .lines()
+                .flat_map(|line| line.split(','))
+                .map(|name| name.trim().replace("\\-", "-"))
+                .filter(|name| !name.is_empty())
+                .collect();
+            out.sort_by_key(|name| name.to_lowercase());
+            out.dedup();
+            Ok(out)
+        }
+        Ok(output) => Err(String::from_utf8_lossy(&output.stderr).trim().to_string()),
+        Err(e) => Err(trf("error.fc_list_exec", &[&e])),
+    }
+}
+
+#[cfg(not(feature = "fontconfig"))]
+pub fn families() -> Result<Vec<String>, String> {
+    Err(tr("error.fontconfig_disabled").to_string())
+}
+
+/// The families in a Pango font description: the comma-separated list
+/// before the style words and size. `Noto Sans, Symbols Bold 10` asks for
+/// `Noto Sans` and `Symbols`.
+pub fn families_of(description: &str) -> Vec<String> {
+    let mut words: Vec<&str> = description.split_whitespace().collect();
+    if words.last().is_some_and(|w| is_size(w)) {
+        words.pop();
+    }
+    while words.last().is_some_and(|w| STYLE_WORDS.contains(&w.to_lowercase().as_str())) {
+        words.pop();
+    }
+    words.join(" ").split(',').map(str::trim).filter(|f| !f.is_empty()).map(String::from).collect()
+}
+
+/// The families in `description` that aren't in `installed`, ignoring case.
+pub fn missing(description: &str, installed: &[String]) -> Vec<String> {
+    families_of(description)
+        .into_iter()
+        .filter(|f| !GENERIC.contains(&f.to_lowercase().as_str()))
+        .filter(|f| !installed.iter().any(|i| i.eq_ignore_ascii_case(f)))
+        .collect()
+}
+
+/// Styles the font picker cycles through, as Pango writes them.
+pub const STYLES: &[&str] = &["Regular", "Bold", "Italic", "Bold Italic", "Light", "Medium", "Semi-Bold", "Condensed"];
+
+/// Which field of the font picker typing goes to.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Field {
+    Family,
+    Size,
+}
+
+/// Building a Pango font description from the installed families: a
+/// fuzzy filter over them, one of `STYLES`, and a size.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct FontPicker {
+    pub families: Vec<String>,
+    pub query: String,
+    /// The highlighted row of `matches()`.
+    pub selected: usize,
+    /// Index into `STYLES`.
+    pub style: usize,
+    pub size: String,
+    pub field: Field,
+}
+
+impl FontPicker {
+    /// Start from `description`, with its family highlighted and its style
+    /// and size filled in where they are ones the picker knows.
+    pub fn new(families: Vec<String>, description: &str) -> Self {
+        let mut words: Vec<&str> = description.split_whitespace().collect();
+        let size = match words.last() {
+            Some(w) if is_size(w) => words.pop().unwrap_or_default().to_string(),
+            _ => String::new(),
+        };
+        let named = families_of(description);
+        let family = named.first().cloned().unwrap_or_default();
+        // The style is whatever follows the last family.
+        let rest = words.join(" ");
+        let style_words = named.last().and_then(|last| rest.rfind(last.as_str()).map(|i| &rest[i + last.len()..])).unwrap_or("");
+        let style = STYLES.iter().position(|s| s.eq_ignore_ascii_case(style_words.trim())).unwrap_or(0);
+        let mut picker = FontPicker { families, query: String::new(), selected: 0, style, size, field: Field::Family };
+        picker.selected = picker.matches().iter().position(|(f, _)| f.eq_ignore_ascii_case(&family)).unwrap_or(0);
+        picker
+    }
+
+    /// The families matching `query`, best first, with the positions of the
+    /// matched characters.
+    pub fn matches(&self) -> Vec<(&str, Vec<usize>)> {
+        let mut out: Vec<(i32, (&str, Vec<usize>))> = self
+            .families
+            .iter()
+            .filter_map(|f| fuzzy::score(&self.query, f).map(|(score, pos)| (score, (f.as_str(), pos))))
+            .collect();
+        out.sort_by_key(|(score, _)| std::cmp::Reverse(*score));
+        out.into_iter().map(|(_, m)| m).collect()
+    }
+
+    /// The description for the highlighted family, e.g. `Inter Bold 10`;
+    /// `None` when nothing matches.
+    pub fn value(&self) -> Option<String> {
+        let matches = self.matches();
+        let (family, _) = matches.get(self.selected)?;
+        let mut out = family.to_string();
+        if self.style > 0 {
+            out.push(' ');
+            out.push_str(STYLES[self.style]);
+        }
+        if !self.size.trim().is_empty() {
+            out.push(' ');
+            out.push_str(self.size.trim());
+        }
+        Some(out)
+    }
+
+    pub fn down(&mut self) {
+        self.selected = (self.selected + 1).min(self.matches().len().saturating_sub(1));
+    }
+
+    pub fn up(&mut self) {
+        self.selected = self.selected.saturating_sub(1);
+    }
+
+    /// Step through `STYLES` by `by`, wrapping around.
+    pub fn cycle_style(&mut self, by: isize) {
+        self.style = (self.style as isize + by).rem_euclid(STYLES.len() as isize) as usize;
+    }
+
+    /// Type `c` into the focused field. The size only takes digits and a
+    /// decimal point.
+    pub fn push(&mut self, c: char) {
+        match self.field {
+            Field::Family => {
+                self.query.push(c);
+                self.selected = 0;
+            }
+            Field::Size if c.is_ascii_digit() || c == '.' => self.size.push(c),
+            Field::Size => {}
+        }
+    }
+
+    pub fn backspace(&mut self) {
+        match self.field {
+            Field::Family => {
+                line_edit::pop(&mut self.query);
+                self.selected = 0;
+            }
+            Field::Size => line_edit::pop(&mut self.size),
+        }
+    }
+}
+
+/// `10`, `10.5` or `14px`.
+fn is_size(word: &str) -> bool {
+    word.strip_suffix("px").unwrap_or(word).parse::<f64>().is_ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn descriptions_split_into_families() {
+        assert_eq!(families_of("DejaVu Sans Mono 10"), ["DejaVu Sans Mono"]);
+        assert_eq!(families_of("Noto Sans, Symbols Bold Italic 10.5"), ["Noto Sans", "Symbols"]);
+        assert_eq!(families_of("Inter semi-bold 14px"), ["Inter"]);
+        assert_eq!(families_of("monospace"), ["monospace"]);
+        assert!(families_of("12").is_empty());
+    }
+
+    #[test]
+    fn picker_starts_from_the_description_and_builds_one() {
+        let families = vec!["DejaVu Sans".to_string(), "DejaVu Sans Mono".to_string(), "Inter".to_string()];
+        let mut picker = FontPicker::new(families, "Inter Bold 10");
+        assert_eq!(picker.value().as_deref(), Some("Inter Bold 10"));
+        for c in "dsm".chars() {
+            picker.push(c);
+        }
+        picker.cycle_style(2);
+        picker.field = Field::Size;
+        picker.push('x');
+        picker.push('2');
+        assert_eq!(picker.value().as_deref(), Some("DejaVu Sans Mono Bold Italic 102"));
+        picker.cycle_style(-3);
+        assert_eq!(picker.value().as_deref(), Some("DejaVu Sans Mono 102"));
+        picker.query = "zzz".to_string();
+        assert_eq!(picker.value(), None);
+    }
+
+    #[test]
+    fn only_uninstalled_named_families_are_missing() {
+        let installed = vec!["DejaVu Sans".to_string(), "Inter".to_string()];
+        assert!(missing("dejavu sans bold 10", &installed).is_empty());
+        assert!(missing("monospace 10", &installed).is_empty());
+        assert_eq!(missing("Inter, Fira Code 10", &installed), ["Fira Code"]);
+    }
+}
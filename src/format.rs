@@ -0,0 +1,269 @@
+//! Helpers for mako's `format` key: `%` specifiers, Pango markup checks
+//! and an example expansion.
+
+use crate::i18n::trf;
+
+/// The `%` specifiers mako expands, with what they stand for.
+pub const SPECIFIERS: &[(char, &str)] = &[
+    ('a', "app name"),
+    ('s', "summary"),
+    ('b', "body"),
+    ('g', "notifications in group"),
+    ('i', "notification id"),
+    ('h', "hidden notifications (for [hidden])"),
+    ('t', "notifications in total (for [hidden])"),
+    ('%', "a literal %"),
+];
+
+/// Pango markup tags mako renders.
+const TAGS: &[&str] = &["b", "big", "i", "s", "span", "sub", "sup", "small", "tt", "u"];
+
+/// Keys whose value is a format string.
+pub fn is_format_key(key: &str) -> bool {
+    key == "format"
+}
+
+/// A made-up notification to expand formats with.
+pub struct Sample {
+    pub app_name: &'static str,
+    pub summary: &'static str,
+    pub body: &'static str,
+    pub group_count: u32,
+    pub id: u32,
+    pub hidden_count: u32,
+    pub total_count: u32,
+}
+
+pub const SAMPLE: Sample = Sample {
+    app_name: "Firefox",
+    summary: "Download complete",
+    body: "report.pdf (2.4 MB) finished downloading",
+    group_count: 3,
+    id: 42,
+    hidden_count: 2,
+    total_count: 7,
+};
+
+/// Expand `format` for `n` like mako does: specifiers are replaced and
+/// `\n` becomes a line break. Unknown specifiers are left as written.
+pub fn expand(format: &str, n: &Sample) -> String {
+    let mut out = String::new();
+    let mut chars = format.chars().peekable();
+    while let Some(c) = chars.next() {
+        match (c, chars.peek().copied()) {
+            ('%', Some(spec)) if SPECIFIERS.iter().any(|(s, _)| *s == spec) => {
+                chars.next();
+                match spec {
+                    'a' => out.push_str(n.app_name),
+                    's' => out.push_str(n.summary),
+                    'b' => out.push_str(n.body),
+                    'g' => out.push_str(&n.group_count.to_string()),
+                    'i' => out.push_str(&n.id.to_string()),
+                    'h' => out.push_str(&n.hidden_count.to_string()),
+                    't' => out.push_str(&n.total_count.to_string()),
+                    _ => out.push('%'),
+                }
+            }
+            ('\\', Some('n')) => {
+                chars.next();
+                out.push('\n');
+            }
+            _ => out.push(c),
+        }
+    }
+    out
+}
+
+/// Tab completion for a specifier at the end of `input`: a trailing lone
+/// `%` gets the first specifier, and a complete one is replaced by the
+/// next, so repeated Tabs cycle through them all.
+pub fn complete(input: &str) -> Option<String> {
+    let trailing = input.len() - input.trim_end_matches('%').len();
+    if trailing % 2 == 1 {
+        return Some(format!("{}{}", input, SPECIFIERS[0].0));
+    }
+    let mut chars = input.chars().rev();
+    let (last, before) = (chars.next()?, chars.next()?);
+    let percents = 1 + chars.take_while(|c| *c == '%').count();
+    if before != '%' || percents % 2 == 0 {
+        return None;
+    }
+    let i = SPECIFIERS.iter().position(|(s, _)| *s == last)?;
+    let next = SPECIFIERS[(i + 1) % SPECIFIERS.len()].0;
+    Some(format!("{}{}", &input[..input.len() - last.len_utf8()], next))
+}
+
+/// Whether `input` ends in an unfinished `%`, i.e. a specifier is being
+/// typed.
+pub fn wants_specifier(input: &str) -> bool {
+    (input.len() - input.trim_end_matches('%').len()) % 2 == 1
+}
+
+/// Problems with `format`: unknown specifiers and unbalanced or unknown
+/// markup tags, in the order they appear.
+pub fn check(format: &str) -> Vec<String> {
+    let mut problems = Vec::new();
+    let mut chars = format.chars().peekable();
+    while let Some(c) = chars.next() {
+        if c == '%' {
+            match chars.next() {
+                Some(spec) if SPECIFIERS.iter().any(|(s, _)| *s == spec) => {}
+                Some(spec) => problems.push(trf("format.unknown_specifier", &[&spec])),
+                None => {}
+            }
+        }
+    }
+    if let Err(e) = parse_markup(format) {
+        problems.push(e);
+    }
+    problems
+}
+
+/// A run of text and the markup tags (innermost last) it is inside, each
+/// as `(name, attributes)`.
+pub type Run = (String, Vec<(String, String)>);
+
+/// Split markup into styled runs, checking that tags are known and
+/// balanced. Entities are decoded.
+pub fn parse_markup(s: &str) -> Result<Vec<Run>, String> {
+    let mut runs: Vec<Run> = Vec::new();
+    let mut stack: Vec<(String, String)> = Vec::new();
+    let mut text = String::new();
+    let mut rest = s;
+    while let Some(start) = rest.find('<') {
+        text.push_str(&decode_entities(&rest[..start]));
+        let end = rest[start..].find('>').ok_or_else(|| trf("format.unterminated", &[]))? + start;
+        let tag = rest[start + 1..end].trim();
+        rest = &rest[end + 1..];
+        if !text.is_empty() {
+            runs.push((std::mem::take(&mut text), stack.clone()));
+        }
+        if let Some(name) = tag.strip_prefix('/') {
+            let name = name.trim();
+            match stack.pop() {
+                Some((open, _)) if open == name => {}
+                Some((open, _)) => return Err(trf("format.mismatched", &[&name, &open])),
+                None => return Err(trf("format.stray_close", &[&name])),
+            }
+        } else {
+            let self_closing = tag.ends_with('/');
+            let tag = tag.trim_end_matches('/');
+            let (name, attrs) = tag.split_once(char::is_whitespace).unwrap_or((tag, ""));
+            if !TAGS.contains(&name) {
+                return Err(trf("format.unknown_tag", &[&name]));
+            }
+            if !self_closing {
+                stack.push((name.to_string(), attrs.trim().to_string()));
+            }
+        }
+    }
+    text.push_str(&decode_entities(rest));
+    if let Some((open, _)) = stack.last() {
+        return Err(trf("format.unclosed", &[open]));
+    }
+    if !text.is_empty() {
+        runs.push((text, Vec::new()));
+    }
+    Ok(runs)
+}
+
+/// The value of attribute `name` in a tag's attribute string such as
+/// `color="#ff0000" weight='bold'`.
+pub fn attr<'a>(attrs: &'a str, name: &str) -> Option<&'a str> {
+    let mut rest = attrs;
+    while let Some(eq) = rest.find('=') {
+        let key = rest[..eq].trim();
+        let after = rest[eq + 1..].trim_start();
+        let quote = after.chars().next().filter(|c| *c == '"' || *c == '\'');
+        let (value, next) = match quote {
+            Some(q) => {
+                let end = after[1..].find(q).map(|i| i + 1).unwrap_or(after.len());
+                (&after[1..end], after.get(end + 1..).unwrap_or(""))
+            }
+            None => {
+                let end = after.find(char::is_whitespace).unwrap_or(after.len());
+                (&after[..end], &after[end..])
+            }
+        };
+        if key == name {
+            return Some(value);
+        }
+        rest = next;
+    }
+    None
+}
+
+/// Parse a Pango color: `#rgb`, `#rrggbb` or one of a few common names.
+pub fn parse_color(s: &str) -> Option<(u8, u8, u8)> {
+    let s = s.trim().to_lowercase();
+    if let Some(hex) = s.strip_prefix('#') {
+        let digit = |i: usize, len: usize| u8::from_str_radix(hex.get(i..i + len)?, 16).ok();
+        return match hex.len() {
+            3 => Some((digit(0, 1)? * 17, digit(1, 1)? * 17, digit(2, 1)? * 17)),
+            6 | 8 => Some((digit(0, 2)?, digit(2, 2)?, digit(4, 2)?)),
+            _ => None,
+        };
+    }
+    Some(match s.as_str() {
+        "black" => (0, 0, 0),
+        "white" => (255, 255, 255),
+        "red" => (255, 0, 0),
+        "green" => (0, 128, 0),
+        "blue" => (0, 0, 255),
+        "yellow" => (255, 255, 0),
+        "cyan" => (0, 255, 255),
+        "magenta" => (255, 0, 255),
+        "orange" => (255, 165, 0),
+        "gray" | "grey" => (190, 190, 190),
+        _ => return None,
+    })
+}
+
+fn decode_entities(s: &str) -> String {
+    s.replace("&lt;", "<").replace("&gt;", ">").replace("&quot;", "\"").replace("&apos;", "'").replace("&amp;", "&")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn expands_specifiers_and_newlines() {
+        assert_eq!(expand("<b>%s</b>\\n%b (%a, %g) 100%%", &SAMPLE), "<b>Download complete</b>\nreport.pdf (2.4 MB) finished downloading (Firefox, 3) 100%");
+        assert_eq!(expand("%x", &SAMPLE), "%x");
+        assert_eq!(expand("(%h more of %t)", &SAMPLE), "(2 more of 7)");
+    }
+
+    #[test]
+    fn tab_cycles_through_specifiers() {
+        assert_eq!(complete("<b>%").as_deref(), Some("<b>%a"));
+        assert_eq!(complete("<b>%a").as_deref(), Some("<b>%s"));
+        assert_eq!(complete("%%").as_deref(), Some("%a"));
+        assert_eq!(complete("%%%").as_deref(), Some("%%%a"));
+        assert_eq!(complete("50%%a"), None);
+        assert_eq!(complete("plain"), None);
+    }
+
+    #[test]
+    fn reads_tag_attributes_and_colors() {
+        let attrs = "color=\"#ff8800\" weight='bold' size=large";
+        assert_eq!(attr(attrs, "color"), Some("#ff8800"));
+        assert_eq!(attr(attrs, "weight"), Some("bold"));
+        assert_eq!(attr(attrs, "size"), Some("large"));
+        assert_eq!(attr(attrs, "style"), None);
+        assert_eq!(parse_color("#ff8800"), Some((255, 136, 0)));
+        assert_eq!(parse_color("#f80"), Some((255, 136, 0)));
+        assert_eq!(parse_color("Red"), Some((255, 0, 0)));
+        assert_eq!(parse_color("#ggg"), None);
+    }
+
+    #[test]
+    fn checks_markup_and_specifiers() {
+        assert!(check("<b>%s</b>\\n<span color=\"#ff0000\">%b</span>").is_empty());
+        assert_eq!(check("<b>%s").len(), 1);
+        assert_eq!(check("<b>%s</i>").len(), 1);
+        assert_eq!(check("</b>").len(), 1);
+        assert_eq!(check("<blink>x</blink>").len(), 1);
+        assert_eq!(check("%q <b").len(), 2);
+    }
+}
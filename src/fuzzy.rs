@@ -0,0 +1,73 @@
+//! Fuzzy matching for the key chooser: the query's characters must appear
+//! in order, and matches that run together or start words score higher,
+//! so "bgc" finds `background-color`.
+
+/// Bonus for a character right after the previous match.
+const CONSECUTIVE: i32 = 6;
+/// Bonus for a match at the start of the text or of a `-`/`_`/space
+/// separated word.
+const WORD_START: i32 = 8;
+/// Most a single gap between matches costs.
+const MAX_GAP_PENALTY: i32 = 6;
+
+/// How well `query` matches `text`, ignoring case, and the positions (in
+/// characters) of the matched characters in `text`. `None` unless every
+/// character of `query` appears in `text` in order. An empty query matches
+/// everything with score 0.
+pub fn score(query: &str, text: &str) -> Option<(i32, Vec<usize>)> {
+    let query: Vec<char> = query.chars().flat_map(char::to_lowercase).collect();
+    let text: Vec<char> = text.chars().flat_map(char::to_lowercase).collect();
+    if query.is_empty() {
+        return Some((0, Vec::new()));
+    }
+    let bonus = |j: usize| if j == 0 || matches!(text[j - 1], '-' | '_' | ' ') { WORD_START } else { 0 };
+    // best[i][j]: the best score for query[..=i] with query[i] matched at
+    // text[j], and where query[i - 1] was matched.
+    let mut best: Vec<Vec<Option<(i32, usize)>>> = vec![vec![None; text.len()]; query.len()];
+    for (i, &q) in query.iter().enumerate() {
+        for j in (i..text.len()).filter(|&j| text[j] == q) {
+            best[i][j] = if i == 0 {
+                Some((1 + bonus(j) - (j as i32).min(MAX_GAP_PENALTY), 0))
+            } else {
+                (i - 1..j)
+                    .filter_map(|k| best[i - 1][k].map(|(s, _)| (s, k)))
+                    .map(|(s, k)| {
+                        let step = if k + 1 == j { CONSECUTIVE } else { -((j - k - 1) as i32).min(MAX_GAP_PENALTY) };
+                        (s + 1 + bonus(j) + step, k)
+                    })
+                    .max_by_key(|&(s, k)| (s, std::cmp::Reverse(k)))
+            };
+        }
+    }
+    let last = query.len() - 1;
+    let (end, (total, _)) = best[last].iter().enumerate().filter_map(|(j, b)| b.map(|b| (j, b))).max_by_key(|&(j, (s, _))| (s, std::cmp::Reverse(j)))?;
+    let mut positions = vec![end];
+    let mut j = end;
+    for i in (1..=last).rev() {
+        j = best[i][j].map(|(_, k)| k)?;
+        positions.push(j);
+    }
+    positions.reverse();
+    Some((total, positions))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn matches_word_starts_in_order() {
+        let (_, positions) = score("bgc", "background-color").unwrap();
+        assert_eq!(positions, [0, 4, 11]);
+        assert!(score("cgb", "background-color").is_none());
+        assert_eq!(score("", "font"), Some((0, Vec::new())));
+        assert_eq!(score("FONT", "font").map(|(_, p)| p), Some(vec![0, 1, 2, 3]));
+    }
+
+    #[test]
+    fn tighter_matches_score_higher() {
+        let score_of = |text| score("bc", text).unwrap().0;
+        assert!(score_of("border-color") > score_of("progress-background-color"));
+        assert!(score("border-c", "border-color").unwrap().0 > score("border-c", "border-radius-color").unwrap().0);
+    }
+}
@@ -0,0 +1,219 @@
+//! Versioning the config in git: with the `git_commit` setting on and the
+//! config inside a repository (`~/.config/mako`, or a dotfiles checkout it
+//! is symlinked into), every save commits the file with a message saying
+//! what changed, and `Command::GitLog` browses and restores its history.
+//! Runs the `git` command, so it works with whatever repository layout
+//! and configuration `git` itself understands.
+
+#[cfg(feature = "git")]
+use std::{
+    fs,
+    io::Write,
+    path::PathBuf,
+    process::{Command, Stdio},
+};
+use std::{
+    path::Path,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+#[cfg(not(feature = "git"))]
+use crate::i18n::tr;
+#[cfg(feature = "git")]
+use crate::i18n::trf;
+#[cfg(feature = "git")]
+use crate::logging;
+
+/// Commits `history` reads, newest first.
+#[cfg_attr(not(feature = "git"), allow(dead_code))]
+const LIMIT: usize = 200;
+
+/// One commit that touched the config.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Commit {
+    pub hash: String,
+    pub time: SystemTime,
+    pub subject: String,
+    /// The config as that commit has it.
+    pub text: String,
+}
+
+impl Commit {
+    pub fn short_hash(&self) -> &str {
+        self.hash.get(..7).unwrap_or(&self.hash)
+    }
+}
+
+/// The commit message for saving `changes`, as `App::pending` lists them:
+/// `set border-radius=8`, or `delete width, set font=Inter 10`.
+pub fn message(changes: &[(String, String)]) -> String {
+    let parts: Vec<String> = changes
+        .iter()
+        .map(|(key, value)| match value.strip_prefix('<').and_then(|v| v.strip_suffix('>')) {
+            Some(what) => {
+                let verb = match what {
+                    "deleted" => "delete",
+                    "restored" => "restore",
+                    "imported" => "import",
+                    "pasted" => "paste",
+                    "order" => "reorder",
+                    other => other,
+                };
+                format!("{} {}", verb, key)
+            }
+            None => format!("set {}={}", key, value),
+        })
+        .collect();
+    if parts.is_empty() { "save".to_string() } else { parts.join(", ") }
+}
+
+/// The commits `git log --format=%H%x1f%ct%x1f%s` printed, without their
+/// text yet.
+#[cfg_attr(not(feature = "git"), allow(dead_code))]
+fn parse_log(out: &str) -> Vec<Commit> {
+    out.lines()
+        .filter_map(|line| {
+            let mut fields = line.splitn(3, '\x1f');
+            let hash = fields.next().filter(|h| !h.is_empty())?.to_string();
+            let secs: u64 = fields.next()?.parse().ok()?;
+            let time = UNIX_EPOCH + Duration::from_secs(secs);
+            Some(Commit { hash, time, subject: fields.next().unwrap_or("").to_string(), text: String::new() })
+        })
+        .collect()
+}
+
+/// The objects `git cat-file --batch` printed, in request order: `None`
+/// for the ones it reported missing (the file didn't exist yet).
+#[cfg_attr(not(feature = "git"), allow(dead_code))]
+fn parse_batch(mut out: &[u8]) -> Vec<Option<String>> {
+    let mut objects = Vec::new();
+    while let Some(end) = out.iter().position(|&b| b == b'\n') {
+        let header = String::from_utf8_lossy(&out[..end]).into_owned();
+        out = &out[end + 1..];
+        match header.rsplit(' ').next().and_then(|size| size.parse::<usize>().ok()) {
+            Some(size) if !header.ends_with(" missing") && size <= out.len() => {
+                objects.push(Some(String::from_utf8_lossy(&out[..size]).into_owned()));
+                out = out.get(size + 1..).unwrap_or_default();
+            }
+            _ => objects.push(None),
+        }
+    }
+    objects
+}
+
+/// Where to run git for `config`: the chosen `repo`, else the directory
+/// the config really lives in, symlinks followed.
+#[cfg(feature = "git")]
+fn locate(config: &Path, repo: Option<&Path>) -> Result<(PathBuf, PathBuf), String> {
+    let file = fs::canonicalize(config).map_err(|e| trf("git.failed", &[&e]))?;
+    let dir = match repo {
+        Some(repo) => repo.to_path_buf(),
+        None => file.parent().map(Path::to_path_buf).unwrap_or_default(),
+    };
+    Ok((dir, file))
+}
+
+#[cfg(feature = "git")]
+fn git(dir: &Path) -> Command {
+    let mut cmd = Command::new("git");
+    cmd.arg("-C").arg(dir);
+    cmd
+}
+
+#[cfg(feature = "git")]
+fn run(cmd: &mut Command) -> Result<String, String> {
+    match logging::output(cmd) {
+        Ok(output) if output.status.success() => Ok(String::from_utf8_lossy(&output.stdout).into_owned()),
+        Ok(output) => Err(String::from_utf8_lossy(&output.stderr).trim().to_string()),
+        Err(e) => Err(trf("error.git_exec", &[&e])),
+    }
+}
+
+/// Whether `dir` is inside a work tree.
+#[cfg(feature = "git")]
+fn in_repo(dir: &Path) -> bool {
+    run(git(dir).args(["rev-parse", "--is-inside-work-tree"])).is_ok_and(|out| out.trim() == "true")
+}
+
+/// Commit the config at `config` with `message`. Nothing happens, with
+/// `Ok(false)`, when it is in no repository (and no `repo` was chosen) or
+/// the save left it as committed.
+#[cfg(feature = "git")]
+pub fn commit(config: &Path, repo: Option<&Path>, message: &str) -> Result<bool, String> {
+    let (dir, file) = locate(config, repo)?;
+    if repo.is_none() && !in_repo(&dir) {
+        return Ok(false);
+    }
+    run(git(&dir).arg("add").arg("--").arg(&file))?;
+    // `diff --quiet` exits 1 when the file has staged changes.
+    if run(git(&dir).args(["diff", "--cached", "--quiet", "--"]).arg(&file)).is_ok() {
+        return Ok(false);
+    }
+    run(git(&dir).args(["commit", "--quiet", "--message", message, "--"]).arg(&file))?;
+    Ok(true)
+}
+
+#[cfg(not(feature = "git"))]
+pub fn commit(_config: &Path, _repo: Option<&Path>, _message: &str) -> Result<bool, String> {
+    Err(tr("error.git_disabled").to_string())
+}
+
+/// The last `LIMIT` commits that touched `config`, newest first, each
+/// with the file as it committed it. Two git runs however long the
+/// history: `log` for the commits, one `cat-file --batch` for the texts.
+#[cfg(feature = "git")]
+pub fn history(config: &Path, repo: Option<&Path>) -> Result<Vec<Commit>, String> {
+    let (dir, file) = locate(config, repo)?;
+    let top = PathBuf::from(run(git(&dir).args(["rev-parse", "--show-toplevel"]))?.trim());
+    let top = fs::canonicalize(&top).unwrap_or(top);
+    let relative = file.strip_prefix(&top).map_err(|_| trf("git.outside", &[&file.display(), &top.display()]))?;
+    let log = run(git(&dir)
+        .args(["log", "--format=%H%x1f%ct%x1f%s", "-n", &LIMIT.to_string(), "--"])
+        .arg(&file))?;
+    let mut commits = parse_log(&log);
+    if commits.is_empty() {
+        return Ok(commits);
+    }
+    let request: String = commits.iter().map(|c| format!("{}:{}\n", c.hash, relative.display())).collect();
+    let mut cmd = git(&dir);
+    cmd.args(["cat-file", "--batch"]).stdin(Stdio::piped()).stdout(Stdio::piped()).stderr(Stdio::piped());
+    log::debug!(target: "command", "{:?}", cmd);
+    let mut child = cmd.spawn().map_err(|e| trf("error.git_exec", &[&e]))?;
+    if let Some(mut stdin) = child.stdin.take() {
+        stdin.write_all(request.as_bytes()).map_err(|e| trf("error.git_exec", &[&e]))?;
+    }
+    let output = child.wait_with_output().map_err(|e| trf("error.git_exec", &[&e]))?;
+    if !output.status.success() {
+        return Err(String::from_utf8_lossy(&output.stderr).trim().to_string());
+    }
+    for (commit, text) in commits.iter_mut().zip(parse_batch(&output.stdout)) {
+        commit.text = text.unwrap_or_default();
+    }
+    Ok(commits)
+}
+
+#[cfg(not(feature = "git"))]
+pub fn history(_config: &Path, _repo: Option<&Path>) -> Result<Vec<Commit>, String> {
+    Err(tr("error.git_disabled").to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn messages_say_what_changed_and_git_output_is_read() {
+        assert_eq!(message(&[("border-radius".to_string(), "8".to_string())]), "set border-radius=8");
+        let changes = [("width".to_string(), "<deleted>".to_string()), ("font".to_string(), "Inter 10".to_string())];
+        assert_eq!(message(&changes), "delete width, set font=Inter 10");
+        assert_eq!(message(&[]), "save");
+
+        let commits = parse_log("abc1234def\x1f1709294400\x1fset width=300\nfff0000\x1f0\x1finitial\n");
+        assert_eq!(commits.len(), 2);
+        assert_eq!(commits[0].short_hash(), "abc1234");
+        assert_eq!(commits[0].subject, "set width=300");
+        assert_eq!(commits[0].time, UNIX_EPOCH + Duration::from_secs(1_709_294_400));
+        let batch = b"abc blob 10\nwidth=300\n\nfff:config missing\nddd blob 0\n\n";
+        assert_eq!(parse_batch(batch), [Some("width=300\n".to_string()), None, Some(String::new())]);
+    }
+}
@@ -0,0 +1,235 @@
+//! Drive the editor from a script instead of a terminal.
+//!
+//! A script is a list of steps, one per line:
+//!
+//! ```text
+//! # comments and blank lines are ignored
+//! press a            # a single key: a character or a name like Enter, Esc, Up
+//! type border-color  # every character of the rest of the line
+//! paste #285577      # the rest of the line as one bracketed paste
+//! press Enter
+//! press ctrl-r       # modifiers: ctrl-, alt-, shift-
+//! resize 60x20       # the terminal changes size
+//! snapshot           # record the rendered screen at this point
+//! ```
+//!
+//! Nothing is written to disk and no processes are started; saves are
+//! only recorded, so a script is a reproducible description of a session.
+
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use ratatui::{backend::TestBackend, buffer::Buffer, layout::Rect, Terminal};
+
+use crate::app::{Action, App};
+use crate::i18n::trf;
+use crate::ui;
+
+/// What a script produced.
+#[derive(Debug, Default)]
+pub struct Outcome {
+    /// The config as it would be saved after the last step.
+    pub config: String,
+    /// Screens recorded by `snapshot` steps, plus the final screen.
+    pub screens: Vec<String>,
+    /// Every side effect the session asked for, in order.
+    pub actions: Vec<Action>,
+}
+
+/// Run `script` against `app` on a virtual `width`x`height` terminal.
+/// Stops early at a quit action.
+pub fn run_script(app: &mut App, script: &str, width: u16, height: u16) -> Result<Outcome, String> {
+    let mut terminal = Terminal::new(TestBackend::new(width, height)).map_err(|e| e.to_string())?;
+    let mut outcome = Outcome::default();
+
+    'steps: for (n, line) in script.lines().enumerate() {
+        let line = line.trim_start();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let (cmd, arg) = line.split_once(' ').unwrap_or((line, ""));
+        let keys = match cmd {
+            "press" => vec![parse_key(arg.trim()).ok_or_else(|| trf("headless.bad_key", &[&(n + 1), &arg.trim()]))?],
+            "type" => arg.chars().map(|c| KeyEvent::from(KeyCode::Char(c))).collect(),
+            "paste" => {
+                terminal.draw(|f| ui::draw(f, app)).map_err(|e| e.to_string())?;
+                app.handle_paste(arg);
+                continue;
+            }
+            "resize" => {
+                let (w, h) = parse_size(arg.trim()).ok_or_else(|| trf("cli.bad_size", &[&arg.trim()]))?;
+                terminal.backend_mut().resize(w, h);
+                terminal.resize(Rect::new(0, 0, w, h)).map_err(|e| e.to_string())?;
+                app.handle_resize();
+                continue;
+            }
+            "snapshot" => {
+                terminal.draw(|f| ui::draw(f, app)).map_err(|e| e.to_string())?;
+                outcome.screens.push(buffer_text(terminal.backend().buffer()));
+                continue;
+            }
+            _ => return Err(trf("headless.bad_step", &[&(n + 1), &cmd])),
+        };
+        for key in keys {
+            // Draw before every key, like the real loop, so anything the
+            // renderer updates (e.g. list offsets) is in place.
+            terminal.draw(|f| ui::draw(f, app)).map_err(|e| e.to_string())?;
+            let action = app.handle_key(key);
+            let quit = matches!(action, Action::Quit | Action::SaveAndQuit);
+            // Nothing is written, but the session goes on as if saves
+            // worked, so quitting doesn't stop to ask about them.
+            if matches!(action, Action::Save { .. } | Action::Write | Action::LiveApply | Action::ResetConfig | Action::SaveAndQuit) {
+                app.mark_saved();
+            }
+            if action != Action::None {
+                outcome.actions.push(action);
+            }
+            if quit {
+                break 'steps;
+            }
+        }
+    }
+
+    terminal.draw(|f| ui::draw(f, app)).map_err(|e| e.to_string())?;
+    outcome.screens.push(buffer_text(terminal.backend().buffer()));
+    outcome.config = app.cfg.to_string();
+    Ok(outcome)
+}
+
+/// Parse a terminal size written as `WIDTHxHEIGHT`, e.g. `100x30`.
+pub fn parse_size(s: &str) -> Option<(u16, u16)> {
+    let (w, h) = s.split_once('x')?;
+    match (w.parse(), h.parse()) {
+        (Ok(w), Ok(h)) if w > 0 && h > 0 => Some((w, h)),
+        _ => None,
+    }
+}
+
+/// Parse a key name such as `j`, `Enter`, `ctrl-r` or `shift-Tab`.
+pub fn parse_key(name: &str) -> Option<KeyEvent> {
+    let mut mods = KeyModifiers::NONE;
+    let mut rest = name;
+    loop {
+        let lower = rest.to_lowercase();
+        if lower.starts_with("ctrl-") && rest.len() > 5 {
+            mods |= KeyModifiers::CONTROL;
+        } else if lower.starts_with("alt-") && rest.len() > 4 {
+            mods |= KeyModifiers::ALT;
+        } else if lower.starts_with("shift-") && rest.len() > 6 {
+            mods |= KeyModifiers::SHIFT;
+        } else {
+            break;
+        }
+        rest = &rest[lower.find('-').unwrap() + 1..];
+    }
+    let mut chars = rest.chars();
+    let code = match (chars.next(), chars.next()) {
+        (Some(c), None) => KeyCode::Char(c),
+        _ => match rest.to_lowercase().as_str() {
+            "enter" | "return" => KeyCode::Enter,
+            "esc" | "escape" => KeyCode::Esc,
+            "backspace" => KeyCode::Backspace,
+            "tab" if mods.contains(KeyModifiers::SHIFT) => KeyCode::BackTab,
+            "tab" => KeyCode::Tab,
+            "backtab" => KeyCode::BackTab,
+            "space" => KeyCode::Char(' '),
+            "up" => KeyCode::Up,
+            "down" => KeyCode::Down,
+            "left" => KeyCode::Left,
+            "right" => KeyCode::Right,
+            "home" => KeyCode::Home,
+            "end" => KeyCode::End,
+            "pageup" => KeyCode::PageUp,
+            "pagedown" => KeyCode::PageDown,
+            "delete" | "del" => KeyCode::Delete,
+            _ => return None,
+        },
+    };
+    Some(KeyEvent::new(code, mods))
+}
+
+/// The visible text of a rendered buffer, one line per row with trailing
+/// spaces trimmed.
+pub fn buffer_text(buf: &Buffer) -> String {
+    let area = buf.area;
+    let mut out = String::new();
+    for y in area.top()..area.bottom() {
+        let mut row = String::new();
+        for x in area.left()..area.right() {
+            row.push_str(buf.get(x, y).symbol());
+        }
+        out.push_str(row.trim_end());
+        out.push('\n');
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::{Config, Param};
+    use crate::i18n::tr;
+
+    #[test]
+    fn parse_key_understands_names_and_modifiers() {
+        assert_eq!(parse_key("j"), Some(KeyEvent::from(KeyCode::Char('j'))));
+        assert_eq!(parse_key("Enter"), Some(KeyEvent::from(KeyCode::Enter)));
+        assert_eq!(parse_key("ctrl-r"), Some(KeyEvent::new(KeyCode::Char('r'), KeyModifiers::CONTROL)));
+        assert_eq!(parse_key("shift-tab"), Some(KeyEvent::new(KeyCode::BackTab, KeyModifiers::SHIFT)));
+        assert_eq!(parse_key("-"), Some(KeyEvent::from(KeyCode::Char('-'))));
+        assert_eq!(parse_key("nope"), None);
+    }
+
+    #[test]
+    fn script_edits_config_and_records_screens() {
+        let mut app = App::new(Config { params: vec![Param::new("font", "mono 10")], ..Config::default() });
+        let script = "press e\npress Backspace\npress Backspace\ntype 12\nsnapshot\npress Enter\npress q\npress j\n";
+        let outcome = run_script(&mut app, script, 80, 16).unwrap();
+        assert_eq!(outcome.config, "font=mono 12\n");
+        assert_eq!(outcome.screens.len(), 2);
+        assert!(outcome.screens[0].contains("mono 12"));
+        assert_eq!(
+            outcome.actions,
+            vec![Action::Save { key: "font".to_string(), value: "mono 12".to_string() }, Action::Quit]
+        );
+    }
+
+    #[test]
+    fn unknown_steps_are_reported_with_line_numbers() {
+        let mut app = App::new(Config::default());
+        let err = run_script(&mut app, "# comment\njump\n", 40, 10).unwrap_err();
+        assert!(err.contains('2') && err.contains("jump"), "{}", err);
+    }
+
+    #[test]
+    fn long_lists_scroll_with_the_selection_and_show_a_scrollbar() {
+        let params = (0..40).map(|i| Param::new(format!("key-{}", i), "1")).collect();
+        let mut app = App::new(Config { params, ..Config::default() });
+        let outcome = run_script(&mut app, "press G\nsnapshot\npress k\npress k\nsnapshot\n", 80, 24).unwrap();
+        assert!(outcome.screens[0].contains("key-39") && !outcome.screens[0].contains("key-0 "));
+        assert!(outcome.screens[0].contains('█'));
+        // Stepping back up inside the view leaves it where it was.
+        assert!(outcome.screens[1].contains("key-39"));
+    }
+
+    #[test]
+    fn short_or_compact_terminals_drop_the_header() {
+        let title = tr("header.title");
+        let mut app = App::new(Config { params: vec![Param::new("font", "mono 10")], ..Config::default() });
+        let outcome = run_script(&mut app, "snapshot\nresize 80x20\n", 80, 30).unwrap();
+        assert!(outcome.screens[0].contains(title));
+        assert!(!outcome.screens[1].contains(title));
+        assert!(outcome.screens[1].lines().next().is_some_and(|l| l.starts_with('┌')));
+        app.compact = true;
+        let outcome = run_script(&mut app, "snapshot\n", 80, 30).unwrap();
+        assert!(!outcome.screens[0].contains(title) && outcome.screens[0].contains("mono 10"));
+    }
+
+    #[test]
+    fn resize_step_redraws_at_the_new_size() {
+        let mut app = App::new(Config::default());
+        let outcome = run_script(&mut app, "snapshot\nresize 50x8\n", 80, 12).unwrap();
+        assert_eq!(outcome.screens[0].lines().count(), 12);
+        assert_eq!(outcome.screens[1].lines().count(), 8);
+        assert!(outcome.screens[1].lines().all(|l| l.chars().count() <= 50));
+        assert_eq!(parse_size("0x10"), None);
+    }
+}
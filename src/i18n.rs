@@ -0,0 +1,592 @@
+//! User-facing strings.
+//!
+//! Every message shown in the UI is looked up by id through `tr()` /
+//! `trf()`. English is the reference table; other locales only need to
+//! translate what they can, since missing entries fall back to English.
+
+use std::env;
+use std::fmt::Display;
+use std::sync::OnceLock;
+
+static LANG: OnceLock<String> = OnceLock::new();
+
+/// Force the UI language (e.g. from a settings file). Only the first call
+/// has an effect, and it must happen before the first lookup.
+pub fn set_language(lang: &str) {
+    let _ = LANG.set(normalize(lang));
+}
+
+/// The active language code, e.g. `"en"` or `"de"`.
+pub fn language() -> &'static str {
+    LANG.get_or_init(detect_language)
+}
+
+/// Look up message `id` in the active language, falling back to English,
+/// then to the `mako-config` messages and, for unknown ids, to the id
+/// itself.
+pub fn tr(id: &'static str) -> &'static str {
+    lookup(language(), id).or_else(|| en(id)).unwrap_or_else(|| mako_config::i18n::tr(id))
+}
+
+/// `tr()` with each `{}` in the message replaced by the next argument.
+pub fn trf(id: &'static str, args: &[&dyn Display]) -> String {
+    let mut out = String::new();
+    let mut args = args.iter();
+    let mut parts = tr(id).split("{}").peekable();
+    while let Some(part) = parts.next() {
+        out.push_str(part);
+        if parts.peek().is_some()
+            && let Some(arg) = args.next()
+        {
+            out.push_str(&arg.to_string());
+        }
+    }
+    out
+}
+
+/// Pick the language from the usual POSIX locale variables.
+fn detect_language() -> String {
+    for var in ["LC_ALL", "LC_MESSAGES", "LANG"] {
+        if let Ok(v) = env::var(var)
+            && !v.is_empty()
+        {
+            return normalize(&v);
+        }
+    }
+    "en".to_string()
+}
+
+/// `de_DE.UTF-8` -> `de`; `C`/`POSIX` -> `en`.
+fn normalize(locale: &str) -> String {
+    let lang = locale.split(['_', '.', '@', '-']).next().unwrap_or("").to_lowercase();
+    match lang.as_str() {
+        "" | "c" | "posix" => "en".to_string(),
+        _ => lang,
+    }
+}
+
+fn lookup(lang: &str, id: &str) -> Option<&'static str> {
+    match lang {
+        "de" => de(id),
+        "es" => es(id),
+        _ => None,
+    }
+}
+
+fn en(id: &str) -> Option<&'static str> {
+    Some(match id {
+        "tab.global" => "Global",
+        "tab.sections" => "Sections",
+        "tab.sections_count" => "{} ({})",
+        "tab.modes" => "Modes",
+        "tab.notifications" => "Notifications",
+        "header.title" => " Mako Config Editor ",
+        "header.navigate" => "↑↓/{}/{}: navigate ",
+        "header.edit" => "{}/Enter: edit ",
+        "header.add" => "{}: add ",
+        "header.delete" => "{}: delete ",
+        "header.settings" => "{}: settings ",
+        "header.help" => "{}: all keys ",
+        "header.quit" => "{}: quit",
+        "header.unsaved" => "● unsaved changes",
+        "header.pending" => "● {} change(s) not written ({}: write)",
+        "header.modified" => "modified {} ago",
+        "header.mako_running" => "mako running",
+        "header.mako_stopped" => "mako not running",
+        "header.warnings" => "⚠ {} warning(s)",
+        "list.known_keys" => "Known keys",
+        "list.known_keys_section" => "Known keys for [{}]",
+        "list.params" => "Parameters",
+        "list.params_in" => "Parameters · {} ({}/{}, {}/{}: switch section)",
+        "list.params_customized" => " · customized only ({}: show all)",
+        "list.params_unset" => " · with unset keys ({}: hide them)",
+        "list.params_sorted" => " · {} ({}: write this order)",
+        "list.section_global" => "global",
+        "list.params_search" => " · /{} ({} matches)",
+        "list.settings" => "Editor settings ({})",
+        "list.did_you_mean" => "Did you mean",
+        "list.keep_custom" => "No, add '{}' as typed",
+        "list.backups" => "Backups (newest first)",
+        "list.notifications" => "Notifications on screen ({})",
+        "list.history" => "Notification history ({})",
+        "list.actions" => "Actions of notification {}",
+        "list.compose" => "Test notification",
+        "list.themes" => "Theme presets",
+        "list.docs" => "Docs: {}",
+        "docs.type" => "Type: ",
+        "docs.default" => "Default: ",
+        "docs.no_default" => "none",
+        "docs.examples" => "Examples:",
+        "docs.history" => "History now: ",
+        "docs.history_count" => "{} notifications (as makoctl history reports)",
+        "docs.history_unread" => "reading makoctl history…",
+        "docs.unknown" => "Not a documented mako key.",
+        "docs.none" => "Select a key to read about it.",
+        "list.diff" => "Unsaved changes to {}",
+        "list.log" => "Activity log (newest first)",
+        "list.fonts" => "Installed fonts ({})",
+        "list.entries" => "Entries ({})",
+        "list.problems" => "Problems in {} ({} errors, {} warnings)",
+        "list.theme_preview" => "Preview",
+        "list.import" => "Import from {}: {} of {} settings selected",
+        "list.paste_config" => "Paste a mako config ({} lines)",
+        "list.backup_entry" => "saved {} ago, {} keys",
+        "list.snapshots" => "Snapshots (newest first)",
+        "list.snapshot_entry" => "ago, {} keys",
+        "list.snapshot_preview" => "Restoring changes",
+        "list.snapshot_same" => "Same as the current config",
+        "list.git_log" => "Commits of the config (newest first)",
+        "list.trash" => "Trash ({} deleted, newest first)",
+        "footer.selected" => "Selected: {} = {}",
+        "footer.no_selection" => "No selection",
+        "footer.hint" => "Press '{}' to add, '{}' to edit, '{}' to delete.",
+        "footer.reloading" => "{} Reloading mako…",
+        "footer.manual_reload" => "Manual reload: saves don't run makoctl ('{}' turns it back on)",
+        "footer.log_hint" => "('{}' shows the full log)",
+        "footer.marked" => "{} marked: '{}' edits them, '{}' deletes them, Space toggles, Esc clears",
+        "bulk.invalid" => "{}: {}",
+        "cmdline.unknown" => "Not a command: :{}",
+        "cmdline.usage_set" => "Usage: :set key=value",
+        "cmdline.usage_delete" => "Usage: :d key",
+        "cmdline.usage_profile" => "Usage: :profile name",
+        "cmdline.unsaved" => "Unsaved changes: :w them first",
+        "cmdline.no_profile" => "No profile '{}' (known: {})",
+        "cmdline.usage_replace" => "Usage: :s/old/new/ (:%s for every section, 'colors' after it for color keys only)",
+        "cmdline.no_match" => "No value contains '{}'",
+        "log.save" => "save",
+        "log.reload" => "reload",
+        "log.notify" => "notify",
+        "log.problem" => "problem",
+        "log.saved" => "Saved {}",
+        "log.saved_changes" => "Saved {} change(s)",
+        "log.reset" => "Replaced the config with mako's defaults",
+        "log.reloaded" => "Reloaded mako",
+        "log.rolled_back" => "Put the previous file back",
+        "log.rollback_failed" => "Putting the previous file back failed: {}",
+        "log.notified" => "Sent the change notification",
+        "log.progress_sent" => "Filled the progress preview to 100%",
+        "log.problems" => "{} problem(s) in the saved file",
+        "log.snapshot_failed" => "Taking a snapshot failed: {}",
+        "log.committed" => "Committed: {}",
+        "log.commit_failed" => "Committing to git failed: {}",
+        "footer.reload_ok" => "Reload OK: {}",
+        "footer.reload_failed" => "Reload failed: {}",
+        "reload.rolled_back" => "{} (mako rejected the file, so the previous version was put back; your edits are unsaved)",
+        "reload.rollback_failed" => "{} (and putting the previous file back failed: {})",
+        "footer.hook_failed" => "Hook failed: {}",
+        "footer.allowed" => "Allowed: {}",
+        "footer.suggested" => "Suggested: {}",
+        "footer.search" => "{}/{}: next/previous match, Esc: clear search",
+        "footer.browse" => "Ctrl-O: browse directories",
+        "footer.picked" => "Paths: {}",
+        "footer.list" => "Ctrl-L: edit as a list",
+        "footer.list_value" => "Value: {}",
+        "footer.font_family" => "Family: ",
+        "footer.font_style" => "  Style: ",
+        "footer.font_size" => "  Size: ",
+        "footer.font_value" => "→ {}",
+        "fonts.unavailable" => "The installed fonts couldn't be listed (is fontconfig installed?)",
+        "footer.duration" => "Type e.g. 5s, 2m, 1m30s or 500ms; stored as milliseconds",
+        "value.no_timeout" => "never expires",
+        "value.no_default" => "no default",
+        "prompt.edit_value" => "Editing value (Enter=save, Esc=cancel): ",
+        "prompt.add_key" => "New key (Enter=next, Esc=cancel). Use ↑/↓ to pick, or type to filter: ",
+        "prompt.custom_key" => "Custom key name (Enter=next, Esc=cancel): ",
+        "prompt.add_value" => "Value for '{}' (Enter=add, Esc=cancel): ",
+        "prompt.confirm_delete" => "Confirm delete? ",
+        "prompt.delete_key" => "Delete '{}' (y/n): ",
+        "prompt.confirm_bulk" => "Confirm change? ",
+        "prompt.bulk_delete" => "Delete {} (y/n): ",
+        "prompt.bulk_set" => "Set {} to {} (y/n): ",
+        "prompt.bulk_replace" => "Replace {} matches of '{}' with '{}' in {} values (y/n): ",
+        "prompt.bulk_edit" => "Value for {} marked: ",
+        "prompt.bulk_edit_keys" => "(* is each one's current value) Enter review, Esc cancel",
+        "prompt.settings" => "↑/↓ pick a setting, Enter=edit, Esc=back",
+        "prompt.did_you_mean" => "'{}' is not a known key.",
+        "prompt.did_you_mean_keys" => "↑/↓ pick, Enter=use, Esc=back to typing",
+        "prompt.search" => "Search keys and values: ",
+        "prompt.search_keys" => "Enter=keep filter, Esc=clear",
+        "prompt.add_section" => "New section criteria, e.g. urgency=critical or app-name=firefox (Enter=add, Esc=cancel): ",
+        "prompt.duplicate" => "Duplicate '{}' as key (Enter=copy, Esc=cancel): ",
+        "prompt.edit_setting" => "{} (Enter=save, Esc=cancel): ",
+        "prompt.notifications" => "↑/↓ pick, a actions, x dismiss, X dismiss all, r refresh (also every couple of seconds), Esc back",
+        "prompt.notifications_empty" => "Nothing on screen right now. r refresh, Esc back",
+        "footer.notifications_failed" => "makoctl list failed: {}",
+        "prompt.history" => "↑/↓ pick, s send again as a test, R restore the most recent, r refresh, Esc back",
+        "prompt.history_empty" => "The history is empty. r refresh, Esc back",
+        "footer.history_failed" => "makoctl history failed: {}",
+        "prompt.actions" => "↑/↓ pick, Enter invoke, Esc back",
+        "footer.no_actions" => "'{}' has no actions",
+        "footer.invoke_failed" => "makoctl invoke failed: {}",
+        "footer.restore_failed" => "makoctl restore failed: {}",
+        "footer.dismiss_failed" => "makoctl dismiss failed: {}",
+        "prompt.import_path" => "dunstrc or swaync config.json to import (Enter=review, Esc=cancel): ",
+        "prompt.paste_config" => "Paste or type the config, then Ctrl-D to review what it changes (Esc=cancel)",
+        "prompt.paste_empty" => "Nothing to import: no key=value lines",
+        "prompt.import_review" => "↑/↓ pick, Space include/skip, Enter apply the selected settings, Esc cancel",
+        "prompt.pick_value" => "Value for {}: ↑/↓ choose, Enter save, Esc cancel",
+        "prompt.pick_value_keys" => "↑/↓ or j/k choose, Enter save, Esc cancel",
+        "list.pick_value" => "Values mako accepts for {}",
+        "list.pick_current" => "(current)",
+        "list.pick_default" => "(default)",
+        "prompt.pick_anchor" => "Where {} puts notifications: arrows move, Enter save, Esc cancel",
+        "list.pick_anchor" => "{} on the screen",
+        "prompt.edit_sides" => "{}: arrows pick a side, digits or +/- set it, Enter save, Esc cancel",
+        "prompt.edit_corners" => "{}: arrows pick a corner, digits or +/- set it, Enter save, Esc cancel",
+        "list.edit_sides" => "{} = {}",
+        "sides.text" => "text",
+        "sides.notification" => "notification",
+        "sides.stack" => "notifications",
+        "help.pick_value" => "Choosing a value",
+        "prompt.build_criteria" => "↑/↓ field, Enter set (cycles choices), r regex match, x clear, s add section, / type the header, Esc cancel",
+        "prompt.edit_criterion" => "{} (Enter=set, empty clears, Esc=back): ",
+        "list.criteria" => "New section [{}]",
+        "list.criterion_any" => "(any)",
+        "list.criterion_set" => "(set)",
+        "help.criteria" => "Adding a section",
+        "prompt.modes" => "↑/↓ pick, Space toggle, Enter edit its [mode=...] section, a add a mode, r refresh, Esc back",
+        "prompt.add_mode" => "New mode name, e.g. do-not-disturb (Enter=add its section, Esc=back): ",
+        "list.modes" => "Modes (● on)",
+        "list.mode_params" => "[mode] section with {} params",
+        "prompt.sections" => "↑/↓ pick, Enter edit its params, {} add a section, Esc back",
+        "list.sections" => "Criteria sections",
+        "list.section_params" => "{} params",
+        "list.section_back" => "Esc: back to global",
+        "list.mode_unstyled" => "no section",
+        "help.modes" => "Modes",
+        "footer.modes_failed" => "makoctl mode failed: {}",
+        "footer.mode_failed" => "Couldn't toggle {}: {}",
+        "footer.nothing_to_write" => "Nothing to write",
+        "footer.written" => "Wrote {} change(s)",
+        "prompt.confirm_quit" => "Unsaved changes. ",
+        "prompt.confirm_quit_keys" => "s save and quit, d discard and quit, Esc keep editing",
+        "footer.save_failed" => "Saving failed: {}",
+        "prompt.diff" => "↑/↓ PgUp/PgDn scroll, Esc back",
+        "prompt.command_line" => "Enter run (:w :q :wq :set key=value :d key :profile name :reload), Esc cancel",
+        "prompt.log" => "↑/↓ PgUp/PgDn Home/End scroll, Esc back",
+        "prompt.log_empty" => "Nothing logged yet: saves, reloads and notifications show up here. Esc back",
+        "prompt.problems" => "↑/↓ pick, Enter go to the line, Esc back",
+        "prompt.problems_empty" => "No problems: mako will read every line. Esc back",
+        "prompt.diff_empty" => "No unsaved changes: the config matches the file. Esc back",
+        "footer.write_hint" => "{}: write",
+        "prompt.themes" => "↑/↓ pick, Enter apply (only colors, font and borders change), Esc back",
+        "themes.applied" => "Applied the {} theme",
+        "themes.sample_summary" => "Download complete",
+        "themes.sample_body" => "report.pdf finished downloading",
+        "themes.sample_critical" => "Battery low",
+        "themes.sets" => "Sets:",
+        "prompt.compose" => "↑/↓ pick a field, Enter=edit, s=send, p=progress bar, Esc=back",
+        "prompt.edit_compose" => "{} (Enter=set, Esc=cancel): ",
+        "footer.test_sent" => "Test notification sent",
+        "footer.test_sending" => "Sending the test notification…",
+        "footer.progress_sending" => "Filling a progress bar from 0 to 100%…",
+        "footer.copied" => "Copied {} to the clipboard",
+        "footer.nothing_yanked" => "Nothing to paste: '{}' yanks the selected param",
+        "prompt.backups" => "↑/↓ pick, Enter restore (the current file becomes the newest backup), Esc back",
+        "prompt.backups_empty" => "No backups yet; they are made on every save. Esc back",
+        "prompt.snapshots" => "↑/↓ pick, Enter restore (saved as a change, so u undoes it), Esc back",
+        "prompt.snapshots_empty" => "No snapshots yet; one is taken on every save. Esc back",
+        "prompt.snapshots_off" => "Snapshots are off: set snapshots = true in the editor settings (s) to keep every saved version. Esc back",
+        "snapshots.name" => "snapshot {}",
+        "prompt.git_log_loading" => "Reading the config's git history… Esc back",
+        "prompt.git_log_empty" => "No commits of the config; with git_commit = true in the editor settings (s), every save in a git repository is committed. Esc back",
+        "footer.git_log_failed" => "Reading the git history failed: {}",
+        "git.name" => "commit {}",
+        "git.failed" => "Finding the config failed: {}",
+        "git.outside" => "{} is not inside the repository at {}",
+        "prompt.trash" => "↑/↓ pick, Enter/r restore, x discard for good, Esc back",
+        "prompt.trash_empty" => "Trash is empty. Esc back",
+        "prompt.edit_list" => "a add, e edit, d remove, K/J move, Enter use, Esc cancel",
+        "prompt.list_entry" => "Entry: ",
+        "prompt.list_entry_keys" => "Enter keep, Esc discard",
+        "prompt.pick_font" => "Type to filter, ↑/↓ family, ←/→ style, Tab size, Enter use, Esc cancel",
+        "prompt.browse" => "Enter/→ open, ←/Backspace up, Space pick directory, Tab use picked, Esc cancel",
+        "list.icon_themes" => "Icon themes",
+        "list.preview" => "Preview",
+        "browser.no_themes" => "No icon themes here",
+        "browser.no_inline" => "inline preview needs a kitty-compatible terminal",
+        "browser.read_failed" => "Cannot read {}: {}",
+        "prompt.reset_all" => "Reset the whole config to mako's defaults? The current file is backed up first. ",
+        "prompt.reset_all_type" => "Type '{}' and Enter to confirm, Esc to cancel: ",
+        "reset.confirm_word" => "reset",
+        "reset.key_done" => "{} reset to the default ({})",
+        "reset.key_removed" => "{} removed: nothing else sets it, so mako uses its default",
+        "reset.no_default" => "'{}' has no mako default; delete it instead",
+        "reset.all_done" => "Config reset to defaults; the previous file is the newest .bak",
+        "reset.failed" => "Reset failed: {}",
+        "undo.undone" => "Undid change to {}",
+        "undo.redone" => "Redid change to {}",
+        "undo.nothing" => "Nothing to undo",
+        "undo.nothing_to_redo" => "Nothing to redo",
+        "undo.reset" => "the whole config",
+        "help.title" => " Help (j/k scroll, Esc/?/q close) ",
+        "help.normal" => "Parameters list",
+        "help.always" => "↑/↓ move and Enter edits whatever the bindings; Ctrl-r redoes an undone change.",
+        "help.cmd.quit" => "quit",
+        "help.cmd.up" => "move up",
+        "help.cmd.down" => "move down",
+        "help.cmd.edit" => "edit the selected value",
+        "help.cmd.add" => "add a key",
+        "help.cmd.delete" => "delete the selected key (or an empty section)",
+        "help.cmd.settings" => "editor settings",
+        "help.cmd.trash" => "trash: restore deleted keys",
+        "help.cmd.duplicate" => "duplicate the selected key under a new name",
+        "help.cmd.customized" => "show only keys that differ from mako's defaults",
+        "help.cmd.reset" => "reset the selected key to mako's default",
+        "help.cmd.reset_all" => "reset the whole config (asks first, keeps a backup)",
+        "help.cmd.next_section" => "next section",
+        "help.cmd.prev_section" => "previous section",
+        "help.cmd.add_section" => "add a [criteria] section",
+        "help.cmd.undo" => "undo the last change",
+        "help.cmd.search" => "search keys and values",
+        "help.cmd.next_match" => "next search match",
+        "help.cmd.prev_match" => "previous search match",
+        "help.cmd.help" => "this help",
+        "help.cmd.backups" => "restore a backup of the config file",
+        "help.cmd.snapshots" => "browse and restore snapshots of every saved version",
+        "help.cmd.git_log" => "browse and restore the git history of the config",
+        "help.cmd.show_unset" => "also list the keys the section doesn't set, with mako's defaults",
+        "help.cmd.increment" => "increase the selected number (also l or →)",
+        "help.cmd.decrement" => "decrease the selected number (also h or ←)",
+        "help.cmd.move_up" => "move the selected key up in the file",
+        "help.cmd.move_down" => "move the selected key down in the file",
+        "help.cmd.notifications" => "show the notifications mako has on screen",
+        "help.cmd.history" => "browse mako's notification history",
+        "help.cmd.compose" => "compose and send a test notification",
+        "help.cmd.progress_preview" => "fill a progress bar on the test notification",
+        "help.cmd.import_dunst" => "import settings from a dunstrc",
+        "help.cmd.paste_config" => "paste a mako config snippet and merge in the lines you pick",
+        "help.cmd.themes" => "pick a built-in theme preset",
+        "help.cmd.copy" => "yank the selected (or marked) params for p, and copy the value to the clipboard",
+        "help.cmd.top" => "jump to the first row (also Home)",
+        "help.cmd.bottom" => "jump to the last row (also End); PageUp/PageDown move a screen",
+        "help.cmd.paste" => "paste the yanked params after the selected one, in any section",
+        "help.cmd.modes" => "turn mako modes on and off and style them",
+        "help.cmd.sort" => "list params in file order, A to Z, or by category",
+        "help.cmd.apply_sort" => "write the params in the order shown",
+        "sort.file" => "file order",
+        "sort.alphabetical" => "A to Z",
+        "sort.category" => "by category",
+        "sort.nothing_to_apply" => "The list is already in file order",
+        "sort.applied" => "Params reordered {}",
+        "sort.move_needs_file_order" => "Moving params needs file order ({}: change order)",
+        "category.appearance" => "Appearance",
+        "category.behavior" => "Behavior",
+        "category.icons" => "Icons",
+        "category.bindings" => "Bindings",
+        "category.custom" => "Custom",
+        "help.cmd.migrate" => "rename old keys to what mako reads now, remove dropped ones",
+        "migrate.nothing" => "No old keys to migrate",
+        "migrate.done" => "Renamed {} old key(s), moved {} dropped one(s) to the trash",
+        "help.cmd.docs" => "show or hide the docs pane for the selected key",
+        "help.cmd.validate" => "check every line of the config mako would read",
+        "help.cmd.auto_reload" => "turn reloading mako (and notifying) after saves off or on",
+        "help.cmd.mark" => "mark the selected param for a bulk edit or delete",
+        "help.cmd.command_line" => "command line: :w, :q, :q!, :wq, :set key=value, :d key, :profile name, :reload",
+        "help.cmd.log" => "activity log: saves, reloads (with makoctl's full output) and notifications",
+        "help.cmd.diff" => "show what would be written, as a diff against the file",
+        "help.cmd.write" => "write held-back changes (with deferred_write)",
+        "help.editing" => "Editing a value",
+        "help.editing_keys" => "Enter save, Esc cancel, Backspace delete a character; pasting inserts the text.",
+        "help.editing_format" => "format: Tab after % completes a specifier and cycles through the rest.",
+        "help.editing_browse" => "icon-path: Ctrl-O opens the directory browser; font: Ctrl-O opens the font picker; icon-path and group-by: Ctrl-L edits the value one entry per row.",
+        "help.adding" => "Adding a key",
+        "help.adding_keys" => "Type to filter the known keys, ↑/↓ pick, Enter next, Esc cancel. <custom> names a key of your own.",
+        "help.search" => "Searching",
+        "help.search_keys" => "Type to filter, Enter keep the filter, Esc clear it.",
+        "help.trash" => "Trash",
+        "help.notifications" => "Notifications",
+        "help.history" => "History",
+        "help.actions" => "Notification actions",
+        "help.compose" => "Test notification",
+        "help.themes" => "Theme presets",
+        "help.diff" => "Diff of unsaved changes",
+        "help.log" => "Activity log",
+        "help.problems" => "Problems",
+        "help.import" => "Importing from dunst",
+        "help.browser" => "Directory browser",
+        "help.font_picker" => "Font picker",
+        "help.list_editor" => "List editor",
+        "help.settings" => "Settings",
+        "backups.restored" => "Restored {}",
+        "backups.read_failed" => "Cannot read {}: {}",
+        "step.not_numeric" => "'{}' isn't a number that can be stepped",
+        "notifications.bad_json" => "unexpected output from makoctl at byte {}",
+        "notifications.truncated" => "makoctl output ended early",
+        "import.read_failed" => "Cannot read {}: {}",
+        "import.done" => "Imported {} settings from {}",
+        "import.no_equivalent" => "no mako equivalent",
+        "import.bad_value" => "value not understood",
+        "import.not_set" => "{} (not set)",
+        "import.bad_json" => "not valid JSON at byte {}",
+        "import.merged_padding" => "merged into padding",
+        "import.unmatched_rule" => "rule without app name, summary, body, category or urgency to match",
+        "validate.force" => "Enter again to save anyway, Esc to cancel",
+        "settings.saved" => "Settings saved to {}",
+        "settings.save_failed" => "Saving settings failed: {}",
+        "settings.invalid" => "'{}' is not a valid value for {}",
+        "settings.unknown" => "Unknown setting '{}'",
+        "settings.key_taken" => "Can't bind '{}' to {}: {} already uses it",
+        "settings.no_home" => "Could not find the home directory",
+        "settings.deprecated" => "Setting '{}' no longer does anything; the editor only redraws when something changes",
+        "settings.idle_slow" => "idle_mode \"slow\" is gone and now means \"poll\"; use \"block\" to never refresh on a timer",
+        "error.makoctl_exec" => "Failed to execute makoctl: {}",
+        "error.mako_exec" => "Failed to execute mako: {}",
+        "error.mako_version" => "can't read a version in '{}'",
+        "error.gdbus_exec" => "Failed to execute gdbus: {} (is glib installed?)",
+        "error.notify_failed" => "Sending the notification failed: {}",
+        "error.makoctl_disabled" => "built without the `makoctl` feature; reload mako manually",
+        "error.clipboard_exec" => "Failed to execute {}: {} (is wl-clipboard installed?)",
+        "error.clipboard_failed" => "{} failed: {}",
+        "error.fc_list_exec" => "Failed to execute fc-list: {} (is fontconfig installed?)",
+        "error.fontconfig_disabled" => "built without the `fontconfig` feature",
+        "error.clipboard_disabled" => "built without the `clipboard` feature",
+        "error.git_exec" => "Failed to execute git: {} (is git installed?)",
+        "error.git_disabled" => "built without the `git` feature",
+        "error.hook_exec" => "failed to run {}: {}",
+        "lint.duplicate" => "'{}' is set {} times and only the last value ({}) is used; remove the others",
+        "lint.alias" => "'{}' and '{}' mean the same but disagree ({} vs {}); keep one",
+        "lint.never_expire" => "ignore-timeout=1 with default-timeout={} means notifications never expire; set default-timeout (e.g. 5000)",
+        "lint.icons_off" => "icons=0 turns icons off, so '{}' has no effect; remove it or enable icons",
+        "lint.no_border" => "border-size=0 hides the border, so border-color has no effect",
+        "keys.needs_version" => "needs mako {}",
+        "lint.needs_version" => "{} needs mako {} or later, but mako {} is installed; it will refuse the config",
+        "lint.path_missing" => "{} is not a directory; mako will skip it",
+        "lint.font_missing" => "no installed font family is called {}; mako will fall back to another font",
+        "lint.renamed" => "{} is the old name of {}; migrate renames it",
+        "lint.dropped" => "mako no longer reads {}; migrate removes it",
+        "format.unknown_specifier" => "unknown specifier %{}",
+        "format.unknown_tag" => "unknown markup tag <{}>",
+        "format.unclosed" => "<{}> is never closed",
+        "format.mismatched" => "</{}> closes <{}>",
+        "format.stray_close" => "</{}> has no opening tag",
+        "format.unterminated" => "'<' without a closing '>'",
+        "format.title" => "Format",
+        "format.specifiers" => "Specifiers (Tab completes after %): ",
+        "format.markup_ok" => "Markup OK",
+        "format.example" => "Example:",
+        "headless.bad_key" => "line {}: unknown key '{}'",
+        "headless.bad_step" => "line {}: unknown step '{}' (expected press, type, paste, resize or snapshot)",
+        "cli.usage" => "usage: mako-tui [--config PATH] [--headless SCRIPT [--size WxH] | --export-minimal | --export-nix]\n       mako-tui [--config PATH] [--section CRITERIA] (get KEY | set KEY VALUE [--force] | list | delete KEY)\n       mako-tui [--config PATH] apply THEME [--force]\n       mako-tui [--config PATH] validate [FILE]\n       mako-tui --refresh-keys [MAN-PAGE]\n       mako-tui completions (bash | zsh | fish)\n       (--log-level LEVEL can come first to log to a file, --compact for a small popup layout)",
+        "cli.validate_summary" => "{}: {} errors, {} warnings",
+        "cli.not_set" => "{} is not set",
+        "cli.save_failed" => "cannot save {}: {}",
+        "cli.invalid" => "{} (use --force to set it anyway)",
+        "cli.no_section" => "no section [{}]",
+        "cli.no_theme" => "no theme '{}': not a file, not in {} and not a preset ({})",
+        "cli.bad_size" => "invalid size '{}', expected WIDTHxHEIGHT",
+        "cli.read_failed" => "cannot read {}: {}",
+        "cli.write_failed" => "cannot write {}: {}",
+        "cli.no_config_path" => "cannot tell where mako's config is ({}); pass --config PATH",
+        "cli.refresh_empty" => "no keys found in mako(5)",
+        "cli.refresh_done" => "read {} keys from mako(5) into {}",
+        "cli.refresh_new" => "not built in, now offered when adding keys: {}",
+        "cli.refresh_gone" => "built in but not in mako(5): {}",
+        "cli.refresh_values" => "listed values differ from the built-in ones: {}",
+        "error.man_exec" => "Failed to execute man: {}",
+        "error.gzip_exec" => "Failed to execute gzip: {}",
+        _ => return None,
+    })
+}
+
+fn de(id: &str) -> Option<&'static str> {
+    Some(match id {
+        "header.title" => " Mako-Konfigurationseditor ",
+        "header.navigate" => "↑↓/{}/{}: bewegen ",
+        "header.edit" => "{}/Enter: bearbeiten ",
+        "header.add" => "{}: hinzufügen ",
+        "header.delete" => "{}: löschen ",
+        "header.settings" => "{}: Einstellungen ",
+        "header.quit" => "{}: beenden",
+        "list.known_keys" => "Bekannte Schlüssel",
+        "list.params" => "Parameter",
+        "list.settings" => "Editor-Einstellungen ({})",
+        "footer.selected" => "Ausgewählt: {} = {}",
+        "footer.no_selection" => "Keine Auswahl",
+        "footer.hint" => "'{}' hinzufügen, '{}' bearbeiten, '{}' löschen.",
+        "footer.reloading" => "{} mako wird neu geladen…",
+        "footer.manual_reload" => "Manuelles Neuladen: Speichern ruft makoctl nicht auf ('{}' schaltet es wieder ein)",
+        "footer.log_hint" => "('{}' zeigt das ganze Protokoll)",
+        "footer.marked" => "{} markiert: '{}' bearbeitet sie, '{}' löscht sie, Leertaste umschalten, Esc aufheben",
+        "log.save" => "speichern",
+        "log.reload" => "neu laden",
+        "log.notify" => "melden",
+        "log.problem" => "problem",
+        "footer.reload_ok" => "Neu geladen: {}",
+        "footer.reload_failed" => "Neuladen fehlgeschlagen: {}",
+        "footer.hook_failed" => "Hook fehlgeschlagen: {}",
+        "footer.allowed" => "Erlaubt: {}",
+        "footer.suggested" => "Vorschläge: {}",
+        "prompt.edit_value" => "Wert bearbeiten (Enter=speichern, Esc=abbrechen): ",
+        "prompt.add_key" => "Neuer Schlüssel (Enter=weiter, Esc=abbrechen). ↑/↓ zum Wählen, tippen zum Filtern: ",
+        "prompt.custom_key" => "Eigener Schlüsselname (Enter=weiter, Esc=abbrechen): ",
+        "prompt.add_value" => "Wert für '{}' (Enter=hinzufügen, Esc=abbrechen): ",
+        "prompt.confirm_delete" => "Wirklich löschen? ",
+        "prompt.delete_key" => "'{}' löschen (y/n): ",
+        "prompt.confirm_bulk" => "Wirklich ändern? ",
+        "prompt.bulk_delete" => "{} löschen (y/n): ",
+        "prompt.bulk_set" => "{} auf {} setzen (y/n): ",
+        "prompt.bulk_edit" => "Wert für {} markierte: ",
+        "prompt.settings" => "↑/↓ Einstellung wählen, Enter=bearbeiten, Esc=zurück",
+        "prompt.edit_setting" => "{} (Enter=speichern, Esc=abbrechen): ",
+        "settings.saved" => "Einstellungen gespeichert in {}",
+        "settings.save_failed" => "Speichern der Einstellungen fehlgeschlagen: {}",
+        "settings.invalid" => "'{}' ist kein gültiger Wert für {}",
+        "settings.unknown" => "Unbekannte Einstellung '{}'",
+        "error.makoctl_exec" => "makoctl konnte nicht ausgeführt werden: {}",
+        "error.makoctl_disabled" => "ohne das Feature `makoctl` gebaut; mako bitte manuell neu laden",
+        "error.hook_exec" => "{} konnte nicht ausgeführt werden: {}",
+        _ => return None,
+    })
+}
+
+fn es(id: &str) -> Option<&'static str> {
+    Some(match id {
+        "header.title" => " Editor de configuración de Mako ",
+        "header.navigate" => "↑↓/{}/{}: navegar ",
+        "header.edit" => "{}/Enter: editar ",
+        "header.add" => "{}: añadir ",
+        "header.delete" => "{}: borrar ",
+        "header.settings" => "{}: ajustes ",
+        "header.quit" => "{}: salir",
+        "list.known_keys" => "Claves conocidas",
+        "list.params" => "Parámetros",
+        "list.settings" => "Ajustes del editor ({})",
+        "footer.selected" => "Seleccionado: {} = {}",
+        "footer.no_selection" => "Sin selección",
+        "footer.hint" => "'{}' para añadir, '{}' para editar, '{}' para borrar.",
+        "footer.reloading" => "{} Recargando mako…",
+        "footer.manual_reload" => "Recarga manual: guardar no ejecuta makoctl ('{}' la vuelve a activar)",
+        "footer.log_hint" => "('{}' muestra el registro completo)",
+        "footer.marked" => "{} marcados: '{}' los edita, '{}' los borra, Espacio alterna, Esc limpia",
+        "log.save" => "guardar",
+        "log.reload" => "recargar",
+        "log.notify" => "notificar",
+        "log.problem" => "problema",
+        "footer.reload_ok" => "Recarga correcta: {}",
+        "footer.reload_failed" => "Error al recargar: {}",
+        "footer.hook_failed" => "Error en el hook: {}",
+        "footer.allowed" => "Permitidos: {}",
+        "footer.suggested" => "Sugerencias: {}",
+        "prompt.edit_value" => "Editando valor (Enter=guardar, Esc=cancelar): ",
+        "prompt.add_key" => "Nueva clave (Enter=siguiente, Esc=cancelar). ↑/↓ para elegir o escribe para filtrar: ",
+        "prompt.custom_key" => "Nombre de clave personalizada (Enter=siguiente, Esc=cancelar): ",
+        "prompt.add_value" => "Valor para '{}' (Enter=añadir, Esc=cancelar): ",
+        "prompt.confirm_delete" => "¿Confirmar borrado? ",
+        "prompt.delete_key" => "Borrar '{}' (y/n): ",
+        "prompt.confirm_bulk" => "¿Confirmar cambio? ",
+        "prompt.bulk_delete" => "Borrar {} (y/n): ",
+        "prompt.bulk_set" => "Poner {} a {} (y/n): ",
+        "prompt.bulk_edit" => "Valor para {} marcados: ",
+        "prompt.settings" => "↑/↓ elige un ajuste, Enter=editar, Esc=volver",
+        "prompt.edit_setting" => "{} (Enter=guardar, Esc=cancelar): ",
+        "settings.saved" => "Ajustes guardados en {}",
+        "settings.save_failed" => "Error al guardar los ajustes: {}",
+        "settings.invalid" => "'{}' no es un valor válido para {}",
+        "settings.unknown" => "Ajuste desconocido '{}'",
+        "error.makoctl_exec" => "No se pudo ejecutar makoctl: {}",
+        "error.makoctl_disabled" => "compilado sin la función `makoctl`; recarga mako manualmente",
+        "error.hook_exec" => "no se pudo ejecutar {}: {}",
+        _ => return None,
+    })
+}
@@ -0,0 +1,97 @@
+//! Icon themes and icon files, for the `icon-path` browser.
+
+use std::{
+    fs::{self, File},
+    io::Read,
+    path::Path,
+};
+
+/// Icon themes directly inside `dir`: subdirectories with an
+/// `index.theme`, by their `Name=` (or directory name when unnamed).
+pub fn themes_in(dir: &Path) -> Vec<String> {
+    let Ok(rd) = fs::read_dir(dir) else { return Vec::new() };
+    let mut themes: Vec<String> = rd
+        .filter_map(Result::ok)
+        .filter_map(|e| {
+            let index = fs::read_to_string(e.path().join("index.theme")).ok()?;
+            let name = index.lines().find_map(|l| l.strip_prefix("Name=")).map(str::trim).filter(|n| !n.is_empty());
+            Some(name.map(String::from).unwrap_or_else(|| e.file_name().to_string_lossy().into_owned()))
+        })
+        .collect();
+    themes.sort();
+    themes
+}
+
+/// Whether mako could load `path` as an icon, judging by its extension.
+pub fn is_image(path: &Path) -> bool {
+    matches!(
+        path.extension().and_then(|e| e.to_str()).map(str::to_lowercase).as_deref(),
+        Some("png" | "svg" | "xpm")
+    )
+}
+
+/// A one-line summary such as `PNG 48×48, 2 KiB`. The size is read from
+/// the PNG header; other formats only report their type and file size.
+pub fn describe(path: &Path) -> Option<String> {
+    let kib = fs::metadata(path).ok()?.len().div_ceil(1024);
+    let kind = path.extension()?.to_str()?.to_uppercase();
+    let mut head = [0u8; 24];
+    let n = File::open(path).ok()?.read(&mut head).ok()?;
+    Some(match png_size(&head[..n]) {
+        Some((w, h)) => format!("{} {}×{}, {} KiB", kind, w, h, kib),
+        None => format!("{}, {} KiB", kind, kib),
+    })
+}
+
+fn png_size(data: &[u8]) -> Option<(u32, u32)> {
+    if data.len() < 24 || &data[..8] != b"\x89PNG\r\n\x1a\n" || &data[12..16] != b"IHDR" {
+        return None;
+    }
+    let w = u32::from_be_bytes(data[16..20].try_into().ok()?);
+    let h = u32::from_be_bytes(data[20..24].try_into().ok()?);
+    Some((w, h))
+}
+
+/// Whether the terminal understands the kitty graphics protocol (kitty,
+/// WezTerm, Ghostty), which can show a PNG straight from its path.
+#[cfg(feature = "image-preview")]
+pub fn inline_images_supported() -> bool {
+    use std::env;
+    env::var_os("KITTY_WINDOW_ID").is_some()
+        || env::var("TERM").is_ok_and(|t| t.contains("kitty") || t.contains("ghostty"))
+        || env::var("TERM_PROGRAM").is_ok_and(|t| t == "WezTerm" || t == "ghostty")
+}
+
+#[cfg(not(feature = "image-preview"))]
+pub fn inline_images_supported() -> bool {
+    false
+}
+
+/// Escape sequence showing the PNG at `path` scaled into a `cols`x`rows`
+/// cell box at the cursor. The terminal reads the file itself.
+pub fn kitty_show(path: &Path, cols: u16, rows: u16) -> String {
+    let path = base64(path.to_string_lossy().as_bytes());
+    format!("\x1b_Ga=T,t=f,f=100,q=2,C=1,c={},r={};{}\x1b\\", cols, rows, path)
+}
+
+/// Escape sequence removing every image shown with `kitty_show`.
+pub fn kitty_clear() -> &'static str {
+    "\x1b_Ga=d,d=A,q=2\x1b\\"
+}
+
+fn base64(data: &[u8]) -> String {
+    const ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+    let mut out = String::new();
+    for chunk in data.chunks(3) {
+        let b = [chunk[0], *chunk.get(1).unwrap_or(&0), *chunk.get(2).unwrap_or(&0)];
+        let n = u32::from(b[0]) << 16 | u32::from(b[1]) << 8 | u32::from(b[2]);
+        for i in 0..4 {
+            if i <= chunk.len() {
+                out.push(ALPHABET[(n >> (18 - 6 * i) & 63) as usize] as char);
+            } else {
+                out.push('=');
+            }
+        }
+    }
+    out
+}
@@ -0,0 +1,267 @@
+//! Just enough JSON for what the editor reads: `makoctl list` output and
+//! SwayNotificationCenter's config.
+
+/// How deep arrays and objects may nest before the text is refused, so
+/// hostile input can't run the parser out of stack.
+const MAX_DEPTH: usize = 128;
+
+/// Where reading stopped making sense.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Error {
+    /// Something unexpected at this byte offset, such as an array or
+    /// object nested deeper than `MAX_DEPTH`.
+    At(usize),
+    /// The text ended inside a value.
+    Truncated,
+}
+
+/// Read `s`, which must hold one value and nothing after it.
+pub fn parse(s: &str) -> Result<Json, Error> {
+    let mut parser = Parser { s: s.as_bytes(), pos: 0, depth: 0 };
+    let value = parser.value()?;
+    parser.skip_ws();
+    if parser.pos != parser.s.len() {
+        return Err(parser.error());
+    }
+    Ok(value)
+}
+
+impl Json {
+    /// The field `name` of an object.
+    pub fn get(&self, name: &str) -> Option<&Json> {
+        match self {
+            Json::Object(fields) => fields.iter().find(|(k, _)| k == name).map(|(_, v)| v),
+            _ => None,
+        }
+    }
+}
+
+/// A JSON value. Objects keep their fields in file order.
+#[derive(Debug, PartialEq)]
+pub enum Json {
+    Null,
+    Bool(bool),
+    Number(f64),
+    String(String),
+    Array(Vec<Json>),
+    Object(Vec<(String, Json)>),
+}
+
+struct Parser<'a> {
+    s: &'a [u8],
+    pos: usize,
+    /// Arrays and objects open around `pos`.
+    depth: usize,
+}
+
+impl Parser<'_> {
+    /// What went wrong at `pos`: running out of text counts as truncated.
+    fn error(&self) -> Error {
+        if self.pos >= self.s.len() { Error::Truncated } else { Error::At(self.pos) }
+    }
+
+    fn skip_ws(&mut self) {
+        while self.s.get(self.pos).is_some_and(u8::is_ascii_whitespace) {
+            self.pos += 1;
+        }
+    }
+
+    fn eat(&mut self, b: u8) -> bool {
+        self.skip_ws();
+        let found = self.s.get(self.pos) == Some(&b);
+        if found {
+            self.pos += 1;
+        }
+        found
+    }
+
+    fn value(&mut self) -> Result<Json, Error> {
+        self.skip_ws();
+        if matches!(self.s.get(self.pos), Some(b'{' | b'[')) {
+            if self.depth == MAX_DEPTH {
+                return Err(self.error());
+            }
+            self.depth += 1;
+            let value = self.container();
+            self.depth -= 1;
+            return value;
+        }
+        self.scalar()
+    }
+
+    /// The object or array at `pos`.
+    fn container(&mut self) -> Result<Json, Error> {
+        match self.s.get(self.pos) {
+            Some(b'{') => {
+                self.pos += 1;
+                let mut fields = Vec::new();
+                if !self.eat(b'}') {
+                    loop {
+                        self.skip_ws();
+                        let key = self.string()?;
+                        if !self.eat(b':') {
+                            return Err(self.error());
+                        }
+                        fields.push((key, self.value()?));
+                        if self.eat(b'}') {
+                            break;
+                        }
+                        if !self.eat(b',') {
+                            return Err(self.error());
+                        }
+                    }
+                }
+                Ok(Json::Object(fields))
+            }
+            Some(b'[') => {
+                self.pos += 1;
+                let mut items = Vec::new();
+                if !self.eat(b']') {
+                    loop {
+                        items.push(self.value()?);
+                        if self.eat(b']') {
+                            break;
+                        }
+                        if !self.eat(b',') {
+                            return Err(self.error());
+                        }
+                    }
+                }
+                Ok(Json::Array(items))
+            }
+            _ => Err(self.error()),
+        }
+    }
+
+    /// The string, number, `true`, `false` or `null` at `pos`.
+    fn scalar(&mut self) -> Result<Json, Error> {
+        match self.s.get(self.pos) {
+            Some(b'"') => self.string().map(Json::String),
+            Some(_) => {
+                let start = self.pos;
+                while self.s.get(self.pos).is_some_and(|b| b.is_ascii_alphanumeric() || b"+-.".contains(b)) {
+                    self.pos += 1;
+                }
+                match &self.s[start..self.pos] {
+                    b"null" => Ok(Json::Null),
+                    b"true" => Ok(Json::Bool(true)),
+                    b"false" => Ok(Json::Bool(false)),
+                    word => std::str::from_utf8(word)
+                        .ok()
+                        .and_then(|w| w.parse().ok())
+                        .map(Json::Number)
+                        .ok_or(Error::At(start)),
+                }
+            }
+            None => Err(Error::Truncated),
+        }
+    }
+
+    fn string(&mut self) -> Result<String, Error> {
+        if self.s.get(self.pos) != Some(&b'"') {
+            return Err(self.error());
+        }
+        self.pos += 1;
+        let mut out = Vec::new();
+        loop {
+            match self.s.get(self.pos) {
+                None => return Err(Error::Truncated),
+                Some(b'"') => break,
+                Some(b'\\') => {
+                    self.pos += 1;
+                    match self.s.get(self.pos) {
+                        Some(b'n') => out.push(b'\n'),
+                        Some(b't') => out.push(b'\t'),
+                        Some(b'r') => out.push(b'\r'),
+                        Some(b'b') => out.push(0x08),
+                        Some(b'f') => out.push(0x0c),
+                        Some(b'u') => {
+                            let mut code = self.hex4(self.pos + 1);
+                            self.pos += 4;
+                            // Characters outside the BMP come as a
+                            // surrogate pair: \uD83D\uDE00.
+                            if let Some(high @ 0xd800..=0xdbff) = code
+                                && self.s.get(self.pos + 1..self.pos + 3) == Some(b"\\u")
+                                && let Some(low @ 0xdc00..=0xdfff) = self.hex4(self.pos + 3)
+                            {
+                                code = Some(0x10000 + ((high - 0xd800) << 10) + (low - 0xdc00));
+                                self.pos += 6;
+                            }
+                            let c = code.and_then(char::from_u32).unwrap_or('\u{fffd}');
+                            out.extend_from_slice(c.encode_utf8(&mut [0; 4]).as_bytes());
+                        }
+                        Some(&b) => out.push(b),
+                        None => return Err(Error::Truncated),
+                    }
+                }
+                Some(&b) => out.push(b),
+            }
+            self.pos += 1;
+        }
+        self.pos += 1;
+        Ok(String::from_utf8_lossy(&out).into_owned())
+    }
+
+    /// The four hex digits at `at`, as after `\u`.
+    fn hex4(&self, at: usize) -> Option<u32> {
+        let hex = std::str::from_utf8(self.s.get(at..at + 4)?).ok()?;
+        u32::from_str_radix(hex, 16).ok()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn objects_and_arrays_keep_their_order() {
+        let json = parse(r#" {"b": [1, -2.5e1, true, false, null], "a": {}, "c": []} "#).unwrap();
+        assert_eq!(
+            json,
+            Json::Object(vec![
+                (
+                    "b".to_string(),
+                    Json::Array(vec![Json::Number(1.0), Json::Number(-25.0), Json::Bool(true), Json::Bool(false), Json::Null])
+                ),
+                ("a".to_string(), Json::Object(Vec::new())),
+                ("c".to_string(), Json::Array(Vec::new())),
+            ])
+        );
+        assert_eq!(json.get("a"), Some(&Json::Object(Vec::new())));
+        assert_eq!(json.get("d"), None);
+    }
+
+    #[test]
+    fn strings_unescape() {
+        let s = |text: &str| parse(text).map(|json| match json {
+            Json::String(s) => s,
+            other => panic!("{:?}", other),
+        });
+        assert_eq!(s(r#""a\"b\\c\/d\n\t""#), Ok("a\"b\\c/d\n\t".to_string()));
+        assert_eq!(s(r#""caf\u00e9 \u2603""#), Ok("café ☃".to_string()));
+        assert_eq!(s(r#""\uD83D\uDE00!""#), Ok("😀!".to_string()));
+        // A lone surrogate can't be a character.
+        assert_eq!(s(r#""\uD83Dx""#), Ok("\u{fffd}x".to_string()));
+        assert_eq!(s(r#""\uDE00""#), Ok("\u{fffd}".to_string()));
+        assert_eq!(s("\"ünïcode\""), Ok("ünïcode".to_string()));
+    }
+
+    #[test]
+    fn bad_input_says_where() {
+        assert_eq!(parse(""), Err(Error::Truncated));
+        assert_eq!(parse(r#"{"a": [1, 2"#), Err(Error::Truncated));
+        assert_eq!(parse(r#""abc"#), Err(Error::Truncated));
+        assert_eq!(parse(r#"{"a": 1} x"#), Err(Error::At(9)));
+        assert_eq!(parse("[1 2]"), Err(Error::At(3)));
+        assert_eq!(parse("[nope]"), Err(Error::At(1)));
+        assert_eq!(parse("{1: 2}"), Err(Error::At(1)));
+    }
+
+    #[test]
+    fn nesting_is_capped() {
+        let nested = |depth: usize| format!("{}{}", "[".repeat(depth), "]".repeat(depth));
+        assert!(parse(&nested(MAX_DEPTH)).is_ok());
+        assert_eq!(parse(&nested(MAX_DEPTH + 1)), Err(Error::At(MAX_DEPTH)));
+        assert_eq!(parse(&"[".repeat(100_000)), Err(Error::At(MAX_DEPTH)));
+    }
+}
@@ -0,0 +1,364 @@
+use crossterm::event::KeyCode;
+
+/// Something the user can do from the params list. Each command can be
+/// rebound to another character in the settings file's `[keys]` table.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Command {
+    Quit,
+    Up,
+    Down,
+    Edit,
+    Add,
+    Delete,
+    Settings,
+    Trash,
+    Duplicate,
+    Customized,
+    Reset,
+    ResetAll,
+    NextSection,
+    PrevSection,
+    AddSection,
+    Undo,
+    Search,
+    NextMatch,
+    PrevMatch,
+    Help,
+    Backups,
+    Increment,
+    Decrement,
+    MoveUp,
+    MoveDown,
+    Notifications,
+    History,
+    Compose,
+    ProgressPreview,
+    ImportDunst,
+    PasteConfig,
+    Themes,
+    Copy,
+    Modes,
+    Write,
+    Diff,
+    Docs,
+    Migrate,
+    Sort,
+    ApplySort,
+    Validate,
+    AutoReload,
+    Log,
+    CommandLine,
+    Mark,
+    Paste,
+    Top,
+    Bottom,
+    Snapshots,
+    GitLog,
+    ShowUnset,
+}
+
+impl Command {
+    pub const ALL: &'static [Command] = &[
+        Command::Quit,
+        Command::Up,
+        Command::Down,
+        Command::Edit,
+        Command::Add,
+        Command::Delete,
+        Command::Settings,
+        Command::Trash,
+        Command::Duplicate,
+        Command::Customized,
+        Command::Reset,
+        Command::ResetAll,
+        Command::NextSection,
+        Command::PrevSection,
+        Command::AddSection,
+        Command::Undo,
+        Command::Search,
+        Command::NextMatch,
+        Command::PrevMatch,
+        Command::Help,
+        Command::Backups,
+        Command::Increment,
+        Command::Decrement,
+        Command::MoveUp,
+        Command::MoveDown,
+        Command::Notifications,
+        Command::History,
+        Command::Compose,
+        Command::ProgressPreview,
+        Command::ImportDunst,
+        Command::PasteConfig,
+        Command::Themes,
+        Command::Copy,
+        Command::Modes,
+        Command::Write,
+        Command::Diff,
+        Command::Docs,
+        Command::Migrate,
+        Command::Sort,
+        Command::ApplySort,
+        Command::Validate,
+        Command::AutoReload,
+        Command::Log,
+        Command::CommandLine,
+        Command::Mark,
+        Command::Paste,
+        Command::Top,
+        Command::Bottom,
+        Command::Snapshots,
+        Command::GitLog,
+        Command::ShowUnset,
+    ];
+
+    /// Name used in the `[keys]` table.
+    pub fn name(self) -> &'static str {
+        match self {
+            Command::Quit => "quit",
+            Command::Up => "up",
+            Command::Down => "down",
+            Command::Edit => "edit",
+            Command::Add => "add",
+            Command::Delete => "delete",
+            Command::Settings => "settings",
+            Command::Trash => "trash",
+            Command::Duplicate => "duplicate",
+            Command::Customized => "customized",
+            Command::Reset => "reset",
+            Command::ResetAll => "reset-all",
+            Command::NextSection => "next-section",
+            Command::PrevSection => "prev-section",
+            Command::AddSection => "add-section",
+            Command::Undo => "undo",
+            Command::Search => "search",
+            Command::NextMatch => "next-match",
+            Command::PrevMatch => "prev-match",
+            Command::Help => "help",
+            Command::Backups => "backups",
+            Command::Increment => "increment",
+            Command::Decrement => "decrement",
+            Command::MoveUp => "move-up",
+            Command::MoveDown => "move-down",
+            Command::Notifications => "notifications",
+            Command::History => "history",
+            Command::Compose => "compose",
+            Command::ProgressPreview => "progress-preview",
+            Command::ImportDunst => "import-dunst",
+            Command::PasteConfig => "paste-config",
+            Command::Themes => "themes",
+            Command::Copy => "copy",
+            Command::Modes => "modes",
+            Command::Write => "write",
+            Command::Diff => "diff",
+            Command::Docs => "docs",
+            Command::Migrate => "migrate",
+            Command::Sort => "sort",
+            Command::ApplySort => "apply-sort",
+            Command::Validate => "validate",
+            Command::AutoReload => "auto-reload",
+            Command::Log => "log",
+            Command::CommandLine => "command-line",
+            Command::Mark => "mark",
+            Command::Paste => "paste",
+            Command::Top => "top",
+            Command::Bottom => "bottom",
+            Command::Snapshots => "snapshots",
+            Command::GitLog => "git-log",
+            Command::ShowUnset => "show-unset",
+        }
+    }
+
+    /// Message id of what the command does, for the help screen.
+    pub fn help_id(self) -> &'static str {
+        match self {
+            Command::Quit => "help.cmd.quit",
+            Command::Up => "help.cmd.up",
+            Command::Down => "help.cmd.down",
+            Command::Edit => "help.cmd.edit",
+            Command::Add => "help.cmd.add",
+            Command::Delete => "help.cmd.delete",
+            Command::Settings => "help.cmd.settings",
+            Command::Trash => "help.cmd.trash",
+            Command::Duplicate => "help.cmd.duplicate",
+            Command::Customized => "help.cmd.customized",
+            Command::Reset => "help.cmd.reset",
+            Command::ResetAll => "help.cmd.reset_all",
+            Command::NextSection => "help.cmd.next_section",
+            Command::PrevSection => "help.cmd.prev_section",
+            Command::AddSection => "help.cmd.add_section",
+            Command::Undo => "help.cmd.undo",
+            Command::Search => "help.cmd.search",
+            Command::NextMatch => "help.cmd.next_match",
+            Command::PrevMatch => "help.cmd.prev_match",
+            Command::Help => "help.cmd.help",
+            Command::Backups => "help.cmd.backups",
+            Command::Increment => "help.cmd.increment",
+            Command::Decrement => "help.cmd.decrement",
+            Command::MoveUp => "help.cmd.move_up",
+            Command::MoveDown => "help.cmd.move_down",
+            Command::Notifications => "help.cmd.notifications",
+            Command::History => "help.cmd.history",
+            Command::Compose => "help.cmd.compose",
+            Command::ProgressPreview => "help.cmd.progress_preview",
+            Command::ImportDunst => "help.cmd.import_dunst",
+            Command::PasteConfig => "help.cmd.paste_config",
+            Command::Themes => "help.cmd.themes",
+            Command::Copy => "help.cmd.copy",
+            Command::Modes => "help.cmd.modes",
+            Command::Write => "help.cmd.write",
+            Command::Diff => "help.cmd.diff",
+            Command::Docs => "help.cmd.docs",
+            Command::Migrate => "help.cmd.migrate",
+            Command::Sort => "help.cmd.sort",
+            Command::ApplySort => "help.cmd.apply_sort",
+            Command::Validate => "help.cmd.validate",
+            Command::AutoReload => "help.cmd.auto_reload",
+            Command::Log => "help.cmd.log",
+            Command::CommandLine => "help.cmd.command_line",
+            Command::Mark => "help.cmd.mark",
+            Command::Paste => "help.cmd.paste",
+            Command::Top => "help.cmd.top",
+            Command::Bottom => "help.cmd.bottom",
+            Command::Snapshots => "help.cmd.snapshots",
+            Command::GitLog => "help.cmd.git_log",
+            Command::ShowUnset => "help.cmd.show_unset",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Command> {
+        Command::ALL.iter().copied().find(|c| c.name() == name)
+    }
+
+    fn default_key(self) -> char {
+        match self {
+            Command::Quit => 'q',
+            Command::Up => 'k',
+            Command::Down => 'j',
+            Command::Edit => 'e',
+            Command::Add => 'a',
+            Command::Delete => 'd',
+            Command::Settings => 's',
+            Command::Trash => 't',
+            Command::Duplicate => 'c',
+            Command::Customized => 'f',
+            Command::Reset => 'r',
+            Command::ResetAll => 'R',
+            Command::NextSection => ']',
+            Command::PrevSection => '[',
+            Command::AddSection => 'S',
+            Command::Undo => 'u',
+            Command::Search => '/',
+            Command::NextMatch => 'n',
+            Command::PrevMatch => 'N',
+            Command::Help => '?',
+            Command::Backups => 'b',
+            Command::Increment => '+',
+            Command::Decrement => '-',
+            Command::MoveUp => 'K',
+            Command::MoveDown => 'J',
+            Command::Notifications => 'm',
+            Command::History => 'H',
+            Command::Compose => 'T',
+            Command::ProgressPreview => 'B',
+            Command::ImportDunst => 'I',
+            Command::PasteConfig => 'v',
+            Command::Themes => 'P',
+            Command::Copy => 'y',
+            Command::Modes => 'M',
+            Command::Write => 'w',
+            Command::Diff => 'D',
+            Command::Docs => 'i',
+            Command::Migrate => 'U',
+            Command::Sort => 'o',
+            Command::ApplySort => 'O',
+            Command::Validate => 'L',
+            Command::AutoReload => 'A',
+            Command::Log => 'E',
+            Command::CommandLine => ':',
+            Command::Mark => ' ',
+            Command::Paste => 'p',
+            Command::Top => 'g',
+            Command::Bottom => 'G',
+            Command::Snapshots => 'V',
+            Command::GitLog => 'C',
+            Command::ShowUnset => 'F',
+        }
+    }
+}
+
+/// Character bindings for `Command`s. Arrow keys and Enter always keep
+/// their meaning on top of whatever is configured here, and Left/Right or
+/// `h`/`l` (unless bound to something else) step numbers like `-`/`+`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Keymap {
+    bindings: Vec<(Command, char)>,
+}
+
+impl Default for Keymap {
+    fn default() -> Self {
+        Keymap { bindings: Command::ALL.iter().map(|c| (*c, c.default_key())).collect() }
+    }
+}
+
+impl Keymap {
+    pub fn key(&self, cmd: Command) -> char {
+        self.bindings.iter().find(|(c, _)| *c == cmd).map(|(_, k)| *k).unwrap_or(cmd.default_key())
+    }
+
+    pub fn bind(&mut self, cmd: Command, key: char) {
+        match self.bindings.iter_mut().find(|(c, _)| *c == cmd) {
+            Some(b) => b.1 = key,
+            None => self.bindings.push((cmd, key)),
+        }
+    }
+
+    /// The command bound to `key`, not counting the fixed keys `command`
+    /// adds on top.
+    pub fn bound_to(&self, key: char) -> Option<Command> {
+        self.bindings.iter().find(|(_, k)| *k == key).map(|(c, _)| *c)
+    }
+
+    /// Apply the `[keys]` bindings from a settings file on top of this
+    /// keymap. Keys the file moves away from a command are free for the
+    /// others, so two commands can swap keys in either order. A binding to a
+    /// key another command keeps is rejected and returned as `(command,
+    /// holder, key)`; the rejected command keeps its default key.
+    pub fn load(&mut self, binds: &[(Command, char)]) -> Vec<(Command, Command, char)> {
+        self.bindings.retain(|(c, _)| !binds.iter().any(|(b, _)| b == c));
+        let mut rejected = Vec::new();
+        for &(cmd, key) in binds {
+            match self.bound_to(key) {
+                Some(holder) if holder != cmd => rejected.push((cmd, holder, key)),
+                _ => self.bind(cmd, key),
+            }
+        }
+        // Commands left without a key go back to their defaults, taking
+        // them from whichever file binding claimed them.
+        while let Some(cmd) = Command::ALL.iter().copied().find(|c| !self.bindings.iter().any(|(b, _)| b == c)) {
+            let key = cmd.default_key();
+            if let Some(holder) = self.bound_to(key) {
+                self.bindings.retain(|(c, _)| *c != holder);
+                rejected.push((holder, cmd, key));
+            }
+            self.bindings.push((cmd, key));
+        }
+        rejected
+    }
+
+    pub fn command(&self, code: KeyCode) -> Option<Command> {
+        match code {
+            KeyCode::Up => Some(Command::Up),
+            KeyCode::Down => Some(Command::Down),
+            KeyCode::Enter => Some(Command::Edit),
+            KeyCode::Left => Some(Command::Decrement),
+            KeyCode::Right => Some(Command::Increment),
+            KeyCode::Char(ch) => self.bound_to(ch).or(match ch {
+                'h' => Some(Command::Decrement),
+                'l' => Some(Command::Increment),
+                _ => None,
+            }),
+            _ => None,
+        }
+    }
+}
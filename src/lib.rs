@@ -0,0 +1,46 @@
+//! Editing model behind the `mako-tui` terminal editor for mako
+//! notification daemon config files.
+//!
+//! The binary in `src/main.rs` is a thin frontend over these modules. The
+//! config parser and key model live in the `mako-config` crate and are
+//! re-exported here as `config` and `mako_config`.
+
+pub mod activity;
+pub mod app;
+pub mod browser;
+pub mod cli;
+pub mod clipboard;
+pub mod command_line;
+pub mod completions;
+pub use ::mako_config::check;
+pub use ::mako_config::config;
+pub mod daemon;
+pub mod diff;
+pub mod dunst;
+pub mod fonts;
+pub mod format;
+pub mod fuzzy;
+pub mod git;
+pub mod headless;
+pub mod i18n;
+pub mod icons;
+pub mod json;
+pub mod keymap;
+pub mod line_edit;
+pub mod lint;
+pub mod list_editor;
+pub mod logging;
+pub use ::mako_config::keys as mako_config;
+pub mod manpage;
+pub mod nix;
+pub mod notifications;
+pub mod plugin;
+pub mod settings;
+pub mod snapshots;
+pub mod snippet;
+pub mod status;
+pub mod suggest;
+pub mod swaync;
+pub mod themes;
+pub mod trash;
+pub mod ui;
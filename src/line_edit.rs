@@ -0,0 +1,153 @@
+//! Cursor movement and editing for the one-line prompts.
+//!
+//! The cursor is kept as the number of characters after it, so a prompt
+//! whose text is replaced wholesale (Tab completion, a picked directory)
+//! still has its cursor at the end. A character here is what the user sees
+//! as one (a grapheme cluster): `é` typed as `e` and a combining accent, or
+//! a flag emoji, moves and deletes as a whole.
+
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use unicode_segmentation::UnicodeSegmentation;
+
+/// Apply `key` to `input`, whose cursor sits `back` characters before the
+/// end. Returns whether it was an editing key; other keys are left for
+/// the prompt to handle.
+///
+/// Left/Right and Home/End move, Backspace and Delete remove around the
+/// cursor, Ctrl-W removes the word before it, Ctrl-U everything before it,
+/// and other characters are inserted at it.
+pub fn edit(input: &mut String, back: &mut usize, key: KeyEvent) -> bool {
+    let len = count(input);
+    *back = (*back).min(len);
+    let pos = len - *back;
+    let ctrl = key.modifiers.contains(KeyModifiers::CONTROL);
+    match key.code {
+        KeyCode::Left => *back = (*back + 1).min(len),
+        KeyCode::Right => *back = back.saturating_sub(1),
+        KeyCode::Home => *back = len,
+        KeyCode::End => *back = 0,
+        KeyCode::Backspace if pos > 0 => remove(input, pos - 1, pos),
+        KeyCode::Backspace => {}
+        KeyCode::Delete if *back > 0 => {
+            remove(input, pos, pos + 1);
+            *back -= 1;
+        }
+        KeyCode::Delete => {}
+        KeyCode::Char('w') if ctrl => {
+            let space: Vec<bool> = input.graphemes(true).take(pos).map(|g| g.chars().all(char::is_whitespace)).collect();
+            let mut start = pos;
+            while start > 0 && space[start - 1] {
+                start -= 1;
+            }
+            while start > 0 && !space[start - 1] {
+                start -= 1;
+            }
+            remove(input, start, pos);
+        }
+        KeyCode::Char('u') if ctrl => remove(input, 0, pos),
+        KeyCode::Char(_) if ctrl => return false,
+        KeyCode::Char(c) => input.insert(byte_index(input, pos), c),
+        _ => return false,
+    }
+    true
+}
+
+/// Insert `text` at the cursor.
+pub fn insert_str(input: &mut String, back: usize, text: &str) {
+    let len = count(input);
+    input.insert_str(byte_index(input, len - back.min(len)), text);
+}
+
+/// `input` split at the cursor.
+pub fn split(input: &str, back: usize) -> (&str, &str) {
+    let len = count(input);
+    input.split_at(byte_index(input, len - back.min(len)))
+}
+
+/// Remove characters `from..to`.
+fn remove(input: &mut String, from: usize, to: usize) {
+    let range = byte_index(input, from)..byte_index(input, to);
+    input.replace_range(range, "");
+}
+
+/// Remove the last character, for the prompts that only type at the end.
+pub fn pop(input: &mut String) {
+    let last = input.grapheme_indices(true).next_back().map_or(0, |(i, _)| i);
+    input.truncate(last);
+}
+
+/// The first character of `s`, if any.
+pub fn first(s: &str) -> Option<&str> {
+    s.graphemes(true).next()
+}
+
+/// How many characters `input` has.
+fn count(input: &str) -> usize {
+    input.graphemes(true).count()
+}
+
+/// The byte offset of character `n`.
+fn byte_index(input: &str, n: usize) -> usize {
+    input.grapheme_indices(true).nth(n).map_or(input.len(), |(i, _)| i)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn apply(input: &mut String, back: &mut usize, code: KeyCode, modifiers: KeyModifiers) {
+        edit(input, back, KeyEvent::new(code, modifiers));
+    }
+
+    #[test]
+    fn moves_inserts_and_deletes_at_the_cursor() {
+        let (mut input, mut back) = ("héllo".to_string(), 0);
+        apply(&mut input, &mut back, KeyCode::Left, KeyModifiers::NONE);
+        apply(&mut input, &mut back, KeyCode::Left, KeyModifiers::NONE);
+        apply(&mut input, &mut back, KeyCode::Char('X'), KeyModifiers::SHIFT);
+        assert_eq!((input.as_str(), back), ("hélXlo", 2));
+        apply(&mut input, &mut back, KeyCode::Home, KeyModifiers::NONE);
+        apply(&mut input, &mut back, KeyCode::Backspace, KeyModifiers::NONE);
+        apply(&mut input, &mut back, KeyCode::Delete, KeyModifiers::NONE);
+        assert_eq!((input.as_str(), back), ("élXlo", 5));
+        apply(&mut input, &mut back, KeyCode::Right, KeyModifiers::NONE);
+        assert_eq!(split(&input, back), ("é", "lXlo"));
+        apply(&mut input, &mut back, KeyCode::End, KeyModifiers::NONE);
+        insert_str(&mut input, back, "!");
+        assert_eq!((input.as_str(), back), ("élXlo!", 0));
+        assert!(!edit(&mut input, &mut back, KeyEvent::new(KeyCode::Char('o'), KeyModifiers::CONTROL)));
+        assert!(!edit(&mut input, &mut back, KeyEvent::new(KeyCode::Enter, KeyModifiers::NONE)));
+    }
+
+    #[test]
+    fn ctrl_w_and_ctrl_u_delete_before_the_cursor() {
+        let (mut input, mut back) = ("monospace bold 10".to_string(), 3);
+        apply(&mut input, &mut back, KeyCode::Char('w'), KeyModifiers::CONTROL);
+        assert_eq!(input, "monospace  10");
+        apply(&mut input, &mut back, KeyCode::Char('w'), KeyModifiers::CONTROL);
+        assert_eq!(input, " 10");
+        let (mut input, mut back) = ("monospace 10".to_string(), 2);
+        apply(&mut input, &mut back, KeyCode::Char('u'), KeyModifiers::CONTROL);
+        assert_eq!((input.as_str(), back), ("10", 2));
+    }
+
+    #[test]
+    fn combined_characters_move_and_delete_as_one() {
+        // `e` + combining acute, and a flag made of two regional indicators.
+        let (mut input, mut back) = ("Cafe\u{301} 🇫🇷".to_string(), 0);
+        apply(&mut input, &mut back, KeyCode::Backspace, KeyModifiers::NONE);
+        assert_eq!(input, "Cafe\u{301} ");
+        apply(&mut input, &mut back, KeyCode::Left, KeyModifiers::NONE);
+        apply(&mut input, &mut back, KeyCode::Left, KeyModifiers::NONE);
+        assert_eq!(split(&input, back), ("Caf", "e\u{301} "));
+        assert_eq!(first(split(&input, back).1), Some("e\u{301}"));
+        apply(&mut input, &mut back, KeyCode::Delete, KeyModifiers::NONE);
+        assert_eq!((input.as_str(), back), ("Caf ", 1));
+        pop(&mut input);
+        pop(&mut input);
+        assert_eq!(input, "Ca");
+        let (mut input, mut back) = ("字体 ヒラギノ角ゴ".to_string(), 0);
+        apply(&mut input, &mut back, KeyCode::Char('w'), KeyModifiers::CONTROL);
+        assert_eq!(input, "字体 ");
+    }
+}
@@ -0,0 +1,211 @@
+//! Warnings about keys that conflict with or cancel out each other. These
+//! are all valid configs as far as mako is concerned, just rarely what was
+//! meant.
+
+use crate::config::{Config, Param};
+use crate::fonts;
+use crate::i18n::trf;
+use crate::mako_config::{deprecated, is_path_list_key, since, supported, MakoConfig};
+use crate::settings::expand_home;
+
+/// Pairs of names that configure the same thing.
+const ALIASES: &[(&str, &str)] = &[("anchor", "anchor-point")];
+
+/// Keys that do nothing while `icons=0`.
+const ICON_KEYS: &[&str] = &["max-icon-size", "icon-path", "icon-location", "icon-border-radius"];
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Warning {
+    /// The keys involved, for marking them in the list.
+    pub keys: Vec<String>,
+    /// What is wrong and how to resolve it.
+    pub message: String,
+}
+
+impl Warning {
+    fn new(keys: &[&str], message: String) -> Self {
+        Warning { keys: keys.iter().map(|k| k.to_string()).collect(), message }
+    }
+}
+
+/// Every warning for section `section` of `cfg` (see `Config::params_of`),
+/// in a stable order. A criteria section is checked with the global values
+/// it doesn't override, but only warnings involving one of its own keys
+/// are reported.
+pub fn check(cfg: &Config, section: usize) -> Vec<Warning> {
+    let params = cfg.params_of(section);
+    // The value mako ends up using: the last one in the section, else the
+    // last global one.
+    let get = |key: &str| last(params, key).or_else(|| last(&cfg.params, key));
+    let typed = MakoConfig::from_params(&[cfg.params.as_slice(), params].concat());
+    let mut out = Vec::new();
+
+    let mut seen: Vec<&str> = Vec::new();
+    for p in params {
+        let count = params.iter().filter(|q| q.key == p.key).count();
+        if count > 1 && !seen.contains(&p.key.as_str()) {
+            seen.push(&p.key);
+            out.push(Warning::new(&[&p.key], trf("lint.duplicate", &[&p.key, &count, &get(&p.key).unwrap_or("")])));
+        }
+    }
+
+    let mut flagged: Vec<&str> = Vec::new();
+    for p in params {
+        let Some(new) = deprecated(&p.key) else { continue };
+        if flagged.contains(&p.key.as_str()) {
+            continue;
+        }
+        flagged.push(&p.key);
+        let message = match new {
+            Some(new) => trf("lint.renamed", &[&p.key, &new]),
+            None => trf("lint.dropped", &[&p.key]),
+        };
+        out.push(Warning::new(&[&p.key], message));
+    }
+
+    for (a, b) in ALIASES {
+        if let (Some(va), Some(vb)) = (get(a), get(b))
+            && va != vb
+        {
+            out.push(Warning::new(&[a, b], trf("lint.alias", &[a, b, &va, &vb])));
+        }
+    }
+
+    if typed.ignore_timeout == Some(true) {
+        let timeout = get("default-timeout");
+        if typed.default_timeout.unwrap_or(0) == 0 {
+            out.push(Warning::new(&["ignore-timeout", "default-timeout"], trf("lint.never_expire", &[&timeout.unwrap_or("0")])));
+        }
+    }
+
+    if typed.icons == Some(false) {
+        for key in ICON_KEYS.iter().filter(|k| get(k).is_some()) {
+            out.push(Warning::new(&["icons", key], trf("lint.icons_off", &[key])));
+        }
+    }
+
+    if typed.border_size == Some(0) && typed.border_color.is_some() {
+        out.push(Warning::new(&["border-size", "border-color"], trf("lint.no_border", &[])));
+    }
+
+    if section > 0 {
+        out.retain(|w| w.keys.iter().any(|k| params.iter().any(|p| p.key == *k)));
+    }
+    out
+}
+
+fn last<'a>(params: &'a [Param], key: &str) -> Option<&'a str> {
+    params.iter().rev().find(|p| p.key == key).map(|p| p.value.trim())
+}
+
+/// A warning for each `font` in `params` naming a family that isn't in
+/// `installed`: mako falls back to another font without saying so.
+pub fn missing_fonts(params: &[Param], installed: &[String]) -> Vec<Warning> {
+    params
+        .iter()
+        .filter(|p| p.key == "font")
+        .flat_map(|p| fonts::missing(&p.value, installed))
+        .map(|family| Warning::new(&["font"], trf("lint.font_missing", &[&family])))
+        .collect()
+}
+
+/// A warning for each key in `params` that mako `version` is too old to
+/// read; it refuses the whole file over one.
+pub fn unsupported(params: &[Param], version: (u32, u32)) -> Vec<Warning> {
+    let mut out: Vec<Warning> = Vec::new();
+    for p in params {
+        if let Some((major, minor)) = since(&p.key).filter(|_| !supported(&p.key, version))
+            && !out.iter().any(|w| w.keys[0] == p.key)
+        {
+            let needs = format!("{}.{}", major, minor);
+            let installed = format!("{}.{}", version.0, version.1);
+            out.push(Warning::new(&[&p.key], trf("lint.needs_version", &[&p.key, &needs, &installed])));
+        }
+    }
+    out
+}
+
+/// A warning for each directory in a path-list value (`icon-path`) of
+/// `params` that doesn't exist; mako skips it without saying so.
+pub fn missing_paths(params: &[Param]) -> Vec<Warning> {
+    params
+        .iter()
+        .filter(|p| is_path_list_key(&p.key))
+        .flat_map(|p| missing_dirs(&p.value).into_iter().map(move |dir| Warning::new(&[&p.key], trf("lint.path_missing", &[&dir]))))
+        .collect()
+}
+
+/// The entries of a colon-separated path list that aren't directories.
+pub fn missing_dirs(value: &str) -> Vec<String> {
+    value.split(':').map(str::trim).filter(|d| !d.is_empty() && !expand_home(d).is_dir()).map(String::from).collect()
+}
+
+/// The warnings that involve `key`.
+pub fn for_key<'a>(warnings: &'a [Warning], key: &'a str) -> impl Iterator<Item = &'a Warning> {
+    warnings.iter().filter(move |w| w.keys.iter().any(|k| k == key))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn keys_of(cfg: &str) -> Vec<Vec<String>> {
+        let Ok(cfg) = cfg.parse::<Config>();
+        check(&cfg, 0).into_iter().map(|w| w.keys).collect()
+    }
+
+    #[test]
+    fn flags_conflicting_and_redundant_keys() {
+        assert_eq!(keys_of("anchor=top-right\nanchor-point=bottom-left\n"), vec![vec!["anchor", "anchor-point"]]);
+        assert_eq!(keys_of("layout=normal\ntext-align=center\n"), vec![vec!["layout"], vec!["text-align"]]);
+        assert_eq!(keys_of("ignore-timeout=1\n"), vec![vec!["ignore-timeout", "default-timeout"]]);
+        assert_eq!(keys_of("icons=0\nmax-icon-size=48\n"), vec![vec!["icons", "max-icon-size"]]);
+        assert_eq!(keys_of("border-size=0px\nborder-color=#fff\n"), vec![vec!["border-size", "border-color"]]);
+        assert_eq!(keys_of("font=a\nfont=b\nfont=c\n"), vec![vec!["font"]]);
+    }
+
+    #[test]
+    fn fonts_that_arent_installed_are_flagged() {
+        let params = [Param::new("font", "Fira Code, monospace 10"), Param::new("width", "300")];
+        let warnings = missing_fonts(&params, &["DejaVu Sans".to_string()]);
+        assert_eq!(warnings.len(), 1);
+        assert!(warnings[0].message.contains("Fira Code"));
+        assert!(missing_fonts(&params, &["fira code".to_string()]).is_empty());
+    }
+
+    #[test]
+    fn icon_paths_that_dont_exist_are_flagged() {
+        let dir = std::env::temp_dir();
+        let value = format!("{}:/no/such/mako-tui-dir:", dir.display());
+        assert_eq!(missing_dirs(&value), ["/no/such/mako-tui-dir"]);
+        let warnings = missing_paths(&[Param::new("icon-path", value)]);
+        assert_eq!(warnings.len(), 1);
+        assert!(warnings[0].message.contains("/no/such/mako-tui-dir"));
+    }
+
+    #[test]
+    fn keys_newer_than_the_installed_mako_are_flagged() {
+        let params = [Param::new("include", "~/a"), Param::new("include", "~/b"), Param::new("icon-border-radius", "4"), Param::new("font", "x")];
+        let keys = |version| unsupported(&params, version).into_iter().map(|w| w.keys).collect::<Vec<_>>();
+        assert_eq!(keys((1, 6)), vec![vec!["include"], vec!["icon-border-radius"]]);
+        assert_eq!(keys((1, 7)), vec![vec!["icon-border-radius"]]);
+        assert!(keys((1, 10)).is_empty());
+        assert!(unsupported(&params, (1, 6))[0].message.contains("1.7"));
+    }
+
+    #[test]
+    fn consistent_configs_are_quiet() {
+        assert!(keys_of("anchor=top-right\nanchor-point=top-right\n").is_empty());
+        assert!(keys_of("ignore-timeout=1\ndefault-timeout=5000\n").is_empty());
+        assert!(keys_of("icons=1\nmax-icon-size=48\nborder-size=2\nborder-color=#fff\n").is_empty());
+    }
+
+    #[test]
+    fn sections_are_checked_against_inherited_values() {
+        let Ok(cfg) = "icons=0\nmax-icon-size=48\n[app-name=a]\nicon-location=top\n[app-name=b]\nfont=x\n".parse::<Config>();
+        let keys = |n| check(&cfg, n).into_iter().map(|w| w.keys).collect::<Vec<_>>();
+        assert_eq!(keys(1), vec![vec!["icons", "icon-location"]]);
+        // The global warning isn't repeated in every section.
+        assert!(keys(2).is_empty());
+    }
+}
@@ -0,0 +1,134 @@
+//! Editing a list-valued key such as `icon-path` one entry per row, so a
+//! long `a:b:c` value never has to be edited by hand.
+
+/// A list value split into its entries, with the highlighted row and the
+/// entry being typed, if any.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ListEditor {
+    pub items: Vec<String>,
+    pub selected: usize,
+    /// `:` for `icon-path`, `,` for `group-by`.
+    pub separator: char,
+    /// The text of the entry being typed.
+    pub input: Option<String>,
+    /// Whether `input` becomes a new row after `selected` rather than
+    /// replacing it.
+    pub adding: bool,
+}
+
+impl ListEditor {
+    /// Split `value` on `separator`, dropping empty entries.
+    pub fn new(value: &str, separator: char) -> Self {
+        let items = value.split(separator).map(str::trim).filter(|e| !e.is_empty()).map(String::from).collect();
+        ListEditor { items, selected: 0, separator, input: None, adding: false }
+    }
+
+    /// The entries joined back into one value.
+    pub fn value(&self) -> String {
+        self.items.join(&self.separator.to_string())
+    }
+
+    pub fn down(&mut self) {
+        self.selected = (self.selected + 1).min(self.items.len().saturating_sub(1));
+    }
+
+    pub fn up(&mut self) {
+        self.selected = self.selected.saturating_sub(1);
+    }
+
+    /// Start typing a new entry.
+    pub fn add(&mut self) {
+        self.input = Some(String::new());
+        self.adding = true;
+    }
+
+    /// Start retyping the highlighted entry.
+    pub fn edit(&mut self) {
+        if let Some(item) = self.items.get(self.selected) {
+            self.input = Some(item.clone());
+            self.adding = false;
+        }
+    }
+
+    /// Store the entry being typed and highlight it. An empty entry is
+    /// dropped: a new one isn't added and an edited one is removed.
+    pub fn commit(&mut self) {
+        let Some(input) = self.input.take() else { return };
+        let entry = input.trim().to_string();
+        match (entry.is_empty(), self.adding) {
+            (true, true) => {}
+            (true, false) => self.remove(),
+            (false, true) => {
+                let at = if self.items.is_empty() { 0 } else { self.selected + 1 };
+                self.items.insert(at, entry);
+                self.selected = at;
+            }
+            (false, false) => self.items[self.selected] = entry,
+        }
+    }
+
+    /// Stop typing without changing the list.
+    pub fn cancel(&mut self) {
+        self.input = None;
+    }
+
+    /// Remove the highlighted entry.
+    pub fn remove(&mut self) {
+        if self.selected < self.items.len() {
+            self.items.remove(self.selected);
+            self.selected = self.selected.min(self.items.len().saturating_sub(1));
+        }
+    }
+
+    /// Move the highlighted entry `by` rows, keeping it highlighted. Stops
+    /// at either end.
+    pub fn move_by(&mut self, by: isize) {
+        let to = self.selected as isize + by;
+        if self.selected < self.items.len() && (0..self.items.len() as isize).contains(&to) {
+            self.items.swap(self.selected, to as usize);
+            self.selected = to as usize;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn entries_are_added_removed_and_moved() {
+        let mut list = ListEditor::new("/a: /b::/c", ':');
+        assert_eq!(list.items, ["/a", "/b", "/c"]);
+        list.add();
+        list.input = Some(" /new ".to_string());
+        list.commit();
+        assert_eq!(list.value(), "/a:/new:/b:/c");
+        assert_eq!(list.selected, 1);
+        list.move_by(-1);
+        list.move_by(-1);
+        assert_eq!(list.value(), "/new:/a:/b:/c");
+        list.down();
+        list.remove();
+        assert_eq!(list.value(), "/new:/b:/c");
+        list.edit();
+        list.input = Some(String::new());
+        list.commit();
+        assert_eq!(list.value(), "/new:/c");
+    }
+
+    #[test]
+    fn an_empty_list_takes_its_first_entry() {
+        let mut list = ListEditor::new("", ',');
+        list.edit();
+        list.remove();
+        list.move_by(1);
+        assert_eq!(list.input, None);
+        list.add();
+        list.input = Some("app-name".to_string());
+        list.commit();
+        list.add();
+        list.input = Some("summary".to_string());
+        list.commit();
+        assert_eq!(list.value(), "app-name,summary");
+    }
+}
@@ -0,0 +1,133 @@
+//! Logging to a file with `--log-level`, through the `log` facade: the
+//! commands the editor runs and how they ended, problems in the config it
+//! loads, and panics, so a bug report about a weird reload failure can
+//! come with what actually happened.
+
+use std::{
+    fs::{self, File, OpenOptions},
+    io::{self, Write},
+    panic,
+    path::PathBuf,
+    process::{Command, Output},
+    sync::Mutex,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use log::{LevelFilter, Log, Metadata, Record};
+
+use crate::settings::state_dir;
+
+/// A log bigger than this is started over rather than appended to.
+const MAX_SIZE: u64 = 1 << 20;
+
+/// Where `init` writes: `$XDG_STATE_HOME/mako-editor/mako-tui.log`.
+pub fn log_file() -> io::Result<PathBuf> {
+    Ok(state_dir()?.join("mako-tui.log"))
+}
+
+struct FileLogger {
+    file: Mutex<File>,
+    level: LevelFilter,
+}
+
+impl Log for FileLogger {
+    fn enabled(&self, metadata: &Metadata) -> bool {
+        metadata.level() <= self.level
+    }
+
+    fn log(&self, record: &Record) {
+        if !self.enabled(record.metadata()) {
+            return;
+        }
+        if let Ok(mut file) = self.file.lock() {
+            let at = SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default();
+            let _ = writeln!(file, "{} {:<5} {}: {}", timestamp(at.as_millis()), record.level(), record.target(), record.args());
+        }
+    }
+
+    fn flush(&self) {
+        if let Ok(mut file) = self.file.lock() {
+            let _ = file.flush();
+        }
+    }
+}
+
+/// Log everything at `level` and above to `log_file()`, appending to
+/// earlier sessions unless that grew past `MAX_SIZE`. Panics are logged
+/// before the panic hook already in place runs.
+pub fn init(level: LevelFilter) -> io::Result<PathBuf> {
+    let path = log_file()?;
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)?;
+    }
+    let too_big = fs::metadata(&path).is_ok_and(|m| m.len() > MAX_SIZE);
+    let file = OpenOptions::new().create(true).append(!too_big).write(true).truncate(too_big).open(&path)?;
+    log::set_boxed_logger(Box::new(FileLogger { file: Mutex::new(file), level })).map_err(io::Error::other)?;
+    log::set_max_level(level);
+    let hook = panic::take_hook();
+    panic::set_hook(Box::new(move |info| {
+        log::error!(target: "panic", "{}", info);
+        log::logger().flush();
+        hook(info);
+    }));
+    log::info!(target: "mako_tui", "mako-tui {} started, args={:?}", env!("CARGO_PKG_VERSION"), std::env::args().skip(1).collect::<Vec<_>>());
+    Ok(path)
+}
+
+/// `cmd.output()`, logging the command line and how it ended: its exit
+/// status, and its stderr when it failed.
+pub fn output(cmd: &mut Command) -> io::Result<Output> {
+    let line = format!("{:?}", cmd);
+    let result = cmd.output();
+    match &result {
+        Ok(output) if output.status.success() => log::debug!(target: "command", "{} -> {}", line, output.status),
+        Ok(output) => log::warn!(
+            target: "command",
+            "{} -> {} stderr={:?}",
+            line,
+            output.status,
+            String::from_utf8_lossy(&output.stderr).trim()
+        ),
+        Err(e) => log::warn!(target: "command", "{} -> failed to start: {}", line, e),
+    }
+    result
+}
+
+/// `millis` since the epoch as an RFC 3339 UTC time, e.g.
+/// `2024-03-01T12:00:00.250Z`.
+pub fn timestamp(millis: u128) -> String {
+    let secs = (millis / 1000) as i64;
+    let (days, rest) = (secs.div_euclid(86400), secs.rem_euclid(86400));
+    // Days since the epoch to a civil date (Howard Hinnant's algorithm).
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z.rem_euclid(146_097);
+    let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + i64::from(month <= 2);
+    format!(
+        "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}.{:03}Z",
+        year,
+        month,
+        day,
+        rest / 3600,
+        rest % 3600 / 60,
+        rest % 60,
+        millis % 1000
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn timestamps_are_utc_rfc3339() {
+        assert_eq!(timestamp(0), "1970-01-01T00:00:00.000Z");
+        assert_eq!(timestamp(951_782_400_250), "2000-02-29T00:00:00.250Z");
+        assert_eq!(timestamp(1_709_294_400_000), "2024-03-01T12:00:00.000Z");
+    }
+}
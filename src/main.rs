@@ -1,454 +1,705 @@
-mod config;
-mod mako_config;
-use mako_config::{known_keys, allowed_values};
+mod terminal;
 
-use config::{Config, Param};
+use terminal::{Ticker, Wake};
 
-use crossterm::{
-    event::{self, Event as CEvent, KeyCode},
-    execute,
-    terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
-};
+use mako_tui::activity::Kind;
+use mako_tui::app::{Action, App};
+use mako_tui::check;
+use mako_tui::cli;
+use mako_tui::clipboard;
+use mako_tui::completions;
+use mako_tui::config::{Config, Param};
+use mako_tui::daemon::{self, MakoCtl};
+use mako_tui::fonts;
+use mako_tui::git;
+use mako_tui::logging;
+use mako_tui::manpage;
+use mako_tui::nix;
+use mako_tui::plugin::Plugins;
+use mako_tui::settings::Settings;
+use mako_tui::snapshots;
+use mako_tui::status::{self, Status};
+use mako_tui::trash::Trash;
+use mako_tui::ui;
+
+use crossterm::event::Event as CEvent;
+use log::LevelFilter;
 use ratatui::{
-    backend::CrosstermBackend,
-    layout::{Constraint, Direction, Layout},
-    style::{Color, Modifier, Style},
-    text::{Span, Line},
-    widgets::{Block, Borders, List, ListItem, ListState, Paragraph},
+    backend::{Backend, CrosstermBackend},
+    layout::Rect,
     Terminal,
 };
 
-use std::io;
-use std::time::Duration;
+use mako_tui::headless;
+use mako_tui::i18n::{tr, trf};
 
-enum Mode {
-    Normal,
-    EditValue { idx: usize, input: String },
-    AddKey { input: String },
-    AddCustomKey { input: String },
-    AddValue { key: String, input: String },
-    ConfirmDelete { idx: usize },
-}
+use std::{
+    env, fs, io,
+    path::PathBuf,
+    process,
+    sync::mpsc::{Receiver, RecvTimeoutError, Sender},
+    thread,
+    time::{Duration, Instant},
+};
 
-fn main() -> Result<(), io::Error> {
-    // Terminal setup
-    enable_raw_mode()?;
-    let mut stdout = io::stdout();
-    execute!(stdout, EnterAlternateScreen)?;
-    let backend = CrosstermBackend::new(stdout);
-    let mut terminal = Terminal::new(backend)?;
+/// How long each frame of the reload spinner stays up.
+const SPINNER_FRAME: Duration = Duration::from_millis(100);
 
-    // Load config (or start empty)
-    let mut cfg = Config::load().unwrap_or_else(|_| Config { params: vec![] });
+fn main() -> Result<(), io::Error> {
+    let (settings, settings_errors) = Settings::load();
 
-    // If the file didn't exist and params empty, seed with a couple helpful keys
-    if cfg.params.is_empty() {
-        cfg.params.push(Param::new("font", "monospace 10"));
-        cfg.params.push(Param::new("background-color", "#1d1f21"));
+    let mut args: Vec<String> = env::args().skip(1).collect();
+    // `--config PATH`, `--log-level LEVEL` and `--compact` can come before
+    // any other option.
+    let mut config_arg = None;
+    let mut compact = false;
+    loop {
+        match args.first().map(String::as_str) {
+            Some("--compact") => {
+                compact = true;
+                args.remove(0);
+            }
+            Some("--config") if args.len() >= 2 => {
+                config_arg = Some(PathBuf::from(args.remove(1)));
+                args.remove(0);
+            }
+            Some("--log-level") if let Some(Ok(level)) = args.get(1).map(|l| l.parse::<LevelFilter>()) => {
+                args.drain(..2);
+                if let Err(e) = logging::init(level) {
+                    match logging::log_file() {
+                        Ok(file) => eprintln!("{}", trf("cli.write_failed", &[&file.display(), &e])),
+                        Err(_) => eprintln!("{}", e),
+                    }
+                }
+            }
+            Some("--config" | "--log-level") => {
+                eprintln!("{}", tr("cli.usage"));
+                process::exit(2);
+            }
+            _ => break,
+        }
     }
 
-    // Try to save initial state so file exists and attempt initial reload
-    let _ = cfg.save();
-    let mut last_reload: Option<(bool, String)> = match cfg.reload() {
-        Ok(msg) => Some((true, msg)),
-        Err(err) => Some((false, err)),
+    // Load config (or start empty): the one asked for, else the default
+    // profile's file, else mako's own.
+    let path = match config_arg.or_else(|| settings.profile_path()).map_or_else(Config::config_path, Ok) {
+        Ok(path) => path,
+        Err(e) => {
+            eprintln!("{}", trf("cli.no_config_path", &[&e]));
+            process::exit(2);
+        }
     };
-
-    let mut list_state = ListState::default();
-    if !cfg.params.is_empty() {
-        list_state.select(Some(0));
+    let mut cfg = Config::load_from(path.clone()).unwrap_or_else(|_| Config::new(path));
+    cfg.palette = settings.palette.clone();
+    if log::log_enabled!(target: "config", log::Level::Warn) {
+        for problem in fs::read_to_string(&cfg.path).map(|text| check::check(&text)).unwrap_or_default() {
+            log::warn!(target: "config", "{}:{}", cfg.path.display(), problem);
+        }
     }
 
-    // separate list state for the known-keys chooser used when adding a key
-    let mut key_list_state = ListState::default();
-    key_list_state.select(Some(0));
+    match args.first().map(String::as_str) {
+        None => {}
+        Some("--headless") => run_headless(cfg, settings, compact, &args[1..]),
+        Some(arg) if cli::is_subcommand(arg) => run_subcommand(cfg, settings, &args),
+        Some("validate") if args.len() <= 2 => run_validate(args.get(1).map(PathBuf::from).unwrap_or(cfg.path)),
+        Some("--refresh-keys") if args.len() <= 2 => run_refresh_keys(args.get(1).map(PathBuf::from)),
+        Some("completions") => match completions::run(&cfg, &args[1..]) {
+            Ok(out) => {
+                print!("{}", out);
+                return Ok(());
+            }
+            Err(e) => {
+                eprintln!("{}", e);
+                process::exit(2);
+            }
+        },
+        Some("--export-minimal") if args.len() == 1 => {
+            // Only what differs from mako's defaults, for sharing or as a
+            // starting point.
+            print!("{}", cfg.without_defaults());
+            return Ok(());
+        }
+        Some("--export-nix") if args.len() == 1 => {
+            print!("{}", nix::export(&cfg));
+            return Ok(());
+        }
+        Some(_) => {
+            eprintln!("{}", tr("cli.usage"));
+            process::exit(2);
+        }
+    }
 
-    let mut mode = Mode::Normal;
+    // If the file didn't exist, seed it with a couple helpful keys and save
+    // it so it exists. An existing file is left alone: it may hold nothing
+    // but comments, e.g. after a reset to defaults.
+    if !cfg.path.exists() {
+        cfg.params.push(Param::new("font", "monospace 10"));
+        cfg.params.push(Param::new("background-color", "#1d1f21"));
+        let _ = cfg.save();
+    }
+    let mut app = App::new(cfg);
+    app.plugins = Plugins::load();
+    app.settings = settings;
+    for e in &settings_errors {
+        log::warn!(target: "settings", "{}", e);
+    }
+    app.message = settings_errors.into_iter().next().map(Err);
+    app.compact = compact;
+    app.trash = Trash::load();
+    app.fonts = fonts::families().ok();
+    app.mako_version = daemon::mako_version().ok();
+    // Terminal setup
+    terminal::install_panic_hook();
+    terminal::install_signal_handlers()?;
+    terminal::setup(app.settings.mouse)?;
+    let backend = CrosstermBackend::new(io::stdout());
+    let mut terminal = Terminal::new(backend)?;
 
-    loop {
-        terminal.draw(|f| {
-            let size = f.size();
+    let (wake, events, ticker) = terminal::spawn_events(status::REFRESH);
+    if app.settings.auto_reload {
+        start_reload(&mut app, &wake, None, false);
+    }
+    let result = run(&mut terminal, &mut app, &events, &wake, &ticker);
 
-            let chunks = Layout::default()
-                .direction(Direction::Vertical)
-                .margin(1)
-                .constraints(
-                    [
-                        Constraint::Length(3),
-                        Constraint::Min(4),
-                        Constraint::Length(3),
-                    ]
-                    .as_ref(),
-                )
-                .split(size);
+    // Cleanup
+    terminal::restore();
+    result
+}
 
-            // Header
-            let header = Paragraph::new(Line::from(vec![
-                Span::styled(" Mako Config Editor ", Style::default().add_modifier(Modifier::BOLD)),
-                Span::raw(" — "),
-                Span::styled("↑↓/j/k: navigate ", Style::default().fg(Color::Gray)),
-                Span::raw(" "),
-                Span::styled("e/Enter: edit ", Style::default().fg(Color::Gray)),
-                Span::raw(" "),
-                Span::styled("a: add ", Style::default().fg(Color::Gray)),
-                Span::raw(" "),
-                Span::styled("d: delete ", Style::default().fg(Color::Gray)),
-                Span::raw(" "),
-                Span::styled("q: quit", Style::default().fg(Color::Gray)),
-            ]))
-            .block(Block::default().borders(Borders::ALL));
-            f.render_widget(header, chunks[0]);
+/// Run `makoctl reload` on a background thread, so a slow or hung makoctl
+/// doesn't freeze the editor; its result arrives as `Wake::Reloaded`, with
+/// `previous` and `quit` as given here. The footer shows a spinner until
+/// then.
+fn start_reload(app: &mut App, wake: &Sender<Wake>, previous: Option<String>, quit: bool) {
+    app.reloading += 1;
+    let wake = wake.clone();
+    thread::spawn(move || {
+        let _ = wake.send(Wake::Reloaded { result: MakoCtl::reload(), previous, quit });
+    });
+}
 
-            // Params list OR known-keys chooser when adding a key
-            // Build either the params list (normal) or a filtered known-keys list when adding
-            let (list, active_is_keys) = match &mode {
-                Mode::AddKey { input } => {
-                    // build filtered list from known keys using input as a substring filter (case-insensitive)
-                    let filter = input.to_lowercase();
-                    let mut filtered: Vec<(String, String)> = Vec::new();
-                    for (k, d) in known_keys() {
-                        if filter.is_empty() || k.to_lowercase().contains(&filter) || d.to_lowercase().contains(&filter) {
-                            filtered.push((k.to_string(), d.to_string()));
-                        }
-                    }
-                    let items: Vec<ListItem> = filtered.iter().map(|(k, desc)| {
-                        let line = Line::from(vec![Span::styled(k.clone(), Style::default().add_modifier(Modifier::BOLD)), Span::raw(" - "), Span::raw(desc.clone())]);
-                        ListItem::new(line)
-                    }).collect();
-                    (
-                        List::new(items)
-                            .block(Block::default().title("Known keys").borders(Borders::ALL))
-                            .highlight_style(Style::default().bg(Color::Yellow).fg(Color::Black).add_modifier(Modifier::BOLD)),
-                        true,
-                    )
-                }
-                _ => {
-                    let items: Vec<ListItem> = cfg
-                        .params
-                        .iter()
-                        .map(|p| {
-                            let left = format!("{:20}", p.key);
-                            let right = p.value.clone();
-                            let line = Line::from(vec![
-                                Span::raw(left),
-                                Span::raw(" = "),
-                                Span::styled(right, Style::default().add_modifier(Modifier::BOLD)),
-                            ]);
-                            ListItem::new(line)
-                        })
-                        .collect();
+/// Run `first`, such as a dismiss, then `makoctl list` on a background
+/// thread, so a hung makoctl doesn't freeze the editor; the list and the
+/// error `first` returned arrive as `Wake::Listed`. `listing` counts the
+/// lists still running.
+fn start_list(wake: &Sender<Wake>, listing: &mut usize, first: impl FnOnce() -> Option<String> + Send + 'static) {
+    *listing += 1;
+    let wake = wake.clone();
+    thread::spawn(move || {
+        let failed = first();
+        wake.send(Wake::Listed { result: MakoCtl::list(), failed })
+    });
+}
 
-                    (
-                        List::new(items)
-                            .block(Block::default().title("Parameters").borders(Borders::ALL))
-                            .highlight_style(
-                                Style::default()
-                                    .bg(Color::Yellow)
-                                    .fg(Color::Black)
-                                    .add_modifier(Modifier::BOLD),
-                            ),
-                        false,
-                    )
-                }
-            };
+/// `start_list` for `makoctl history`, whose result arrives as
+/// `Wake::History`.
+fn start_history(wake: &Sender<Wake>, listing: &mut usize, first: impl FnOnce() -> Option<String> + Send + 'static) {
+    *listing += 1;
+    let wake = wake.clone();
+    thread::spawn(move || {
+        let failed = first();
+        wake.send(Wake::History { result: MakoCtl::history(), failed })
+    });
+}
 
-            if active_is_keys {
-                // Ensure key_list_state selected index is within bounds
-                // If out-of-bounds, clamp to 0
-                // (List widget will ignore invalid selections but we keep state sane)
-                f.render_stateful_widget(list, chunks[1], &mut key_list_state);
-            } else {
-                f.render_stateful_widget(list, chunks[1], &mut list_state);
+/// Take the `result` of a reload after a save that replaced `previous`,
+/// the file as it was. If mako rejected the new file, `previous` goes back
+/// on disk and mako is reloaded on it, so it never stays stuck on a broken
+/// config; the edits stay in memory, unsaved, to be fixed. Returns false
+/// when mako rejected the new file.
+fn finish_reload(app: &mut App, result: Result<String, String>, previous: Option<String>) -> bool {
+    app.reloading = app.reloading.saturating_sub(1);
+    app.activity.push_result(Kind::Reload, &result, tr("log.reloaded"));
+    // With mako not running, the reload failing says nothing about the file.
+    if let (Err(e), Some(previous)) = (&result, previous)
+        && app.status.daemon_running != Some(false)
+    {
+        match app.cfg.roll_back(&previous) {
+            Ok(()) => {
+                thread::spawn(|| {
+                    let _ = MakoCtl::reload();
+                });
+                app.saved_text = previous;
+                app.last_reload = Some(Err(trf("reload.rolled_back", &[e])));
+                app.activity.push(Kind::Save, false, tr("log.rolled_back"));
+            }
+            Err(rollback) => {
+                app.last_reload = Some(Err(trf("reload.rollback_failed", &[e, &rollback])));
+                app.activity.push(Kind::Save, true, trf("log.rollback_failed", &[&rollback]));
             }
+        }
+        return false;
+    }
+    app.last_reload = Some(result);
+    true
+}
 
-            // Footer area depends on mode and reload status
-            let footer = match &mode {
-                Mode::Normal => {
-                    let selected = list_state.selected().map(|i| format!("Selected: {} = {}", cfg.params[i].key, cfg.params[i].value)).unwrap_or_else(|| "No selection".to_string());
+/// When the changes `Action::LiveApply` holds back are due to be written,
+/// and when they last were.
+#[derive(Default)]
+struct LiveApply {
+    due: Option<Instant>,
+    written: Option<Instant>,
+}
 
-                    // build status spans
-                    let mut spans = vec![
-                        Span::raw(selected),
-                        Span::raw("    "),
-                        Span::styled("Press 'a' to add, 'e' to edit, 'd' to delete.", Style::default().fg(Color::Gray)),
-                    ];
+/// Log a save that worked and, with `snapshots` on, keep a snapshot of
+/// the file as written. With `git_commit` on, it is also committed with
+/// `message` in the background, in case a hook takes its time.
+fn saved(app: &mut App, wake: &Sender<Wake>, what: String, message: String) {
+    app.log_saved(what);
+    if app.settings.snapshots
+        && let Err(e) = snapshots::record(&app.cfg.path)
+    {
+        app.activity.push(Kind::Save, true, trf("log.snapshot_failed", &[&e]));
+    }
+    if app.settings.git_commit {
+        let (wake, path, repo) = (wake.clone(), app.cfg.path.clone(), app.settings.git_repo.clone());
+        thread::spawn(move || {
+            let result = git::commit(&path, repo.as_deref(), &message);
+            wake.send(Wake::Committed { message, result })
+        });
+    }
+}
 
-                    if let Some((ok, msg)) = &last_reload {
-                        if *ok {
-                            spans.push(Span::raw("    "));
-                            spans.push(Span::styled(format!("Reload OK: {}", msg), Style::default().fg(Color::Green)));
-                        } else {
-                            spans.push(Span::raw("    "));
-                            spans.push(Span::styled(format!("Reload failed: {}", msg), Style::default().fg(Color::Red)));
-                        }
-                    }
+/// Run the plugins' post-save actions for `changes` on a background
+/// thread, in case a hook takes its time; what failed arrives as
+/// `Wake::Hooked`.
+fn start_hooks(app: &App, wake: &Sender<Wake>, changes: Vec<(String, String)>) {
+    let (wake, plugins, cfg) = (wake.clone(), app.plugins.clone(), app.cfg.clone());
+    thread::spawn(move || wake.send(Wake::Hooked(plugins.after_save(&cfg, &changes))));
+}
 
-                    Paragraph::new(Line::from(spans)).block(Block::default().borders(Borders::ALL))
+/// Save the config, keeping `keep` backups, and report the changes in
+/// `App::pending` as `Action::Save` does for one. Returns false, with the
+/// changes still pending, if saving failed.
+fn write_pending(app: &mut App, wake: &Sender<Wake>, keep: usize) -> bool {
+    let _ = app.trash.save();
+    let pending = std::mem::take(&mut app.pending);
+    let previous = fs::read_to_string(&app.cfg.path).ok();
+    let saved = match app.cfg.save_with_backups(keep) {
+        Ok(_) => {
+            app.mark_saved();
+            saved(app, wake, trf("log.saved_changes", &[&pending.len()]), git::message(&pending));
+            start_hooks(app, wake, pending.clone());
+            if app.settings.auto_reload && app.settings.notify_on_save && !pending.is_empty() {
+                let wake = wake.clone();
+                thread::spawn(move || wake.send(Wake::Notified(daemon::notify_changes(&pending))));
+            }
+            if app.settings.auto_reload {
+                start_reload(app, wake, previous, false);
+            }
+            true
+        }
+        Err(e) => {
+            app.pending = pending;
+            app.message = Some(Err(trf("footer.save_failed", &[&e])));
+            app.activity.push(Kind::Save, true, trf("footer.save_failed", &[&e]));
+            false
+        }
+    };
+    app.status = Status::probe(&app.cfg.path);
+    saved
+}
+
+fn run<B: Backend>(
+    terminal: &mut Terminal<B>,
+    app: &mut App,
+    events: &Receiver<Wake>,
+    wake: &Sender<Wake>,
+    ticker: &Ticker,
+) -> io::Result<()> {
+    let disconnected = || io::Error::new(io::ErrorKind::BrokenPipe, "input thread stopped");
+    let mut shown_image = None;
+    let mut live = LiveApply::default();
+    app.status = Status::probe(&app.cfg.path);
+    // Whether the screen is out of date, and the file age the header last
+    // showed.
+    let (mut dirty, mut shown_age) = (true, None);
+    let (mut listing, mut listing_history) = (0, 0);
+    loop {
+        if live.due.is_some_and(|due| due <= Instant::now()) {
+            // Only the first write of a burst backs the file up, so holding
+            // + doesn't push every real backup out.
+            let interval = Duration::from_millis(app.settings.live_apply_ms);
+            let burst = live.written.is_some_and(|at| at.elapsed() <= interval * 2);
+            write_pending(app, wake, if burst { 0 } else { app.settings.backup_retention });
+            live = LiveApply { due: None, written: Some(Instant::now()) };
+            dirty = true;
+        }
+        if dirty {
+            terminal.draw(|f| ui::draw(f, app))?;
+            terminal::sync_image(&mut shown_image, &app.image_preview)?;
+            shown_age = app.status.age();
+        }
+
+        // Sleep until there is input or it is time to refresh the status
+        // (and the notifications view, which follows mako on its own),
+        // waking up to turn the spinner while a reload is running and for
+        // a pending live write. Nothing ticks while none of that is on
+        // screen.
+        ticker.set(app.wants_refresh());
+        let spin = (app.reloading > 0).then_some(SPINNER_FRAME);
+        let timeout = spin.into_iter().chain(live.due.map(|due| due.saturating_duration_since(Instant::now()))).min();
+        let next = match timeout {
+            Some(timeout) => match events.recv_timeout(timeout) {
+                Ok(next) => next,
+                Err(RecvTimeoutError::Timeout) => {
+                    app.spinner = app.spinner.wrapping_add(1);
+                    dirty = true;
+                    continue;
                 }
-                Mode::EditValue { idx, input } => {
-                    let key = if *idx < cfg.params.len() { cfg.params[*idx].key.clone() } else { "".to_string() };
-                    let mut spans = vec![
-                        Span::raw("Editing value (Enter=save, Esc=cancel): "),
-                        Span::styled(input.clone(), Style::default().add_modifier(Modifier::BOLD)),
-                    ];
-                    if let Some(vals) = allowed_values(&key) {
-                        spans.push(Span::raw("    "));
-                        spans.push(Span::styled(format!("Allowed: {}", vals.join(" | ")), Style::default().fg(Color::Gray)));
-                    }
-                    Paragraph::new(Line::from(spans)).block(Block::default().borders(Borders::ALL))
+                Err(RecvTimeoutError::Disconnected) => return Err(disconnected()),
+            },
+            None => events.recv().map_err(|_| disconnected())?,
+        };
+        dirty = true;
+        let ev = match next {
+            Wake::Input(ev) => ev?,
+            Wake::Reloaded { result, previous, quit } => {
+                if finish_reload(app, result, previous) && quit {
+                    return Ok(());
                 }
-                Mode::AddKey { input } => {
-                    Paragraph::new(Line::from(vec![
-                        Span::raw("New key (Enter=next, Esc=cancel). Use ↑/↓ to pick, or type to filter: "),
-                        Span::styled(input.clone(), Style::default().add_modifier(Modifier::BOLD)),
-                    ])).block(Block::default().borders(Borders::ALL))
+                continue;
+            }
+            Wake::Notified(result) => {
+                app.activity.push_result(Kind::Notify, &result.map(|()| String::new()), tr("log.notified"));
+                continue;
+            }
+            Wake::Sent(result) => {
+                let result = result.map(|()| String::new());
+                app.activity.push_result(Kind::Notify, &result, tr("footer.test_sent"));
+                app.message = Some(result.map(|_| tr("footer.test_sent").to_string()));
+                continue;
+            }
+            Wake::Progressed(result) => {
+                let result = result.map(|()| String::new());
+                app.activity.push_result(Kind::Notify, &result, tr("log.progress_sent"));
+                app.message = Some(result.map(|_| tr("log.progress_sent").to_string()));
+                continue;
+            }
+            Wake::Listed { result, failed } => {
+                listing -= 1;
+                dirty = app.set_notifications(result) || failed.is_some();
+                if let Some(e) = failed {
+                    app.message = Some(Err(e));
                 }
-                Mode::AddCustomKey { input } => {
-                    Paragraph::new(Line::from(vec![
-                        Span::raw("Custom key name (Enter=next, Esc=cancel): "),
-                        Span::styled(input.clone(), Style::default().add_modifier(Modifier::BOLD)),
-                    ])).block(Block::default().borders(Borders::ALL))
+                continue;
+            }
+            Wake::History { result, failed } => {
+                listing_history -= 1;
+                dirty = app.set_history(result) || failed.is_some();
+                if let Some(e) = failed {
+                    app.message = Some(Err(e));
                 }
-                Mode::AddValue { key, input } => {
-                    let mut spans = vec![
-                        Span::raw(format!("Value for '{}' (Enter=add, Esc=cancel): ", key)),
-                        Span::styled(input.clone(), Style::default().add_modifier(Modifier::BOLD)),
-                    ];
-                    if let Some(vals) = allowed_values(&key) {
-                        spans.push(Span::raw("    "));
-                        spans.push(Span::styled(format!("Allowed: {}", vals.join(" | ")), Style::default().fg(Color::Gray)));
-                    }
-                    Paragraph::new(Line::from(spans)).block(Block::default().borders(Borders::ALL))
+                continue;
+            }
+            Wake::GitLog(result) => {
+                app.set_git_log(result);
+                continue;
+            }
+            Wake::Hooked(errors) => {
+                app.hook_errors = errors;
+                continue;
+            }
+            Wake::Committed { message, result } => {
+                match result {
+                    Ok(true) => app.activity.push(Kind::Save, false, trf("log.committed", &[&message])),
+                    Ok(false) => {}
+                    Err(e) => app.activity.push(Kind::Save, true, trf("log.commit_failed", &[&e])),
                 }
-                Mode::ConfirmDelete { idx } => {
-                    let key = &cfg.params[*idx].key;
-                    Paragraph::new(Line::from(vec![
-                        Span::styled("Confirm delete? ", Style::default().fg(Color::Red).add_modifier(Modifier::BOLD)),
-                        Span::raw(format!("Delete '{}' (y/n): ", key)),
-                    ])).block(Block::default().borders(Borders::ALL))
+                continue;
+            }
+            Wake::Refresh => {
+                // Only redraw for what changed, the header's file age
+                // included.
+                let status = Status::probe(&app.cfg.path);
+                dirty = status != app.status || status.age() != shown_age;
+                app.status = status;
+                // A list still running will bring the news.
+                if app.notifications_showing() && listing == 0 {
+                    start_list(wake, &mut listing, || None);
                 }
-            };
-
-            f.render_widget(footer, chunks[2]);
-        })?;
-
-        // Input handling
-        if event::poll(Duration::from_millis(120))? {
-            if let CEvent::Key(key) = event::read()? {
-                match &mut mode {
-                    Mode::Normal => match key.code {
-                        KeyCode::Char('q') => break,
-                        KeyCode::Down | KeyCode::Char('j') => {
-                            if !cfg.params.is_empty() {
-                                let i = list_state.selected().unwrap_or(0);
-                                let next = (i + 1) % cfg.params.len();
-                                list_state.select(Some(next));
-                            }
-                        }
-                        KeyCode::Up | KeyCode::Char('k') => {
-                            if !cfg.params.is_empty() {
-                                let i = list_state.selected().unwrap_or(0);
-                                let prev = if i == 0 { cfg.params.len() - 1 } else { i - 1 };
-                                list_state.select(Some(prev));
-                            }
-                        }
-                        KeyCode::Enter | KeyCode::Char('e') => {
-                            if let Some(i) = list_state.selected() {
-                                let current = cfg.params[i].value.clone();
-                                mode = Mode::EditValue { idx: i, input: current };
-                            }
-                        }
-                        KeyCode::Char('a') => {
-                            mode = Mode::AddKey { input: String::new() };
-                        }
-                        KeyCode::Char('d') => {
-                            if let Some(i) = list_state.selected() {
-                                mode = Mode::ConfirmDelete { idx: i };
-                            }
-                        }
-                        _ => {}
-                    },
-                    Mode::EditValue { idx, input } => match key.code {
-                        KeyCode::Esc => {
-                            mode = Mode::Normal;
-                        }
-                        KeyCode::Enter => {
-                            // commit
-                            if *idx < cfg.params.len() {
-                                cfg.params[*idx].value = input.clone();
-                                if let Ok(_) = cfg.save() {
-                                    cfg.notify(&cfg.params[*idx].key, &cfg.params[*idx].value);
-                                    // attempt reload and capture result
-                                    match cfg.reload() {
-                                        Ok(msg) => last_reload = Some((true, msg)),
-                                        Err(err) => last_reload = Some((false, err)),
-                                    }
-                                }
-                            }
-                            mode = Mode::Normal;
+                if app.docs_show_history() && listing_history == 0 {
+                    start_history(wake, &mut listing_history, || None);
+                }
+                continue;
+            }
+        };
+        let action = match ev {
+            CEvent::Key(key) => app.handle_key(key),
+            CEvent::Paste(text) => app.handle_paste(&text),
+            CEvent::Mouse(mouse) => app.handle_mouse(mouse),
+            CEvent::Resize(width, height) => {
+                // Resize right away rather than on the next draw's size
+                // check, so no frame is rendered against the old buffers.
+                terminal.resize(Rect::new(0, 0, width, height))?;
+                app.handle_resize();
+                continue;
+            }
+            _ => continue,
+        };
+        match action {
+            Action::None => {}
+            Action::Quit => return Ok(()),
+            Action::SaveAndQuit => {
+                let previous = fs::read_to_string(&app.cfg.path).ok();
+                match app.cfg.save_with_backups(app.settings.backup_retention) {
+                    Ok(path) => {
+                        let message = git::message(&app.pending);
+                        saved(app, wake, trf("log.saved", &[&path.display()]), message);
+                        // Quit once mako took the file, staying if it got
+                        // rejected so the edits that broke it aren't lost
+                        // with the rollback.
+                        if !app.settings.auto_reload {
+                            return Ok(());
                         }
-                        KeyCode::Backspace => {
-                            input.pop();
+                        start_reload(app, wake, previous, true);
+                    }
+                    Err(e) => {
+                        app.message = Some(Err(trf("footer.save_failed", &[&e])));
+                        app.activity.push(Kind::Save, true, trf("footer.save_failed", &[&e]));
+                    }
+                }
+            }
+            Action::Save { key, value } => {
+                let _ = app.trash.save();
+                let previous = fs::read_to_string(&app.cfg.path).ok();
+                match app.cfg.save_with_backups(app.settings.backup_retention) {
+                    Ok(_) => {
+                        app.mark_saved();
+                        let message = git::message(&[(key.clone(), value.clone())]);
+                        saved(app, wake, trf("log.saved", &[&format!("{} = {}", key, value)]), message);
+                        start_hooks(app, wake, vec![(key.clone(), value.clone())]);
+                        if app.settings.auto_reload && app.settings.notify_on_save {
+                            let wake = wake.clone();
+                            thread::spawn(move || wake.send(Wake::Notified(daemon::notify(&key, &value))));
                         }
-                        KeyCode::Char(c) => {
-                            input.push(c);
+                        if app.settings.auto_reload {
+                            start_reload(app, wake, previous, false);
                         }
-                        _ => {}
-                    },
-                    Mode::AddKey { input } => match key.code {
-                        KeyCode::Esc => {
-                            mode = Mode::Normal;
+                    }
+                    Err(e) => app.activity.push(Kind::Save, true, trf("footer.save_failed", &[&e])),
+                }
+                app.status = Status::probe(&app.cfg.path);
+            }
+            Action::Write => {
+                let count = app.pending.len();
+                if write_pending(app, wake, app.settings.backup_retention) {
+                    app.message = Some(Ok(trf("footer.written", &[&count])));
+                }
+            }
+            Action::LiveApply => {
+                // The first change after a pause goes out right away; the
+                // rest of a burst waits, so mako reloads at most once per
+                // interval.
+                let interval = Duration::from_millis(app.settings.live_apply_ms);
+                live.due.get_or_insert_with(|| {
+                    live.written.map_or_else(Instant::now, |at| (at + interval).max(Instant::now()))
+                });
+            }
+            Action::ResetConfig => {
+                match app.cfg.reset_to_defaults(app.settings.backup_retention) {
+                    Ok(_) => {
+                        app.mark_saved();
+                        saved(app, wake, tr("log.reset").to_string(), git::message(&[("config".to_string(), "<reset>".to_string())]));
+                        if app.settings.auto_reload {
+                            start_reload(app, wake, None, false);
                         }
-                        KeyCode::Enter => {
-                            // Build filtered key list the same way as the renderer
-                            let filter = input.to_lowercase();
-                            let filtered: Vec<String> = known_keys()
-                                .into_iter()
-                                .filter_map(|(k, d)| {
-                                    let kl = k.to_lowercase();
-                                    let dl = d.to_lowercase();
-                                    if filter.is_empty() || kl.contains(&filter) || dl.contains(&filter) {
-                                        Some(k.to_string())
-                                    } else {
-                                        None
-                                    }
-                                })
-                                .collect();
-
-                            // Only accept a selection from the filtered list. If '<custom>' selected, open custom-key prompt.
-                            if let Some(i) = key_list_state.selected() {
-                                if let Some(k) = filtered.get(i) {
-                                    if k == "<custom>" {
-                                        mode = Mode::AddCustomKey { input: String::new() };
-                                        continue;
-                                    } else {
-                                        mode = Mode::AddValue { key: k.clone(), input: String::new() };
-                                        continue;
-                                    }
-                                }
-                            }
+                    }
+                    Err(e) => {
+                        app.message = Some(Err(trf("reset.failed", &[&e])));
+                        app.activity.push(Kind::Save, true, trf("reset.failed", &[&e]));
+                    }
+                }
+                app.status = Status::probe(&app.cfg.path);
+            }
+            Action::Dismiss { id } => start_list(wake, &mut listing, move || {
+                let result = match id {
+                    Some(id) => MakoCtl::dismiss(id),
+                    None => MakoCtl::dismiss_all(),
+                };
+                result.err().map(|e| trf("footer.dismiss_failed", &[&e]))
+            }),
+            Action::SendTest => {
+                let (wake, n) = (wake.clone(), app.test_notification.clone());
+                thread::spawn(move || wake.send(Wake::Sent(daemon::send_notification(&n))));
+                app.message = Some(Ok(tr("footer.test_sending").to_string()));
+            }
+            Action::SendProgress => {
+                let (wake, n) = (wake.clone(), app.test_notification.clone());
+                thread::spawn(move || wake.send(Wake::Progressed(daemon::send_progress(&n))));
+                app.message = Some(Ok(tr("footer.progress_sending").to_string()));
+            }
+            Action::ListNotifications => start_list(wake, &mut listing, || None),
+            Action::Invoke { id, action } => start_list(wake, &mut listing, move || {
+                MakoCtl::invoke(id, &action).err().map(|e| trf("footer.invoke_failed", &[&e]))
+            }),
+            Action::ListHistory => start_history(wake, &mut listing_history, || None),
+            Action::RestoreHistory => start_history(wake, &mut listing_history, || {
+                MakoCtl::restore().err().map(|e| trf("footer.restore_failed", &[&e]))
+            }),
+            Action::ListGitLog => {
+                let (wake, path, repo) = (wake.clone(), app.cfg.path.clone(), app.settings.git_repo.clone());
+                thread::spawn(move || wake.send(Wake::GitLog(git::history(&path, repo.as_deref()))));
+            }
+            Action::ListModes => app.set_modes(MakoCtl::modes()),
+            Action::ToggleMode { name } => {
+                if let Err(e) = MakoCtl::toggle_mode(&name) {
+                    app.message = Some(Err(trf("footer.mode_failed", &[&name, &e])));
+                }
+                app.set_modes(MakoCtl::modes());
+            }
+            Action::Copy { text } => {
+                app.message = Some(clipboard::copy(&text).map(|()| trf("footer.copied", &[&text])));
+            }
+            Action::PasteClipboard => match clipboard::paste() {
+                Ok(text) => {
+                    app.handle_paste(&text);
+                }
+                Err(e) => app.message = Some(Err(e)),
+            },
+            Action::SaveTrash => {
+                let _ = app.trash.save();
+            }
+            Action::Reload => start_reload(app, wake, None, false),
+            Action::OpenProfile { path } => {
+                app.open(Config::load_from(path.clone()).unwrap_or_else(|_| Config::new(path)));
+                app.status = Status::probe(&app.cfg.path);
+            }
+            Action::SaveSettings => {
+                app.message = Some(match app.settings.save() {
+                    Ok(path) => Ok(trf("settings.saved", &[&path.display()])),
+                    Err(e) => Err(trf("settings.save_failed", &[&e])),
+                });
+            }
+        }
+    }
+}
 
-                            // If nothing highlighted (shouldn't happen), return to normal
-                            mode = Mode::Normal;
-                        }
-                        KeyCode::Down | KeyCode::Char('j') => {
-                            // move selection over the filtered list shown in the UI
-                            let filter = input.to_lowercase();
-                            let filtered: Vec<String> = known_keys()
-                                .into_iter()
-                                .filter_map(|(k, d)| {
-                                    let kl = k.to_lowercase();
-                                    let dl = d.to_lowercase();
-                                    if filter.is_empty() || kl.contains(&filter) || dl.contains(&filter) {
-                                        Some(k.to_string())
-                                    } else {
-                                        None
-                                    }
-                                })
-                                .collect();
-                            if !filtered.is_empty() {
-                                let i = key_list_state.selected().unwrap_or(0);
-                                let next = (i + 1) % filtered.len();
-                                key_list_state.select(Some(next));
-                            }
-                        }
-                        KeyCode::Up | KeyCode::Char('k') => {
-                            let filter = input.to_lowercase();
-                            let filtered: Vec<String> = known_keys()
-                                .into_iter()
-                                .filter_map(|(k, d)| {
-                                    let kl = k.to_lowercase();
-                                    let dl = d.to_lowercase();
-                                    if filter.is_empty() || kl.contains(&filter) || dl.contains(&filter) {
-                                        Some(k.to_string())
-                                    } else {
-                                        None
-                                    }
-                                })
-                                .collect();
-                            if !filtered.is_empty() {
-                                let i = key_list_state.selected().unwrap_or(0);
-                                let prev = if i == 0 { filtered.len() - 1 } else { i - 1 };
-                                key_list_state.select(Some(prev));
-                            }
-                        }
-                        KeyCode::Backspace => { input.pop(); }
-                        KeyCode::Char(c) => { input.push(c); }
-                        _ => {}
-                    },
-                    Mode::AddCustomKey { input } => match key.code {
-                        KeyCode::Esc => { mode = Mode::Normal; }
-                        KeyCode::Enter => {
-                            let keyname = input.trim().to_string();
-                            if !keyname.is_empty() {
-                                mode = Mode::AddValue { key: keyname, input: String::new() };
-                            } else {
-                                mode = Mode::Normal;
-                            }
-                        }
-                        KeyCode::Backspace => { input.pop(); }
-                        KeyCode::Char(c) => { input.push(c); }
-                        _ => {}
-                    },
-                    Mode::AddValue { key: key_str, input } => match key.code {
-                        KeyCode::Esc => {
-                            mode = Mode::Normal;
-                        }
-                        KeyCode::Enter => {
-                            let val = input.clone();
-                            if !key_str.trim().is_empty() {
-                                cfg.add_param(key_str.clone(), val.clone());
-                                if let Ok(_) = cfg.save() {
-                                    cfg.notify(&key_str, &val);
-                                    match cfg.reload() {
-                                        Ok(msg) => last_reload = Some((true, msg)),
-                                        Err(err) => last_reload = Some((false, err)),
-                                    }
-                                }
-                                // select the newly added item
-                                let last = cfg.params.len() - 1;
-                                list_state.select(Some(last));
-                            }
-                            mode = Mode::Normal;
-                        }
-                        KeyCode::Backspace => { input.pop(); }
-                        KeyCode::Char(c) => { input.push(c); }
-                        _ => {}
-                    },
-                    Mode::ConfirmDelete { idx } => match key.code {
-                        KeyCode::Char('y') | KeyCode::Char('Y') => {
-                            if *idx < cfg.params.len() {
-                                let removed = cfg.params[*idx].key.clone();
-                                cfg.remove_param(*idx);
-                                if let Ok(_) = cfg.save() {
-                                    cfg.notify(&removed, "<deleted>");
-                                    match cfg.reload() {
-                                        Ok(msg) => last_reload = Some((true, msg)),
-                                        Err(err) => last_reload = Some((false, err)),
-                                    }
-                                }
-                                // adjust selection
-                                if cfg.params.is_empty() {
-                                    list_state.select(None);
-                                } else {
-                                    let new_idx = if *idx == 0 { 0 } else { *idx - 1 };
-                                    list_state.select(Some(new_idx));
-                                }
-                            }
-                            mode = Mode::Normal;
-                        }
-                        KeyCode::Char('n') | KeyCode::Char('N') | KeyCode::Esc => {
-                            mode = Mode::Normal;
-                        }
-                        _ => {}
-                    },
+/// `get`, `set`, `list`, `delete` or `apply`: print the result, or save
+/// the changed config (with backups) and reload mako like the editor does.
+fn run_subcommand(mut cfg: Config, settings: Settings, args: &[String]) -> ! {
+    match cli::run(&mut cfg, args) {
+        Ok(outcome) => {
+            print!("{}", outcome.output);
+            if !outcome.changes.is_empty() {
+                if let Err(e) = cfg.save_with_backups(settings.backup_retention) {
+                    eprintln!("{}", trf("cli.save_failed", &[&cfg.path.display(), &e]));
+                    process::exit(1);
+                }
+                if settings.snapshots
+                    && let Err(e) = snapshots::record(&cfg.path)
+                {
+                    eprintln!("{}", trf("log.snapshot_failed", &[&e]));
+                }
+                for e in Plugins::load().after_save(&cfg, &outcome.changes) {
+                    eprintln!("{}", e);
+                }
+                if settings.git_commit
+                    && let Err(e) = git::commit(&cfg.path, settings.git_repo.as_deref(), &git::message(&outcome.changes))
+                {
+                    eprintln!("{}", trf("log.commit_failed", &[&e]));
+                }
+                if settings.auto_reload
+                    && let Err(e) = MakoCtl::reload()
+                {
+                    eprintln!("{}", trf("footer.reload_failed", &[&e]));
                 }
             }
+            process::exit(0);
+        }
+        Err(e) => {
+            eprintln!("{}", e);
+            process::exit(1);
         }
     }
+}
 
-    // Cleanup
-    disable_raw_mode()?;
-    execute!(terminal.backend_mut(), LeaveAlternateScreen)?;
-    Ok(())
+/// `validate [FILE]`: report every problem in the file (the config by
+/// default), exiting with status 1 if any is an error and 2 if the file
+/// can't be read.
+fn run_validate(path: PathBuf) -> ! {
+    let text = match fs::read_to_string(&path) {
+        Ok(text) => text,
+        Err(e) => {
+            eprintln!("{}", trf("cli.read_failed", &[&path.display(), &e]));
+            process::exit(2);
+        }
+    };
+    let (report, failed) = cli::validate_report(&path, &text);
+    print!("{}", report);
+    process::exit(if failed { 1 } else { 0 });
+}
+
+/// `--refresh-keys [FILE]`: read the keys mako(5) documents (from FILE, a
+/// scdoc or roff page, else the installed one) into `manpage::keys_file`,
+/// and say how they differ from the built-in tables.
+fn run_refresh_keys(path: Option<PathBuf>) -> ! {
+    let docs = match manpage::read(path.as_deref()) {
+        Ok(text) => manpage::parse(&text),
+        Err(e) => {
+            eprintln!("{}", e);
+            process::exit(2);
+        }
+    };
+    if docs.is_empty() {
+        eprintln!("{}", tr("cli.refresh_empty"));
+        process::exit(1);
+    }
+    let file = manpage::keys_file().unwrap_or_else(|e| {
+        eprintln!("{}", e);
+        process::exit(2);
+    });
+    if let Err(e) = file.parent().map_or(Ok(()), fs::create_dir_all).and_then(|()| fs::write(&file, manpage::to_tsv(&docs))) {
+        eprintln!("{}", trf("cli.write_failed", &[&file.display(), &e]));
+        process::exit(2);
+    }
+    println!("{}", trf("cli.refresh_done", &[&docs.len(), &file.display()]));
+    let (new, gone, values) = manpage::compare(&docs);
+    for (id, keys) in [("cli.refresh_new", new), ("cli.refresh_gone", gone), ("cli.refresh_values", values)] {
+        if !keys.is_empty() {
+            println!("{}", trf(id, &[&keys.join(", ")]));
+        }
+    }
+    process::exit(0);
+}
+
+/// `--headless SCRIPT [--size WxH]`: run a script of key presses against
+/// the config without a terminal and print the recorded screens followed by
+/// the resulting config. Nothing is saved.
+fn run_headless(cfg: Config, settings: Settings, compact: bool, args: &[String]) -> ! {
+    let fail = |msg: String| -> ! {
+        eprintln!("{}", msg);
+        process::exit(2);
+    };
+    let Some(script_path) = args.first() else { fail(tr("cli.usage").to_string()) };
+    let (mut width, mut height) = (100, 30);
+    if let [_, flag, size] = args
+        && flag == "--size"
+    {
+        match headless::parse_size(size) {
+            Some(s) => (width, height) = s,
+            None => fail(trf("cli.bad_size", &[size])),
+        }
+    } else if args.len() != 1 {
+        fail(tr("cli.usage").to_string());
+    }
+    let script = if script_path == "-" { io::read_to_string(io::stdin()) } else { fs::read_to_string(script_path) };
+    let script = script.unwrap_or_else(|e| fail(trf("cli.read_failed", &[script_path, &e])));
+
+    let mut app = App::new(cfg);
+    app.settings = settings;
+    app.compact = compact;
+    match headless::run_script(&mut app, &script, width, height) {
+        Ok(outcome) => {
+            for (i, screen) in outcome.screens.iter().enumerate() {
+                println!("--- screen {} ---", i + 1);
+                print!("{}", screen);
+            }
+            println!("--- config ---");
+            print!("{}", outcome.config);
+            process::exit(0);
+        }
+        Err(e) => fail(e),
+    }
 }
@@ -0,0 +1,227 @@
+//! Reading the keys mako(5) documents, so `--refresh-keys` can catch keys
+//! and values the built-in tables don't know yet. Both the scdoc source
+//! (`mako.5.scd`) and the installed roff page are understood.
+
+use std::{fs, io, path::{Path, PathBuf}, process::Command};
+
+use crate::i18n::trf;
+use crate::logging;
+use crate::mako_config::{allowed_values, deprecated, known_keys};
+use crate::settings::state_dir;
+
+/// One key as mako(5) documents it.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct KeyDoc {
+    pub key: String,
+    /// The first paragraph of its description, without markup.
+    pub description: String,
+    /// The values its description lists, when it lists them.
+    pub values: Vec<String>,
+}
+
+/// The file `--refresh-keys` writes and `plugin::ManPageKeys` reads.
+pub fn keys_file() -> io::Result<PathBuf> {
+    Ok(state_dir()?.join("keys.tsv"))
+}
+
+/// The text of mako(5): the file at `path` (roff, possibly gzipped, or
+/// scdoc), else the installed page as `man -w` finds it.
+pub fn read(path: Option<&Path>) -> Result<String, String> {
+    let path = match path {
+        Some(path) => path.to_path_buf(),
+        None => match logging::output(Command::new("man").args(["-w", "5", "mako"])) {
+            Ok(output) if output.status.success() => PathBuf::from(String::from_utf8_lossy(&output.stdout).trim()),
+            Ok(output) => return Err(String::from_utf8_lossy(&output.stderr).trim().to_string()),
+            Err(e) => return Err(trf("error.man_exec", &[&e])),
+        },
+    };
+    if path.extension().is_some_and(|e| e == "gz") {
+        return match logging::output(Command::new("gzip").arg("-dc").arg(&path)) {
+            Ok(output) if output.status.success() => Ok(String::from_utf8_lossy(&output.stdout).into_owned()),
+            Ok(output) => Err(String::from_utf8_lossy(&output.stderr).trim().to_string()),
+            Err(e) => Err(trf("error.gzip_exec", &[&e])),
+        };
+    }
+    fs::read_to_string(&path).map_err(|e| trf("cli.read_failed", &[&path.display(), &e]))
+}
+
+/// Every key documented in the `... OPTIONS` sections of `text`, in page
+/// order; criteria are documented the same way but elsewhere. Keys listed
+/// together (`on-button-left`, `on-button-middle`, ...) share their
+/// description.
+pub fn parse(text: &str) -> Vec<KeyDoc> {
+    let roff = text.lines().any(|l| l.starts_with(".TH") || l.starts_with(".SH"));
+    let lines: Vec<String> = text.lines().filter_map(|l| if roff { from_roff(l) } else { Some(l.trim().to_string()) }).collect();
+    let mut out: Vec<KeyDoc> = Vec::new();
+    let mut keys: Vec<String> = Vec::new();
+    let mut paragraph: Vec<&str> = Vec::new();
+    let mut in_options = false;
+    let mut flush = |keys: &mut Vec<String>, paragraph: &mut Vec<&str>| {
+        let text = paragraph.join(" ");
+        for key in keys.drain(..) {
+            if !out.iter().any(|d| d.key == key) {
+                out.push(KeyDoc { key, description: plain(&text), values: listed_values(&text) });
+            }
+        }
+        paragraph.clear();
+    };
+    for line in &lines {
+        if let Some(heading) = line.strip_prefix('#') {
+            flush(&mut keys, &mut paragraph);
+            in_options = heading.contains("OPTIONS");
+        } else if let Some(key) = key_of(line).filter(|_| in_options) {
+            if !paragraph.is_empty() {
+                flush(&mut keys, &mut paragraph);
+            }
+            keys.push(key);
+        } else if line.is_empty() {
+            if !paragraph.is_empty() {
+                flush(&mut keys, &mut paragraph);
+            }
+        } else if !keys.is_empty() {
+            paragraph.push(line);
+        }
+    }
+    flush(&mut keys, &mut paragraph);
+    out
+}
+
+/// `key` from a `*key*=_value_` line.
+fn key_of(line: &str) -> Option<String> {
+    let rest = line.strip_prefix('*')?;
+    let (key, after) = rest.split_once('*')?;
+    let valid = !key.is_empty() && key.chars().all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-');
+    (valid && after.starts_with('=')).then(|| key.to_string())
+}
+
+/// A roff line in scdoc's markup: `\fBbold\fR` becomes `*bold*`, `\fIit\fR`
+/// becomes `_it_`. Paragraph macros become blank lines and section headings
+/// `#` lines; other macros are dropped.
+fn from_roff(line: &str) -> Option<String> {
+    if let Some(mac) = line.strip_prefix('.') {
+        return match mac.split_whitespace().next() {
+            Some("PP" | "P" | "LP" | "RE" | "sp") => Some(String::new()),
+            Some("SH" | "SS") => Some(format!("# {}", mac.get(3..).unwrap_or("").trim())),
+            _ => None,
+        };
+    }
+    let mut out = String::new();
+    let mut font: Option<char> = None;
+    let mut chars = line.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            out.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('f') => {
+                let close = font.take();
+                match chars.next() {
+                    Some('B') => font = Some('*'),
+                    Some('I') => font = Some('_'),
+                    _ => {}
+                }
+                if let Some(close) = close {
+                    out.push(close);
+                }
+                if let Some(open) = font {
+                    out.push(open);
+                }
+            }
+            Some('&') => {}
+            Some(c) => out.push(c),
+            None => {}
+        }
+    }
+    if let Some(close) = font {
+        out.push(close);
+    }
+    Some(out.trim().to_string())
+}
+
+/// `text` without scdoc's `*` and `_` markup.
+fn plain(text: &str) -> String {
+    text.replace(['*', '_'], "")
+}
+
+/// The `_italic_` words of the sentence listing the supported values, e.g.
+/// "Supported values are _top-right_, _top-center_ and _center_."
+fn listed_values(text: &str) -> Vec<String> {
+    let lower = text.to_lowercase();
+    let Some(start) = ["values are", "one of", "supported values"].iter().filter_map(|m| lower.find(m)).min() else {
+        return Vec::new();
+    };
+    let sentence = text[start..].split(". ").next().unwrap_or("");
+    let values: Vec<String> = sentence
+        .split('_')
+        .skip(1)
+        .step_by(2)
+        .map(|v| v.trim_matches(|c: char| !c.is_alphanumeric() && c != '-'))
+        .filter(|v| !v.is_empty() && !v.contains(' '))
+        .map(String::from)
+        .collect();
+    if values.len() >= 2 { values } else { Vec::new() }
+}
+
+/// `docs` as `keys_file` stores them: one `key<TAB>v1,v2<TAB>description`
+/// per line.
+pub fn to_tsv(docs: &[KeyDoc]) -> String {
+    docs.iter().map(|d| format!("{}\t{}\t{}\n", d.key, d.values.join(","), d.description)).collect()
+}
+
+pub fn from_tsv(text: &str) -> Vec<KeyDoc> {
+    text.lines()
+        .filter_map(|line| {
+            let mut fields = line.splitn(3, '\t');
+            let key = fields.next().filter(|k| !k.is_empty())?.to_string();
+            let values = fields.next().unwrap_or("").split(',').filter(|v| !v.is_empty()).map(String::from).collect();
+            Some(KeyDoc { key, values, description: fields.next().unwrap_or("").to_string() })
+        })
+        .collect()
+}
+
+/// How `docs` differ from the built-in tables: keys the editor doesn't
+/// know, keys it knows that the page no longer lists, and keys whose
+/// listed values include ones `allowed_values` lacks.
+pub fn compare(docs: &[KeyDoc]) -> (Vec<String>, Vec<String>, Vec<String>) {
+    let builtin: Vec<&str> =
+        known_keys().into_iter().map(|(k, _)| k).filter(|k| *k != "<custom>" && deprecated(k).is_none()).collect();
+    let new = docs.iter().filter(|d| !builtin.contains(&d.key.as_str())).map(|d| d.key.clone()).collect();
+    let gone = builtin.iter().filter(|k| !docs.iter().any(|d| d.key == **k)).map(|k| k.to_string()).collect();
+    let values = docs
+        .iter()
+        .filter(|d| allowed_values(&d.key).is_some_and(|known| d.values.iter().any(|v| !known.contains(&v.as_str()))))
+        .map(|d| d.key.clone())
+        .collect();
+    (new, gone, values)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SCDOC: &str = "mako(5)\n\n# GLOBAL CONFIGURATION OPTIONS\n\n*max-history*=_n_\n\tSet maximum number of expired notifications to keep.\n\n\tDefault: 5\n\n*sort*=_+/-time_ | _+/-priority_\n\tSorts incoming notifications.\n\n# STYLE OPTIONS\n\n*on-button-left*=_action_\n*on-button-right*=_action_\n\tPerforms the action when clicked. Supported values are _none_, _dismiss_,\n\t_invoke-default-action_ and _exec_.\n\n*shiny*=_0_|_1_\n\tA key from the future.\n\n# CRITERIA\n\n*app-name*=_name_\n\tThe application.\n";
+
+    #[test]
+    fn reads_keys_from_scdoc() {
+        let docs = parse(SCDOC);
+        let keys: Vec<&str> = docs.iter().map(|d| d.key.as_str()).collect();
+        assert_eq!(keys, ["max-history", "sort", "on-button-left", "on-button-right", "shiny"]);
+        assert_eq!(docs[0].description, "Set maximum number of expired notifications to keep.");
+        assert_eq!(docs[3].values, ["none", "dismiss", "invoke-default-action", "exec"]);
+        assert!(docs[1].values.is_empty());
+        assert_eq!(from_tsv(&to_tsv(&docs)), docs);
+        let (new, gone, _) = compare(&docs);
+        assert_eq!(new, ["shiny"]);
+        assert!(gone.contains(&"font".to_string()));
+    }
+
+    #[test]
+    fn reads_keys_from_roff() {
+        let roff = ".TH \"mako\" \"5\"\n.SH GLOBAL CONFIGURATION OPTIONS\n.PP\n\\fBmax-history\\fR=\\fIn\\fR\n.RS 4\nSet maximum number of expired notifications to keep\\&.\n.PP\nDefault: 5\n.RE\n.PP\n\\fBlayer\\fR=\\fIlayer\\fR\n.RS 4\nArrange mako at the specified layer\\&. Supported values are \\fIbackground\\fR, \\fIbottom\\fR, \\fItop\\fR and \\fIoverlay\\fR\\&.\n";
+        let docs = parse(roff);
+        assert_eq!(docs.len(), 2);
+        assert_eq!(docs[0].description, "Set maximum number of expired notifications to keep.");
+        assert_eq!(docs[1].values, ["background", "bottom", "top", "overlay"]);
+    }
+}
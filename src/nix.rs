@@ -0,0 +1,80 @@
+//! `--export-nix`: the config as a home-manager module, so a config tuned
+//! in the editor can move into a Nix setup. Global keys become
+//! `services.mako.settings`; criteria sections go into `extraConfig`
+//! verbatim, since mako reads them in file order and an attribute set has
+//! none.
+
+use crate::config::{Config, Param};
+
+/// A home-manager module setting `services.mako` to `cfg`.
+pub fn export(cfg: &Config) -> String {
+    let mut out = String::from("{\n  services.mako = {\n    enable = true;\n");
+    // Later duplicates win in mako as they would in `settings`, which
+    // only holds one value per key.
+    let mut settings: Vec<&Param> = Vec::new();
+    for p in &cfg.params {
+        match settings.iter_mut().find(|s| s.key == p.key) {
+            Some(s) => *s = p,
+            None => settings.push(p),
+        }
+    }
+    if !settings.is_empty() {
+        out.push_str("    settings = {\n");
+        for p in settings {
+            out.push_str(&format!("      {} = {};\n", attr_name(&p.key), value(&p.value)));
+        }
+        out.push_str("    };\n");
+    }
+    if !cfg.sections.is_empty() {
+        let sections = Config { params: Vec::new(), ..cfg.clone() }.to_string();
+        out.push_str("    extraConfig = ''\n");
+        for line in sections.lines() {
+            if line.is_empty() {
+                out.push('\n');
+            } else {
+                out.push_str(&format!("      {}\n", line.replace("''", "'''").replace("${", "''${")));
+            }
+        }
+        out.push_str("    '';\n");
+    }
+    out.push_str("  };\n}\n");
+    out
+}
+
+/// `key` as a Nix attribute name: bare when it is an identifier (mako's
+/// keys, dashes and all), quoted otherwise.
+fn attr_name(key: &str) -> String {
+    let bare = key.starts_with(|c: char| c.is_ascii_alphabetic() || c == '_')
+        && key.chars().all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '\''));
+    if bare { key.to_string() } else { string(key) }
+}
+
+/// Whole numbers stay numbers, like home-manager's own examples; anything
+/// else is a string.
+fn value(value: &str) -> String {
+    let value = value.trim();
+    let digits = value.trim_start_matches('-').chars().all(|c| c.is_ascii_digit());
+    if digits && value.parse::<i64>().is_ok() { value.to_string() } else { string(value) }
+}
+
+fn string(s: &str) -> String {
+    format!("\"{}\"", s.replace('\\', "\\\\").replace('"', "\\\"").replace("${", "\\${"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn exports_settings_and_criteria_as_extra_config() {
+        let cfg: Config =
+            "font=Inter 10\nborder-radius=8\nformat=<b>%s</b>\\n${body}\nborder-radius=10\n[urgency=critical]\nborder-color=#ff0000\n\n[app-name=\"Fire fox\"]\ninvisible=1\n"
+                .parse()
+                .unwrap();
+        assert_eq!(
+            export(&cfg),
+            "{\n  services.mako = {\n    enable = true;\n    settings = {\n      font = \"Inter 10\";\n      border-radius = 10;\n      format = \"<b>%s</b>\\\\n\\${body}\";\n    };\n    extraConfig = ''\n      [urgency=critical]\n      border-color=#ff0000\n\n      [app-name=\"Fire fox\"]\n      invisible=1\n    '';\n  };\n}\n"
+        );
+        assert_eq!(export(&Config::default()), "{\n  services.mako = {\n    enable = true;\n  };\n}\n");
+    }
+}
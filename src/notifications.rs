@@ -0,0 +1,310 @@
+//! The notifications mako is showing right now and those in its history,
+//! as reported by `makoctl list` and `makoctl history` (see
+//! `daemon::MakoCtl`).
+
+use crate::i18n::{tr, trf};
+use crate::json::{self, Json};
+use crate::mako_config::{format_duration_ms, parse_duration_ms};
+
+/// One notification on screen.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Notification {
+    pub id: u32,
+    pub app_name: String,
+    pub summary: String,
+    pub body: String,
+    pub category: String,
+    /// `low`, `normal` or `critical`.
+    pub urgency: &'static str,
+    /// The actions it offers, as `(key, label)`, for `makoctl invoke`.
+    pub actions: Vec<(String, String)>,
+}
+
+/// A notification to send on purpose, to see how the config renders it.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TestNotification {
+    pub summary: String,
+    pub body: String,
+    /// `low`, `normal` or `critical`.
+    pub urgency: String,
+    pub app_name: String,
+    /// In milliseconds; `None` leaves it to mako's `default-timeout`.
+    pub timeout: Option<u64>,
+    /// The `category` hint, which `[category=...]` sections match.
+    pub category: String,
+    /// The `image-path` hint: an icon name or file to show.
+    pub image_path: String,
+    /// The `value` hint, 0 to 100, which mako draws as a progress bar.
+    pub progress: Option<u8>,
+    /// How many copies to send at once, to see how `group-by` groups them.
+    pub count: u32,
+    /// The id of a notification this one takes the place of, 0 for none.
+    pub replaces: u32,
+}
+
+impl Default for TestNotification {
+    fn default() -> Self {
+        TestNotification {
+            summary: "Test notification".to_string(),
+            body: "How does this look?".to_string(),
+            urgency: "normal".to_string(),
+            app_name: "mako-tui".to_string(),
+            timeout: None,
+            category: String::new(),
+            image_path: String::new(),
+            progress: None,
+            count: 1,
+            replaces: 0,
+        }
+    }
+}
+
+impl TestNotification {
+    /// A copy of `n`, to send again and see it in the current style.
+    pub fn like(n: &Notification) -> Self {
+        TestNotification {
+            summary: n.summary.clone(),
+            body: n.body.clone(),
+            urgency: n.urgency.to_string(),
+            app_name: n.app_name.clone(),
+            category: n.category.clone(),
+            ..TestNotification::default()
+        }
+    }
+
+    /// Every field with its current value, in display order.
+    pub fn fields(&self) -> Vec<(&'static str, String)> {
+        vec![
+            ("summary", self.summary.clone()),
+            ("body", self.body.clone()),
+            ("urgency", self.urgency.clone()),
+            ("app-name", self.app_name.clone()),
+            ("timeout", self.timeout.map(format_duration_ms).unwrap_or_default()),
+            ("category", self.category.clone()),
+            ("image-path", self.image_path.clone()),
+            ("progress", self.progress.map(|p| p.to_string()).unwrap_or_default()),
+            ("count", self.count.to_string()),
+        ]
+    }
+
+    /// Set `field` from text as typed, checking urgency, timeout, progress
+    /// and count.
+    pub fn set(&mut self, field: &str, value: &str) -> Result<(), String> {
+        let invalid = || trf("settings.invalid", &[&value, &field]);
+        match field {
+            "summary" => self.summary = value.to_string(),
+            "body" => self.body = value.to_string(),
+            "urgency" => match value.trim() {
+                u @ ("low" | "normal" | "critical") => self.urgency = u.to_string(),
+                _ => return Err(invalid()),
+            },
+            "app-name" => self.app_name = value.to_string(),
+            "timeout" if value.trim().is_empty() => self.timeout = None,
+            "timeout" => self.timeout = Some(parse_duration_ms(value).ok_or_else(invalid)?),
+            "category" => self.category = value.trim().to_string(),
+            "image-path" => self.image_path = value.trim().to_string(),
+            "progress" if value.trim().is_empty() => self.progress = None,
+            "progress" => self.progress = Some(value.trim().parse().ok().filter(|p| *p <= 100).ok_or_else(invalid)?),
+            "count" => self.count = value.trim().parse().ok().filter(|n| (1..=MAX_COUNT).contains(n)).ok_or_else(invalid)?,
+            _ => return Err(trf("settings.unknown", &[&field])),
+        }
+        Ok(())
+    }
+
+    /// Arguments for `gdbus call` that send one copy through
+    /// `org.freedesktop.Notifications.Notify`, hints and all. Each argument
+    /// is in GVariant text form, typed so it doesn't depend on mako
+    /// answering introspection.
+    pub fn gdbus_args(&self) -> Vec<String> {
+        let urgency = match self.urgency.as_str() {
+            "low" => 0,
+            "critical" => 2,
+            _ => 1,
+        };
+        let mut hints = vec![format!("'urgency': <byte {}>", urgency)];
+        if !self.category.is_empty() {
+            hints.push(format!("'category': <{}>", gvariant_str(&self.category)));
+        }
+        if !self.image_path.is_empty() {
+            hints.push(format!("'image-path': <{}>", gvariant_str(&self.image_path)));
+        }
+        if let Some(p) = self.progress {
+            hints.push(format!("'value': <int32 {}>", p));
+        }
+        let mut args: Vec<String> = [
+            "call",
+            "--session",
+            "--dest",
+            "org.freedesktop.Notifications",
+            "--object-path",
+            "/org/freedesktop/Notifications",
+            "--method",
+            "org.freedesktop.Notifications.Notify",
+        ]
+        .map(String::from)
+        .into();
+        args.extend([
+            gvariant_str(&self.app_name),
+            format!("uint32 {}", self.replaces),
+            gvariant_str(""),
+            gvariant_str(&self.summary),
+            gvariant_str(&self.body),
+            "@as []".to_string(),
+            format!("{{{}}}", hints.join(", ")),
+            format!("int32 {}", self.timeout.map_or(-1, |ms| ms.min(i32::MAX as u64) as i64)),
+        ]);
+        args
+    }
+}
+
+/// The id `Notify` answered with, from gdbus's `(uint32 7,)`.
+pub fn parse_notify_reply(s: &str) -> Option<u32> {
+    s.trim().trim_start_matches('(').strip_prefix("uint32 ")?.trim_end_matches([',', ')']).parse().ok()
+}
+
+/// Most copies `count` sends at once.
+const MAX_COUNT: u32 = 20;
+
+/// `s` as a quoted GVariant string.
+fn gvariant_str(s: &str) -> String {
+    format!("'{}'", s.replace('\\', "\\\\").replace('\'', "\\'"))
+}
+
+/// Read the JSON `makoctl list` prints. Both the D-Bus shaped output of
+/// older mako (`{"type": "aa{sv}", "data": [[{"id": {"type": "u", "data":
+/// 3}, ...}]]}`) and the plain objects of newer mako are understood:
+/// every object with an `id` and a `summary` is a notification.
+pub fn parse_list(s: &str) -> Result<Vec<Notification>, String> {
+    let value = json::parse(s).map_err(|e| match e {
+        json::Error::At(pos) => trf("notifications.bad_json", &[&pos]),
+        json::Error::Truncated => tr("notifications.truncated").to_string(),
+    })?;
+    let mut out = Vec::new();
+    collect(&value, &mut out);
+    Ok(out)
+}
+
+fn collect(value: &Json, out: &mut Vec<Notification>) {
+    match value {
+        Json::Object(fields) => {
+            let field = |names: &[&str]| fields.iter().find(|(k, _)| names.contains(&k.as_str())).map(|(_, v)| unwrap_variant(v));
+            let text = |names: &[&str]| match field(names) {
+                Some(Json::String(s)) => s.clone(),
+                _ => String::new(),
+            };
+            if let (Some(Json::Number(id)), Some(Json::String(summary))) = (field(&["id"]), field(&["summary"])) {
+                let urgency = match field(&["urgency"]) {
+                    Some(Json::Number(0.0)) => "low",
+                    Some(Json::Number(2.0)) => "critical",
+                    _ => "normal",
+                };
+                out.push(Notification {
+                    id: *id as u32,
+                    app_name: text(&["app-name", "app_name"]),
+                    summary: summary.clone(),
+                    body: text(&["body"]),
+                    category: text(&["category"]),
+                    urgency,
+                    actions: match field(&["actions"]) {
+                        Some(Json::Object(actions)) => actions
+                            .iter()
+                            .map(|(key, label)| match unwrap_variant(label) {
+                                Json::String(label) => (key.clone(), label.clone()),
+                                _ => (key.clone(), key.clone()),
+                            })
+                            .collect(),
+                        _ => Vec::new(),
+                    },
+                });
+            } else {
+                fields.iter().for_each(|(_, v)| collect(v, out));
+            }
+        }
+        Json::Array(items) => items.iter().for_each(|v| collect(v, out)),
+        _ => {}
+    }
+}
+
+/// `{"type": "s", "data": x}` -> `x`, as D-Bus variants are printed.
+fn unwrap_variant(value: &Json) -> &Json {
+    match value {
+        Json::Object(fields) if fields.len() == 2 && fields.iter().any(|(k, _)| k == "type") => {
+            fields.iter().find(|(k, _)| k == "data").map_or(value, |(_, v)| v)
+        }
+        _ => value,
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_notification_builds_notify_call() {
+        let mut n = TestNotification::default();
+        n.set("urgency", "critical").unwrap();
+        n.set("timeout", "5s").unwrap();
+        n.set("body", "").unwrap();
+        n.set("summary", r"It's C:\").unwrap();
+        n.set("category", "transfer").unwrap();
+        n.set("progress", "40").unwrap();
+        assert!(n.set("urgency", "panic").is_err());
+        assert!(n.set("progress", "101").is_err());
+        assert!(n.set("count", "0").is_err());
+        assert_eq!(
+            n.gdbus_args()[8..],
+            [
+                "'mako-tui'",
+                "uint32 0",
+                "''",
+                r"'It\'s C:\\'",
+                "''",
+                "@as []",
+                "{'urgency': <byte 2>, 'category': <'transfer'>, 'value': <int32 40>}",
+                "int32 5000",
+            ]
+        );
+        assert_eq!(n.fields()[4].1, "5s");
+        n.set("timeout", "").unwrap();
+        n.set("progress", "").unwrap();
+        assert_eq!((n.timeout, n.progress), (None, None));
+        assert_eq!(n.gdbus_args().last().map(String::as_str), Some("int32 -1"));
+        n.replaces = parse_notify_reply("(uint32 42,)\n").unwrap();
+        assert_eq!(n.gdbus_args()[9], "uint32 42");
+        assert_eq!(parse_notify_reply("Error: no reply"), None);
+    }
+
+    #[test]
+    fn reads_dbus_shaped_output() {
+        let json = r#"{"type": "aa{sv}", "data": [[
+            {"app-name": {"type": "s", "data": "Firefox"}, "summary": {"type": "s", "data": "Download \"done\""},
+             "body": {"type": "s", "data": "file.zip"}, "category": {"type": "s", "data": "transfer.complete"},
+             "id": {"type": "u", "data": 12}, "urgency": {"type": "y", "data": 2},
+             "actions": {"type": "a{ss}", "data": {"default": "Open", "show": "Show in folder"}}}
+        ]]}"#;
+        assert_eq!(
+            parse_list(json).unwrap(),
+            vec![Notification {
+                id: 12,
+                app_name: "Firefox".into(),
+                summary: "Download \"done\"".into(),
+                body: "file.zip".into(),
+                category: "transfer.complete".into(),
+                urgency: "critical",
+                actions: vec![("default".into(), "Open".into()), ("show".into(), "Show in folder".into())],
+            }]
+        );
+    }
+
+    #[test]
+    fn reads_plain_objects_and_rejects_garbage() {
+        let json = r#"[{"id": 3, "app_name": "mail", "summary": "café", "urgency": 0}, {"id": 4, "summary": "x"}]"#;
+        let list = parse_list(json).unwrap();
+        assert_eq!(list.len(), 2);
+        assert_eq!((list[0].summary.as_str(), list[0].urgency), ("café", "low"));
+        assert_eq!(list[1].urgency, "normal");
+        assert!(parse_list("Notification 3: hello").is_err());
+        assert!(parse_list("[{\"id\": 3").is_err());
+    }
+}
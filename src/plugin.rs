@@ -0,0 +1,288 @@
+use std::{
+    collections::HashMap,
+    fs, io,
+    path::{Path, PathBuf},
+    process::Command,
+    sync::{Arc, Mutex},
+};
+
+use crate::config::Config;
+use crate::i18n::trf;
+use crate::logging;
+use crate::manpage::{self, KeyDoc};
+use crate::mako_config::{allowed_values, known_keys};
+use crate::settings::config_dir;
+
+/// Extension point for workflows that don't belong in the core editor:
+/// extra keys, site-specific value suggestions, or actions to run after
+/// every save. Every method has a no-op default so a plugin only
+/// implements what it needs. Post-save actions run on a background thread,
+/// hence `Send + Sync`.
+pub trait Plugin: Send + Sync {
+    /// Short name used in error messages.
+    fn name(&self) -> &str;
+
+    /// Extra `(key, description)` pairs offered in the add-key chooser.
+    fn known_keys(&self) -> Vec<(String, String)> {
+        Vec::new()
+    }
+
+    /// Suggested values for `key`, shown alongside the allowed values
+    /// while editing (e.g. a palette of corporate theme colors).
+    fn suggest_values(&self, _key: &str) -> Vec<String> {
+        Vec::new()
+    }
+
+    /// Called after the config was written to disk. `key` and `value`
+    /// describe the change that triggered the save.
+    fn after_save(&self, _cfg: &Config, _key: &str, _value: &str) -> Result<(), String> {
+        Ok(())
+    }
+}
+
+/// The set of plugins active for this session. Clones share the plugins,
+/// for handing them to a background thread.
+#[derive(Clone, Default)]
+pub struct Plugins {
+    plugins: Vec<Arc<dyn Plugin>>,
+}
+
+impl Plugins {
+    /// Plugins every session gets: the script hooks and the keys last read
+    /// from mako(5). Without a home directory to find them in there are
+    /// neither, which is logged.
+    pub fn load() -> Self {
+        let mut plugins = Plugins::default();
+        match ScriptHooks::hooks_dir().and_then(|hooks| Ok((hooks, manpage::keys_file()?))) {
+            Ok((hooks, keys)) => {
+                plugins.register(Box::new(ScriptHooks::load(hooks)));
+                plugins.register(Box::new(ManPageKeys::load(&keys)));
+            }
+            Err(e) => log::warn!(target: "plugins", "{}", e),
+        }
+        plugins
+    }
+
+    pub fn register(&mut self, plugin: Box<dyn Plugin>) {
+        self.plugins.push(plugin.into());
+    }
+
+    pub fn known_keys(&self) -> Vec<(String, String)> {
+        self.plugins.iter().flat_map(|p| p.known_keys()).collect()
+    }
+
+    pub fn suggest_values(&self, key: &str) -> Vec<String> {
+        let mut out: Vec<String> = Vec::new();
+        for v in self.plugins.iter().flat_map(|p| p.suggest_values(key)) {
+            if !out.contains(&v) {
+                out.push(v);
+            }
+        }
+        out
+    }
+
+    /// Run every post-save action for each `(key, value)` change the save
+    /// wrote, collecting failures as `"<plugin>: <error>"` so one broken
+    /// hook doesn't hide the others. Hooks may take their time; the editor
+    /// calls this off the UI thread.
+    pub fn after_save(&self, cfg: &Config, changes: &[(String, String)]) -> Vec<String> {
+        changes
+            .iter()
+            .flat_map(|(key, value)| {
+                self.plugins.iter().filter_map(|p| p.after_save(cfg, key, value).err().map(|e| format!("{}: {}", p.name(), e)))
+            })
+            .collect()
+    }
+}
+
+/// Plugin backed by executables in `$XDG_CONFIG_HOME/mako-editor/hooks/`:
+///
+/// - `known-keys` prints one `key<TAB>description` per line; run once at startup.
+/// - `suggest-values <key>` prints one suggested value per line.
+/// - `post-save` runs after every save with `MAKO_EDITOR_CONFIG`,
+///   `MAKO_EDITOR_KEY` and `MAKO_EDITOR_VALUE` set in its environment.
+///
+/// Missing scripts are simply skipped. Suggestions are cached per key so
+/// redrawing the edit prompt doesn't spawn a process every frame.
+pub struct ScriptHooks {
+    dir: PathBuf,
+    known_keys: Vec<(String, String)>,
+    suggestions: Mutex<HashMap<String, Vec<String>>>,
+}
+
+impl ScriptHooks {
+    pub fn hooks_dir() -> io::Result<PathBuf> {
+        Ok(config_dir()?.join("mako-editor/hooks"))
+    }
+
+    pub fn load(dir: PathBuf) -> Self {
+        let mut hooks = ScriptHooks { dir, known_keys: Vec::new(), suggestions: Mutex::default() };
+        if let Some(out) = hooks.run("known-keys", &[]) {
+            hooks.known_keys = parse_known_keys(&out);
+        }
+        hooks
+    }
+
+    fn script(&self, name: &str) -> Option<PathBuf> {
+        let path = self.dir.join(name);
+        fs::metadata(&path).ok().filter(|m| m.is_file()).map(|_| path)
+    }
+
+    /// Run a hook and return its stdout, or `None` when the hook is
+    /// missing or fails.
+    fn run(&self, name: &str, args: &[&str]) -> Option<String> {
+        let output = logging::output(Command::new(self.script(name)?).args(args)).ok()?;
+        output.status.success().then(|| String::from_utf8_lossy(&output.stdout).into_owned())
+    }
+}
+
+impl Plugin for ScriptHooks {
+    fn name(&self) -> &str {
+        "hooks"
+    }
+
+    fn known_keys(&self) -> Vec<(String, String)> {
+        self.known_keys.clone()
+    }
+
+    fn suggest_values(&self, key: &str) -> Vec<String> {
+        let Ok(mut suggestions) = self.suggestions.lock() else { return Vec::new() };
+        suggestions
+            .entry(key.to_string())
+            .or_insert_with(|| self.run("suggest-values", &[key]).map(|out| parse_values(&out)).unwrap_or_default())
+            .clone()
+    }
+
+    fn after_save(&self, cfg: &Config, key: &str, value: &str) -> Result<(), String> {
+        let Some(script) = self.script("post-save") else {
+            return Ok(());
+        };
+        let output = logging::output(
+            Command::new(script)
+                .env("MAKO_EDITOR_CONFIG", &cfg.path)
+                .env("MAKO_EDITOR_KEY", key)
+                .env("MAKO_EDITOR_VALUE", value),
+        )
+        .map_err(|e| trf("error.hook_exec", &[&"post-save", &e]))?;
+        if output.status.success() {
+            Ok(())
+        } else {
+            Err(String::from_utf8_lossy(&output.stderr).trim().to_string())
+        }
+    }
+}
+
+/// The `key<TAB>description` lines `known-keys` prints; the description
+/// may be left out.
+fn parse_known_keys(out: &str) -> Vec<(String, String)> {
+    out.lines()
+        .filter_map(|l| {
+            let (k, d) = l.split_once('\t').unwrap_or((l, ""));
+            let k = k.trim();
+            (!k.is_empty()).then(|| (k.to_string(), d.trim().to_string()))
+        })
+        .collect()
+}
+
+/// The values `suggest-values` prints, one per non-blank line.
+fn parse_values(out: &str) -> Vec<String> {
+    out.lines().map(str::trim).filter(|l| !l.is_empty()).map(String::from).collect()
+}
+
+/// Plugin offering what `mako-tui --refresh-keys` read from mako(5) and the
+/// built-in tables lack: keys newer than this build, and the values the
+/// page lists for keys whose values aren't built in.
+pub struct ManPageKeys {
+    docs: Vec<KeyDoc>,
+}
+
+impl ManPageKeys {
+    /// Read `path` as `--refresh-keys` wrote it; no file means no keys.
+    pub fn load(path: &Path) -> Self {
+        ManPageKeys { docs: fs::read_to_string(path).map(|text| manpage::from_tsv(&text)).unwrap_or_default() }
+    }
+}
+
+impl Plugin for ManPageKeys {
+    fn name(&self) -> &str {
+        "mako(5)"
+    }
+
+    fn known_keys(&self) -> Vec<(String, String)> {
+        let builtin: Vec<&str> = known_keys().into_iter().map(|(k, _)| k).collect();
+        self.docs.iter().filter(|d| !builtin.contains(&d.key.as_str())).map(|d| (d.key.clone(), d.description.clone())).collect()
+    }
+
+    fn suggest_values(&self, key: &str) -> Vec<String> {
+        if allowed_values(key).is_some() {
+            return Vec::new();
+        }
+        self.docs.iter().find(|d| d.key == key).map(|d| d.values.clone()).unwrap_or_default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::os::unix::fs::PermissionsExt;
+
+    fn script(dir: &Path, name: &str, body: &str) {
+        let path = dir.join(name);
+        fs::write(&path, format!("#!/bin/sh\n{}\n", body)).unwrap();
+        fs::set_permissions(&path, fs::Permissions::from_mode(0o755)).unwrap();
+    }
+
+    #[test]
+    fn hook_output_is_read_line_by_line() {
+        let keys = parse_known_keys("x-glow\tGlow around the border\n\n  x-plain  \n\tno key\n");
+        assert_eq!(keys, [("x-glow".to_string(), "Glow around the border".to_string()), ("x-plain".to_string(), String::new())]);
+        assert_eq!(parse_values(" #ff0000 \n\n#00ff00\n"), ["#ff0000", "#00ff00"]);
+    }
+
+    #[test]
+    fn script_hooks_are_found_in_their_directory() {
+        let dir = std::env::temp_dir().join(format!("mako-tui-hooks-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(dir.join("post-save")).unwrap();
+        script(&dir, "known-keys", "printf 'x-glow\\tGlow\\n'");
+        script(&dir, "suggest-values", "printf '%s-1\\n\\n%s-2\\n' \"$1\" \"$1\"");
+
+        let hooks = ScriptHooks::load(dir.clone());
+        assert_eq!(hooks.known_keys(), [("x-glow".to_string(), "Glow".to_string())]);
+        assert_eq!(hooks.suggest_values("font"), ["font-1", "font-2"]);
+        // A directory isn't a hook.
+        let cfg = Config::new(dir.join("config"));
+        assert_eq!(hooks.after_save(&cfg, "font", "mono 10"), Ok(()));
+
+        fs::remove_dir(dir.join("post-save")).unwrap();
+        script(&dir, "post-save", "[ \"$MAKO_EDITOR_KEY=$MAKO_EDITOR_VALUE\" = font=bad ] && echo \"no $MAKO_EDITOR_CONFIG\" >&2 && exit 1; exit 0");
+        let mut plugins = Plugins::default();
+        plugins.register(Box::new(hooks));
+        let changes = [("font".to_string(), "mono 10".to_string()), ("font".to_string(), "bad".to_string())];
+        assert_eq!(plugins.after_save(&cfg, &changes), [format!("hooks: no {}", cfg.path.display())]);
+
+        let missing = ScriptHooks::load(dir.join("missing"));
+        assert!(missing.known_keys().is_empty() && missing.suggest_values("font").is_empty());
+        assert_eq!(missing.after_save(&cfg, "font", "bad"), Ok(()));
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn man_page_keys_only_add_what_is_not_built_in() {
+        let file = std::env::temp_dir().join(format!("mako-tui-keys-{}.tsv", std::process::id()));
+        let doc = |key: &str, values: &[&str]| KeyDoc {
+            key: key.to_string(),
+            description: format!("About {}", key),
+            values: values.iter().map(|v| v.to_string()).collect(),
+        };
+        fs::write(&file, manpage::to_tsv(&[doc("font", &["a", "b"]), doc("x-new", &["on", "off"]), doc("layer", &["top"])])).unwrap();
+        let keys = ManPageKeys::load(&file);
+        assert_eq!(keys.known_keys(), [("x-new".to_string(), "About x-new".to_string())]);
+        assert_eq!(keys.suggest_values("x-new"), ["on", "off"]);
+        assert_eq!(keys.suggest_values("font"), ["a", "b"]);
+        // The built-in values win.
+        assert!(keys.suggest_values("layer").is_empty());
+        assert!(ManPageKeys::load(&file.with_extension("missing")).known_keys().is_empty());
+        let _ = fs::remove_file(&file);
+    }
+}
@@ -0,0 +1,417 @@
+use std::{env, fs, io, path::PathBuf};
+
+use home::home_dir;
+
+pub use crate::config::config_dir;
+use crate::config::write_atomic;
+use crate::i18n::{tr, trf};
+use crate::keymap::{Command, Keymap};
+
+/// Names accepted by the `theme` setting.
+pub const UI_THEMES: &[&str] = &["default", "ocean", "mono"];
+
+/// Whether the editor wakes up on its own while idle.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum IdleMode {
+    /// Refresh the daemon status (and the notifications list) every couple
+    /// of seconds while they are on screen.
+    Poll,
+    /// Never wake up on a timer; the status is only refreshed after saves
+    /// and key presses.
+    Block,
+}
+
+impl IdleMode {
+    fn parse(s: &str) -> Option<Self> {
+        match s.trim().to_lowercase().as_str() {
+            // `slow` polled at `idle_tick_rate_ms`, which is gone.
+            "poll" | "slow" => Some(IdleMode::Poll),
+            "block" => Some(IdleMode::Block),
+            _ => None,
+        }
+    }
+
+    fn name(self) -> &'static str {
+        match self {
+            IdleMode::Poll => "poll",
+            IdleMode::Block => "block",
+        }
+    }
+}
+
+/// Settings for the editor itself (not mako), read from
+/// `$XDG_CONFIG_HOME/mako-editor/config.toml`.
+#[derive(Clone, Debug)]
+pub struct Settings {
+    pub idle_mode: IdleMode,
+    /// Run `makoctl reload` after every save. Toggled at runtime with
+    /// `Command::AutoReload`.
+    pub auto_reload: bool,
+    /// Send a desktop notification describing each saved change, as long
+    /// as `auto_reload` is on.
+    pub notify_on_save: bool,
+    /// Keep changes in memory until `Command::Write` instead of saving
+    /// (and reloading mako) after every edit.
+    pub deferred_write: bool,
+    /// Hold quick successive changes, such as holding `+`, in memory and
+    /// save (and reload mako) at most once every `live_apply_ms`.
+    pub live_apply: bool,
+    pub live_apply_ms: u64,
+    /// Capture the mouse for clicking and scrolling the list. Off leaves the
+    /// terminal's own text selection alone. Read at startup.
+    pub mouse: bool,
+    /// Editor color theme, one of `UI_THEMES`.
+    pub theme: String,
+    pub keys: Keymap,
+    /// How many previous versions of the mako config to keep as
+    /// `config.bak.1` (newest) .. `config.bak.N`. 0 disables backups.
+    pub backup_retention: usize,
+    /// Also keep every saved version in the snapshot history
+    /// (`crate::snapshots`), which `Command::Snapshots` browses.
+    pub snapshots: bool,
+    /// Commit every save when the config is in a git repository
+    /// (`crate::git`), whose history `Command::GitLog` browses.
+    pub git_commit: bool,
+    /// Repository to commit in instead of the one around the config.
+    pub git_repo: Option<PathBuf>,
+    /// Named mako config files from the `[profiles]` table.
+    pub profiles: Vec<(String, PathBuf)>,
+    /// Values params can use as `@name`, from the `[palette]` table; see
+    /// `Config::palette`.
+    pub palette: Vec<(String, String)>,
+    /// Profile to edit at startup instead of the default config file.
+    pub default_profile: Option<String>,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Settings {
+            idle_mode: IdleMode::Poll,
+            auto_reload: true,
+            notify_on_save: true,
+            deferred_write: false,
+            live_apply: false,
+            live_apply_ms: 300,
+            mouse: true,
+            theme: "default".to_string(),
+            keys: Keymap::default(),
+            backup_retention: 5,
+            snapshots: false,
+            git_commit: false,
+            git_repo: None,
+            profiles: Vec::new(),
+            palette: Vec::new(),
+            default_profile: None,
+        }
+    }
+}
+
+impl Settings {
+    /// Fails when neither `$XDG_CONFIG_HOME` nor a home directory is set.
+    pub fn settings_path() -> io::Result<PathBuf> {
+        Ok(config_dir()?.join("mako-editor/config.toml"))
+    }
+
+    /// Load settings, falling back to defaults when the file is missing.
+    /// Unknown keys and unparsable values are ignored so an old editor
+    /// never refuses to start because of a newer settings file. Also
+    /// returns why any `[keys]` binding was rejected.
+    pub fn load() -> (Self, Vec<String>) {
+        match Self::settings_path().and_then(fs::read_to_string) {
+            Ok(s) => Self::parse(&s),
+            Err(_) => (Settings::default(), Vec::new()),
+        }
+    }
+
+    /// Settings from the text of a settings file, and an error for each
+    /// `[keys]` binding that collides with another command's key and a
+    /// warning for each setting that no longer does anything.
+    pub fn parse(s: &str) -> (Self, Vec<String>) {
+        let mut settings = Settings::default();
+        let mut binds = Vec::new();
+        let mut errors = Vec::new();
+        for (key, value) in parse_toml(s) {
+            if let Some(warning) = deprecated(&key, &value) {
+                errors.push(warning);
+            }
+            let bind = key.strip_prefix("keys.").and_then(Command::from_name).zip(single_char(&value));
+            match bind {
+                Some(bind) => binds.push(bind),
+                None => {
+                    let _ = settings.set(&key, &value);
+                }
+            }
+        }
+        errors.extend(
+            settings
+                .keys
+                .load(&binds)
+                .into_iter()
+                .map(|(cmd, holder, key)| trf("settings.key_taken", &[&key, &cmd.name(), &holder.name()])),
+        );
+        (settings, errors)
+    }
+
+    pub fn save(&self) -> io::Result<PathBuf> {
+        let path = Self::settings_path()?;
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        write_atomic(&path, &self.to_toml())?;
+        Ok(path)
+    }
+
+    /// Set one setting from its file key (`table.key` inside tables) and
+    /// string value, as read from the file or typed in the settings screen.
+    pub fn set(&mut self, key: &str, value: &str) -> Result<(), String> {
+        let value = value.trim();
+        let invalid = || trf("settings.invalid", &[&value, &key]);
+        match key {
+            // The main loop used to poll; files from then still load.
+            "tick_rate_ms" | "idle_tick_rate_ms" => {}
+            "idle_mode" => self.idle_mode = IdleMode::parse(value).ok_or_else(invalid)?,
+            "auto_reload" => self.auto_reload = parse_bool(value).ok_or_else(invalid)?,
+            "notify_on_save" => self.notify_on_save = parse_bool(value).ok_or_else(invalid)?,
+            "deferred_write" => self.deferred_write = parse_bool(value).ok_or_else(invalid)?,
+            "live_apply" => self.live_apply = parse_bool(value).ok_or_else(invalid)?,
+            "live_apply_ms" => self.live_apply_ms = value.parse().map_err(|_| invalid())?,
+            "mouse" => self.mouse = parse_bool(value).ok_or_else(invalid)?,
+            "theme" => {
+                if !UI_THEMES.contains(&value) {
+                    return Err(invalid());
+                }
+                self.theme = value.to_string();
+            }
+            "backup_retention" => self.backup_retention = value.parse().map_err(|_| invalid())?,
+            "snapshots" => self.snapshots = parse_bool(value).ok_or_else(invalid)?,
+            "git_commit" => self.git_commit = parse_bool(value).ok_or_else(invalid)?,
+            "git_repo" => self.git_repo = (!value.is_empty()).then(|| expand_home(value)),
+            "default_profile" => self.default_profile = (!value.is_empty()).then(|| value.to_string()),
+            _ => {
+                if let Some(name) = key.strip_prefix("keys.") {
+                    let cmd = Command::from_name(name).ok_or_else(|| trf("settings.unknown", &[&key]))?;
+                    let c = single_char(value).ok_or_else(invalid)?;
+                    match self.keys.bound_to(c) {
+                        Some(holder) if holder != cmd => {
+                            return Err(trf("settings.key_taken", &[&c, &cmd.name(), &holder.name()]));
+                        }
+                        _ => self.keys.bind(cmd, c),
+                    }
+                } else if let Some(name) = key.strip_prefix("palette.") {
+                    match self.palette.iter_mut().find(|(n, _)| n == name) {
+                        Some(p) => p.1 = value.to_string(),
+                        None => self.palette.push((name.to_string(), value.to_string())),
+                    }
+                } else if let Some(name) = key.strip_prefix("profiles.") {
+                    let path = expand_home(value);
+                    match self.profiles.iter_mut().find(|(n, _)| n == name) {
+                        Some(p) => p.1 = path,
+                        None => self.profiles.push((name.to_string(), path)),
+                    }
+                } else {
+                    return Err(trf("settings.unknown", &[&key]));
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Every setting as `(file key, current value)`, in file order. This is
+    /// what the settings screen lists.
+    pub fn fields(&self) -> Vec<(String, String)> {
+        let mut out = vec![
+            ("idle_mode".to_string(), self.idle_mode.name().to_string()),
+            ("auto_reload".to_string(), self.auto_reload.to_string()),
+            ("notify_on_save".to_string(), self.notify_on_save.to_string()),
+            ("deferred_write".to_string(), self.deferred_write.to_string()),
+            ("live_apply".to_string(), self.live_apply.to_string()),
+            ("live_apply_ms".to_string(), self.live_apply_ms.to_string()),
+            ("mouse".to_string(), self.mouse.to_string()),
+            ("theme".to_string(), self.theme.clone()),
+            ("backup_retention".to_string(), self.backup_retention.to_string()),
+            ("snapshots".to_string(), self.snapshots.to_string()),
+            ("git_commit".to_string(), self.git_commit.to_string()),
+            ("git_repo".to_string(), self.git_repo.as_ref().map(|p| p.display().to_string()).unwrap_or_default()),
+            ("default_profile".to_string(), self.default_profile.clone().unwrap_or_default()),
+        ];
+        for cmd in Command::ALL {
+            out.push((format!("keys.{}", cmd.name()), self.keys.key(*cmd).to_string()));
+        }
+        for (name, path) in &self.profiles {
+            out.push((format!("profiles.{}", name), path.display().to_string()));
+        }
+        for (name, value) in &self.palette {
+            out.push((format!("palette.{}", name), value.clone()));
+        }
+        out
+    }
+
+    pub fn to_toml(&self) -> String {
+        let mut out = String::new();
+        let mut table = String::new();
+        for (key, value) in self.fields() {
+            let (t, k) = key.split_once('.').unwrap_or(("", &key));
+            if t != table {
+                out.push_str(&format!("\n[{}]\n", t));
+                table = t.to_string();
+            }
+            let quoted = value.parse::<u64>().is_err() && value != "true" && value != "false";
+            if quoted {
+                out.push_str(&format!("{} = \"{}\"\n", k, value.replace('\\', "\\\\").replace('"', "\\\"")));
+            } else {
+                out.push_str(&format!("{} = {}\n", k, value));
+            }
+        }
+        out
+    }
+
+    /// The mako config file to edit at startup: the default profile's path
+    /// when one is configured and known.
+    pub fn profile_path(&self) -> Option<PathBuf> {
+        let name = self.default_profile.as_ref()?;
+        self.profiles.iter().find(|(n, _)| n == name).map(|(_, p)| p.clone())
+    }
+}
+
+/// Directory for what the editor keeps between sessions (such as the
+/// trash): `$XDG_STATE_HOME/mako-editor`, or `~/.local/state/mako-editor`.
+/// Fails when `$XDG_STATE_HOME` is unset and there is no home directory.
+pub fn state_dir() -> io::Result<PathBuf> {
+    let base = match env::var_os("XDG_STATE_HOME") {
+        Some(dir) if !dir.is_empty() => PathBuf::from(dir),
+        _ => home_dir().ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, tr("settings.no_home")))?.join(".local/state"),
+    };
+    Ok(base.join("mako-editor"))
+}
+
+/// Why `key = value` no longer does what it did, for settings left over
+/// from when the main loop polled.
+fn deprecated(key: &str, value: &str) -> Option<String> {
+    match key {
+        "tick_rate_ms" | "idle_tick_rate_ms" => Some(trf("settings.deprecated", &[&key])),
+        "idle_mode" if value.trim().eq_ignore_ascii_case("slow") => Some(tr("settings.idle_slow").to_string()),
+        _ => None,
+    }
+}
+
+/// `s` if it is exactly one character, as a key binding must be.
+fn single_char(s: &str) -> Option<char> {
+    let mut chars = s.trim().chars();
+    match (chars.next(), chars.next()) {
+        (Some(c), None) => Some(c),
+        _ => None,
+    }
+}
+
+fn parse_bool(s: &str) -> Option<bool> {
+    match s.trim().to_lowercase().as_str() {
+        "true" | "1" | "yes" | "on" => Some(true),
+        "false" | "0" | "no" | "off" => Some(false),
+        _ => None,
+    }
+}
+
+/// `~/foo` -> `$HOME/foo`.
+pub fn expand_home(s: &str) -> PathBuf {
+    match (s.strip_prefix("~/"), home_dir()) {
+        (Some(rest), Some(home)) => home.join(rest),
+        _ => PathBuf::from(s),
+    }
+}
+
+/// Parse the small subset of TOML the settings file needs: `[table]`
+/// headers, `key = value` pairs, `#` comments, and basic quoted strings.
+/// Keys inside a table are returned as `table.key`.
+fn parse_toml(s: &str) -> Vec<(String, String)> {
+    toml_entries(s)
+        .into_iter()
+        .map(|(table, key, value)| (if table.is_empty() { key } else { format!("{}.{}", table, key) }, value))
+        .collect()
+}
+
+/// Every `key = value` line of `s` as `(table, key, value)`, with the
+/// table empty before the first `[table]` and quotes taken off names and
+/// strings. This is all the TOML the settings and theme files use.
+pub fn toml_entries(s: &str) -> Vec<(String, String, String)> {
+    let mut out = Vec::new();
+    let mut table = String::new();
+    for line in s.lines() {
+        let line = strip_comment(line).trim();
+        if line.is_empty() {
+            continue;
+        }
+        if line.starts_with('[') && line.ends_with(']') {
+            table = line[1..line.len() - 1].trim().trim_matches('"').to_string();
+            continue;
+        }
+        if let Some(idx) = line.find('=') {
+            let key = line[..idx].trim().trim_matches('"');
+            let mut value = line[idx + 1..].trim().to_string();
+            if value.len() >= 2 && ((value.starts_with('"') && value.ends_with('"')) || (value.starts_with('\'') && value.ends_with('\''))) {
+                value = value[1..value.len() - 1].replace("\\\"", "\"").replace("\\\\", "\\");
+            }
+            out.push((table.clone(), key.to_string(), value));
+        }
+    }
+    out
+}
+
+/// Drop a trailing `# comment`, ignoring `#` inside quoted strings (colors!).
+fn strip_comment(line: &str) -> &str {
+    let mut quote: Option<char> = None;
+    for (i, c) in line.char_indices() {
+        match (quote, c) {
+            (None, '"') | (None, '\'') => quote = Some(c),
+            (Some(q), c) if c == q => quote = None,
+            (None, '#') => return &line[..i],
+            _ => {}
+        }
+    }
+    line
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn a_key_binding_can_not_take_another_commands_key() {
+        let mut settings = Settings::default();
+        assert_eq!(settings.set("keys.edit", "d"), Err(trf("settings.key_taken", &[&'d', &"edit", &"delete"])));
+        assert_eq!(settings.keys.key(Command::Edit), 'e');
+        settings.set("keys.delete", "x").unwrap();
+        settings.set("keys.edit", "d").unwrap();
+        assert_eq!(settings.keys.bound_to('d'), Some(Command::Edit));
+    }
+
+    #[test]
+    fn loading_rejects_colliding_bindings_but_allows_swaps() {
+        let (settings, errors) = Settings::parse("[keys]\nedit = \"d\"\ndelete = \"e\"\n");
+        assert!(errors.is_empty());
+        assert_eq!((settings.keys.key(Command::Edit), settings.keys.key(Command::Delete)), ('d', 'e'));
+
+        let (settings, errors) = Settings::parse("[keys]\nedit = \"d\"\nsearch = \"x\"\n");
+        assert_eq!(errors, [trf("settings.key_taken", &[&'d', &"edit", &"delete"])]);
+        assert_eq!(settings.keys.key(Command::Edit), 'e');
+        assert_eq!(settings.keys.command(crossterm::event::KeyCode::Char('d')), Some(Command::Delete));
+        assert_eq!(settings.keys.key(Command::Search), 'x');
+
+        let keys = Keymap::default();
+        assert!(Command::ALL.iter().all(|c| keys.bound_to(keys.key(*c)) == Some(*c)), "two default keys collide");
+    }
+
+    #[test]
+    fn the_old_polling_settings_load_with_a_warning() {
+        let (settings, errors) = Settings::parse("tick_rate_ms = 120\nidle_mode = \"block\"\nidle_tick_rate_ms = 1000\n");
+        assert_eq!(settings.idle_mode, IdleMode::Block);
+        assert_eq!(
+            errors,
+            [trf("settings.deprecated", &[&"tick_rate_ms"]), trf("settings.deprecated", &[&"idle_tick_rate_ms"])]
+        );
+
+        let (settings, errors) = Settings::parse("idle_mode = \"slow\"\n");
+        assert_eq!(settings.idle_mode, IdleMode::Poll);
+        assert_eq!(errors, [tr("settings.idle_slow")]);
+        assert!(Settings::parse(&settings.to_toml()).1.is_empty());
+    }
+}
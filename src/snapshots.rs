@@ -0,0 +1,100 @@
+//! Snapshots: with the `snapshots` setting on, every save also keeps a
+//! copy of the file, named by when it was taken, under
+//! `$XDG_STATE_HOME/mako-editor/snapshots/`. Unlike the numbered backups
+//! next to the config, which rotate out after `backup_retention` saves,
+//! they last until `LIMIT` newer ones have been taken.
+
+use std::{
+    cmp::Reverse,
+    fs, io,
+    path::{Path, PathBuf},
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use crate::config::Config;
+use crate::settings::state_dir;
+
+/// Snapshots kept per config file; older ones are deleted.
+const LIMIT: usize = 200;
+
+/// One saved version of the config.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Snapshot {
+    pub path: PathBuf,
+    pub taken: SystemTime,
+    pub text: String,
+    /// How many params it holds, counting every section.
+    pub keys: usize,
+}
+
+/// The snapshots of `config`, one directory per config file (profiles get
+/// their own), named after its path: `%home%me%.config%mako%config`.
+pub fn dir(config: &Path) -> io::Result<PathBuf> {
+    Ok(state_dir()?.join("snapshots").join(config.display().to_string().replace('/', "%")))
+}
+
+/// Copy the file at `config` into its snapshots, unless the newest one
+/// already has the same text. Returns the new snapshot's path.
+pub fn record(config: &Path) -> io::Result<Option<PathBuf>> {
+    record_in(&dir(config)?, &fs::read_to_string(config)?, SystemTime::now())
+}
+
+fn record_in(dir: &Path, text: &str, now: SystemTime) -> io::Result<Option<PathBuf>> {
+    let existing = list_in(dir);
+    if existing.first().is_some_and(|s| s.text == text) {
+        return Ok(None);
+    }
+    fs::create_dir_all(dir)?;
+    let millis = now.duration_since(UNIX_EPOCH).unwrap_or_default().as_millis();
+    let path = dir.join(millis.to_string());
+    fs::write(&path, text)?;
+    for old in existing.iter().skip(LIMIT - 1) {
+        let _ = fs::remove_file(&old.path);
+    }
+    Ok(Some(path))
+}
+
+/// The snapshots of `config`, newest first; none without a home
+/// directory to keep them in.
+pub fn list(config: &Path) -> Vec<Snapshot> {
+    dir(config).map(|dir| list_in(&dir)).unwrap_or_default()
+}
+
+fn list_in(dir: &Path) -> Vec<Snapshot> {
+    let Ok(entries) = fs::read_dir(dir) else { return Vec::new() };
+    let mut out: Vec<Snapshot> = entries
+        .flatten()
+        .filter_map(|e| {
+            let millis: u64 = e.file_name().to_str()?.parse().ok()?;
+            let text = fs::read_to_string(e.path()).ok()?;
+            let Ok(cfg) = text.parse::<Config>();
+            let keys = cfg.params.len() + cfg.sections.iter().map(|s| s.params.len()).sum::<usize>();
+            Some(Snapshot { path: e.path(), taken: UNIX_EPOCH + Duration::from_millis(millis), text, keys })
+        })
+        .collect();
+    out.sort_by_key(|s| Reverse(s.taken));
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn records_changed_versions_newest_first_up_to_the_limit() {
+        let dir = std::env::temp_dir().join(format!("mako-tui-snapshots-{}", std::process::id()));
+        let at = |s: u64| UNIX_EPOCH + Duration::from_secs(s);
+        assert!(record_in(&dir, "width=300\n", at(1)).unwrap().is_some());
+        assert_eq!(record_in(&dir, "width=300\n", at(2)).unwrap(), None);
+        record_in(&dir, "width=300\nheight=100\n", at(3)).unwrap();
+        let snaps = list_in(&dir);
+        assert_eq!(snaps.iter().map(|s| (s.taken, s.keys)).collect::<Vec<_>>(), [(at(3), 2), (at(1), 1)]);
+        for s in 4..LIMIT as u64 + 10 {
+            record_in(&dir, &format!("width={}\n", s), at(s)).unwrap();
+        }
+        let snaps = list_in(&dir);
+        assert_eq!(snaps.len(), LIMIT);
+        assert_eq!(snaps[0].text, format!("width={}\n", LIMIT + 9));
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,71 @@
+//! Importing a mako config pasted into the editor, say a snippet from
+//! someone's rice: each line becomes a `Mapping` for the import review,
+//! its `from` what the config has now, so the review doubles as a merge
+//! preview. Lines that change nothing start out deselected.
+
+use crate::config::{Config, Criteria, Param};
+use crate::dunst::Mapping;
+use crate::i18n::trf;
+use crate::mako_config::{normalize_value, validate};
+
+/// What pasting `text` into `cfg` would change, line by line.
+pub fn review(text: &str, cfg: &Config) -> Vec<Mapping> {
+    let Ok(pasted) = text.parse::<Config>();
+    let sections = pasted.sections.iter().map(|s| (Some(&s.criteria), &s.params));
+    let mut out = Vec::new();
+    for (criteria, params) in std::iter::once((None, &pasted.params)).chain(sections) {
+        for p in params {
+            out.push(mapping(cfg, criteria, p));
+        }
+    }
+    out
+}
+
+fn mapping(cfg: &Config, criteria: Option<&Criteria>, p: &Param) -> Mapping {
+    let key = match criteria {
+        Some(c) => format!("[{}] {}", c, p.key),
+        None => p.key.clone(),
+    };
+    let section = criteria.cloned();
+    let value = normalize_value(&p.key, &p.value);
+    if validate(&p.key, &cfg.expand(&value)).is_err() {
+        let from = format!("{}={}", key, p.value);
+        return Mapping { from, section, param: None, skipped: Some("import.bad_value"), enabled: false };
+    }
+    let params = match criteria {
+        Some(c) => cfg.sections.iter().find(|s| s.criteria == *c).map(|s| s.params.as_slice()),
+        None => Some(cfg.params.as_slice()),
+    };
+    let current = params.and_then(|params| params.iter().rev().find(|q| q.key == p.key));
+    let from = match current {
+        Some(q) => format!("{}={}", key, q.value),
+        None => trf("import.not_set", &[&key]),
+    };
+    let enabled = current.is_none_or(|q| q.value != value);
+    Mapping { from, section, param: Some(Param::new(&p.key, value)), skipped: None, enabled }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn pasted_lines_are_compared_with_the_config() {
+        let cfg: Config = "border-size=2\nwidth=300\n[urgency=critical]\nborder-color=#ff0000\n".parse().unwrap();
+        let pasted = "# from r/unixporn\nborder-size=4\nwidth=300\nheight=wide\nfont=Iosevka 11\n\n[urgency=critical]\nborder-color=#BF616A\n";
+        let mappings = review(pasted, &cfg);
+        let summary: Vec<(&str, bool)> = mappings.iter().map(|m| (m.from.as_str(), m.enabled)).collect();
+        assert_eq!(
+            summary,
+            [
+                ("border-size=2", true),
+                ("width=300", false),
+                ("height=wide", false),
+                ("font (not set)", true),
+                ("[urgency=critical] border-color=#ff0000", true),
+            ]
+        );
+        assert_eq!(mappings[4].param, Some(Param::new("border-color", "#bf616a")));
+        assert_eq!(mappings[2].param, None);
+    }
+}
@@ -0,0 +1,52 @@
+//! What the header reports about the outside world: whether mako is
+//! running and when the config file last changed on disk.
+
+use std::{
+    fs,
+    path::Path,
+    time::{Duration, SystemTime},
+};
+
+/// How often the runtime re-probes the status.
+pub const REFRESH: Duration = Duration::from_secs(2);
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Status {
+    /// Whether a `mako` process is running; `None` when that can't be told
+    /// (no `/proc`).
+    pub daemon_running: Option<bool>,
+    /// Last-modified time of the config file; `None` if it doesn't exist.
+    pub modified: Option<SystemTime>,
+}
+
+impl Status {
+    pub fn probe(config: &Path) -> Self {
+        Status { daemon_running: mako_running(), modified: fs::metadata(config).and_then(|m| m.modified()).ok() }
+    }
+
+    /// How long ago the config file changed, as the header shows it.
+    pub fn age(&self) -> Option<String> {
+        self.modified.and_then(|m| m.elapsed().ok()).map(short_duration)
+    }
+}
+
+/// Look for a process named `mako` in `/proc`. Cheaper than spawning
+/// `pgrep` every couple of seconds.
+fn mako_running() -> Option<bool> {
+    let procs = fs::read_dir("/proc").ok()?;
+    Some(procs.filter_map(Result::ok).any(|e| {
+        e.file_name().to_string_lossy().bytes().all(|b| b.is_ascii_digit())
+            && fs::read_to_string(e.path().join("comm")).is_ok_and(|c| c.trim_end() == "mako")
+    }))
+}
+
+/// `d` as a short age: `42s`, `5m`, `3h`, `2d`.
+pub fn short_duration(d: Duration) -> String {
+    let s = d.as_secs();
+    match s {
+        0..60 => format!("{}s", s),
+        60..3600 => format!("{}m", s / 60),
+        3600..86400 => format!("{}h", s / 3600),
+        _ => format!("{}d", s / 86400),
+    }
+}
@@ -0,0 +1,61 @@
+//! Spelling suggestions for key names.
+
+/// Candidates close enough to `name` to probably be what was meant, best
+/// first. Exact matches aren't suggestions, and at most `MAX` are returned.
+pub fn did_you_mean<'a>(name: &str, candidates: impl IntoIterator<Item = &'a str>) -> Vec<String> {
+    const MAX: usize = 3;
+    let name = name.to_lowercase();
+    // Allow about one typo per four characters, and at least two.
+    let limit = (name.chars().count() / 4).max(2);
+    let mut scored: Vec<(usize, &str)> = Vec::new();
+    for c in candidates {
+        let d = edit_distance(&name, &c.to_lowercase());
+        if d > 0 && d <= limit && !scored.iter().any(|(_, s)| *s == c) {
+            scored.push((d, c));
+        }
+    }
+    scored.sort_by_key(|(d, _)| *d);
+    scored.into_iter().take(MAX).map(|(_, c)| c.to_string()).collect()
+}
+
+/// Levenshtein distance, counting a swap of two neighbours as one edit.
+pub fn edit_distance(a: &str, b: &str) -> usize {
+    let a: Vec<char> = a.chars().collect();
+    let b: Vec<char> = b.chars().collect();
+    let mut prev2: Vec<usize> = vec![0; b.len() + 1];
+    let mut prev: Vec<usize> = (0..=b.len()).collect();
+    for i in 1..=a.len() {
+        let mut cur = vec![i; b.len() + 1];
+        for j in 1..=b.len() {
+            let cost = usize::from(a[i - 1] != b[j - 1]);
+            cur[j] = (prev[j] + 1).min(cur[j - 1] + 1).min(prev[j - 1] + cost);
+            if i > 1 && j > 1 && a[i - 1] == b[j - 2] && a[i - 2] == b[j - 1] {
+                cur[j] = cur[j].min(prev2[j - 2] + 1);
+            }
+        }
+        prev2 = std::mem::replace(&mut prev, cur);
+    }
+    prev[b.len()]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn suggests_close_known_keys() {
+        let keys = ["border-color", "border-size", "background-color", "font"];
+        assert_eq!(did_you_mean("boarder-color", keys), vec!["border-color"]);
+        assert_eq!(did_you_mean("fnot", keys), vec!["font"]);
+        assert!(did_you_mean("font", keys).is_empty());
+        assert!(did_you_mean("my-own-key", keys).is_empty());
+    }
+
+    #[test]
+    fn distance_counts_transpositions_once() {
+        assert_eq!(edit_distance("border", "border"), 0);
+        assert_eq!(edit_distance("boarder", "border"), 1);
+        assert_eq!(edit_distance("fnot", "font"), 1);
+        assert_eq!(edit_distance("", "abc"), 3);
+    }
+}
@@ -0,0 +1,363 @@
+//! Importing a SwayNotificationCenter setup: the popup settings of its
+//! `config.json` (position, layer, timeouts, width, icons, per-app
+//! visibility) and the colors and shape `style.css` gives notifications,
+//! reviewed like a dunstrc import.
+
+use std::{fs, io, path::{Path, PathBuf}};
+
+use crate::config::{Condition, Criteria, Param};
+use crate::dunst::Mapping;
+use crate::i18n::trf;
+use crate::json::{self, Json};
+
+/// Where swaync reads its config: `$XDG_CONFIG_HOME/swaync/config.json`.
+pub fn default_path() -> io::Result<PathBuf> {
+    Ok(crate::settings::config_dir()?.join("swaync/config.json"))
+}
+
+/// Whether the import prompt's `path` is a swaync config rather than a
+/// dunstrc: its `config.json` or `style.css`, or their directory.
+pub fn is_swaync(path: &Path) -> bool {
+    path.is_dir() || path.extension().is_some_and(|e| e == "json" || e == "css")
+}
+
+/// Read and translate the `config.json` and `style.css` next to `path`
+/// (either may be missing, not both).
+pub fn read(path: &Path) -> Result<Vec<Mapping>, String> {
+    let dir = if path.is_dir() { path } else { path.parent().unwrap_or(Path::new(".")) };
+    let config = fs::read_to_string(dir.join("config.json"));
+    let style = fs::read_to_string(dir.join("style.css"));
+    if let (Err(e), Err(_)) = (&config, &style) {
+        return Err(trf("import.read_failed", &[&dir.join("config.json").display(), &e]));
+    }
+    let mut out = match &config {
+        Ok(text) => import_config(text)?,
+        Err(_) => Vec::new(),
+    };
+    if let Ok(text) = &style {
+        out.extend(import_style(text));
+    }
+    Ok(out)
+}
+
+/// Translate `config.json`. Top-level settings become global params, the
+/// low and critical timeouts urgency sections, and `notification-visibility`
+/// rules sections on what they match.
+pub fn import_config(text: &str) -> Result<Vec<Mapping>, String> {
+    let value = json::parse(text).map_err(|e| match e {
+        json::Error::At(pos) => trf("import.bad_json", &[&pos]),
+        json::Error::Truncated => trf("import.bad_json", &[&text.len()]),
+    })?;
+    let Json::Object(fields) = &value else { return Err(trf("import.bad_json", &[&0])) };
+    let mut out = Vec::new();
+    let position = (value.get("positionX"), value.get("positionY"));
+    for (key, v) in fields {
+        let from = format!("config.json: \"{}\": {}", key, show(v));
+        let one = |mako: &str, value: String, section: Option<Criteria>| Mapping {
+            from: from.clone(),
+            section,
+            param: Some(Param::new(mako, value)),
+            skipped: None,
+            enabled: true,
+        };
+        let skip = |why| Mapping { from: from.clone(), section: None, param: None, skipped: Some(why), enabled: false };
+        let seconds = |level: Option<&str>| match v {
+            Json::Number(secs) if *secs >= 0.0 => one("default-timeout", ((secs * 1000.0).round() as u64).to_string(), level.map(urgency)),
+            _ => skip("import.bad_value"),
+        };
+        let mapping = match (key.as_str(), v) {
+            ("$schema" | "cssPriority", _) => continue,
+            // One anchor from both; `positionY` is listed with `positionX`.
+            ("positionY", _) if position.0.is_some() => continue,
+            ("positionX" | "positionY", _) => {
+                let text = |v: Option<&Json>| match v {
+                    Some(Json::String(s)) => s.clone(),
+                    _ => "center".to_string(),
+                };
+                let (x, y) = (text(position.0), text(position.1));
+                let from = match position {
+                    (Some(x), Some(y)) => format!("config.json: \"positionX\": {}, \"positionY\": {}", show(x), show(y)),
+                    _ => from.clone(),
+                };
+                match anchor(&x, &y) {
+                    Some(a) => Mapping { from, ..one("anchor", a.to_string(), None) },
+                    None => Mapping { from, ..skip("import.bad_value") },
+                }
+            }
+            ("layer", Json::String(layer)) => one("layer", layer.clone(), None),
+            ("timeout", _) => seconds(None),
+            ("timeout-low", _) => seconds(Some("low")),
+            ("timeout-critical", _) => seconds(Some("critical")),
+            ("notification-window-width", Json::Number(w)) => one("width", w.to_string(), None),
+            ("notification-icon-size", Json::Number(size)) => one("max-icon-size", size.to_string(), None),
+            ("image-visibility", Json::String(v)) if v == "never" => one("icons", "0".to_string(), None),
+            ("image-visibility", Json::String(_)) => one("icons", "1".to_string(), None),
+            ("notification-visibility", Json::Object(rules)) => {
+                out.extend(rules.iter().flat_map(|(name, rule)| import_rule(name, rule)));
+                continue;
+            }
+            _ => skip("import.no_equivalent"),
+        };
+        out.push(mapping);
+    }
+    Ok(out)
+}
+
+/// A `notification-visibility` rule: swaync matches regexes, as mako's
+/// `~=` does. `ignored` and `muted` hide the popup; `transient` keeps it
+/// out of the history.
+fn import_rule(name: &str, rule: &Json) -> Vec<Mapping> {
+    let from = format!("config.json: notification-visibility \"{}\"", name);
+    let state = match rule.get("state") {
+        Some(Json::String(s)) => s.as_str(),
+        _ => "enabled",
+    };
+    let mut conditions = Vec::new();
+    for (field, key) in [("app-name", "app-name"), ("summary", "summary"), ("body", "body"), ("category", "category")] {
+        if let Some(Json::String(pattern)) = rule.get(field) {
+            conditions.push(Condition { key: key.to_string(), regex: true, value: Some(pattern.clone()) });
+        }
+    }
+    if let Some(Json::String(level)) = rule.get("urgency") {
+        conditions.push(Condition { key: "urgency".to_string(), regex: false, value: Some(level.to_lowercase()) });
+    }
+    let param = match state {
+        "ignored" | "muted" => Some(Param::new("invisible", "1")),
+        "transient" => Some(Param::new("history", "0")),
+        _ => None,
+    };
+    let skipped = match (&param, conditions.is_empty()) {
+        (None, _) => Some("import.no_equivalent"),
+        (Some(_), true) => Some("import.unmatched_rule"),
+        (Some(_), false) => None,
+    };
+    let param = param.filter(|_| skipped.is_none());
+    let section = (!conditions.is_empty()).then_some(Criteria { conditions });
+    vec![Mapping { from: format!("{} ({})", from, state), section, enabled: param.is_some(), param, skipped }]
+}
+
+/// The notification styling of `style.css`: the `noti-*` colors of the
+/// default theme and the `.notification` rule's own properties.
+pub fn import_style(text: &str) -> Vec<Mapping> {
+    let text = strip_comments(text);
+    let mut colors: Vec<(String, String)> = Vec::new();
+    let mut out = Vec::new();
+    let mut rest = text.as_str();
+    while !rest.trim().is_empty() {
+        let trimmed = rest.trim_start();
+        if let Some(define) = trimmed.strip_prefix("@define-color") {
+            let (line, after) = define.split_once(';').unwrap_or((define, ""));
+            if let Some((name, value)) = line.trim().split_once(char::is_whitespace) {
+                let value = value.trim().to_string();
+                let mako = match name {
+                    "noti-bg" => Some("background-color"),
+                    "noti-border-color" => Some("border-color"),
+                    "text-color" => Some("text-color"),
+                    _ => None,
+                };
+                if let Some(mako) = mako {
+                    out.push(style_mapping(format!("style.css: @define-color {} {}", name, value), mako, color(&value, &colors)));
+                }
+                colors.push((name.to_string(), value));
+            }
+            rest = after;
+            continue;
+        }
+        let Some((selector, after)) = trimmed.split_once('{') else { break };
+        let (body, after) = after.split_once('}').unwrap_or((after, ""));
+        rest = after;
+        if !is_notification(selector) {
+            continue;
+        }
+        let selector = selector.split_whitespace().collect::<Vec<_>>().join(" ");
+        for decl in body.split(';') {
+            let Some((prop, value)) = decl.split_once(':') else { continue };
+            let (prop, value) = (prop.trim(), value.trim().trim_end_matches("!important").trim());
+            let from = format!("style.css: {} {{ {}: {} }}", selector, prop, value);
+            match prop {
+                "background" | "background-color" => out.push(style_mapping(from, "background-color", color(value, &colors))),
+                "color" => out.push(style_mapping(from, "text-color", color(value, &colors))),
+                "border-color" => out.push(style_mapping(from, "border-color", color(value, &colors))),
+                "border-radius" => out.push(style_mapping(from, "border-radius", pixels(value))),
+                "border-width" => out.push(style_mapping(from, "border-size", pixels(value))),
+                "padding" => out.push(style_mapping(from, "padding", pixels_list(value))),
+                "margin" => out.push(style_mapping(from, "margin", pixels_list(value))),
+                // `border: 1px solid #fff`, in any order.
+                "border" => {
+                    let words: Vec<&str> = value.split_whitespace().collect();
+                    let width = words.iter().find_map(|w| pixels(w));
+                    let col = words.iter().find_map(|w| color(w, &colors));
+                    if width.is_some() || col.is_some() {
+                        if let Some(width) = width {
+                            out.push(style_mapping(from.clone(), "border-size", Some(width)));
+                        }
+                        if let Some(col) = col {
+                            out.push(style_mapping(from, "border-color", Some(col)));
+                        }
+                    } else {
+                        out.push(style_mapping(from, "border-size", None));
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+    out
+}
+
+fn style_mapping(from: String, key: &str, value: Option<String>) -> Mapping {
+    match value {
+        Some(v) => Mapping { from, section: None, param: Some(Param::new(key, v)), skipped: None, enabled: true },
+        None => Mapping { from, section: None, param: None, skipped: Some("import.bad_value"), enabled: false },
+    }
+}
+
+/// Whether a rule styles the notification itself: its last compound
+/// selector has the `notification` class (not `notification-row` and
+/// the like), as in `.notification-row .notification`.
+fn is_notification(selector: &str) -> bool {
+    selector.split(',').any(|s| {
+        s.split_whitespace().last().is_some_and(|last| last.split(['.', ':']).skip(1).any(|class| class == "notification"))
+    })
+}
+
+fn strip_comments(text: &str) -> String {
+    let mut out = String::new();
+    let mut rest = text;
+    while let Some(start) = rest.find("/*") {
+        out.push_str(&rest[..start]);
+        rest = rest[start + 2..].split_once("*/").map_or("", |(_, after)| after);
+    }
+    out.push_str(rest);
+    out
+}
+
+/// A CSS color as mako writes it: `#rrggbb` or `#rrggbbaa`, following
+/// `@name` references to `@define-color`s.
+fn color(value: &str, colors: &[(String, String)]) -> Option<String> {
+    let value = value.trim();
+    if let Some(name) = value.strip_prefix('@') {
+        let i = colors.iter().rposition(|(n, _)| n == name)?;
+        return color(&colors[i].1, &colors[..i]);
+    }
+    if let Some(hex) = value.strip_prefix('#') {
+        let hex: String = match hex.len() {
+            3 | 4 => hex.chars().flat_map(|c| [c, c]).collect(),
+            6 | 8 => hex.to_string(),
+            _ => return None,
+        };
+        return hex.chars().all(|c| c.is_ascii_hexdigit()).then(|| format!("#{}", hex.to_lowercase()));
+    }
+    let args = value.strip_prefix("rgba(").or_else(|| value.strip_prefix("rgb("))?.strip_suffix(')')?;
+    let parts: Vec<&str> = args.split(',').map(str::trim).collect();
+    let channel = |s: &str| s.parse::<f64>().ok().filter(|v| (0.0..=255.0).contains(v)).map(|v| v.round() as u8);
+    let (r, g, b) = (channel(parts.first()?)?, channel(parts.get(1)?)?, channel(parts.get(2)?)?);
+    match parts.get(3) {
+        None => Some(format!("#{:02x}{:02x}{:02x}", r, g, b)),
+        Some(alpha) => {
+            let alpha = alpha.parse::<f64>().ok().filter(|a| (0.0..=1.0).contains(a))?;
+            Some(format!("#{:02x}{:02x}{:02x}{:02x}", r, g, b, (alpha * 255.0).round() as u8))
+        }
+    }
+}
+
+/// `12px` (or a bare `0`) as mako's pixel count.
+fn pixels(value: &str) -> Option<String> {
+    let value = value.trim();
+    let n: f64 = match value.strip_suffix("px") {
+        Some(n) => n.parse().ok()?,
+        None if value == "0" => 0.0,
+        None => return None,
+    };
+    Some((n.round() as i64).to_string())
+}
+
+/// CSS's `top right bottom left` shorthand as mako's comma list.
+fn pixels_list(value: &str) -> Option<String> {
+    let parts: Option<Vec<String>> = value.split_whitespace().map(pixels).collect();
+    parts.filter(|p| (1..=4).contains(&p.len())).map(|p| p.join(","))
+}
+
+fn anchor(x: &str, y: &str) -> Option<&'static str> {
+    Some(match (y, x) {
+        ("top", "left") => "top-left",
+        ("top", "center") => "top-center",
+        ("top", "right") => "top-right",
+        ("bottom", "left") => "bottom-left",
+        ("bottom", "center") => "bottom-center",
+        ("bottom", "right") => "bottom-right",
+        ("center", "left") => "center-left",
+        ("center", "right") => "center-right",
+        ("center", "center") => "center",
+        _ => return None,
+    })
+}
+
+fn urgency(level: &str) -> Criteria {
+    Criteria { conditions: vec![Condition { key: "urgency".to_string(), regex: false, value: Some(level.to_string()) }] }
+}
+
+/// A config value as the review list shows it.
+fn show(value: &Json) -> String {
+    match value {
+        Json::Null => "null".to_string(),
+        Json::Bool(b) => b.to_string(),
+        Json::Number(n) => n.to_string(),
+        Json::String(s) => format!("\"{}\"", s),
+        Json::Array(_) => "[…]".to_string(),
+        Json::Object(_) => "{…}".to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn describe(mappings: &[Mapping]) -> Vec<String> {
+        mappings.iter().map(crate::dunst::describe).collect()
+    }
+
+    #[test]
+    fn translates_config_json_and_style_css() {
+        let config = r#"{
+            "$schema": "/etc/xdg/swaync/configSchema.json",
+            "positionX": "right",
+            "positionY": "top",
+            "layer": "overlay",
+            "timeout": 10,
+            "timeout-low": 5,
+            "timeout-critical": 0,
+            "notification-window-width": 400,
+            "image-visibility": "never",
+            "widgets": ["title", "dnd"],
+            "notification-visibility": {
+                "spotify": {"state": "ignored", "app-name": "Spotify"},
+                "example": {"state": "enabled", "app-name": "x"}
+            }
+        }"#;
+        let mappings = import_config(config).unwrap();
+        assert_eq!(
+            describe(&mappings),
+            [
+                "anchor=top-right",
+                "layer=overlay",
+                "default-timeout=10000",
+                "[urgency=low] default-timeout=5000",
+                "[urgency=critical] default-timeout=0",
+                "width=400",
+                "icons=0",
+                "no mako equivalent",
+                "[app-name~=Spotify] invisible=1",
+                "no mako equivalent",
+            ]
+        );
+        assert_eq!(mappings[0].from, "config.json: \"positionX\": \"right\", \"positionY\": \"top\"");
+        assert!(import_config("[1, 2").is_err());
+
+        let style = "@define-color noti-bg rgba(48, 48, 48, 0.8);\n@define-color accent #abc;\n/* .notification { color: red } */\n.notification-row { margin: 4px; }\n.notification-row .notification {\n  border-radius: 12px;\n  border: 2px solid @accent;\n  padding: 4px 8px;\n  color: white;\n}\n";
+        assert_eq!(
+            describe(&import_style(style)),
+            ["background-color=#303030cc", "border-radius=12", "border-size=2", "border-color=#aabbcc", "padding=4,8", "value not understood"]
+        );
+    }
+}
@@ -0,0 +1,177 @@
+use std::io::{self, Write};
+use std::panic;
+use std::path::PathBuf;
+use std::process;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::sync::Arc;
+use std::thread::{self, Thread};
+use std::time::Duration;
+
+use mako_tui::git::Commit;
+use mako_tui::icons;
+use mako_tui::notifications::Notification;
+
+use crossterm::{
+    cursor::MoveTo,
+    event::{self, DisableBracketedPaste, DisableMouseCapture, EnableBracketedPaste, EnableMouseCapture, Event},
+    execute, queue,
+    style::Print,
+    terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
+};
+use ratatui::layout::Rect;
+use signal_hook::{
+    consts::{SIGHUP, SIGINT, SIGQUIT, SIGTERM},
+    iterator::Signals,
+};
+
+/// Put the terminal into raw mode on the alternate screen, with
+/// bracketed paste so a paste arrives as one `Event::Paste` instead of a
+/// burst of key presses that could trigger bindings, and mouse reporting
+/// if `mouse` is set.
+pub fn setup(mouse: bool) -> io::Result<()> {
+    enable_raw_mode()?;
+    execute!(io::stdout(), EnterAlternateScreen, EnableBracketedPaste)?;
+    if mouse {
+        execute!(io::stdout(), EnableMouseCapture)?;
+    }
+    Ok(())
+}
+
+/// Undo `setup()`. Safe to call more than once; errors are ignored because
+/// this also runs from the panic hook and signal handler where there is
+/// nothing sensible left to do with them.
+pub fn restore() {
+    let _ = disable_raw_mode();
+    let _ = execute!(io::stdout(), DisableMouseCapture, DisableBracketedPaste, LeaveAlternateScreen);
+}
+
+/// Bring the inline image on screen in line with `wanted`, as laid out by
+/// the last frame. `shown` tracks what is displayed so an unchanged image
+/// isn't re-sent every frame.
+pub fn sync_image(shown: &mut Option<(PathBuf, Rect)>, wanted: &Option<(PathBuf, Rect)>) -> io::Result<()> {
+    if shown == wanted {
+        return Ok(());
+    }
+    let mut out = io::stdout();
+    if shown.is_some() {
+        queue!(out, Print(icons::kitty_clear()))?;
+    }
+    if let Some((path, area)) = wanted {
+        queue!(out, MoveTo(area.x, area.y), Print(icons::kitty_show(path, area.width, area.height)))?;
+    }
+    out.flush()?;
+    *shown = wanted.clone();
+    Ok(())
+}
+
+/// What wakes the main loop up.
+#[derive(Debug)]
+pub enum Wake {
+    /// A terminal event, or the error that ended reading them.
+    Input(io::Result<Event>),
+    /// Time to probe the daemon and the config file again.
+    Refresh,
+    /// A background `makoctl reload` finished. `previous` is the file as it
+    /// was before the save, to roll back to if mako rejected the new one,
+    /// and `quit` whether the editor quits once it hasn't.
+    Reloaded { result: Result<String, String>, previous: Option<String>, quit: bool },
+    /// A background notification about a save was sent, or failed to be.
+    Notified(Result<(), String>),
+    /// The test notification was sent, or failed to be.
+    Sent(Result<(), String>),
+    /// The progress preview filled its bar, or failed to be sent.
+    Progressed(Result<(), String>),
+    /// A background `makoctl list` finished, after the dismiss or invoke
+    /// before it failed with `failed` if it did.
+    Listed { result: Result<Vec<Notification>, String>, failed: Option<String> },
+    /// A background `makoctl history` finished, likewise after a restore.
+    History { result: Result<Vec<Notification>, String>, failed: Option<String> },
+    /// A background `git::history` of the config finished.
+    GitLog(Result<Vec<Commit>, String>),
+    /// The plugins' post-save actions ran, failing with these errors.
+    Hooked(Vec<String>),
+    /// A background git commit of a save finished: whether there was
+    /// anything to commit, with its message.
+    Committed { message: String, result: Result<bool, String> },
+}
+
+/// Turns the `Wake::Refresh` ticker of `spawn_events` on and off. It
+/// starts off, and sleeps without waking while off.
+pub struct Ticker {
+    on: Arc<AtomicBool>,
+    thread: Thread,
+}
+
+impl Ticker {
+    /// Tick while `on`; turning it on sends a `Wake::Refresh` right away.
+    pub fn set(&self, on: bool) {
+        if self.on.swap(on, Ordering::Relaxed) != on && on {
+            self.thread.unpark();
+        }
+    }
+}
+
+/// Read terminal events on a background thread and deliver them over a
+/// channel, with a `Wake::Refresh` every `refresh` in between while the
+/// returned `Ticker` is on, so the main loop sleeps until there is
+/// something to draw. The returned sender is for the main loop's own
+/// background work. The input thread ends when the receiver is dropped or
+/// reading fails; a read error is forwarded as its last message.
+pub fn spawn_events(refresh: Duration) -> (Sender<Wake>, Receiver<Wake>, Ticker) {
+    let (tx, rx) = mpsc::channel();
+    let ticker = tx.clone();
+    let wake = tx.clone();
+    let on = Arc::new(AtomicBool::new(false));
+    let ticking = on.clone();
+    thread::spawn(move || {
+        loop {
+            let ev = event::read();
+            let failed = ev.is_err();
+            if tx.send(Wake::Input(ev)).is_err() || failed {
+                break;
+            }
+        }
+    });
+    let thread = thread::spawn(move || {
+        loop {
+            if !ticking.load(Ordering::Relaxed) {
+                thread::park();
+            } else if ticker.send(Wake::Refresh).is_ok() {
+                thread::sleep(refresh);
+            } else {
+                break;
+            }
+        }
+    })
+    .thread()
+    .clone();
+    (wake, rx, Ticker { on, thread })
+}
+
+/// Restore the terminal before the default panic hook prints its message,
+/// so the message lands on the normal screen instead of being wiped along
+/// with the alternate one.
+pub fn install_panic_hook() {
+    let default_hook = panic::take_hook();
+    panic::set_hook(Box::new(move |info| {
+        restore();
+        default_hook(info);
+    }));
+}
+
+/// Restore the terminal and exit when the process is asked to terminate.
+///
+/// Raw mode swallows Ctrl-C, but SIGTERM/SIGHUP (closing the terminal,
+/// `kill`, a tmux pane going away) still arrive and would otherwise leave
+/// the shell in raw mode on the alternate screen.
+pub fn install_signal_handlers() -> io::Result<()> {
+    let mut signals = Signals::new([SIGTERM, SIGINT, SIGHUP, SIGQUIT])?;
+    thread::spawn(move || {
+        if let Some(sig) = signals.forever().next() {
+            restore();
+            process::exit(128 + sig);
+        }
+    });
+    Ok(())
+}
@@ -0,0 +1,233 @@
+//! Curated style presets: colors, font and borders from popular color
+//! schemes, and theme files of the user's own for `mako-tui apply`.
+
+use std::{fs, io, path::{Path, PathBuf}};
+
+use crate::config::{Condition, Config, Criteria, Param, Section};
+use crate::i18n::trf;
+use crate::settings::{config_dir, toml_entries};
+
+/// A named set of style params, plus the border color critical
+/// notifications get.
+pub struct Preset {
+    pub name: &'static str,
+    pub params: &'static [(&'static str, &'static str)],
+    pub critical_border: &'static str,
+}
+
+/// The keys a preset may change. Everything else (timeouts, anchor,
+/// grouping, actions, ...) is behavior and left alone.
+pub const STYLE_KEYS: &[&str] = &[
+    "font",
+    "background-color",
+    "text-color",
+    "border-color",
+    "border-size",
+    "border-radius",
+    "progress-color",
+    "padding",
+];
+
+pub const PRESETS: &[Preset] = &[
+    Preset {
+        name: "Nord",
+        params: &[
+            ("font", "sans-serif 10"),
+            ("background-color", "#2e3440"),
+            ("text-color", "#d8dee9"),
+            ("border-color", "#88c0d0"),
+            ("progress-color", "over #5e81ac"),
+            ("border-size", "2"),
+            ("border-radius", "6"),
+        ],
+        critical_border: "#bf616a",
+    },
+    Preset {
+        name: "Dracula",
+        params: &[
+            ("font", "monospace 10"),
+            ("background-color", "#282a36"),
+            ("text-color", "#f8f8f2"),
+            ("border-color", "#bd93f9"),
+            ("progress-color", "over #44475a"),
+            ("border-size", "2"),
+            ("border-radius", "8"),
+        ],
+        critical_border: "#ff5555",
+    },
+    Preset {
+        name: "Gruvbox",
+        params: &[
+            ("font", "monospace 10"),
+            ("background-color", "#282828"),
+            ("text-color", "#ebdbb2"),
+            ("border-color", "#d79921"),
+            ("progress-color", "over #504945"),
+            ("border-size", "2"),
+            ("border-radius", "0"),
+        ],
+        critical_border: "#cc241d",
+    },
+    Preset {
+        name: "Catppuccin Mocha",
+        params: &[
+            ("font", "sans-serif 11"),
+            ("background-color", "#1e1e2e"),
+            ("text-color", "#cdd6f4"),
+            ("border-color", "#89b4fa"),
+            ("progress-color", "over #313244"),
+            ("border-size", "2"),
+            ("border-radius", "10"),
+        ],
+        critical_border: "#fab387",
+    },
+    Preset {
+        name: "Solarized Dark",
+        params: &[
+            ("font", "monospace 10"),
+            ("background-color", "#002b36"),
+            ("text-color", "#839496"),
+            ("border-color", "#268bd2"),
+            ("progress-color", "over #073642"),
+            ("border-size", "1"),
+            ("border-radius", "4"),
+        ],
+        critical_border: "#dc322f",
+    },
+    Preset {
+        name: "Tokyo Night",
+        params: &[
+            ("font", "sans-serif 10"),
+            ("background-color", "#1a1b26"),
+            ("text-color", "#c0caf5"),
+            ("border-color", "#7aa2f7"),
+            ("progress-color", "over #283457"),
+            ("border-size", "2"),
+            ("border-radius", "8"),
+        ],
+        critical_border: "#f7768e",
+    },
+];
+
+impl Preset {
+    /// The value this preset gives `key`, if any.
+    pub fn get(&self, key: &str) -> Option<&'static str> {
+        self.params.iter().find(|(k, _)| *k == key).map(|(_, v)| *v)
+    }
+
+    /// Set the preset's style keys in the global params and the critical
+    /// border color in an `[urgency=critical]` section. Other keys,
+    /// including style keys the preset doesn't set (like `padding`), keep
+    /// their values.
+    pub fn apply(&self, cfg: &mut Config) {
+        for (key, value) in self.params {
+            cfg.set(0, key, value.to_string());
+        }
+        let n = cfg.section_for(&critical());
+        cfg.set(n, "border-color", self.critical_border.to_string());
+    }
+
+    /// The preset as a partial config, the way a theme file would have it.
+    pub fn to_config(&self) -> Config {
+        let mut cfg = Config::new(PathBuf::new());
+        cfg.params = self.params.iter().map(|(k, v)| Param::new(*k, *v)).collect();
+        cfg.sections.push(Section { criteria: critical(), params: vec![Param::new("border-color", self.critical_border)] });
+        cfg
+    }
+}
+
+fn critical() -> Criteria {
+    Criteria { conditions: vec![Condition { key: "urgency".to_string(), regex: false, value: Some("critical".to_string()) }] }
+}
+
+/// Where `apply` looks for themes given by name:
+/// `~/.config/mako-editor/themes/NAME.toml`.
+pub fn themes_dir() -> io::Result<PathBuf> {
+    Ok(config_dir()?.join("mako-editor/themes"))
+}
+
+/// The theme `name` stands for, as a partial config: the file at that
+/// path, else `NAME.toml` in `themes_dir()`, else the built-in preset of
+/// that name (`nord`, `tokyo-night`, ...).
+pub fn load(name: &str) -> Result<Config, String> {
+    let dir = themes_dir();
+    let named = dir.as_ref().ok().map(|dir| dir.join(format!("{}.toml", name)));
+    for path in [Some(Path::new(name)), named.as_deref()].into_iter().flatten() {
+        if path.is_file() {
+            let text = fs::read_to_string(path).map_err(|e| trf("cli.read_failed", &[&path.display(), &e]))?;
+            return Ok(parse_file(path, &text));
+        }
+    }
+    let wanted = name.to_lowercase().replace(['-', '_'], " ");
+    match PRESETS.iter().find(|p| p.name.to_lowercase() == wanted) {
+        Some(preset) => Ok(preset.to_config()),
+        None => {
+            let presets: Vec<&str> = PRESETS.iter().map(|p| p.name).collect();
+            let dir = dir.map_or_else(|e| e.to_string(), |dir| dir.display().to_string());
+            Err(trf("cli.no_theme", &[&name, &dir, &presets.join(", ")]))
+        }
+    }
+}
+
+/// A theme file: with a `.toml` name, keys at the top level style every
+/// notification and a `["criteria"]` table styles that section, e.g.
+///
+/// ```toml
+/// background-color = "#2e3440"
+/// border-size = 2
+///
+/// ["urgency=critical"]
+/// border-color = "#bf616a"
+/// ```
+///
+/// Any other file is read as mako config.
+pub fn parse_file(path: &Path, text: &str) -> Config {
+    if path.extension().is_none_or(|e| e != "toml") {
+        let Ok(mut cfg) = text.parse::<Config>();
+        cfg.path = path.to_path_buf();
+        return cfg;
+    }
+    let mut cfg = Config::new(path.to_path_buf());
+    for (table, key, value) in toml_entries(text) {
+        let n = if table.is_empty() {
+            0
+        } else {
+            let Ok(criteria) = table.parse::<Criteria>();
+            cfg.section_for(&criteria)
+        };
+        cfg.params_of_mut(n).push(Param::new(key, value));
+    }
+    cfg
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn apply_only_touches_style_keys() {
+        let mut cfg: Config = "default-timeout=5000\nborder-color=#ffffff\nfont=Sans 9\n".parse().unwrap();
+        PRESETS[0].apply(&mut cfg);
+        assert_eq!(cfg.params[0].value, "5000");
+        assert_eq!(cfg.params[1].value, "#88c0d0");
+        assert_eq!(cfg.params[2].value, "sans-serif 10");
+        assert!(!cfg.params.iter().any(|p| p.key == "padding"));
+        assert_eq!(cfg.sections[0].criteria.to_string(), "urgency=critical");
+        assert_eq!(cfg.sections[0].params[0].value, "#bf616a");
+        assert!(PRESETS.iter().all(|p| p.params.iter().all(|(k, _)| STYLE_KEYS.contains(k))));
+    }
+
+    #[test]
+    fn theme_files_are_toml_or_mako_config() {
+        let toml = "# nord\nbackground-color = \"#2e3440\"\nborder-size = 2\n\n[\"urgency=critical\"]\nborder-color = \"#bf616a\"\n";
+        let theme = parse_file(Path::new("nord.toml"), toml);
+        assert_eq!(theme.params, [Param::new("background-color", "#2e3440"), Param::new("border-size", "2")]);
+        assert_eq!(theme.sections[0].criteria, critical());
+        assert_eq!(theme.sections[0].params, [Param::new("border-color", "#bf616a")]);
+        let mako = parse_file(Path::new("nord"), "border-size=2\n[urgency=critical]\nborder-color=#bf616a\n");
+        assert_eq!(mako.params, theme.params[1..]);
+        assert_eq!(mako.sections[0].params, theme.sections[0].params);
+        assert_eq!(load("tokyo-night").map(|c| c.params.len()), Ok(PRESETS[5].params.len()));
+        assert!(load("no-such-theme").is_err());
+    }
+}
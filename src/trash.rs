@@ -0,0 +1,60 @@
+use std::{fs, io, path::PathBuf};
+
+use crate::config::{write_atomic, Config, Param};
+use crate::settings::state_dir;
+
+/// How many deletions survive the end of a session.
+pub const PERSISTED: usize = 10;
+
+/// Params deleted from the config, oldest first, so a deletion can be
+/// undone. Every deletion is kept for the session; the last `PERSISTED`
+/// are written to the state file and come back next time.
+#[derive(Clone, Debug, Default)]
+pub struct Trash {
+    pub items: Vec<Param>,
+}
+
+impl Trash {
+    pub fn path() -> io::Result<PathBuf> {
+        Ok(state_dir()?.join("trash"))
+    }
+
+    /// Load persisted deletions; a missing or unreadable file, or no home
+    /// directory to find it in, is an empty trash.
+    pub fn load() -> Self {
+        let items = Self::path()
+            .and_then(fs::read_to_string)
+            .map(|s| {
+                let Ok(cfg) = s.parse::<Config>();
+                cfg.params
+            })
+            .unwrap_or_default();
+        Trash { items }
+    }
+
+    /// Write the newest `PERSISTED` items, in the config file format.
+    pub fn save(&self) -> io::Result<()> {
+        let path = Self::path()?;
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        let keep = self.items.len().saturating_sub(PERSISTED);
+        let cfg = Config { params: self.items[keep..].to_vec(), ..Config::default() };
+        write_atomic(&path, &cfg.to_string())
+    }
+
+    pub fn push(&mut self, param: Param) {
+        self.items.push(param);
+    }
+
+    /// The item `n` places from the newest (0 = most recently deleted).
+    pub fn newest(&self, n: usize) -> Option<&Param> {
+        self.items.iter().rev().nth(n)
+    }
+
+    /// Remove and return `newest(n)`.
+    pub fn take_newest(&mut self, n: usize) -> Option<Param> {
+        let idx = self.items.len().checked_sub(n + 1)?;
+        Some(self.items.remove(idx))
+    }
+}
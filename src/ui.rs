@@ -0,0 +1,1782 @@
+use std::{path::PathBuf, time::SystemTime};
+
+use ratatui::{
+    layout::{Alignment, Constraint, Direction, Layout, Margin, Rect},
+    style::{Color, Modifier, Style},
+    text::{Span, Line},
+    widgets::{Block, Borders, Clear, List, ListItem, ListState, Paragraph, Scrollbar, ScrollbarOrientation, ScrollbarState, Tabs, Wrap},
+    Frame,
+};
+use unicode_width::UnicodeWidthStr;
+
+use crate::app::{filtered_known_keys, App, BulkChange, ModesScreen, NotificationsScreen, Overlay, ParamsScreen, Row, SortOrder, Tab, DIFF_CONTEXT};
+use crate::i18n::{tr, trf};
+use crate::keymap::{Command, Keymap};
+use crate::line_edit;
+use crate::browser::Browser;
+use crate::config::Criteria;
+use crate::check::Severity;
+use crate::diff;
+use crate::fonts;
+use crate::dunst;
+use crate::format;
+use crate::fuzzy;
+use crate::icons;
+use crate::lint;
+use crate::notifications::Notification;
+use crate::mako_config::{allowed_values, sides_value, ANCHOR_GRID, category, default_value, key_doc, CRITERIA, format_duration_ms, is_duration_key, is_path_list_key, list_separator, normalize_value, since, supported, validate, MakoConfig};
+use crate::settings::Settings;
+use crate::snapshots::Snapshot;
+use crate::git::Commit;
+use crate::status::short_duration;
+use crate::themes::{Preset, PRESETS};
+
+/// Colors for the editor's own UI, picked by the `theme` setting.
+struct Theme {
+    highlight: Style,
+    hint: Style,
+}
+
+fn theme(name: &str) -> Theme {
+    match name {
+        "ocean" => Theme {
+            highlight: Style::default().bg(Color::Cyan).fg(Color::Black).add_modifier(Modifier::BOLD),
+            hint: Style::default().fg(Color::Blue),
+        },
+        "mono" => Theme {
+            highlight: Style::default().add_modifier(Modifier::REVERSED | Modifier::BOLD),
+            hint: Style::default().add_modifier(Modifier::DIM),
+        },
+        _ => Theme {
+            highlight: Style::default().bg(Color::Yellow).fg(Color::Black).add_modifier(Modifier::BOLD),
+            hint: Style::default().fg(Color::Gray),
+        },
+    }
+}
+
+/// Most rows the footer may take before it is cut off.
+const MAX_FOOTER_ROWS: u16 = 4;
+
+/// Terminals shorter than this get the compact layout `--compact` asks
+/// for: no header, margin or footer box, leaving the rows to the list.
+const COMPACT_HEIGHT: u16 = 24;
+
+/// Frames of the footer spinner shown while `makoctl reload` runs.
+const SPINNER: [&str; 10] = ["⠋", "⠙", "⠹", "⠸", "⠼", "⠴", "⠦", "⠧", "⠇", "⠏"];
+
+/// Roughly how many rows `line` needs when wrapped to `width` columns
+/// (at least one).
+fn wrapped_rows(line: &Line, width: u16) -> u16 {
+    if width == 0 {
+        return 1;
+    }
+    line.width().div_ceil(width as usize).clamp(1, u16::MAX as usize) as u16
+}
+
+/// Render one frame of the editor.
+pub fn draw(f: &mut Frame, app: &mut App) {
+    let size = f.size();
+    let theme = theme(&app.settings.theme);
+    let keys = &app.settings.keys;
+    let mut warnings = lint::check(&app.cfg, app.section);
+    if let Some(installed) = &app.fonts {
+        warnings.extend(lint::missing_fonts(app.cfg.params_of(app.section), installed));
+    }
+    warnings.extend(lint::missing_paths(app.cfg.params_of(app.section)));
+    if let Some(version) = app.mako_version {
+        warnings.extend(lint::unsupported(app.cfg.params_of(app.section), version));
+    }
+    let warn_style = Style::default().fg(Color::Yellow);
+
+    // Footer area depends on the screen and reload status
+    let footer_line = match (&app.overlay, &app.params_screen) {
+        (None, ParamsScreen::List) if app.tab == Tab::Notifications => match &app.notifications_screen {
+            NotificationsScreen::List { .. } => {
+                let mut spans = match &app.notifications {
+                    Ok(list) if list.is_empty() => vec![Span::styled(tr("prompt.notifications_empty"), theme.hint)],
+                    Ok(_) => vec![Span::styled(tr("prompt.notifications"), theme.hint)],
+                    Err(e) => vec![
+                        Span::styled(trf("footer.notifications_failed", &[e]), Style::default().fg(Color::Red)),
+                        Span::raw("    "),
+                        Span::styled(tr("prompt.notifications_empty"), theme.hint),
+                    ],
+                };
+                push_message(&mut spans, &app.message);
+                Line::from(spans)
+            }
+            NotificationsScreen::Actions { .. } => Line::from(Span::styled(tr("prompt.actions"), theme.hint)),
+        },
+        (None, ParamsScreen::List) if app.tab == Tab::Modes => match &app.modes_screen {
+            ModesScreen::List { .. } => {
+                let mut spans = match &app.modes {
+                    Ok(_) => vec![Span::styled(tr("prompt.modes"), theme.hint)],
+                    Err(e) => vec![
+                        Span::styled(trf("footer.modes_failed", &[e]), Style::default().fg(Color::Red)),
+                        Span::raw("    "),
+                        Span::styled(tr("prompt.modes"), theme.hint),
+                    ],
+                };
+                push_message(&mut spans, &app.message);
+                Line::from(spans)
+            }
+            ModesScreen::Add { input } => {
+                let mut spans = vec![Span::raw(tr("prompt.add_mode"))];
+                spans.extend(input_spans(input, app.cursor));
+                Line::from(spans)
+            }
+        },
+        (None, ParamsScreen::List) if app.tab == Tab::Sections && app.section == 0 => {
+            Line::from(Span::styled(trf("prompt.sections", &[&app.settings.keys.key(Command::AddSection)]), theme.hint))
+        }
+        (None, ParamsScreen::List) => {
+            let selected = app.list_state.selected().map(|i| trf("footer.selected", &[&app.cfg.params_of(app.section)[i].key, &app.cfg.params_of(app.section)[i].value])).unwrap_or_else(|| tr("footer.no_selection").to_string());
+            let selected_key = app.list_state.selected().map(|i| app.cfg.params_of(app.section)[i].key.as_str()).unwrap_or("");
+
+            // build status spans
+            let mut spans = vec![
+                Span::raw(selected),
+                Span::raw("    "),
+                Span::styled(
+                    trf("footer.hint", &[&app.settings.keys.key(Command::Add), &app.settings.keys.key(Command::Edit), &app.settings.keys.key(Command::Delete)]),
+                    theme.hint,
+                ),
+            ];
+
+            match &app.last_reload {
+                _ if app.reloading > 0 => {
+                    let frame = SPINNER[app.spinner % SPINNER.len()];
+                    spans.push(Span::raw("    "));
+                    spans.push(Span::styled(trf("footer.reloading", &[&frame]), theme.hint));
+                }
+                Some(Ok(msg)) => {
+                    spans.push(Span::raw("    "));
+                    spans.push(Span::styled(trf("footer.reload_ok", &[msg]), Style::default().fg(Color::Green)));
+                }
+                Some(Err(msg)) => {
+                    spans.push(Span::raw("    "));
+                    spans.push(Span::styled(trf("footer.reload_failed", &[msg]), Style::default().fg(Color::Red)));
+                    spans.push(Span::raw("  "));
+                    spans.push(Span::styled(trf("footer.log_hint", &[&keys.key(Command::Log)]), theme.hint));
+                }
+                None => {}
+            }
+            if !app.marked.is_empty() {
+                spans.push(Span::raw("    "));
+                spans.push(Span::styled(
+                    trf("footer.marked", &[&app.marked.len(), &keys.key(Command::Edit), &keys.key(Command::Delete)]),
+                    Style::default().fg(Color::Cyan),
+                ));
+            }
+            if !app.settings.auto_reload {
+                spans.push(Span::raw("    "));
+                spans.push(Span::styled(trf("footer.manual_reload", &[&keys.key(Command::AutoReload)]), theme.hint));
+            }
+            for w in lint::for_key(&warnings, selected_key) {
+                spans.push(Span::raw("    "));
+                spans.push(Span::styled(format!("⚠ {}", w.message), warn_style));
+            }
+            for err in &app.hook_errors {
+                spans.push(Span::raw("    "));
+                spans.push(Span::styled(trf("footer.hook_failed", &[err]), Style::default().fg(Color::Red)));
+            }
+            push_message(&mut spans, &app.message);
+            if app.search.is_some() {
+                spans.push(Span::raw("    "));
+                spans.push(Span::styled(
+                    trf("footer.search", &[&keys.key(Command::NextMatch), &keys.key(Command::PrevMatch)]),
+                    theme.hint,
+                ));
+            }
+
+            Line::from(spans)
+        }
+        (None, ParamsScreen::EditValue { idx, input }) => {
+            let key = if *idx < app.cfg.params_of(app.section).len() { app.cfg.params_of(app.section)[*idx].key.clone() } else { "".to_string() };
+            let mut spans = vec![Span::raw(tr("prompt.edit_value"))];
+            spans.extend(input_spans(input, app.cursor));
+            if let Some(vals) = allowed_values(&key) {
+                spans.push(Span::raw("    "));
+                spans.push(Span::styled(trf("footer.allowed", &[&vals.join(" | ")]), theme.hint));
+            }
+            let suggested = app.plugins.suggest_values(&key);
+            if !suggested.is_empty() {
+                spans.push(Span::raw("    "));
+                spans.push(Span::styled(trf("footer.suggested", &[&suggested.join(" | ")]), theme.hint));
+            }
+            if is_path_list_key(&key) {
+                spans.push(Span::raw("    "));
+                spans.push(Span::styled(tr("footer.browse"), theme.hint));
+            }
+            if list_separator(&key).is_some() {
+                spans.push(Span::raw("    "));
+                spans.push(Span::styled(tr("footer.list"), theme.hint));
+            }
+            if is_duration_key(&key) {
+                spans.push(Span::raw("    "));
+                spans.push(Span::styled(tr("footer.duration"), theme.hint));
+            }
+            push_invalid(&mut spans, app, &key, input);
+            push_message(&mut spans, &app.message);
+            Line::from(spans)
+        }
+        (None, ParamsScreen::AddKey { input }) => {
+            Line::from(vec![
+                Span::raw(tr("prompt.add_key")),
+                Span::styled(input.clone(), Style::default().add_modifier(Modifier::BOLD)),
+            ])
+        }
+        (None, ParamsScreen::AddCustomKey { input }) => {
+            let mut spans = vec![Span::raw(tr("prompt.custom_key"))];
+            spans.extend(input_spans(input, app.cursor));
+            push_message(&mut spans, &app.message);
+            Line::from(spans)
+        }
+        (None, ParamsScreen::AddValue { key, input }) => {
+            let mut spans = vec![Span::raw(trf("prompt.add_value", &[key]))];
+            spans.extend(input_spans(input, app.cursor));
+            if let Some(vals) = allowed_values(key) {
+                spans.push(Span::raw("    "));
+                spans.push(Span::styled(trf("footer.allowed", &[&vals.join(" | ")]), theme.hint));
+            }
+            let suggested = app.plugins.suggest_values(key);
+            if !suggested.is_empty() {
+                spans.push(Span::raw("    "));
+                spans.push(Span::styled(trf("footer.suggested", &[&suggested.join(" | ")]), theme.hint));
+            }
+            if is_path_list_key(key) {
+                spans.push(Span::raw("    "));
+                spans.push(Span::styled(tr("footer.browse"), theme.hint));
+            }
+            if list_separator(key).is_some() {
+                spans.push(Span::raw("    "));
+                spans.push(Span::styled(tr("footer.list"), theme.hint));
+            }
+            if is_duration_key(key) {
+                spans.push(Span::raw("    "));
+                spans.push(Span::styled(tr("footer.duration"), theme.hint));
+            }
+            push_invalid(&mut spans, app, key, input);
+            push_message(&mut spans, &app.message);
+            Line::from(spans)
+        }
+        (None, ParamsScreen::EditSides { key, .. }) => {
+            let prompt = if key == "border-radius" { "prompt.edit_corners" } else { "prompt.edit_sides" };
+            let mut spans = vec![Span::raw(trf(prompt, &[key]))];
+            push_message(&mut spans, &app.message);
+            Line::from(spans)
+        }
+        (None, ParamsScreen::PickAnchor { key, .. }) => {
+            let mut spans = vec![Span::raw(trf("prompt.pick_anchor", &[key]))];
+            push_message(&mut spans, &app.message);
+            Line::from(spans)
+        }
+        (None, ParamsScreen::PickValue { key, .. }) => {
+            let mut spans = vec![Span::raw(trf("prompt.pick_value", &[key]))];
+            push_message(&mut spans, &app.message);
+            Line::from(spans)
+        }
+        (None, ParamsScreen::DidYouMean { key, .. }) => {
+            Line::from(vec![
+                Span::styled(trf("prompt.did_you_mean", &[key]), Style::default().fg(Color::Yellow)),
+                Span::raw(" "),
+                Span::styled(tr("prompt.did_you_mean_keys"), theme.hint),
+            ])
+        }
+        (Some(Overlay::ConfirmQuit), _) => Line::from(vec![
+            Span::styled(tr("prompt.confirm_quit"), Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD)),
+            Span::raw(tr("prompt.confirm_quit_keys")),
+        ]),
+        (None, ParamsScreen::ConfirmDelete { idx }) => {
+            let key = &app.cfg.params_of(app.section)[*idx].key;
+            Line::from(vec![
+                Span::styled(tr("prompt.confirm_delete"), Style::default().fg(Color::Red).add_modifier(Modifier::BOLD)),
+                Span::raw(trf("prompt.delete_key", &[key])),
+            ])
+        }
+        (None, ParamsScreen::BulkEdit { input }) => {
+            let mut spans = vec![Span::raw(trf("prompt.bulk_edit", &[&app.marked.len()]))];
+            spans.extend(input_spans(input, app.cursor));
+            spans.push(Span::raw("  "));
+            spans.push(Span::styled(tr("prompt.bulk_edit_keys"), theme.hint));
+            push_message(&mut spans, &app.message);
+            Line::from(spans)
+        }
+        (None, ParamsScreen::ConfirmBulk { change }) => {
+            let keys: Vec<&str> = app.marked.iter().map(|&i| app.cfg.params_of(app.section)[i].key.as_str()).collect();
+            let (prompt, text) = match change {
+                BulkChange::Delete => (tr("prompt.confirm_delete"), trf("prompt.bulk_delete", &[&keys.join(", ")])),
+                BulkChange::Set { pattern, .. } => (tr("prompt.confirm_bulk"), trf("prompt.bulk_set", &[&keys.join(", "), pattern])),
+                BulkChange::Replace { from, to, count, values } => {
+                    (tr("prompt.confirm_bulk"), trf("prompt.bulk_replace", &[count, from, to, &values.len()]))
+                }
+            };
+            Line::from(vec![
+                Span::styled(prompt, Style::default().fg(Color::Red).add_modifier(Modifier::BOLD)),
+                Span::raw(text),
+            ])
+        }
+        (None, ParamsScreen::Search { input }) => {
+            Line::from(vec![
+                Span::raw(tr("prompt.search")),
+                Span::styled(input.clone(), Style::default().add_modifier(Modifier::BOLD)),
+                Span::raw("  "),
+                Span::styled(tr("prompt.search_keys"), theme.hint),
+            ])
+        }
+        (None, ParamsScreen::BuildCriteria { .. }) => Line::from(Span::styled(tr("prompt.build_criteria"), theme.hint)),
+        (None, ParamsScreen::EditCriterion { idx, input, .. }) => {
+            let mut spans = vec![Span::raw(trf("prompt.edit_criterion", &[&CRITERIA[*idx].0]))];
+            spans.extend(input_spans(input, app.cursor));
+            Line::from(spans)
+        }
+        (None, ParamsScreen::AddSection { input }) => {
+            Line::from(vec![
+                Span::raw(tr("prompt.add_section")),
+                Span::styled(input.clone(), Style::default().add_modifier(Modifier::BOLD)),
+            ])
+        }
+        (None, ParamsScreen::ConfirmResetAll { input }) => {
+            Line::from(vec![
+                Span::styled(tr("prompt.reset_all"), Style::default().fg(Color::Red).add_modifier(Modifier::BOLD)),
+                Span::raw(trf("prompt.reset_all_type", &[&tr("reset.confirm_word")])),
+                Span::styled(input.clone(), Style::default().add_modifier(Modifier::BOLD)),
+            ])
+        }
+        (None, ParamsScreen::Duplicate { idx, input }) => {
+            let key = app.cfg.params_of(app.section).get(*idx).map(|p| p.key.as_str()).unwrap_or("");
+            Line::from(vec![
+                Span::raw(trf("prompt.duplicate", &[&key])),
+                Span::styled(input.clone(), Style::default().add_modifier(Modifier::BOLD)),
+            ])
+        }
+        (Some(Overlay::Settings { .. }), _) => {
+            let mut spans = vec![Span::styled(tr("prompt.settings"), theme.hint)];
+            push_message(&mut spans, &app.message);
+            Line::from(spans)
+        }
+        (Some(Overlay::EditSetting { idx, input }), _) => {
+            let key = app.settings.fields().get(*idx).map(|(k, _)| k.clone()).unwrap_or_default();
+            let mut spans = vec![
+                Span::raw(trf("prompt.edit_setting", &[&key])),
+                Span::styled(input.clone(), Style::default().add_modifier(Modifier::BOLD)),
+            ];
+            push_message(&mut spans, &app.message);
+            Line::from(spans)
+        }
+        (Some(Overlay::Themes { .. }), _) => {
+            let mut spans = vec![Span::styled(tr("prompt.themes"), theme.hint)];
+            push_message(&mut spans, &app.message);
+            Line::from(spans)
+        }
+        (Some(Overlay::ImportPath { input }), _) => {
+            let mut spans = vec![
+                Span::raw(tr("prompt.import_path")),
+                Span::styled(input.clone(), Style::default().add_modifier(Modifier::BOLD)),
+            ];
+            push_message(&mut spans, &app.message);
+            Line::from(spans)
+        }
+        (Some(Overlay::ImportReview { .. }), _) => Line::from(Span::styled(tr("prompt.import_review"), theme.hint)),
+        (Some(Overlay::PasteConfig { .. }), _) => {
+            let mut spans = vec![Span::styled(tr("prompt.paste_config"), theme.hint)];
+            push_message(&mut spans, &app.message);
+            Line::from(spans)
+        }
+        (Some(Overlay::Diff { .. }), _) if !app.is_dirty() => Line::from(Span::styled(tr("prompt.diff_empty"), theme.hint)),
+        (Some(Overlay::Diff { .. }), _) => Line::from(vec![
+            Span::styled(tr("prompt.diff"), theme.hint),
+            Span::raw("    "),
+            Span::styled(trf("footer.write_hint", &[&keys.key(Command::Write)]), theme.hint),
+        ]),
+        (None, ParamsScreen::CommandLine { input }) => {
+            let mut spans = vec![Span::raw(":")];
+            spans.extend(input_spans(input, app.cursor));
+            spans.push(Span::raw("  "));
+            spans.push(Span::styled(tr("prompt.command_line"), theme.hint));
+            Line::from(spans)
+        }
+        (Some(Overlay::Log { .. }), _) if app.activity.is_empty() => Line::from(Span::styled(tr("prompt.log_empty"), theme.hint)),
+        (Some(Overlay::Log { .. }), _) => Line::from(Span::styled(tr("prompt.log"), theme.hint)),
+        (Some(Overlay::Problems { problems, .. }), _) if problems.is_empty() => Line::from(Span::styled(tr("prompt.problems_empty"), theme.hint)),
+        (Some(Overlay::Problems { .. }), _) => Line::from(Span::styled(tr("prompt.problems"), theme.hint)),
+        (Some(Overlay::Compose { .. }), _) => {
+            let mut spans = vec![Span::styled(tr("prompt.compose"), theme.hint)];
+            push_message(&mut spans, &app.message);
+            Line::from(spans)
+        }
+        (Some(Overlay::EditCompose { idx, input }), _) => {
+            let field = app.test_notification.fields().get(*idx).map(|(k, _)| *k).unwrap_or_default();
+            let mut spans = vec![
+                Span::raw(trf("prompt.edit_compose", &[&field])),
+                Span::styled(input.clone(), Style::default().add_modifier(Modifier::BOLD)),
+            ];
+            push_message(&mut spans, &app.message);
+            Line::from(spans)
+        }
+        (Some(Overlay::Backups { backups, .. }), _) => {
+            let hint = if backups.is_empty() { tr("prompt.backups_empty") } else { tr("prompt.backups") };
+            Line::from(Span::styled(hint, theme.hint))
+        }
+        (Some(Overlay::Snapshots { snapshots, .. }), _) if snapshots.is_empty() && !app.settings.snapshots => {
+            Line::from(Span::styled(tr("prompt.snapshots_off"), theme.hint))
+        }
+        (Some(Overlay::Snapshots { snapshots, .. }), _) => {
+            let hint = if snapshots.is_empty() { tr("prompt.snapshots_empty") } else { tr("prompt.snapshots") };
+            Line::from(Span::styled(hint, theme.hint))
+        }
+        (Some(Overlay::GitLog { commits: None, .. }), _) => Line::from(Span::styled(tr("prompt.git_log_loading"), theme.hint)),
+        (Some(Overlay::GitLog { commits: Some(Err(e)), .. }), _) => Line::from(vec![
+            Span::styled(trf("footer.git_log_failed", &[e]), Style::default().fg(Color::Red)),
+            Span::raw("    "),
+            Span::styled(tr("prompt.git_log_empty"), theme.hint),
+        ]),
+        (Some(Overlay::GitLog { commits: Some(Ok(commits)), .. }), _) => {
+            let hint = if commits.is_empty() { tr("prompt.git_log_empty") } else { tr("prompt.snapshots") };
+            Line::from(Span::styled(hint, theme.hint))
+        }
+        (Some(Overlay::History { .. }), _) => {
+            let mut spans = match &app.history {
+                Ok(list) if list.is_empty() => vec![Span::styled(tr("prompt.history_empty"), theme.hint)],
+                Ok(_) => vec![Span::styled(tr("prompt.history"), theme.hint)],
+                Err(e) => vec![
+                    Span::styled(trf("footer.history_failed", &[e]), Style::default().fg(Color::Red)),
+                    Span::raw("    "),
+                    Span::styled(tr("prompt.history_empty"), theme.hint),
+                ],
+            };
+            push_message(&mut spans, &app.message);
+            Line::from(spans)
+        }
+        (Some(Overlay::Trash { .. }), _) => {
+            let hint = if app.trash.items.is_empty() { tr("prompt.trash_empty") } else { tr("prompt.trash") };
+            Line::from(Span::styled(hint, theme.hint))
+        }
+        (None, ParamsScreen::PickFont { picker, .. }) => {
+            let bold = Style::default().add_modifier(Modifier::BOLD);
+            let focused = |field| if picker.field == field { bold.add_modifier(Modifier::UNDERLINED) } else { bold };
+            Line::from(vec![
+                Span::raw(tr("footer.font_family")),
+                Span::styled(picker.query.clone(), focused(fonts::Field::Family)),
+                Span::raw(tr("footer.font_style")),
+                Span::styled(format!("‹ {} ›", fonts::STYLES[picker.style]), bold),
+                Span::raw(tr("footer.font_size")),
+                Span::styled(picker.size.clone(), focused(fonts::Field::Size)),
+                Span::raw("    "),
+                Span::raw(trf("footer.font_value", &[&picker.value().unwrap_or_default()])),
+                Span::raw("    "),
+                Span::styled(tr("prompt.pick_font"), theme.hint),
+            ])
+        }
+        (None, ParamsScreen::BrowsePath { browser, .. }) => {
+            let mut spans = vec![
+                Span::raw(trf("footer.picked", &[&browser.value()])),
+                Span::raw("    "),
+                Span::styled(tr("prompt.browse"), theme.hint),
+            ];
+            if let Some(err) = &browser.error {
+                spans.push(Span::raw("    "));
+                spans.push(Span::styled(err.clone(), Style::default().fg(Color::Red)));
+            }
+            for dir in lint::missing_dirs(&browser.value()) {
+                spans.push(Span::raw("    "));
+                spans.push(Span::styled(format!("⚠ {}", trf("lint.path_missing", &[&dir])), warn_style));
+            }
+            Line::from(spans)
+        }
+        (None, ParamsScreen::EditList { list, .. }) => match &list.input {
+            Some(input) => {
+                let mut spans = vec![Span::raw(tr("prompt.list_entry"))];
+                spans.extend(input_spans(input, app.cursor));
+                spans.push(Span::raw("    "));
+                spans.push(Span::styled(tr("prompt.list_entry_keys"), theme.hint));
+                Line::from(spans)
+            }
+            None => Line::from(vec![
+                Span::raw(trf("footer.list_value", &[&list.value()])),
+                Span::raw("    "),
+                Span::styled(tr("prompt.edit_list"), theme.hint),
+            ]),
+        },
+    };
+
+    // Wrap the footer instead of cutting it off; it grows (up to a few
+    // rows) on narrow terminals and shrinks back when there is room.
+    let compact = app.compact || size.height < COMPACT_HEIGHT;
+    let (margin, borders) = if compact { (0, Borders::NONE) } else { (1, Borders::ALL) };
+    let footer_rows = wrapped_rows(&footer_line, size.width.saturating_sub(4 * margin)).min(MAX_FOOTER_ROWS);
+    let footer = Paragraph::new(footer_line).wrap(Wrap { trim: true }).block(Block::default().borders(borders));
+
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .margin(margin)
+        .constraints(
+            [
+                Constraint::Length(if compact { 0 } else { 5 }),
+                Constraint::Min(3),
+                Constraint::Length(footer_rows + 2 * margin),
+            ]
+            .as_ref(),
+        )
+        .split(size);
+
+    // Header: what is being edited and the state of things, then the keys
+    let dirty = app.is_dirty().then(|| match app.pending.len() {
+        0 => tr("header.unsaved").to_string(),
+        n => trf("header.pending", &[&n, &app.settings.keys.key(Command::Write)]),
+    });
+    let mut status = vec![Span::raw(app.cfg.path.display().to_string())];
+    if let Some(marker) = &dirty {
+        status.push(Span::raw("  "));
+        status.push(Span::styled(marker.clone(), Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD)));
+    }
+    if let Some(age) = app.status.age() {
+        status.push(Span::raw("  "));
+        status.push(Span::styled(trf("header.modified", &[&age]), theme.hint));
+    }
+    if !warnings.is_empty() {
+        status.push(Span::raw("  "));
+        status.push(Span::styled(trf("header.warnings", &[&warnings.len()]), warn_style));
+    }
+    match app.status.daemon_running {
+        Some(true) => {
+            status.push(Span::raw("  "));
+            status.push(Span::styled(tr("header.mako_running"), Style::default().fg(Color::Green)));
+        }
+        Some(false) => {
+            status.push(Span::raw("  "));
+            status.push(Span::styled(tr("header.mako_stopped"), Style::default().fg(Color::Red)));
+        }
+        None => {}
+    }
+    let hints = Line::from(vec![
+        Span::styled(trf("header.navigate", &[&keys.key(Command::Down), &keys.key(Command::Up)]), theme.hint),
+        Span::raw(" "),
+        Span::styled(trf("header.edit", &[&keys.key(Command::Edit)]), theme.hint),
+        Span::raw(" "),
+        Span::styled(trf("header.add", &[&keys.key(Command::Add)]), theme.hint),
+        Span::raw(" "),
+        Span::styled(trf("header.delete", &[&keys.key(Command::Delete)]), theme.hint),
+        Span::raw(" "),
+        Span::styled(trf("header.settings", &[&keys.key(Command::Settings)]), theme.hint),
+        Span::raw(" "),
+        Span::styled(trf("header.help", &[&keys.key(Command::Help)]), theme.hint),
+        Span::raw(" "),
+        Span::styled(trf("header.quit", &[&keys.key(Command::Quit)]), theme.hint),
+    ]);
+    let header = Block::default()
+        .title(Span::styled(tr("header.title"), Style::default().add_modifier(Modifier::BOLD)))
+        .borders(Borders::ALL);
+    if !compact {
+        let rows = Layout::default()
+            .constraints([Constraint::Length(1), Constraint::Min(0)])
+            .split(header.inner(chunks[0]));
+        f.render_widget(header, chunks[0]);
+        f.render_widget(tabs(app, &theme), rows[0]);
+        f.render_widget(Paragraph::new(vec![Line::from(status), hints]), rows[1]);
+    }
+
+    // Params list, known-keys chooser when adding a key, the settings
+    // screen, the trash, spelling suggestions, or the path browser. Lists
+    // that keep their selection in the screen share a throwaway list state.
+    let mut settings_state = ListState::default();
+    let (list, active) = match (&app.overlay, &app.params_screen) {
+        (None, ParamsScreen::List) if app.tab == Tab::Notifications => match &app.notifications_screen {
+            NotificationsScreen::List { idx } => {
+                let list = app.notifications.as_deref().unwrap_or_default();
+                settings_state.select((!list.is_empty()).then_some(*idx));
+                let items: Vec<ListItem> = list.iter().map(|n| notification_item(n, &theme)).collect();
+                (
+                    List::new(items)
+                        .block(Block::default().title(trf("list.notifications", &[&list.len()])).borders(Borders::ALL))
+                        .highlight_style(theme.highlight),
+                    ActiveList::Settings,
+                )
+            }
+            NotificationsScreen::Actions { id, actions, idx, .. } => {
+                settings_state.select(Some(*idx));
+                let items: Vec<ListItem> = actions
+                    .iter()
+                    .map(|(key, label)| {
+                        ListItem::new(Line::from(vec![
+                            Span::styled(label.clone(), Style::default().add_modifier(Modifier::BOLD)),
+                            Span::styled(format!("  ({})", key), theme.hint),
+                        ]))
+                    })
+                    .collect();
+                (
+                    List::new(items)
+                        .block(Block::default().title(trf("list.actions", &[id])).borders(Borders::ALL))
+                        .highlight_style(theme.highlight),
+                    ActiveList::Settings,
+                )
+            }
+        },
+        (None, ParamsScreen::List) if app.tab == Tab::Sections && app.section == 0 => {
+            settings_state.select(Some(app.section_list));
+            let items: Vec<ListItem> = app
+                .cfg
+                .sections
+                .iter()
+                .map(|s| {
+                    ListItem::new(Line::from(vec![
+                        Span::styled(pad(&format!("[{}]", s.criteria), 40), Style::default().add_modifier(Modifier::BOLD)),
+                        Span::styled(trf("list.section_params", &[&s.params.len()]), theme.hint),
+                    ]))
+                })
+                .collect();
+            (
+                List::new(items)
+                    .block(Block::default().title(tr("list.sections")).borders(Borders::ALL))
+                    .highlight_style(theme.highlight),
+                ActiveList::Settings,
+            )
+        }
+        (None, ParamsScreen::List) if app.tab == Tab::Modes => {
+            let rows = app.mode_rows();
+            settings_state.select(match &app.modes_screen {
+                ModesScreen::List { idx } if !rows.is_empty() => Some((*idx).min(rows.len() - 1)),
+                _ => None,
+            });
+            let on = app.modes.as_deref().unwrap_or_default();
+            let items: Vec<ListItem> = rows
+                .iter()
+                .map(|name| {
+                    let active = on.contains(name);
+                    let styled = app.cfg.sections.iter().find(|s| s.criteria == Criteria::mode(name));
+                    let note = match styled {
+                        Some(s) => trf("list.mode_params", &[&s.params.len()]),
+                        None => tr("list.mode_unstyled").to_string(),
+                    };
+                    ListItem::new(Line::from(vec![
+                        Span::styled(if active { "● " } else { "○ " }, if active { Style::default().fg(Color::Green) } else { theme.hint }),
+                        Span::styled(pad(name, 24), Style::default().add_modifier(Modifier::BOLD)),
+                        Span::styled(note, theme.hint),
+                    ]))
+                })
+                .collect();
+            (
+                List::new(items)
+                    .block(Block::default().title(tr("list.modes")).borders(Borders::ALL))
+                    .highlight_style(theme.highlight),
+                ActiveList::Settings,
+            )
+        }
+        (None, ParamsScreen::BuildCriteria { criteria, idx } | ParamsScreen::EditCriterion { criteria, idx, .. }) => {
+            settings_state.select(Some(*idx));
+            let items: Vec<ListItem> = CRITERIA
+                .iter()
+                .map(|(field, _)| {
+                    let mut spans = vec![Span::raw(format!("{:16}", field))];
+                    match criteria.get(field) {
+                        Some(c) => {
+                            // Written the way the header will have it, quotes and all.
+                            let one = Criteria { conditions: vec![c.clone()] }.to_string();
+                            spans.push(Span::styled(one[field.len()..].to_string(), Style::default().add_modifier(Modifier::BOLD)));
+                            if c.value.is_none() {
+                                spans.push(Span::styled(tr("list.criterion_set"), Style::default().add_modifier(Modifier::BOLD)));
+                            }
+                        }
+                        None => spans.push(Span::styled(tr("list.criterion_any"), theme.hint)),
+                    }
+                    ListItem::new(Line::from(spans))
+                })
+                .collect();
+            (
+                List::new(items)
+                    .block(Block::default().title(trf("list.criteria", &[&criteria])).borders(Borders::ALL))
+                    .highlight_style(theme.highlight),
+                ActiveList::Settings,
+            )
+        }
+        (None, ParamsScreen::PickValue { idx, key, choice }) => {
+            settings_state.select(Some(*choice));
+            let current = idx.and_then(|i| app.cfg.params_of(app.section).get(i)).map(|p| p.value.trim());
+            let items: Vec<ListItem> = allowed_values(key)
+                .unwrap_or_default()
+                .into_iter()
+                .map(|v| {
+                    let mut spans = vec![Span::styled(format!("{:16}", v), Style::default().add_modifier(Modifier::BOLD))];
+                    if current == Some(v) {
+                        spans.push(Span::styled(tr("list.pick_current"), theme.hint));
+                    } else if default_value(key) == Some(v) {
+                        spans.push(Span::styled(tr("list.pick_default"), theme.hint));
+                    }
+                    ListItem::new(Line::from(spans))
+                })
+                .collect();
+            (
+                List::new(items)
+                    .block(Block::default().title(trf("list.pick_value", &[key])).borders(Borders::ALL))
+                    .highlight_style(theme.highlight),
+                ActiveList::Settings,
+            )
+        }
+        (None, ParamsScreen::EditSides { key, sides, side, .. }) => (
+            List::new(Vec::<ListItem>::new()).block(Block::default().title(trf("list.edit_sides", &[key, &sides_value(*sides)])).borders(Borders::ALL)),
+            ActiveList::Sides { key, sides: *sides, side: *side },
+        ),
+        (None, ParamsScreen::PickAnchor { idx, key, cell }) => {
+            let current = idx.and_then(|i| app.cfg.params_of(app.section).get(i)).map(|p| p.value.trim());
+            (
+                List::new(Vec::<ListItem>::new()).block(Block::default().title(trf("list.pick_anchor", &[key])).borders(Borders::ALL)),
+                ActiveList::Anchor { cell: *cell, current: current.and_then(|v| ANCHOR_GRID.iter().position(|a| *a == v)) },
+            )
+        }
+        (None, ParamsScreen::DidYouMean { key, suggestions, idx }) => {
+            settings_state.select(Some(*idx));
+            let mut items: Vec<ListItem> = suggestions
+                .iter()
+                .map(|s| ListItem::new(Span::styled(s.clone(), Style::default().add_modifier(Modifier::BOLD))))
+                .collect();
+            items.push(ListItem::new(Span::raw(trf("list.keep_custom", &[key]))));
+            (
+                List::new(items)
+                    .block(Block::default().title(tr("list.did_you_mean")).borders(Borders::ALL))
+                    .highlight_style(theme.highlight),
+                ActiveList::Settings,
+            )
+        }
+        (Some(Overlay::Backups { idx, backups }), _) => {
+            settings_state.select(Some(*idx));
+            let now = SystemTime::now();
+            let items: Vec<ListItem> = backups
+                .iter()
+                .map(|b| {
+                    let name = b.path.file_name().unwrap_or_default().to_string_lossy().into_owned();
+                    let age = b.modified.and_then(|m| now.duration_since(m).ok()).map(short_duration).unwrap_or_default();
+                    ListItem::new(Line::from(vec![
+                        Span::styled(pad(&name, 20), Style::default().add_modifier(Modifier::BOLD)),
+                        Span::raw(trf("list.backup_entry", &[&age, &b.keys])),
+                    ]))
+                })
+                .collect();
+            (
+                List::new(items)
+                    .block(Block::default().title(tr("list.backups")).borders(Borders::ALL))
+                    .highlight_style(theme.highlight),
+                ActiveList::Settings,
+            )
+        }
+        (Some(Overlay::Snapshots { idx, snapshots }), _) => {
+            settings_state.select(Some(*idx));
+            let now = SystemTime::now();
+            let items: Vec<ListItem> = snapshots
+                .iter()
+                .map(|s| {
+                    let age = now.duration_since(s.taken).map(short_duration).unwrap_or_default();
+                    ListItem::new(Line::from(vec![
+                        Span::styled(format!("{:>6} ", age), Style::default().add_modifier(Modifier::BOLD)),
+                        Span::raw(trf("list.snapshot_entry", &[&s.keys])),
+                    ]))
+                })
+                .collect();
+            (
+                List::new(items)
+                    .block(Block::default().title(tr("list.snapshots")).borders(Borders::ALL))
+                    .highlight_style(theme.highlight),
+                match snapshots.get(*idx) {
+                    Some(snapshot) => ActiveList::Snapshot(snapshot),
+                    None => ActiveList::Settings,
+                },
+            )
+        }
+        (Some(Overlay::GitLog { idx, commits }), _) => {
+            let commits = commits.as_ref().and_then(|c| c.as_deref().ok()).unwrap_or_default();
+            settings_state.select(Some(*idx));
+            let now = SystemTime::now();
+            let items: Vec<ListItem> = commits
+                .iter()
+                .map(|c| {
+                    let age = now.duration_since(c.time).map(short_duration).unwrap_or_default();
+                    ListItem::new(Line::from(vec![
+                        Span::styled(format!("{:>6} ", age), Style::default().add_modifier(Modifier::BOLD)),
+                        Span::styled(format!("{} ", c.short_hash()), theme.hint),
+                        Span::raw(c.subject.clone()),
+                    ]))
+                })
+                .collect();
+            (
+                List::new(items)
+                    .block(Block::default().title(tr("list.git_log")).borders(Borders::ALL))
+                    .highlight_style(theme.highlight),
+                match commits.get(*idx) {
+                    Some(commit) => ActiveList::Commit(commit),
+                    None => ActiveList::Settings,
+                },
+            )
+        }
+        (Some(Overlay::History { idx }), _) => {
+            let list = app.history.as_deref().unwrap_or_default();
+            settings_state.select((!list.is_empty()).then_some(*idx));
+            let items: Vec<ListItem> = list.iter().map(|n| notification_item(n, &theme)).collect();
+            (
+                List::new(items)
+                    .block(Block::default().title(trf("list.history", &[&list.len()])).borders(Borders::ALL))
+                    .highlight_style(theme.highlight),
+                ActiveList::Settings,
+            )
+        }
+        (Some(Overlay::Trash { idx }), _) => {
+            settings_state.select(Some(*idx));
+            let items: Vec<ListItem> = app
+                .trash
+                .items
+                .iter()
+                .rev()
+                .map(|p| {
+                    ListItem::new(Line::from(vec![
+                        Span::raw(pad(&p.key, 20)),
+                        Span::raw(" = "),
+                        Span::styled(p.value.clone(), Style::default().add_modifier(Modifier::BOLD)),
+                    ]))
+                })
+                .collect();
+            (
+                List::new(items)
+                    .block(Block::default().title(trf("list.trash", &[&app.trash.items.len()])).borders(Borders::ALL))
+                    .highlight_style(theme.highlight),
+                ActiveList::Settings,
+            )
+        }
+        (None, ParamsScreen::BrowsePath { browser, .. }) => {
+            let items: Vec<ListItem> = browser
+                .entries
+                .iter()
+                .map(|e| {
+                    let mark = match (e.is_dir, e.name == "..") {
+                        (true, false) if browser.is_picked(e) => "[x] ",
+                        (true, false) => "[ ] ",
+                        _ => "    ",
+                    };
+                    let name = if e.is_dir { format!("{}/", e.name) } else { e.name.clone() };
+                    let style = if e.is_dir { Style::default().add_modifier(Modifier::BOLD) } else { Style::default() };
+                    ListItem::new(Line::from(vec![Span::raw(mark), Span::styled(name, style)]))
+                })
+                .collect();
+            (
+                List::new(items)
+                    .block(Block::default().title(browser.dir.display().to_string()).borders(Borders::ALL))
+                    .highlight_style(theme.highlight),
+                ActiveList::Browser(browser),
+            )
+        }
+        (None, ParamsScreen::EditList { list, .. }) => {
+            settings_state.select((!list.items.is_empty()).then_some(list.selected));
+            let missing = if list.separator == ':' { lint::missing_dirs(&list.value()) } else { Vec::new() };
+            let items: Vec<ListItem> = list
+                .items
+                .iter()
+                .map(|item| {
+                    let mut spans = vec![Span::raw(item.clone())];
+                    if missing.contains(item) {
+                        spans.push(Span::styled(format!("  ⚠ {}", trf("lint.path_missing", &[item])), warn_style));
+                    }
+                    ListItem::new(Line::from(spans))
+                })
+                .collect();
+            (
+                List::new(items)
+                    .block(Block::default().title(trf("list.entries", &[&list.items.len()])).borders(Borders::ALL))
+                    .highlight_style(theme.highlight),
+                ActiveList::Settings,
+            )
+        }
+        (None, ParamsScreen::PickFont { picker, .. }) => {
+            let matches = picker.matches();
+            settings_state.select((!matches.is_empty()).then_some(picker.selected));
+            let bold = Style::default().add_modifier(Modifier::BOLD);
+            let matched = bold.fg(Color::Yellow).add_modifier(Modifier::UNDERLINED);
+            let items: Vec<ListItem> = matches
+                .iter()
+                .map(|(family, positions)| {
+                    let spans: Vec<Span> = family
+                        .chars()
+                        .enumerate()
+                        .map(|(i, c)| Span::styled(c.to_string(), if positions.contains(&i) { matched } else { Style::default() }))
+                        .collect();
+                    ListItem::new(Line::from(spans))
+                })
+                .collect();
+            (
+                List::new(items)
+                    .block(Block::default().title(trf("list.fonts", &[&matches.len()])).borders(Borders::ALL))
+                    .highlight_style(theme.highlight),
+                ActiveList::Settings,
+            )
+        }
+        (None, ParamsScreen::AddKey { input }) => {
+            let filtered = filtered_known_keys(input, &app.plugins, app.section);
+            let bold = Style::default().add_modifier(Modifier::BOLD);
+            let matched = bold.fg(Color::Yellow).add_modifier(Modifier::UNDERLINED);
+            let items: Vec<ListItem> = filtered.iter().map(|(k, desc)| {
+                let positions = fuzzy::score(input, k).map(|(_, p)| p).unwrap_or_default();
+                let mut spans: Vec<Span> =
+                    k.chars().enumerate().map(|(i, c)| Span::styled(c.to_string(), if positions.contains(&i) { matched } else { bold })).collect();
+                spans.push(Span::raw(" - "));
+                spans.push(Span::raw(desc.clone()));
+                if let (Some(version), Some((major, minor))) = (app.mako_version, since(k))
+                    && !supported(k, version)
+                {
+                    spans.push(Span::styled(format!("  ⚠ {}", trf("keys.needs_version", &[&format!("{}.{}", major, minor)])), warn_style));
+                }
+                ListItem::new(Line::from(spans))
+            }).collect();
+            (
+                List::new(items)
+                    .block(Block::default().title(known_keys_title(app)).borders(Borders::ALL))
+                    .highlight_style(theme.highlight),
+                ActiveList::Keys,
+            )
+        }
+        (Some(Overlay::Themes { idx }), _) => {
+            settings_state.select(Some(*idx));
+            let items: Vec<ListItem> = PRESETS.iter().map(|p| ListItem::new(p.name)).collect();
+            (
+                List::new(items)
+                    .block(Block::default().title(tr("list.themes")).borders(Borders::ALL))
+                    .highlight_style(theme.highlight),
+                ActiveList::Themes(&PRESETS[*idx]),
+            )
+        }
+        (Some(Overlay::Diff { idx }), _) => {
+            let rows = diff::unified(&app.saved_text, &app.cfg.to_string(), DIFF_CONTEXT);
+            settings_state.select((!rows.is_empty()).then_some(*idx));
+            let items: Vec<ListItem> = rows
+                .into_iter()
+                .map(|(kind, line)| ListItem::new(Line::from(Span::styled(line, diff_style(kind)))))
+                .collect();
+            (
+                List::new(items)
+                    .block(Block::default().title(trf("list.diff", &[&app.cfg.path.display()])).borders(Borders::ALL))
+                    .highlight_style(Style::default().add_modifier(Modifier::BOLD)),
+                ActiveList::Settings,
+            )
+        }
+        (Some(Overlay::Log { idx }), _) => {
+            let rows = app.activity.rows();
+            settings_state.select((!rows.is_empty()).then_some(*idx));
+            let now = SystemTime::now();
+            let items: Vec<ListItem> = rows
+                .into_iter()
+                .map(|(entry, line, first)| {
+                    if !first {
+                        return ListItem::new(Line::from(Span::raw(format!("{:15}{}", "", line))));
+                    }
+                    let age = now.duration_since(entry.at).map(short_duration).unwrap_or_default();
+                    let color = if entry.failed { Color::Red } else { Color::Green };
+                    ListItem::new(Line::from(vec![
+                        Span::styled(format!("{:>4}  ", age), theme.hint),
+                        Span::styled(format!("{:<9}", tr(entry.kind.label_id())), Style::default().fg(color)),
+                        Span::raw(line.to_string()),
+                    ]))
+                })
+                .collect();
+            (
+                List::new(items)
+                    .block(Block::default().title(tr("list.log")).borders(Borders::ALL))
+                    .highlight_style(Style::default().add_modifier(Modifier::BOLD)),
+                ActiveList::Settings,
+            )
+        }
+        (Some(Overlay::Problems { idx, problems }), _) => {
+            settings_state.select((!problems.is_empty()).then_some(*idx));
+            let errors = problems.iter().filter(|p| p.severity == Severity::Error).count();
+            let items: Vec<ListItem> = problems
+                .iter()
+                .map(|p| {
+                    let color = match p.severity {
+                        Severity::Error => Color::Red,
+                        Severity::Warning => Color::Yellow,
+                    };
+                    ListItem::new(Line::from(vec![
+                        Span::styled(format!("{:>4}  ", p.line), theme.hint),
+                        Span::styled(format!("{:<8}", p.severity.label()), Style::default().fg(color)),
+                        Span::raw(p.message.clone()),
+                    ]))
+                })
+                .collect();
+            let title = trf("list.problems", &[&app.cfg.path.display(), &errors, &(problems.len() - errors)]);
+            (
+                List::new(items).block(Block::default().title(title).borders(Borders::ALL)).highlight_style(theme.highlight),
+                ActiveList::Settings,
+            )
+        }
+        (Some(Overlay::PasteConfig { input }), _) => {
+            let lines: Vec<&str> = input.split('\n').collect();
+            // Keep the end, where the typing happens, in view.
+            settings_state.select(Some(lines.len() - 1));
+            let last = lines.len() - 1;
+            let items: Vec<ListItem> = lines
+                .iter()
+                .enumerate()
+                .map(|(i, line)| {
+                    let cursor = if i == last { "▏" } else { "" };
+                    ListItem::new(Line::from(format!("{}{}", line, cursor)))
+                })
+                .collect();
+            let title = trf("list.paste_config", &[&input.lines().filter(|l| !l.trim().is_empty()).count()]);
+            (List::new(items).block(Block::default().title(title).borders(Borders::ALL)), ActiveList::Settings)
+        }
+        (Some(Overlay::ImportReview { idx, mappings, source }), _) => {
+            settings_state.select(Some(*idx));
+            let items: Vec<ListItem> = mappings
+                .iter()
+                .map(|m| {
+                    let mark = match (&m.param, m.enabled) {
+                        (Some(_), true) => "[x] ",
+                        (Some(_), false) => "[ ] ",
+                        (None, _) => "    ",
+                    };
+                    let target = if m.param.is_some() { Style::default().add_modifier(Modifier::BOLD) } else { theme.hint };
+                    ListItem::new(Line::from(vec![
+                        Span::raw(mark),
+                        Span::raw(pad(&m.from, 44)),
+                        Span::raw(" → "),
+                        Span::styled(dunst::describe(m), target),
+                    ]))
+                })
+                .collect();
+            let selected = mappings.iter().filter(|m| m.enabled).count();
+            (
+                List::new(items)
+                    .block(Block::default().title(trf("list.import", &[source, &selected, &mappings.len()])).borders(Borders::ALL))
+                    .highlight_style(theme.highlight),
+                ActiveList::Settings,
+            )
+        }
+        (Some(Overlay::Compose { idx } | Overlay::EditCompose { idx, .. }), _) => {
+            settings_state.select(Some(*idx));
+            let items: Vec<ListItem> = app
+                .test_notification
+                .fields()
+                .into_iter()
+                .map(|(k, v)| {
+                    ListItem::new(Line::from(vec![
+                        Span::raw(pad(k, 20)),
+                        Span::raw(" = "),
+                        Span::styled(v, Style::default().add_modifier(Modifier::BOLD)),
+                    ]))
+                })
+                .collect();
+            (
+                List::new(items)
+                    .block(Block::default().title(tr("list.compose")).borders(Borders::ALL))
+                    .highlight_style(theme.highlight),
+                ActiveList::Settings,
+            )
+        }
+        (Some(Overlay::Settings { idx } | Overlay::EditSetting { idx, .. }), _) => {
+            settings_state.select(Some(*idx));
+            let items: Vec<ListItem> = app
+                .settings
+                .fields()
+                .into_iter()
+                .map(|(k, v)| {
+                    ListItem::new(Line::from(vec![
+                        Span::raw(pad(&k, 20)),
+                        Span::raw(" = "),
+                        Span::styled(v, Style::default().add_modifier(Modifier::BOLD)),
+                    ]))
+                })
+                .collect();
+            let path = Settings::settings_path().map(|p| p.display().to_string()).unwrap_or_else(|e| e.to_string());
+            let title = trf("list.settings", &[&path]);
+            (
+                List::new(items)
+                    .block(Block::default().title(title).borders(Borders::ALL))
+                    .highlight_style(theme.highlight),
+                ActiveList::Settings,
+            )
+        }
+        _ => {
+            let section = match app.section.checked_sub(1).and_then(|i| app.cfg.sections.get(i)) {
+                Some(section) => format!("[{}]", section.criteria),
+                None => tr("list.section_global").to_string(),
+            };
+            let mut params_title = trf(
+                "list.params_in",
+                &[&section, &(app.section + 1), &app.cfg.section_count(), &keys.key(Command::PrevSection), &keys.key(Command::NextSection)],
+            );
+            if app.sort != SortOrder::File {
+                params_title.push_str(&trf("list.params_sorted", &[&tr(app.sort.label_id()), &keys.key(Command::ApplySort)]));
+            }
+            if app.only_customized {
+                params_title.push_str(&trf("list.params_customized", &[&app.settings.keys.key(Command::Customized)]));
+            }
+            if app.show_unset {
+                params_title.push_str(&trf("list.params_unset", &[&app.settings.keys.key(Command::ShowUnset)]));
+            }
+            let query = match &app.params_screen {
+                ParamsScreen::Search { input } => Some(input.as_str()),
+                _ => app.search.as_deref(),
+            };
+            if let Some(query) = query {
+                params_title.push_str(&trf("list.params_search", &[&query, &app.visible_params().len()]));
+            }
+            // Without the header, the list says what it would have.
+            if compact && let Some(marker) = &dirty {
+                params_title = format!("{}  {}", params_title, marker);
+            }
+            let params = app.cfg.params_of(app.section);
+            let unset = app.unset_keys();
+            let items: Vec<ListItem> = app
+                .rows()
+                .into_iter()
+                .map(|row| {
+                    let i = match row {
+                        Row::Param(i) => i,
+                        Row::Unset(key) => {
+                            let indent = if app.marked.is_empty() { "" } else { "  " };
+                            let default = default_value(key).unwrap_or_else(|| tr("value.no_default"));
+                            let dim = Style::default().add_modifier(Modifier::DIM);
+                            return ListItem::new(Line::from(Span::styled(format!("{}{:20} = {}", indent, key, default), dim)));
+                        }
+                        Row::Header(c) => {
+                            let count = app.visible_params().into_iter().filter(|&i| category(&params[i].key) == c).count()
+                                + unset.iter().filter(|k| category(k) == c).count();
+                            let arrow = if app.collapsed.contains(&c) { "▸" } else { "▾" };
+                            let label = format!("{} {} ({})", arrow, tr(c.label_id()), count);
+                            return ListItem::new(Line::from(Span::styled(label, theme.hint.add_modifier(Modifier::BOLD))));
+                        }
+                    };
+                    let p = &params[i];
+                    let left = pad(&p.key, 20);
+                    let right = p.value.clone();
+                    let mut spans = Vec::new();
+                    if app.marked.contains(&i) {
+                        spans.push(Span::styled("● ", Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD)));
+                    } else if !app.marked.is_empty() {
+                        spans.push(Span::raw("  "));
+                    }
+                    spans.extend([
+                        Span::raw(left),
+                        Span::raw(" = "),
+                        Span::styled(right, Style::default().add_modifier(Modifier::BOLD)),
+                    ]);
+                    if is_duration_key(&p.key)
+                        && let Ok(ms) = p.value.trim().parse::<u64>()
+                    {
+                        let human = if ms == 0 { tr("value.no_timeout").to_string() } else { format_duration_ms(ms) };
+                        spans.push(Span::styled(format!("  ({})", human), theme.hint));
+                    }
+                    if lint::for_key(&warnings, &p.key).next().is_some() {
+                        spans.push(Span::styled("  ⚠", warn_style));
+                    }
+                    ListItem::new(Line::from(spans))
+                })
+                .collect();
+
+            (
+                List::new(items)
+                    .block(Block::default().title(params_title).borders(Borders::ALL))
+                    .highlight_style(theme.highlight),
+                ActiveList::Params,
+            )
+        }
+    };
+
+    app.image_preview = None;
+    app.list_hitbox = None;
+    match active {
+        ActiveList::Params => {
+            let mut area = chunks[1];
+            // The section being edited, above its params: where add, edit
+            // and delete go, and the way back.
+            if let Some(section) = app.section.checked_sub(1).and_then(|i| app.cfg.sections.get(i)) {
+                let rows = Layout::default()
+                    .constraints([Constraint::Length(1), Constraint::Min(3)])
+                    .split(area);
+                let crumb = Line::from(vec![
+                    Span::styled(tr("tab.global"), theme.hint),
+                    Span::styled(" › ", theme.hint),
+                    Span::styled(format!("[{}]", section.criteria), theme.highlight),
+                    Span::raw("  "),
+                    Span::styled(tr("list.section_back"), theme.hint),
+                ]);
+                f.render_widget(Paragraph::new(crumb), rows[0]);
+                area = rows[1];
+            }
+            let area = if app.show_docs {
+                let cols = Layout::default()
+                    .direction(Direction::Horizontal)
+                    .constraints([Constraint::Percentage(60), Constraint::Percentage(40)])
+                    .split(area);
+                let history = app.docs_show_history().then(|| match &app.history {
+                    Ok(list) => trf("docs.history_count", &[&list.len()]),
+                    Err(e) => e.clone(),
+                });
+                draw_docs(f, cols[1], app.docs_key(), history, &theme);
+                cols[0]
+            } else {
+                area
+            };
+            let format_input = match &app.params_screen {
+                ParamsScreen::EditValue { input, .. } | ParamsScreen::AddValue { input, .. } if app.editing_key().is_some_and(format::is_format_key) => {
+                    Some(input.clone())
+                }
+                // Preview the selected format while browsing, too.
+                ParamsScreen::List if app.overlay.is_none() => app
+                    .list_state
+                    .selected()
+                    .and_then(|i| app.cfg.params_of(app.section).get(i))
+                    .filter(|p| format::is_format_key(&p.key))
+                    .map(|p| p.value.clone()),
+                _ => None,
+            };
+            match format_input {
+                Some(input) => {
+                    let rows = Layout::default()
+                        .direction(Direction::Vertical)
+                        .constraints([Constraint::Min(3), Constraint::Length(FORMAT_ROWS)])
+                        .split(area);
+                    render_params(f, list, rows[0], app);
+                    let typed = MakoConfig::from_params(&[app.cfg.params.as_slice(), app.cfg.params_of(app.section)].concat());
+                    draw_format_panel(f, rows[1], &input, typed.markup.unwrap_or(true), &theme);
+                }
+                None => render_params(f, list, area, app),
+            }
+        }
+        ActiveList::Keys => f.render_stateful_widget(list, chunks[1], &mut app.key_list_state),
+        ActiveList::Settings => f.render_stateful_widget(list, chunks[1], &mut settings_state),
+        ActiveList::Themes(preset) => {
+            let cols = Layout::default()
+                .direction(Direction::Horizontal)
+                .constraints([Constraint::Percentage(35), Constraint::Percentage(65)])
+                .split(chunks[1]);
+            f.render_stateful_widget(list, cols[0], &mut settings_state);
+            draw_theme_preview(f, cols[1], preset, &theme);
+        }
+        ActiveList::Snapshot(snapshot) => {
+            let cols = Layout::default()
+                .direction(Direction::Horizontal)
+                .constraints([Constraint::Percentage(30), Constraint::Percentage(70)])
+                .split(chunks[1]);
+            f.render_stateful_widget(list, cols[0], &mut settings_state);
+            draw_diff(f, cols[1], tr("list.snapshot_preview"), &diff::unified(&app.cfg.to_string(), &snapshot.text, DIFF_CONTEXT));
+        }
+        ActiveList::Commit(commit) => {
+            let cols = Layout::default()
+                .direction(Direction::Horizontal)
+                .constraints([Constraint::Percentage(40), Constraint::Percentage(60)])
+                .split(chunks[1]);
+            f.render_stateful_widget(list, cols[0], &mut settings_state);
+            draw_diff(f, cols[1], tr("list.snapshot_preview"), &diff::unified(&app.cfg.to_string(), &commit.text, DIFF_CONTEXT));
+        }
+        ActiveList::Anchor { cell, current } => {
+            let inner = Block::default().borders(Borders::ALL).inner(chunks[1]);
+            f.render_widget(list, chunks[1]);
+            draw_anchor_grid(f, inner, cell, current, &theme);
+        }
+        ActiveList::Sides { key, sides, side } => {
+            let inner = Block::default().borders(Borders::ALL).inner(chunks[1]);
+            f.render_widget(list, chunks[1]);
+            if key == "border-radius" {
+                draw_corners(f, inner, sides, side, &theme);
+            } else {
+                draw_sides(f, inner, key, sides, side, &theme);
+            }
+        }
+        ActiveList::Browser(browser) => {
+            let cols = Layout::default()
+                .direction(Direction::Horizontal)
+                .constraints([Constraint::Percentage(60), Constraint::Percentage(40)])
+                .split(chunks[1]);
+            let mut state = ListState::default().with_selected(Some(browser.selected));
+            f.render_stateful_widget(list, cols[0], &mut state);
+            app.image_preview = draw_browser_side(f, cols[1], browser, &theme);
+        }
+    }
+
+    f.render_widget(footer, chunks[2]);
+
+    if let Some(scroll) = app.help {
+        app.help = Some(draw_help(f, size, &app.settings.keys, scroll, &theme));
+    }
+}
+
+/// `ANCHOR_GRID` laid out like the screen it stands for, `cell` lit and
+/// the position set now marked.
+fn draw_anchor_grid(f: &mut Frame, area: Rect, cell: usize, current: Option<usize>, theme: &Theme) {
+    let thirds = [Constraint::Ratio(1, 3), Constraint::Ratio(1, 3), Constraint::Ratio(1, 3)];
+    let rows = Layout::default().constraints(thirds).split(area);
+    for (r, row) in rows.iter().enumerate() {
+        let cols = Layout::default().direction(Direction::Horizontal).constraints(thirds).split(*row);
+        for (c, col) in cols.iter().enumerate() {
+            let i = r * 3 + c;
+            let mut lines = vec![Line::from(ANCHOR_GRID[i])];
+            if current == Some(i) {
+                lines.push(Line::from(tr("list.pick_current")));
+            }
+            let style = if i == cell { theme.highlight } else { Style::default() };
+            let border = if current == Some(i) { Style::default().add_modifier(Modifier::BOLD) } else { theme.hint };
+            let block = Block::default().borders(Borders::ALL).border_style(border);
+            f.render_widget(Paragraph::new(lines).alignment(Alignment::Center).style(style).block(block), *col);
+        }
+    }
+}
+
+/// A box model of `sides`: the four sizes around what they space out (the
+/// text for padding, a notification or the stack for margins), the side
+/// being typed lit.
+fn draw_sides(f: &mut Frame, area: Rect, key: &str, sides: [u32; 4], side: usize, theme: &Theme) {
+    let value = |i: usize| Span::styled(format!(" {} ", sides[i]), if i == side { theme.highlight } else { Style::default() });
+    let rows = Layout::default()
+        .constraints([Constraint::Length(1), Constraint::Min(3), Constraint::Length(1)])
+        .split(area.inner(Margin { horizontal: 1, vertical: 1 }));
+    let cols = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([Constraint::Length(8), Constraint::Min(10), Constraint::Length(8)])
+        .split(rows[1]);
+    // The left and right values sit level with the middle of the box.
+    let middle = |i: usize| {
+        let mut lines = vec![Line::from(""); usize::from(cols[1].height / 2)];
+        lines.push(Line::from(value(i)));
+        Paragraph::new(lines).alignment(Alignment::Center)
+    };
+    f.render_widget(Paragraph::new(Line::from(value(0))).alignment(Alignment::Center), rows[0]);
+    f.render_widget(middle(3), cols[0]);
+    f.render_widget(middle(1), cols[2]);
+    f.render_widget(Paragraph::new(Line::from(value(2))).alignment(Alignment::Center), rows[2]);
+    let inner = match key {
+        "padding" => "sides.text",
+        "outer-margin" => "sides.stack",
+        _ => "sides.notification",
+    };
+    let mut lines = vec![Line::from(""); usize::from(cols[1].height.saturating_sub(2) / 2)];
+    lines.push(Line::from(Span::styled(tr(inner), theme.hint)));
+    f.render_widget(Paragraph::new(lines).alignment(Alignment::Center).block(Block::default().borders(Borders::ALL)), cols[1]);
+}
+
+/// A notification drawn with its corners rounded where the radius isn't 0,
+/// each corner's radius beside it and the one being typed lit.
+fn draw_corners(f: &mut Frame, area: Rect, radii: [u32; 4], corner: usize, theme: &Theme) {
+    let value = |i: usize| Span::styled(format!(" {} ", radii[i]), if i == corner { theme.highlight } else { Style::default() });
+    let glyph = |i: usize, round: char, square: char| if radii[i] > 0 { round } else { square };
+    let width = usize::from(area.width.saturating_sub(4)).clamp(12, 40);
+    let edge = "─".repeat(width - 2);
+    let inside = format!("│{}│", " ".repeat(width - 2));
+    let mut lines = vec![
+        Line::from(vec![value(0), Span::raw(" ".repeat(width - 6)), value(1)]),
+        Line::from(format!("{}{}{}", glyph(0, '╭', '┌'), edge, glyph(1, '╮', '┐'))),
+    ];
+    lines.extend(std::iter::repeat_n(Line::from(inside), 3));
+    lines.push(Line::from(format!("{}{}{}", glyph(3, '╰', '└'), edge, glyph(2, '╯', '┘'))));
+    lines.push(Line::from(vec![value(3), Span::raw(" ".repeat(width - 6)), value(2)]));
+    let top = area.height.saturating_sub(lines.len() as u16) / 2;
+    let area = Rect { y: area.y + top, height: area.height - top, ..area };
+    f.render_widget(Paragraph::new(lines).alignment(Alignment::Center), area);
+}
+
+/// The help overlay over `area`, scrolled by `scroll` rows but never past
+/// its end. Returns the scroll actually used.
+fn draw_help(f: &mut Frame, area: Rect, keys: &Keymap, scroll: u16, theme: &Theme) -> u16 {
+    let heading = |id: &'static str| Line::from(Span::styled(tr(id), Style::default().add_modifier(Modifier::BOLD)));
+    let text = |s: &str| Line::from(format!("  {}", s));
+    let mut lines = vec![heading("help.normal")];
+    for cmd in Command::ALL {
+        lines.push(Line::from(vec![
+            Span::styled(format!("  {:<4}", keys.key(*cmd)), theme.highlight),
+            Span::raw(tr(cmd.help_id())),
+        ]));
+    }
+    lines.push(Line::from(Span::styled(format!("  {}", tr("help.always")), theme.hint)));
+    for (title, body) in [
+        ("help.editing", &["help.editing_keys", "help.editing_format", "help.editing_browse"][..]),
+        ("help.adding", &["help.adding_keys"][..]),
+        ("help.search", &["help.search_keys"][..]),
+        ("help.trash", &["prompt.trash"][..]),
+        ("help.notifications", &["prompt.notifications"][..]),
+        ("help.history", &["prompt.history"][..]),
+        ("help.actions", &["prompt.actions"][..]),
+        ("help.compose", &["prompt.compose"][..]),
+        ("help.import", &["prompt.import_review"][..]),
+        ("help.pick_value", &["prompt.pick_value_keys"][..]),
+        ("help.criteria", &["prompt.build_criteria"][..]),
+        ("help.modes", &["prompt.modes"][..]),
+        ("help.themes", &["prompt.themes"][..]),
+        ("help.diff", &["prompt.diff"][..]),
+        ("help.problems", &["prompt.problems"][..]),
+        ("help.log", &["prompt.log"][..]),
+        ("help.browser", &["prompt.browse"][..]),
+        ("help.font_picker", &["prompt.pick_font"][..]),
+        ("help.list_editor", &["prompt.edit_list"][..]),
+        ("help.settings", &["prompt.settings"][..]),
+    ] {
+        lines.push(Line::from(""));
+        lines.push(heading(title));
+        lines.extend(body.iter().map(|id| text(tr(id))));
+    }
+
+    let popup = centered(area, 90, 90);
+    let visible = popup.height.saturating_sub(2);
+    let rows: u16 = lines.iter().map(|l| wrapped_rows(l, popup.width.saturating_sub(2))).sum();
+    let scroll = scroll.min(rows.saturating_sub(visible));
+    let block = Block::default().title(tr("help.title")).borders(Borders::ALL);
+    f.render_widget(Clear, popup);
+    f.render_widget(Paragraph::new(lines).block(block).wrap(Wrap { trim: false }).scroll((scroll, 0)), popup);
+    scroll
+}
+
+/// A `percent_x` by `percent_y` rectangle in the middle of `area`.
+fn centered(area: Rect, percent_x: u16, percent_y: u16) -> Rect {
+    let width = area.width * percent_x / 100;
+    let height = area.height * percent_y / 100;
+    Rect::new(area.x + (area.width - width) / 2, area.y + (area.height - height) / 2, width, height)
+}
+
+/// Draw the params list, with a scrollbar on its border when it doesn't
+/// fit. When some params are hidden, reordered or under headers, the rows
+/// no longer line up with `app.list_state`, so the list is drawn with a
+/// state for the rows shown; its offset is kept in `app.list_state` so the
+/// view only scrolls when the selection would leave it.
+fn render_params(f: &mut Frame, list: List, area: Rect, app: &mut App) {
+    let rows = app.rows();
+    let hitbox = area.inner(Margin { horizontal: 1, vertical: 1 });
+    let mut state = ListState::default().with_selected(app.selected_row(&rows)).with_offset(app.list_state.offset());
+    f.render_stateful_widget(list.scroll_padding(1), area, &mut state);
+    *app.list_state.offset_mut() = state.offset();
+    app.list_hitbox = Some((hitbox, state.offset()));
+    let visible = usize::from(hitbox.height);
+    if rows.len() > visible {
+        let mut bar = ScrollbarState::new(rows.len() - visible + 1).position(state.offset()).viewport_content_length(visible);
+        let track = area.inner(Margin { horizontal: 0, vertical: 1 });
+        f.render_stateful_widget(Scrollbar::new(ScrollbarOrientation::VerticalRight).begin_symbol(None).end_symbol(None), track, &mut bar);
+    }
+}
+
+/// Which list (and so which selection state) is on screen.
+enum ActiveList<'a> {
+    Params,
+    Keys,
+    Settings,
+    Browser(&'a Browser),
+    Themes(&'a Preset),
+    Snapshot(&'a Snapshot),
+    Commit(&'a Commit),
+    /// The anchor grid: the `cell` being picked and the one set now.
+    Anchor { cell: usize, current: Option<usize> },
+    /// The box model for a margin or padding, `side` being typed.
+    Sides { key: &'a str, sides: [u32; 4], side: usize },
+}
+
+/// Rows of the format panel, borders included.
+const FORMAT_ROWS: u16 = 9;
+
+/// Specifier legend, markup problems and an example expansion for the
+/// format string being edited, styled like Pango would (as far as a
+/// terminal can) unless `markup` is off.
+fn draw_format_panel(f: &mut Frame, area: Rect, input: &str, markup: bool, theme: &Theme) {
+    let legend_style = if format::wants_specifier(input) { theme.highlight } else { theme.hint };
+    let mut legend = vec![Span::raw(tr("format.specifiers"))];
+    for (spec, desc) in format::SPECIFIERS {
+        legend.push(Span::styled(format!("%{} {}", spec, desc), legend_style));
+        legend.push(Span::raw("  "));
+    }
+    let mut lines = vec![Line::from(legend)];
+
+    let problems = format::check(input);
+    if problems.is_empty() {
+        lines.push(Line::from(Span::styled(tr("format.markup_ok"), Style::default().fg(Color::Green))));
+    }
+    for p in problems {
+        lines.push(Line::from(Span::styled(format!("⚠ {}", p), Style::default().fg(Color::Red))));
+    }
+
+    lines.push(Line::from(Span::styled(tr("format.example"), theme.hint)));
+    let example = format::expand(input, &format::SAMPLE);
+    let runs = match format::parse_markup(&example) {
+        Ok(runs) if markup => runs,
+        _ => vec![(example, Vec::new())],
+    };
+    lines.extend(markup_lines(runs, "  "));
+
+    let block = Block::default().title(tr("format.title")).borders(Borders::ALL);
+    f.render_widget(Paragraph::new(lines).wrap(Wrap { trim: false }).block(block), area);
+}
+
+/// Lay out markup runs as terminal lines, each starting with `indent`.
+fn markup_lines(runs: Vec<format::Run>, indent: &str) -> Vec<Line<'static>> {
+    let mut lines = vec![Line::from(indent.to_string())];
+    for (text, tags) in runs {
+        let style = markup_style(&tags);
+        for (i, part) in text.split('\n').enumerate() {
+            if i > 0 {
+                lines.push(Line::from(indent.to_string()));
+            }
+            if !part.is_empty() {
+                lines.last_mut().unwrap().spans.push(Span::styled(part.to_string(), style));
+            }
+        }
+    }
+    lines
+}
+
+/// The terminal style closest to text inside `tags`. Sizes and fonts
+/// can't be shown; `small` is approximated by dim text.
+fn markup_style(tags: &[(String, String)]) -> Style {
+    let rgb = |v: Option<&str>| v.and_then(format::parse_color).map(|(r, g, b)| Color::Rgb(r, g, b));
+    let mut style = Style::default();
+    for (name, attrs) in tags {
+        style = match name.as_str() {
+            "b" => style.add_modifier(Modifier::BOLD),
+            "i" => style.add_modifier(Modifier::ITALIC),
+            "u" => style.add_modifier(Modifier::UNDERLINED),
+            "s" => style.add_modifier(Modifier::CROSSED_OUT),
+            "small" => style.add_modifier(Modifier::DIM),
+            "span" => {
+                let attr = |names: &[&str]| names.iter().find_map(|n| format::attr(attrs, n));
+                if let Some(c) = rgb(attr(&["color", "foreground", "fgcolor"])) {
+                    style = style.fg(c);
+                }
+                if let Some(c) = rgb(attr(&["background", "bgcolor"])) {
+                    style = style.bg(c);
+                }
+                if matches!(attr(&["weight", "font_weight"]), Some("bold" | "heavy" | "ultrabold" | "700" | "800" | "900")) {
+                    style = style.add_modifier(Modifier::BOLD);
+                }
+                if matches!(attr(&["style", "font_style"]), Some("italic" | "oblique")) {
+                    style = style.add_modifier(Modifier::ITALIC);
+                }
+                if attr(&["underline"]).is_some_and(|u| u != "none") {
+                    style = style.add_modifier(Modifier::UNDERLINED);
+                }
+                if attr(&["strikethrough"]) == Some("true") {
+                    style = style.add_modifier(Modifier::CROSSED_OUT);
+                }
+                style
+            }
+            _ => style,
+        };
+    }
+    style
+}
+
+/// Rows of the icon preview box, borders included.
+const PREVIEW_ROWS: u16 = 12;
+
+/// The icon themes in the browsed directory and a preview of the
+/// highlighted icon. Returns where an inline image should go, if the
+/// terminal can show one.
+fn draw_browser_side(f: &mut Frame, area: Rect, browser: &Browser, theme: &Theme) -> Option<(PathBuf, Rect)> {
+    let rows = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Min(3), Constraint::Length(PREVIEW_ROWS)])
+        .split(area);
+
+    let themes: Vec<ListItem> = if browser.themes.is_empty() {
+        vec![ListItem::new(Span::styled(tr("browser.no_themes"), theme.hint))]
+    } else {
+        browser.themes.iter().map(|t| ListItem::new(t.as_str())).collect()
+    };
+    f.render_widget(List::new(themes).block(Block::default().title(tr("list.icon_themes")).borders(Borders::ALL)), rows[0]);
+
+    let block = Block::default().title(tr("list.preview")).borders(Borders::ALL);
+    let inner = block.inner(rows[1]);
+    f.render_widget(block, rows[1]);
+    let icon = browser.selected_entry().filter(|e| !e.is_dir && icons::is_image(&e.path))?;
+    let info = icons::describe(&icon.path).unwrap_or_default();
+    let is_png = icon.path.extension().is_some_and(|e| e.eq_ignore_ascii_case("png"));
+    let inline = is_png && icons::inline_images_supported();
+    let note = if inline || !is_png { String::new() } else { format!("  ({})", tr("browser.no_inline")) };
+    f.render_widget(Paragraph::new(Line::from(vec![Span::raw(info), Span::styled(note, theme.hint)])).wrap(Wrap { trim: true }), inner);
+    let image = Rect { y: inner.y + 1, height: inner.height.saturating_sub(1), ..inner };
+    (inline && !image.is_empty()).then(|| (icon.path.clone(), image))
+}
+
+/// Mock notifications in `preset`'s colors, a normal and a critical one,
+/// followed by the params it sets.
+/// `rows` of a diff in a box titled `title`, as far as they fit.
+fn draw_diff(f: &mut Frame, area: Rect, title: &str, rows: &[(diff::Kind, String)]) {
+    let lines: Vec<Line> = if rows.is_empty() {
+        vec![Line::from(tr("list.snapshot_same"))]
+    } else {
+        rows.iter().map(|(kind, line)| Line::from(Span::styled(line.as_str(), diff_style(*kind)))).collect()
+    };
+    f.render_widget(Paragraph::new(lines).block(Block::default().title(title).borders(Borders::ALL)), area);
+}
+
+fn diff_style(kind: diff::Kind) -> Style {
+    match kind {
+        diff::Kind::Hunk => Style::default().fg(Color::Cyan),
+        diff::Kind::Removed => Style::default().fg(Color::Red),
+        diff::Kind::Added => Style::default().fg(Color::Green),
+        diff::Kind::Same => Style::default(),
+    }
+}
+
+fn draw_theme_preview(f: &mut Frame, area: Rect, preset: &Preset, theme: &Theme) {
+    let block = Block::default().title(tr("list.theme_preview")).borders(Borders::ALL);
+    let inner = block.inner(area);
+    f.render_widget(block, area);
+    let rows = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Length(4), Constraint::Length(4), Constraint::Min(0)])
+        .split(inner.inner(Margin { horizontal: 1, vertical: 0 }));
+    let rgb = |v: Option<&str>| v.and_then(format::parse_color).map(|(r, g, b)| Color::Rgb(r, g, b));
+    let bg = rgb(preset.get("background-color")).unwrap_or(Color::Reset);
+    let fg = rgb(preset.get("text-color")).unwrap_or(Color::Reset);
+    let mock = |border: Option<Color>, summary: &'static str, body: &'static str| {
+        Paragraph::new(vec![
+            Line::from(Span::styled(tr(summary), Style::default().fg(fg).add_modifier(Modifier::BOLD))),
+            Line::from(Span::styled(tr(body), Style::default().fg(fg))),
+        ])
+        .style(Style::default().bg(bg))
+        .block(Block::default().borders(Borders::ALL).border_style(Style::default().fg(border.unwrap_or(Color::Reset)).bg(bg)))
+    };
+    f.render_widget(mock(rgb(preset.get("border-color")), "themes.sample_summary", "themes.sample_body"), rows[0]);
+    f.render_widget(mock(rgb(Some(preset.critical_border)), "themes.sample_critical", "themes.sample_body"), rows[1]);
+    let mut lines = vec![Line::from(Span::styled(tr("themes.sets"), theme.hint))];
+    lines.extend(preset.params.iter().map(|(k, v)| Line::from(format!("  {}={}", k, v))));
+    lines.push(Line::from(format!("  [urgency=critical] border-color={}", preset.critical_border)));
+    f.render_widget(Paragraph::new(lines), rows[2]);
+}
+
+/// The docs pane: what `key` does, its type, default and examples, then
+/// `history`, how full mako's history is, for the keys that size it.
+fn draw_docs(f: &mut Frame, area: Rect, key: Option<&str>, history: Option<String>, theme: &Theme) {
+    let title = trf("list.docs", &[&key.unwrap_or_default()]);
+    let bold = Style::default().add_modifier(Modifier::BOLD);
+    let lines = match key.map(|k| (k, key_doc(k))) {
+        None => vec![Line::from(Span::styled(tr("docs.none"), theme.hint))],
+        Some((_, None)) => vec![Line::from(Span::styled(tr("docs.unknown"), theme.hint))],
+        Some((key, Some(doc))) => {
+            let mut lines = vec![
+                Line::from(doc.description),
+                Line::from(""),
+                Line::from(vec![Span::styled(tr("docs.type"), bold), Span::raw(doc.kind)]),
+                Line::from(vec![Span::styled(tr("docs.default"), bold), Span::raw(default_value(key).unwrap_or(tr("docs.no_default")))]),
+                Line::from(""),
+                Line::from(Span::styled(tr("docs.examples"), bold)),
+            ];
+            lines.extend(doc.examples.iter().map(|e| Line::from(Span::styled(format!("  {}={}", key, e), theme.hint))));
+            if let Some(history) = history {
+                lines.extend([Line::from(""), Line::from(vec![Span::styled(tr("docs.history"), bold), Span::raw(history)])]);
+            }
+            lines
+        }
+    };
+    let block = Block::default().title(title).borders(Borders::ALL);
+    f.render_widget(Paragraph::new(lines).block(block).wrap(Wrap { trim: false }), area);
+}
+
+/// One row of the notifications and history views: id, urgency (colored),
+/// app and summary.
+fn notification_item(n: &Notification, theme: &Theme) -> ListItem<'static> {
+    let urgency = match n.urgency {
+        "critical" => Style::default().fg(Color::Red).add_modifier(Modifier::BOLD),
+        "low" => theme.hint,
+        _ => Style::default(),
+    };
+    ListItem::new(Line::from(vec![
+        Span::raw(format!("{:>5}  ", n.id)),
+        Span::styled(format!("{:9}", n.urgency), urgency),
+        Span::raw(format!("{} ", pad(&n.app_name, 16))),
+        Span::styled(n.summary.clone(), Style::default().add_modifier(Modifier::BOLD)),
+    ]))
+}
+
+/// `input` in bold with the character under the cursor reversed, or a
+/// reversed space when the cursor is at the end.
+fn input_spans(input: &str, back: usize) -> Vec<Span<'static>> {
+    let bold = Style::default().add_modifier(Modifier::BOLD);
+    let (before, after) = line_edit::split(input, back);
+    let (under, rest) = match line_edit::first(after) {
+        Some(c) => (c, &after[c.len()..]),
+        None => (" ", ""),
+    };
+    vec![
+        Span::styled(before.to_string(), bold),
+        Span::styled(under.to_string(), bold.add_modifier(Modifier::REVERSED)),
+        Span::styled(rest.to_string(), bold),
+    ]
+}
+
+/// The add-key list's title, naming the section when the keys on offer
+/// are the ones that fit in it.
+fn known_keys_title(app: &App) -> String {
+    match app.section.checked_sub(1).and_then(|i| app.cfg.sections.get(i)) {
+        Some(section) => trf("list.known_keys_section", &[&section.criteria]),
+        None => tr("list.known_keys").to_string(),
+    }
+}
+
+/// The tab bar: the screen on show lit, Sections dimmed (and skipped by
+/// Tab) while the config has none.
+fn tabs<'a>(app: &App, theme: &Theme) -> Tabs<'a> {
+    let titles: Vec<Line> = Tab::ALL
+        .iter()
+        .map(|&t| {
+            let label = match t {
+                Tab::Sections => trf("tab.sections_count", &[&tr(t.label_id()), &app.cfg.sections.len()]),
+                _ => tr(t.label_id()).to_string(),
+            };
+            let style = if t == Tab::Sections && app.cfg.sections.is_empty() { theme.hint } else { Style::default() };
+            Line::from(Span::styled(label, style))
+        })
+        .collect();
+    let selected = Tab::ALL.iter().position(|&t| t == app.tab).unwrap_or(0);
+    Tabs::new(titles).select(selected).highlight_style(theme.highlight).divider(Span::styled("│", theme.hint))
+}
+
+/// `s` padded with spaces to `width` columns, where `format!("{:width$}")`
+/// would count a wide character (CJK, most emoji) as one.
+fn pad(s: &str, width: usize) -> String {
+    format!("{}{}", s, " ".repeat(width.saturating_sub(s.width())))
+}
+
+/// A red note when `input` wouldn't be a valid value for `key`, plus how
+/// to save it anyway once Enter has refused it.
+fn push_invalid(spans: &mut Vec<Span<'_>>, app: &App, key: &str, input: &str) {
+    let value = normalize_value(key, input);
+    if let Err(e) = validate(key, &app.cfg.expand(&value)) {
+        spans.push(Span::raw("    "));
+        spans.push(Span::styled(e, Style::default().fg(Color::Red)));
+        if app.refused.as_ref() == Some(&value) {
+            spans.push(Span::raw("  "));
+            spans.push(Span::styled(tr("validate.force"), Style::default().fg(Color::Red).add_modifier(Modifier::BOLD)));
+        }
+    } else if key == "font"
+        && let Some(installed) = &app.fonts
+    {
+        for family in fonts::missing(&value, installed) {
+            spans.push(Span::raw("    "));
+            spans.push(Span::styled(format!("⚠ {}", trf("lint.font_missing", &[&family])), Style::default().fg(Color::Yellow)));
+        }
+    } else if is_path_list_key(key) {
+        for dir in lint::missing_dirs(&value) {
+            spans.push(Span::raw("    "));
+            spans.push(Span::styled(format!("⚠ {}", trf("lint.path_missing", &[&dir])), Style::default().fg(Color::Yellow)));
+        }
+    }
+}
+
+fn push_message(spans: &mut Vec<Span<'_>>, message: &Option<Result<String, String>>) {
+    match message {
+        Some(Ok(msg)) => {
+            spans.push(Span::raw("    "));
+            spans.push(Span::styled(msg.clone(), Style::default().fg(Color::Green)));
+        }
+        Some(Err(msg)) => {
+            spans.push(Span::raw("    "));
+            spans.push(Span::styled(msg.clone(), Style::default().fg(Color::Red)));
+        }
+        None => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn markup_maps_to_terminal_styles() {
+        let runs = format::parse_markup("<b>bold <span color='#ff0000' style=\"italic\">red</span></b>\nplain").unwrap();
+        let lines = markup_lines(runs, "");
+        assert_eq!(lines.len(), 2);
+        let red = &lines[0].spans[1];
+        assert_eq!(red.content, "red");
+        assert_eq!(red.style.fg, Some(Color::Rgb(255, 0, 0)));
+        assert!(red.style.add_modifier.contains(Modifier::BOLD | Modifier::ITALIC));
+        assert_eq!(lines[1].spans[0].style, Style::default());
+    }
+}
@@ -0,0 +1,99 @@
+//! Parse/save and draw-cycle timings for a config with thousands of lines.
+//!
+//! These are ignored tests, so `cargo test` skips them. Run them with
+//! `cargo test --release --test large_config -- --ignored --nocapture --test-threads=1`.
+
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+use std::{env, fs};
+
+use crossterm::event::{KeyCode, KeyEvent};
+use mako_tui::app::App;
+use mako_tui::config::Config;
+use mako_tui::ui;
+use ratatui::{backend::TestBackend, Terminal};
+
+const LINES: usize = 5_000;
+const ITERS: u32 = 100;
+
+fn large_config_text() -> String {
+    let mut s = String::new();
+    for i in 0..LINES {
+        match i % 4 {
+            0 => s.push_str(&format!("# comment line {}\n", i)),
+            1 => s.push_str(&format!("background-color-{}=#1d1f21\n", i)),
+            2 => s.push_str(&format!("font-{} = \"monospace {}\"\n", i, i % 20)),
+            _ => s.push_str(&format!("width-{}={}px\n", i, i)),
+        }
+    }
+    s
+}
+
+fn large_config() -> Config {
+    let Ok(cfg) = large_config_text().parse();
+    cfg
+}
+
+/// Run `f` `ITERS` times after a warm-up and print min/mean/max per iteration.
+fn bench<F: FnMut()>(name: &str, mut f: F) {
+    f();
+    let mut times: Vec<Duration> = Vec::with_capacity(ITERS as usize);
+    for _ in 0..ITERS {
+        let start = Instant::now();
+        f();
+        times.push(start.elapsed());
+    }
+    let total: Duration = times.iter().sum();
+    let min = times.iter().min().copied().unwrap_or_default();
+    let max = times.iter().max().copied().unwrap_or_default();
+    println!("{:<28} time: [{:>10.3?} {:>10.3?} {:>10.3?}]  ({} iterations)", name, min, total / ITERS, max, ITERS);
+}
+
+#[test]
+#[ignore = "benchmark"]
+fn parse() {
+    let text = large_config_text();
+    bench("parse", || {
+        let Ok(c) = black_box(&text).parse::<Config>();
+        black_box(c);
+    });
+}
+
+#[test]
+#[ignore = "benchmark"]
+fn serialize() {
+    let cfg = large_config();
+    bench("serialize", || {
+        black_box(black_box(&cfg).to_string());
+    });
+}
+
+#[test]
+#[ignore = "benchmark"]
+fn save_and_load() {
+    let cfg = large_config();
+    let path = env::temp_dir().join(format!("mako-tui-bench-{}", std::process::id()));
+    bench("save to disk", || {
+        fs::write(&path, cfg.to_string()).unwrap();
+    });
+    bench("load from disk", || {
+        let Ok(c) = fs::read_to_string(&path).unwrap().parse::<Config>();
+        black_box(c);
+    });
+    let _ = fs::remove_file(&path);
+}
+
+#[test]
+#[ignore = "benchmark"]
+fn draw() {
+    let mut terminal = Terminal::new(TestBackend::new(160, 50)).unwrap();
+    let mut app = App::new(large_config());
+    bench("draw (params list)", || {
+        terminal.draw(|f| ui::draw(f, &mut app)).unwrap();
+    });
+
+    app.handle_key(KeyEvent::from(KeyCode::Char('a')));
+    bench("draw (add-key chooser)", || {
+        terminal.draw(|f| ui::draw(f, &mut app)).unwrap();
+    });
+}